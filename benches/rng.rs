@@ -4,31 +4,41 @@ extern crate test;
 
 use std::mem::size_of;
 use test::{black_box, Bencher};
-use urandom::rng::{SplitMix64, Xoshiro256, ChaCha20};
+use urandom::rng::{SplitMix64, Xoshiro256, Xoroshiro128pp, Xoroshiro128p, ChaCha20, Philox};
 
 const RAND_BENCH_N: u64 = 1000;
 const BYTES_LEN: usize = 1024;
 
 macro_rules! fill_bytes {
-	($fnn:ident, $gen:expr) => {
+	($fnn:ident, $len:expr, $gen:expr) => {
 		#[bench]
 		fn $fnn(b: &mut Bencher) {
 			let mut rng = $gen;
-			let mut buf = [0u8; BYTES_LEN];
+			let mut buf = vec![0u8; $len];
 			b.iter(|| {
 				for _ in 0..RAND_BENCH_N {
 					rng.fill_bytes(&mut buf);
-					black_box(buf);
+					black_box(&buf);
 				}
 			});
-			b.bytes = BYTES_LEN as u64 * RAND_BENCH_N;
+			b.bytes = $len as u64 * RAND_BENCH_N;
 		}
 	};
 }
 
-fill_bytes!(fill_bytes_splitmix64, SplitMix64::new());
-fill_bytes!(fill_bytes_xoshiro256, Xoshiro256::new());
-fill_bytes!(fill_bytes_chacha20, ChaCha20::new());
+fill_bytes!(fill_bytes_splitmix64, BYTES_LEN, SplitMix64::new());
+fill_bytes!(fill_bytes_xoshiro256, BYTES_LEN, Xoshiro256::new());
+fill_bytes!(fill_bytes_xoroshiro128pp, BYTES_LEN, Xoroshiro128pp::new());
+fill_bytes!(fill_bytes_xoroshiro128p, BYTES_LEN, Xoroshiro128p::new());
+fill_bytes!(fill_bytes_chacha20, BYTES_LEN, ChaCha20::new());
+fill_bytes!(fill_bytes_philox, BYTES_LEN, Philox::new());
+
+fill_bytes!(fill_bytes_64k_splitmix64, 64 * BYTES_LEN, SplitMix64::new());
+fill_bytes!(fill_bytes_64k_xoshiro256, 64 * BYTES_LEN, Xoshiro256::new());
+fill_bytes!(fill_bytes_64k_xoroshiro128pp, 64 * BYTES_LEN, Xoroshiro128pp::new());
+fill_bytes!(fill_bytes_64k_xoroshiro128p, 64 * BYTES_LEN, Xoroshiro128p::new());
+fill_bytes!(fill_bytes_64k_chacha20, 64 * BYTES_LEN, ChaCha20::new());
+fill_bytes!(fill_bytes_64k_philox, 64 * BYTES_LEN, Philox::new());
 
 macro_rules! fill_u32 {
 	($fnn:ident, $gen:expr) => {
@@ -49,7 +59,35 @@ macro_rules! fill_u32 {
 
 fill_u32!(fill_u32_splitmix64, SplitMix64::new());
 fill_u32!(fill_u32_xoshiro256, Xoshiro256::new());
+fill_u32!(fill_u32_xoroshiro128pp, Xoroshiro128pp::new());
+fill_u32!(fill_u32_xoroshiro128p, Xoroshiro128p::new());
 fill_u32!(fill_u32_chacha20, ChaCha20::new());
+fill_u32!(fill_u32_philox, Philox::new());
+
+#[bench]
+fn fill_u32_4k_chacha20(b: &mut Bencher) {
+	let mut rng = ChaCha20::new();
+	let mut buf = vec![0u32; 4096];
+	b.iter(|| {
+		rng.fill_u32(&mut buf);
+		black_box(&buf);
+	});
+	b.bytes = 4096 * 4;
+}
+
+#[bench]
+fn fill_bytes_4k_words_chacha20(b: &mut Bencher) {
+	let mut rng = ChaCha20::new();
+	let mut buf = vec![0u32; 4096];
+	b.iter(|| {
+		rng.fill_bytes(dataview::bytes_mut(&mut buf[..]));
+		for word in buf.iter_mut() {
+			*word = u32::from_le(*word);
+		}
+		black_box(&buf);
+	});
+	b.bytes = 4096 * 4;
+}
 
 macro_rules! fill_u64 {
 	($fnn:ident, $gen:expr) => {
@@ -70,7 +108,10 @@ macro_rules! fill_u64 {
 
 fill_u64!(fill_u64_splitmix64, SplitMix64::new());
 fill_u64!(fill_u64_xoshiro256, Xoshiro256::new());
+fill_u64!(fill_u64_xoroshiro128pp, Xoroshiro128pp::new());
+fill_u64!(fill_u64_xoroshiro128p, Xoroshiro128p::new());
 fill_u64!(fill_u64_chacha20, ChaCha20::new());
+fill_u64!(fill_u64_philox, Philox::new());
 
 macro_rules! next_uint {
 	($fnn:ident, $ty:ty, $gen:expr) => {
@@ -91,11 +132,70 @@ macro_rules! next_uint {
 
 next_uint!(next_u32_splitmix64, u32, SplitMix64::new());
 next_uint!(next_u32_xoshiro256, u32, Xoshiro256::new());
+next_uint!(next_u32_xoroshiro128pp, u32, Xoroshiro128pp::new());
+next_uint!(next_u32_xoroshiro128p, u32, Xoroshiro128p::new());
 next_uint!(next_u32_chacha20, u32, ChaCha20::new());
+next_uint!(next_u32_philox, u32, Philox::new());
 
 next_uint!(next_u64_splitmix64, u64, SplitMix64::new());
 next_uint!(next_u64_xoshiro256, u64, Xoshiro256::new());
+next_uint!(next_u64_xoroshiro128pp, u64, Xoroshiro128pp::new());
+next_uint!(next_u64_xoroshiro128p, u64, Xoroshiro128p::new());
 next_uint!(next_u64_chacha20, u64, ChaCha20::new());
+next_uint!(next_u64_philox, u64, Philox::new());
+
+#[bench]
+fn next_u8_chacha20(b: &mut Bencher) {
+	let mut rng = ChaCha20::new();
+	b.iter(|| {
+		let mut accum = 0u8;
+		for _ in 0..RAND_BENCH_N {
+			accum = accum.wrapping_add(rng.next::<u8>());
+		}
+		accum
+	});
+	b.bytes = RAND_BENCH_N;
+}
+
+#[bench]
+fn small_ints_u8_chacha20(b: &mut Bencher) {
+	let mut rng = ChaCha20::new();
+	b.iter(|| {
+		let mut ints = rng.small_ints();
+		let mut accum = 0u8;
+		for _ in 0..RAND_BENCH_N {
+			accum = accum.wrapping_add(ints.next_u8());
+		}
+		accum
+	});
+	b.bytes = RAND_BENCH_N;
+}
+
+#[bench]
+fn fill_u16_chacha20(b: &mut Bencher) {
+	let mut rng = ChaCha20::new();
+	let mut buf = [0u16; BYTES_LEN / 2];
+	b.iter(|| {
+		for _ in 0..RAND_BENCH_N {
+			rng.fill_u16(&mut buf);
+			black_box(buf);
+		}
+	});
+	b.bytes = BYTES_LEN as u64 * RAND_BENCH_N;
+}
+
+#[bench]
+fn fill_generic_u16_chacha20(b: &mut Bencher) {
+	let mut rng = ChaCha20::new();
+	let mut buf = [0u16; BYTES_LEN / 2];
+	b.iter(|| {
+		for _ in 0..RAND_BENCH_N {
+			rng.fill(&mut buf);
+			black_box(buf);
+		}
+	});
+	b.bytes = BYTES_LEN as u64 * RAND_BENCH_N;
+}
 
 macro_rules! next_float {
 	($fnn:ident, $ty:ty, $gen:expr) => {
@@ -116,11 +216,17 @@ macro_rules! next_float {
 
 next_float!(next_f32_splitmix64, f32, SplitMix64::new());
 next_float!(next_f32_xoshiro256, f32, Xoshiro256::new());
+next_float!(next_f32_xoroshiro128pp, f32, Xoroshiro128pp::new());
+next_float!(next_f32_xoroshiro128p, f32, Xoroshiro128p::new());
 next_float!(next_f32_chacha20, f32, ChaCha20::new());
+next_float!(next_f32_philox, f32, Philox::new());
 
 next_float!(next_f64_splitmix64, f64, SplitMix64::new());
 next_float!(next_f64_xoshiro256, f64, Xoshiro256::new());
+next_float!(next_f64_xoroshiro128pp, f64, Xoroshiro128pp::new());
+next_float!(next_f64_xoroshiro128p, f64, Xoroshiro128p::new());
 next_float!(next_f64_chacha20, f64, ChaCha20::new());
+next_float!(next_f64_philox, f64, Philox::new());
 
 macro_rules! init {
 	($fnn:ident, $gen:path) => {
@@ -135,4 +241,7 @@ macro_rules! init {
 
 init!(init_splitmix64, SplitMix64);
 init!(init_xoshiro256, Xoshiro256);
+init!(init_xoroshiro128pp, Xoroshiro128pp);
+init!(init_xoroshiro128p, Xoroshiro128p);
 init!(init_chacha20, ChaCha20);
+init!(init_philox, Philox);
@@ -57,8 +57,8 @@ fn main() {
 
 fn print_buckets(buckets: &[u32]) {
 	println!("```");
-	for i in 0..buckets.len() {
-		println!("{:>#05b}: {}", i, buckets[i]);
+	for (i, count) in buckets.iter().enumerate() {
+		println!("{:>#05b}: {}", i, count);
 	}
 	println!("```");
 }
@@ -0,0 +1,36 @@
+/*!
+Compares the variance of Monte Carlo integration with independent uniform samples against Latin hypercube samples.
+*/
+
+// A smooth function on the unit square, its integral is `(1 - cos 1)²`
+fn f(x: f64, y: f64) -> f64 {
+	x.sin() * y.sin()
+}
+
+fn main() {
+	let uniform = urandom::distributions::Uniform::from(0.0f64..1.0f64);
+
+	let mut rng = urandom::new();
+
+	// The number of samples per estimate
+	const N: usize = 100;
+	// The number of estimates to compute the variance
+	const RUNS: usize = 1000;
+
+	let exact = (1.0 - 1.0f64.cos()).powi(2);
+
+	let mut uniform_error = 0.0;
+	let mut latin_error = 0.0;
+	for _ in 0..RUNS {
+		let estimate = (0..N).map(|_| f(rng.sample(&uniform), rng.sample(&uniform))).sum::<f64>() / N as f64;
+		uniform_error += (estimate - exact).powi(2);
+
+		let points = rng.latin_hypercube(2, N);
+		let estimate = points.iter().map(|point| f(point[0], point[1])).sum::<f64>() / N as f64;
+		latin_error += (estimate - exact).powi(2);
+	}
+
+	println!("Exact integral:        {}", exact);
+	println!("Uniform sampling RMSE: {:e}", (uniform_error / RUNS as f64).sqrt());
+	println!("Latin hypercube RMSE:  {:e}", (latin_error / RUNS as f64).sqrt());
+}
@@ -0,0 +1,80 @@
+/*!
+Monte Carlo estimation split across threads.
+
+A single seeded generator is split into independent generators for every worker thread with [`Random::split`].
+The result only depends on the seed and the number of workers, not on how the threads are scheduled.
+
+[`Random::split`]: urandom::Random::split
+*/
+
+use std::thread;
+use urandom::distributions::{Float01, Uniform};
+use urandom::rng::Xoshiro256;
+
+const WORKERS: usize = 4;
+const SAMPLES_PER_WORKER: usize = 250_000;
+
+struct Estimate {
+	pi: f64,
+	integral: f64,
+}
+
+fn estimate(seed: u64) -> Estimate {
+	let mut rng = Xoshiro256::from_seed(seed);
+
+	let workers: Vec<_> = (0..WORKERS).map(|_| {
+		let mut rng = rng.split();
+		thread::spawn(move || {
+			let square = Uniform::from(-1.0f64..1.0f64);
+			let mut inside = 0usize;
+			let mut sum = 0.0;
+			for _ in 0..SAMPLES_PER_WORKER {
+				// Count the points of the square with side 2.0 which fall within the unit circle
+				let x = rng.sample(&square);
+				let y = rng.sample(&square);
+				if x * x + y * y < 1.0 {
+					inside += 1;
+				}
+
+				// Integrate exp(-x²) over the interval (0, 1)
+				let x: f64 = rng.sample(&Float01);
+				sum += (-x * x).exp();
+			}
+			(inside, sum)
+		})
+	}).collect();
+
+	// Join in spawn order so the floating point sum is deterministic
+	let mut inside = 0;
+	let mut sum = 0.0;
+	for worker in workers {
+		let (worker_inside, worker_sum) = worker.join().unwrap();
+		inside += worker_inside;
+		sum += worker_sum;
+	}
+
+	let n = (WORKERS * SAMPLES_PER_WORKER) as f64;
+	Estimate {
+		pi: 4.0 * inside as f64 / n,
+		integral: sum / n,
+	}
+}
+
+pub fn main() {
+	let first = estimate(42);
+	println!("PI is estimated as {}", first.pi);
+	println!("The integral of exp(-x²) over (0, 1) is estimated as {}", first.integral);
+
+	// The integral is (√π / 2) erf(1)
+	assert!((first.pi - std::f64::consts::PI).abs() < 0.01, "pi: {}", first.pi);
+	assert!((first.integral - 0.746824132812427).abs() < 0.001, "integral: {}", first.integral);
+
+	// Splitting is deterministic, the same seed reproduces the exact same estimates
+	let second = estimate(42);
+	assert_eq!(first.pi, second.pi);
+	assert_eq!(first.integral, second.integral);
+
+	// And a different seed gives different estimates
+	let other = estimate(43);
+	assert_ne!(first.pi, other.pi);
+}
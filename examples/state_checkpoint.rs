@@ -0,0 +1,74 @@
+/*!
+Checkpointing the generator state of a simulation.
+
+A toy simulation of particles taking random steps is interrupted halfway through.
+The generator state is saved as text with [`Xoshiro256::to_seed_bytes`], restored with [`Xoshiro256::from_seed_bytes`]
+and the resumed simulation is verified to match an uninterrupted run exactly.
+
+[`Xoshiro256::to_seed_bytes`]: urandom::rng::Xoshiro256::to_seed_bytes
+[`Xoshiro256::from_seed_bytes`]: urandom::rng::Xoshiro256::from_seed_bytes
+*/
+
+use urandom::Random;
+use urandom::rng::Xoshiro256;
+
+const PARTICLES: usize = 16;
+const STEPS: usize = 1000;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Simulation {
+	positions: [i64; PARTICLES],
+}
+
+impl Simulation {
+	fn new() -> Simulation {
+		Simulation { positions: [0; PARTICLES] }
+	}
+	fn step(&mut self, rng: &mut Random<Xoshiro256>) {
+		for position in &mut self.positions {
+			*position += rng.range(-3..=3);
+		}
+		// A random particle jumps to another particle
+		let from = rng.index(PARTICLES);
+		let to = rng.index(PARTICLES);
+		self.positions[from] = self.positions[to];
+	}
+}
+
+fn save(rng: &Random<Xoshiro256>) -> String {
+	rng.0.to_seed_bytes().iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn restore(text: &str) -> Random<Xoshiro256> {
+	let mut seed = [0u8; 32];
+	for (i, byte) in seed.iter_mut().enumerate() {
+		*byte = u8::from_str_radix(&text[i * 2..i * 2 + 2], 16).unwrap();
+	}
+	Xoshiro256::from_seed_bytes(seed)
+}
+
+pub fn main() {
+	// The uninterrupted run
+	let mut rng = Xoshiro256::from_seed(42);
+	let mut expected = Simulation::new();
+	for _ in 0..STEPS {
+		expected.step(&mut rng);
+	}
+
+	// The interrupted run, checkpointed halfway through
+	let mut rng = Xoshiro256::from_seed(42);
+	let mut sim = Simulation::new();
+	for _ in 0..STEPS / 2 {
+		sim.step(&mut rng);
+	}
+	let checkpoint = save(&rng);
+	println!("Checkpoint: {}", checkpoint);
+
+	let mut rng = restore(&checkpoint);
+	for _ in STEPS / 2..STEPS {
+		sim.step(&mut rng);
+	}
+
+	println!("Positions: {:?}", sim.positions);
+	assert_eq!(sim, expected);
+}
@@ -0,0 +1,167 @@
+/*!
+Playing cards.
+
+A standard 52-card deck for card games, to be shuffled and dealt with [`Random::shuffle`](crate::Random::shuffle),
+[`Random::deal`](crate::Random::deal) and [`Random::cut`](crate::Random::cut).
+
+# Examples
+
+```
+use urandom::cards;
+
+let mut rng = urandom::new();
+let mut deck = cards::deck();
+let [player, dealer] = rng.deal::<_, 2, 2>(&mut deck);
+println!("player: {} {}, dealer: {} {}", player[0], player[1], dealer[0], dealer[1]);
+```
+*/
+
+use core::fmt;
+
+/// Suit of a playing card.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Suit {
+	Clubs,
+	Diamonds,
+	Hearts,
+	Spades,
+}
+
+impl Suit {
+	/// All suits in ascending order.
+	pub const ALL: [Suit; 4] = [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades];
+
+	/// Returns the single letter abbreviation of the suit: `c`, `d`, `h` or `s`.
+	#[inline]
+	pub const fn to_char(self) -> char {
+		match self {
+			Suit::Clubs => 'c',
+			Suit::Diamonds => 'd',
+			Suit::Hearts => 'h',
+			Suit::Spades => 's',
+		}
+	}
+}
+
+/// Rank of a playing card, aces high.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Rank {
+	Two = 2,
+	Three,
+	Four,
+	Five,
+	Six,
+	Seven,
+	Eight,
+	Nine,
+	Ten,
+	Jack,
+	Queen,
+	King,
+	Ace,
+}
+
+impl Rank {
+	/// All ranks in ascending order.
+	pub const ALL: [Rank; 13] = [
+		Rank::Two, Rank::Three, Rank::Four, Rank::Five, Rank::Six, Rank::Seven, Rank::Eight,
+		Rank::Nine, Rank::Ten, Rank::Jack, Rank::Queen, Rank::King, Rank::Ace,
+	];
+
+	/// Returns the single character abbreviation of the rank: `2`-`9`, `T`, `J`, `Q`, `K` or `A`.
+	#[inline]
+	pub const fn to_char(self) -> char {
+		b"23456789TJQKA"[self as usize - 2] as char
+	}
+}
+
+/// A playing card.
+///
+/// Formats with the conventional two character abbreviation of rank and suit, eg. `As` for the ace of spades and `Td` for the ten of diamonds.
+/// Cards are ordered by suit then rank, the same order as [`deck`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Card {
+	pub suit: Suit,
+	pub rank: Rank,
+}
+
+impl Card {
+	/// Constructs a new card.
+	#[inline]
+	pub const fn new(rank: Rank, suit: Suit) -> Card {
+		Card { suit, rank }
+	}
+}
+
+impl fmt::Display for Card {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}{}", self.rank.to_char(), self.suit.to_char())
+	}
+}
+
+/// Returns a new 52-card deck in order by suit then rank.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::cards::{self, Card, Rank, Suit};
+///
+/// let deck = cards::deck();
+/// assert_eq!(deck[0], Card::new(Rank::Two, Suit::Clubs));
+/// assert_eq!(deck[51], Card::new(Rank::Ace, Suit::Spades));
+/// ```
+pub fn deck() -> [Card; 52] {
+	core::array::from_fn(|i| Card::new(Rank::ALL[i % 13], Suit::ALL[i / 13]))
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_deck() {
+	let deck = deck();
+	for (i, a) in deck.iter().enumerate() {
+		assert!(deck[i + 1..].iter().all(|b| a != b));
+	}
+	assert!(deck.windows(2).all(|pair| pair[0] < pair[1]));
+	assert_eq!(deck.iter().map(|card| card.to_string()).collect::<Vec<_>>().join(" ").len(), 52 * 3 - 1);
+	assert_eq!(Card::new(Rank::Ten, Suit::Diamonds).to_string(), "Td");
+	assert_eq!(Card::new(Rank::Two, Suit::Clubs).to_string(), "2c");
+}
+
+#[test]
+fn test_deal() {
+	let mut rng = crate::new();
+	let mut deck = deck();
+	let hands = rng.deal::<_, 4, 5>(&mut deck);
+	let dealt: Vec<Card> = hands.iter().flatten().copied().collect();
+	for (i, a) in dealt.iter().enumerate() {
+		assert!(dealt[i + 1..].iter().all(|b| a != b));
+	}
+	let mut sorted = deck;
+	sorted.sort();
+	assert_eq!(sorted, self::deck());
+
+	// Dealing the full deck is a permutation
+	let hands = rng.deal::<_, 4, 13>(&mut deck);
+	let mut dealt: Vec<Card> = hands.iter().flatten().copied().collect();
+	dealt.sort();
+	assert_eq!(dealt, self::deck());
+}
+
+#[test]
+fn test_golden() {
+	let mut rng = crate::rng::Xoshiro256::from_seed(42);
+	let mut deck = deck();
+	let hands = rng.deal::<_, 2, 3>(&mut deck);
+	let hands = hands.map(|hand| hand.map(|card| card.to_string()).join(" "));
+	assert_eq!(hands, ["5s As 2c", "6d Kh 8h"]);
+	rng.cut(&mut deck);
+	assert_eq!(deck[0].to_string(), "8c");
+}
+
+#[test]
+#[should_panic]
+fn test_deal_too_many() {
+	let mut deck = deck();
+	let _ = crate::new().deal::<_, 6, 9>(&mut deck);
+}
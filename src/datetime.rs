@@ -0,0 +1,252 @@
+/*!
+Calendar dates and times of day for fixture data.
+
+Dates use the proleptic Gregorian calendar and count days relative to the Unix epoch `1970-01-01` for interop with other date libraries.
+
+* [`Random::date`](crate::Random::date) samples a date uniformly over the days in a range of years.
+* [`Random::time_of_day`](crate::Random::time_of_day) samples a time of day with second resolution.
+
+# Examples
+
+```
+use urandom::datetime::Date;
+
+let mut rng = urandom::new();
+let date = rng.date(2020..=2024);
+assert!(date.is_valid() && date.year >= 2020 && date.year <= 2024);
+println!("{} was a {:?}", date, date.weekday());
+
+let (hour, minute, second) = rng.time_of_day();
+assert!(hour < 24 && minute < 60 && second < 60);
+```
+*/
+
+use core::fmt;
+use crate::{Distribution, Random, Rng};
+use crate::distributions::Standard;
+
+/// A calendar date in the proleptic Gregorian calendar.
+///
+/// The fields are public, dates constructed directly are not validated. See [`Date::new`](Date::new) and [`Date::is_valid`](Date::is_valid).
+///
+/// Dates are ordered chronologically.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Date {
+	/// The year, astronomical year numbering where the year `0` is `1 BC`.
+	pub year: i32,
+	/// The month in `1..=12`.
+	pub month: u8,
+	/// The day of the month in `1..=31`.
+	pub day: u8,
+}
+
+/// Day of the week.
+///
+/// Sampled uniformly from the [`Standard`](Standard) distribution.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Weekday {
+	Monday,
+	Tuesday,
+	Wednesday,
+	Thursday,
+	Friday,
+	Saturday,
+	Sunday,
+}
+
+impl Weekday {
+	/// All days of the week starting on Monday.
+	pub const ALL: [Weekday; 7] = [Weekday::Monday, Weekday::Tuesday, Weekday::Wednesday, Weekday::Thursday, Weekday::Friday, Weekday::Saturday, Weekday::Sunday];
+}
+
+/// Returns `true` if the year is a leap year.
+#[inline]
+pub const fn is_leap_year(year: i32) -> bool {
+	year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// Returns the number of days in the month of the year.
+///
+/// Returns zero if the month is not in `1..=12`.
+#[inline]
+pub const fn days_in_month(year: i32, month: u8) -> u8 {
+	match month {
+		1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+		4 | 6 | 9 | 11 => 30,
+		2 => if is_leap_year(year) { 29 } else { 28 },
+		_ => 0,
+	}
+}
+
+impl Date {
+	/// Constructs a new date.
+	///
+	/// Returns `None` if the month or the day of the month is out of range.
+	#[inline]
+	pub const fn new(year: i32, month: u8, day: u8) -> Option<Date> {
+		let date = Date { year, month, day };
+		if date.is_valid() { Some(date) } else { None }
+	}
+	/// Returns `true` if the month and the day of the month are in range.
+	#[inline]
+	pub const fn is_valid(&self) -> bool {
+		self.day >= 1 && self.day <= days_in_month(self.year, self.month)
+	}
+	/// Returns the number of days since `1970-01-01`, negative for earlier dates.
+	///
+	/// The result for invalid dates is unspecified.
+	pub const fn to_days(&self) -> i64 {
+		// See http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+		let month = self.month as i64;
+		let year = self.year as i64 - (month <= 2) as i64;
+		let era = year.div_euclid(400);
+		let yoe = year - era * 400;
+		let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + self.day as i64 - 1;
+		let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+		era * 146097 + doe - 719468
+	}
+	/// Returns the date the given number of days after `1970-01-01`, negative for earlier dates.
+	///
+	/// The year wraps around if it does not fit in `i32`.
+	pub const fn from_days(days: i64) -> Date {
+		// See http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+		let z = days + 719468;
+		let era = z.div_euclid(146097);
+		let doe = z - era * 146097;
+		let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+		let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+		let mp = (5 * doy + 2) / 153;
+		let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+		let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+		let year = (yoe + era * 400 + (month <= 2) as i64) as i32;
+		Date { year, month, day }
+	}
+	/// Returns the day of the week.
+	#[inline]
+	pub const fn weekday(&self) -> Weekday {
+		// 1970-01-01 was a Thursday
+		Weekday::ALL[(self.to_days() + 3).rem_euclid(7) as usize]
+	}
+}
+
+impl fmt::Display for Date {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+	}
+}
+
+impl Distribution<Weekday> for Standard {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> Weekday {
+		Weekday::ALL[rng.index(7)]
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_days() {
+	let cases = [
+		(Date { year: 1970, month: 1, day: 1 }, 0),
+		(Date { year: 1969, month: 12, day: 31 }, -1),
+		(Date { year: 2000, month: 3, day: 1 }, 11017),
+		(Date { year: 2024, month: 2, day: 29 }, 19782),
+		(Date { year: 0, month: 3, day: 1 }, -719468),
+		(Date { year: -1, month: 12, day: 31 }, -719529),
+	];
+	for &(date, days) in &cases {
+		assert_eq!(date.to_days(), days, "{}", date);
+		assert_eq!(Date::from_days(days), date, "{}", days);
+	}
+	assert_eq!(Date { year: 1970, month: 1, day: 1 }.weekday(), Weekday::Thursday);
+	assert_eq!(Date { year: 2024, month: 2, day: 29 }.weekday(), Weekday::Thursday);
+	assert_eq!(Date { year: 2000, month: 1, day: 1 }.weekday(), Weekday::Saturday);
+}
+
+#[test]
+fn test_days_sweep() {
+	// Step through the calendar one day at a time and compare with the closed form
+	let mut date = Date { year: 1600, month: 1, day: 1 };
+	let start = date.to_days();
+	for days in start..start + 3 * 146097 {
+		assert!(date.is_valid(), "{}", date);
+		assert_eq!(date.to_days(), days, "{}", date);
+		assert_eq!(Date::from_days(days), date, "{}", days);
+		date.day += 1;
+		if date.day > days_in_month(date.year, date.month) {
+			date.day = 1;
+			date.month += 1;
+			if date.month > 12 {
+				date.month = 1;
+				date.year += 1;
+			}
+		}
+	}
+	assert_eq!(date, Date { year: 2800, month: 1, day: 1 });
+}
+
+#[test]
+fn test_validation() {
+	assert!(Date::new(2024, 2, 29).is_some());
+	assert!(Date::new(2023, 2, 29).is_none());
+	assert!(Date::new(1900, 2, 29).is_none());
+	assert!(Date::new(2000, 2, 29).is_some());
+	assert!(Date::new(2024, 13, 1).is_none());
+	assert!(Date::new(2024, 4, 31).is_none());
+	assert!(Date::new(2024, 1, 0).is_none());
+	assert_eq!(Date { year: 7, month: 3, day: 9 }.to_string(), "0007-03-09");
+}
+
+#[test]
+fn test_weekday() {
+	let mut rng = crate::seeded(42);
+	let mut counts = [0u64; 7];
+	for _ in 0..7000 {
+		counts[rng.next::<Weekday>() as usize] += 1;
+	}
+	crate::stats::assert_uniform_counts(&counts, 1e-4);
+}
+
+#[test]
+fn test_date_uniform() {
+	let mut rng = crate::seeded(42);
+	let mut months = [0u64; 12];
+	let mut february = [0u64; 2];
+	for _ in 0..400000 {
+		let date = rng.date(1900..=1999);
+		assert!(date.is_valid() && date.year >= 1900 && date.year <= 1999, "{}", date);
+		months[date.month as usize - 1] += 1;
+		if date.month == 2 && date.day >= 28 {
+			february[date.day as usize - 28] += 1;
+		}
+	}
+	// February 29 appears in the 24 leap years of the century, 1900 is not a leap year
+	crate::stats::assert_counts(&february, &[100.0, 24.0], 1e-4);
+	let days: Vec<f64> = (1..=12).map(|month| (0..100).map(|year| days_in_month(1900 + year, month) as f64).sum()).collect();
+	crate::stats::assert_counts(&months, &days, 1e-4);
+
+	assert_eq!(rng.date(2000..=2000).year, 2000);
+	for _ in 0..1000 {
+		let date = rng.date(-5..=5);
+		assert!(date.is_valid() && date.year >= -5 && date.year <= 5, "{}", date);
+	}
+}
+
+#[test]
+#[should_panic(expected = "date range of years 2024..=2023 should not be empty")]
+fn test_date_empty() {
+	let (start, end) = (2024, 2023);
+	crate::new().date(start..=end);
+}
+
+#[test]
+fn test_time_of_day() {
+	let mut rng = crate::seeded(42);
+	let mut hours = [0u64; 24];
+	for _ in 0..24000 {
+		let (hour, minute, second) = rng.time_of_day();
+		assert!(hour < 24 && minute < 60 && second < 60);
+		hours[hour as usize] += 1;
+	}
+	crate::stats::assert_uniform_counts(&hours, 1e-4);
+}
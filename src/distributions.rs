@@ -22,6 +22,9 @@ including most primitive types, tuples, arrays, and a few derived types. See the
 
 Implementing `Distribution<T>` for [`Standard`](Standard) for user types `T` makes it possible to generate type `T` with [`Random::next`](Random::next).
 
+Quick one-off distributions can be defined with a closure by [`FromFn`](FromFn) without declaring a type.
+Distributions whose parameters are themselves random are built with [`Compound`](Compound) or [`Distribution::and_then`](Distribution::and_then).
+
 # The `Uniform` distribution
 
 The [`Uniform`](Uniform) distribution is similar to the [`Standard`](Standard) distribution
@@ -34,6 +37,25 @@ When the range limits are not known at compile time it is typically faster to re
 User types `T` may also implement `Distribution<T>` for [`Uniform`](Uniform), although this is less straightforward than for [`Standard`](Standard)
 (see the documentation in the uniform module. Doing so enables generation of values of type `T` with [`Random::range`].
 
+# Equality and hashing
+
+Distributions whose parameters can be compared exactly such as [`UniformInt`](UniformInt), [`Dice`](Dice) and [`Bernoulli`](Bernoulli) implement `Eq` and `Hash`.
+Their representation is normalized at construction so that distributions which behave the same compare and hash equal, eg. `0..10` and `0..=9`.
+This makes them usable as keys in hash maps, eg. to deduplicate simulation configurations.
+
+Distributions with floating point parameters such as [`UniformFloat`](UniformFloat) only implement `PartialEq`,
+equality of float parameters is not reflexive (`NaN`) and not a reliable indicator of equal behavior.
+
+# Persisting distributions
+
+This crate has no serialization integration. The constructors are deterministic functions of their parameters,
+to persist a distribution store the parameters it was constructed with and construct it again when loading.
+The reconstructed distribution samples exactly the same values as the original given the same generator state.
+Several distributions return their parameters, eg. [`Bernoulli::p`](Bernoulli::p), [`LogUniform::low`](LogUniform::low) and [`PiecewiseConstant::edges`](PiecewiseConstant::edges).
+
+The adapters created by [`Distribution::map`](Distribution::map) and friends contain closures and cannot be persisted,
+store the parameters of the inner distribution and apply the same adapter again after loading.
+
 [probability distribution]: https://en.wikipedia.org/wiki/Probability_distribution
 */
 
@@ -45,6 +67,13 @@ mod float01;
 mod bernoulli;
 mod dice;
 mod alphanumeric;
+mod chars;
+mod quantile;
+mod bits;
+mod maybe;
+mod unit_ball;
+mod uniform_step;
+mod float_bits;
 
 pub use self::standard::Standard;
 pub use self::uniform::*;
@@ -52,6 +81,85 @@ pub use self::float01::Float01;
 pub use self::bernoulli::Bernoulli;
 pub use self::dice::Dice;
 pub use self::alphanumeric::Alphanumeric;
+pub use self::chars::{AsciiPrintable, Bmp, GraphicChar};
+pub use self::quantile::FromQuantile;
+pub use self::bits::UniformBits;
+pub use self::maybe::{Maybe, OrElse};
+pub use self::unit_ball::{UnitDisc, UnitBall};
+pub use self::uniform_step::{UniformStep, UniformStepError, SampleUniformStep};
+pub use self::float_bits::{FloatBits, FloatBitsError, SampleFloatBits};
+
+cfg_if::cfg_if! {
+	if #[cfg(feature = "alloc")] {
+		mod cdf_table;
+		pub use self::cdf_table::{FromCdfTable, CdfTableError};
+
+		mod empirical;
+		pub use self::empirical::{Empirical, EmpiricalError};
+
+		mod weighted_index;
+		pub use self::weighted_index::{WeightedIndex, WeightedIndexError, SampleWeight};
+
+		mod weighted_alias;
+		pub use self::weighted_alias::{WeightedAliasIndex, WeightedAliasIndexError};
+
+		mod collections;
+		pub use self::collections::{VecOf, StringOf};
+		#[cfg(feature = "std")]
+		pub use self::collections::MapOf;
+	}
+}
+
+cfg_if::cfg_if! {
+	if #[cfg(feature = "std")] {
+		mod net;
+		pub use self::net::{UniformIpv4Addr, UniformIpv6Addr, Subnet};
+
+		mod laplace;
+		pub use self::laplace::{Laplace, LaplaceError};
+
+		mod poisson;
+		pub use self::poisson::{Poisson, PoissonError};
+
+		mod kumaraswamy;
+		pub use self::kumaraswamy::{Kumaraswamy, KumaraswamyError};
+
+		mod log_uniform;
+		pub use self::log_uniform::{LogUniform, LogUniformError, SampleLogUniform};
+
+		mod log_uniform_candidates;
+		pub use self::log_uniform_candidates::{LogUniformCandidates, LogUniformCandidatesError};
+
+		mod piecewise;
+		pub use self::piecewise::{PiecewiseConstant, PiecewiseLinear, PiecewiseError};
+
+		mod gaussian;
+
+		mod ball;
+		pub use self::ball::Ball;
+
+		mod rotation;
+		pub use self::rotation::{UnitQuaternion, Rotation2};
+
+		mod multivariate_normal;
+		pub use self::multivariate_normal::{MultivariateNormal, MultivariateNormalError};
+
+		mod order_statistic;
+		pub use self::order_statistic::{OrderStatistic, OrderStatisticError};
+
+		mod weighted_fn;
+		pub use self::weighted_fn::{WeightedFn, WeightedFnError};
+
+		mod weighted_map;
+		pub use self::weighted_map::{WeightedMap, WeightedMapError};
+
+		mod dynamic;
+		pub use self::dynamic::{Dynamic, DynamicError, DynamicSampler};
+
+		pub mod fit;
+		pub mod process;
+	}
+}
 
 /// Types (distributions) that can be used to create a random instance of `T`.
 ///
@@ -71,7 +179,102 @@ pub use self::alphanumeric::Alphanumeric;
 pub trait Distribution<T> {
 	/// Generate a random value of `T`, using rng as the source of randomness.
 	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> T;
+
+	/// Creates a distribution that maps the samples with a function.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use urandom::Distribution;
+	/// use urandom::distributions::Uniform;
+	///
+	/// let mut rng = urandom::new();
+	/// let distr = Uniform::from(0u32..10).map(|x| x * 10);
+	/// let value = rng.sample(&distr);
+	/// assert!(value % 10 == 0 && value < 100);
+	/// ```
+	#[inline]
+	fn map<U, F: Fn(T) -> U>(self, f: F) -> Map<Self, F, T> where Self: Sized {
+		Map::new(self, f)
+	}
+
+	/// Creates a distribution that resamples until the predicate accepts the sample.
+	///
+	/// This is [rejection sampling](https://en.wikipedia.org/wiki/Rejection_sampling), the expected number of samples is the inverse of the acceptance probability.
+	///
+	/// # Panics
+	///
+	/// In debug builds sampling panics after `2²⁰` rejected samples in a row, which likely means the predicate (almost) never accepts.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use urandom::Distribution;
+	/// use urandom::distributions::Uniform;
+	///
+	/// let mut rng = urandom::new();
+	/// let odd = Uniform::from(0u32..100).filter(|x| x % 2 == 1);
+	/// assert_eq!(rng.sample(&odd) % 2, 1);
+	/// ```
+	#[inline]
+	fn filter<F: Fn(&T) -> bool>(self, pred: F) -> Filtered<Self, F> where Self: Sized {
+		Filtered::new(self, pred)
+	}
+
+	/// Creates a distribution that maps the samples with a function and resamples until it returns `Some`.
+	///
+	/// This is the generalization of [`filter`](Distribution::filter) and [`map`](Distribution::map).
+	///
+	/// # Panics
+	///
+	/// In debug builds sampling panics after `2²⁰` rejected samples in a row, which likely means the function (almost) always returns `None`.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use urandom::Distribution;
+	/// use urandom::distributions::Uniform;
+	///
+	/// let mut rng = urandom::new();
+	/// let distr = Uniform::from(-100i32..100).filter_map(|x| u8::try_from(x).ok());
+	/// let value: u8 = rng.sample(&distr);
+	/// assert!(value < 100);
+	/// ```
+	#[inline]
+	fn filter_map<U, F: Fn(T) -> Option<U>>(self, f: F) -> FilterMap<Self, F, T> where Self: Sized {
+		FilterMap::new(self, f)
+	}
+
+	/// Creates a distribution that samples the parameters of another distribution from this distribution.
+	///
+	/// Every sample constructs the distribution returned by the function from a sample of this distribution and samples it.
+	/// See [`Compound`](Compound) for more.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use urandom::Distribution;
+	/// use urandom::distributions::Uniform;
+	///
+	/// let mut rng = urandom::new();
+	/// let distr = Uniform::from(1u32..=10).and_then(|n| Uniform::from(0..n));
+	/// let value = rng.sample(&distr);
+	/// assert!(value < 10);
+	/// ```
+	#[inline]
+	fn and_then<E, F: Fn(T) -> E>(self, f: F) -> Compound<Self, F, T> where Self: Sized {
+		Compound::new(self, f)
+	}
 }
 
+mod adapters;
+pub use self::adapters::{Map, Filtered, FilterMap, Compound, FromFn};
+
 mod samples;
-pub use self::samples::Samples;
+pub use self::samples::{Samples, IntoSamples};
+
+mod permuted_range;
+pub use self::permuted_range::PermutedRange;
+
+mod small_ints;
+pub use self::small_ints::SmallInts;
@@ -0,0 +1,415 @@
+use core::fmt;
+use core::marker::PhantomData;
+use crate::{Distribution, Random, Rng};
+use crate::rng::JumpStrength;
+
+/// Maximum number of rejected samples before giving up in debug builds.
+const MAX_ATTEMPTS: u32 = 1 << 20;
+
+/// A distribution that maps the samples of another distribution.
+///
+/// This struct is created by the [`Distribution::map`](Distribution::map) method. See its documentation for more.
+pub struct Map<D, F, T> {
+	distr: D,
+	f: F,
+	_phantom: PhantomData<fn() -> T>,
+}
+impl<D: Clone, F: Clone, T> Clone for Map<D, F, T> {
+	#[inline]
+	fn clone(&self) -> Self {
+		Map { distr: self.distr.clone(), f: self.f.clone(), _phantom: PhantomData }
+	}
+}
+impl<D: Copy, F: Copy, T> Copy for Map<D, F, T> {}
+impl<D: fmt::Debug, F, T> fmt::Debug for Map<D, F, T> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Map").field("distr", &self.distr).finish_non_exhaustive()
+	}
+}
+impl<D, F, T> Map<D, F, T> {
+	#[inline]
+	pub(crate) fn new(distr: D, f: F) -> Self {
+		Map { distr, f, _phantom: PhantomData }
+	}
+}
+impl<D: Distribution<T>, F: Fn(T) -> U, T, U> Distribution<U> for Map<D, F, T> {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> U {
+		(self.f)(self.distr.sample(rng))
+	}
+}
+
+/// A distribution that rejects the samples of another distribution which do not satisfy a predicate.
+///
+/// This struct is created by the [`Distribution::filter`](Distribution::filter) method. See its documentation for more.
+#[derive(Copy, Clone)]
+pub struct Filtered<D, F> {
+	distr: D,
+	pred: F,
+}
+impl<D: fmt::Debug, F> fmt::Debug for Filtered<D, F> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Filtered").field("distr", &self.distr).finish_non_exhaustive()
+	}
+}
+impl<D, F> Filtered<D, F> {
+	#[inline]
+	pub(crate) fn new(distr: D, pred: F) -> Self {
+		Filtered { distr, pred }
+	}
+}
+impl<D: Distribution<T>, F: Fn(&T) -> bool, T> Distribution<T> for Filtered<D, F> {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> T {
+		let mut attempts = 0;
+		loop {
+			let value = self.distr.sample(rng);
+			if (self.pred)(&value) {
+				return value;
+			}
+			attempts += 1;
+			if cfg!(debug_assertions) && attempts >= MAX_ATTEMPTS {
+				rejection_error("Filtered");
+			}
+		}
+	}
+}
+
+/// A distribution that maps the samples of another distribution and rejects those mapped to `None`.
+///
+/// This struct is created by the [`Distribution::filter_map`](Distribution::filter_map) method. See its documentation for more.
+pub struct FilterMap<D, F, T> {
+	distr: D,
+	f: F,
+	_phantom: PhantomData<fn() -> T>,
+}
+impl<D: Clone, F: Clone, T> Clone for FilterMap<D, F, T> {
+	#[inline]
+	fn clone(&self) -> Self {
+		FilterMap { distr: self.distr.clone(), f: self.f.clone(), _phantom: PhantomData }
+	}
+}
+impl<D: Copy, F: Copy, T> Copy for FilterMap<D, F, T> {}
+impl<D: fmt::Debug, F, T> fmt::Debug for FilterMap<D, F, T> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("FilterMap").field("distr", &self.distr).finish_non_exhaustive()
+	}
+}
+impl<D, F, T> FilterMap<D, F, T> {
+	#[inline]
+	pub(crate) fn new(distr: D, f: F) -> Self {
+		FilterMap { distr, f, _phantom: PhantomData }
+	}
+}
+impl<D: Distribution<T>, F: Fn(T) -> Option<U>, T, U> Distribution<U> for FilterMap<D, F, T> {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> U {
+		let mut attempts = 0;
+		loop {
+			if let Some(value) = (self.f)(self.distr.sample(rng)) {
+				return value;
+			}
+			attempts += 1;
+			if cfg!(debug_assertions) && attempts >= MAX_ATTEMPTS {
+				rejection_error("FilterMap");
+			}
+		}
+	}
+}
+
+#[cold]
+fn rejection_error(name: &str) -> ! {
+	panic!("{} rejected {} samples in a row, the acceptance probability is too low or zero", name, MAX_ATTEMPTS)
+}
+
+/// A distribution whose parameters are sampled from another distribution.
+///
+/// Every sample first samples the parameter from the outer distribution, then constructs the inner distribution from the parameter and samples it.
+/// Both draws use the same generator within the same call to [`sample`](Distribution::sample).
+///
+/// This struct is created by [`Compound::new`](Compound::new) or the [`Distribution::and_then`](Distribution::and_then) method.
+///
+/// # Hierarchical models
+///
+/// Compound distributions express hierarchical models such as a normal distribution whose standard deviation is itself random.
+/// They nest to any depth, the outer distribution of a compound can be another compound.
+///
+/// The inner constructor runs for every sample, keep it cheap: prefer distributions with trivial constructors
+/// over those which build tables such as [`WeightedFn`](super::WeightedFn).
+/// Panics of the inner constructor, eg. for invalid parameters, propagate out of `sample`.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::Distribution;
+/// use urandom::distributions::{Compound, Uniform};
+///
+/// // Roll a die with a random number of sides between 4 and 20
+/// let die = Compound::new(Uniform::from(4..=20), |sides| Uniform::from(1..=sides));
+/// let mut rng = urandom::new();
+/// let value = rng.sample(&die);
+/// assert!(value >= 1 && value <= 20);
+/// ```
+pub struct Compound<D, F, P> {
+	outer: D,
+	make_inner: F,
+	_phantom: PhantomData<fn() -> P>,
+}
+impl<D: Clone, F: Clone, P> Clone for Compound<D, F, P> {
+	#[inline]
+	fn clone(&self) -> Self {
+		Compound { outer: self.outer.clone(), make_inner: self.make_inner.clone(), _phantom: PhantomData }
+	}
+}
+impl<D: Copy, F: Copy, P> Copy for Compound<D, F, P> {}
+impl<D: fmt::Debug, F, P> fmt::Debug for Compound<D, F, P> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Compound").field("outer", &self.outer).finish_non_exhaustive()
+	}
+}
+impl<D, F, P> Compound<D, F, P> {
+	/// Creates a distribution which samples the parameter from `outer` and the value from the distribution returned by `make_inner`.
+	#[inline]
+	pub const fn new<E>(outer: D, make_inner: F) -> Compound<D, F, P> where D: Distribution<P>, F: Fn(P) -> E {
+		Compound { outer, make_inner, _phantom: PhantomData }
+	}
+}
+impl<D: Distribution<P>, F: Fn(P) -> E, E: Distribution<T>, P, T> Distribution<T> for Compound<D, F, P> {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> T {
+		let param = self.outer.sample(rng);
+		(self.make_inner)(param).sample(rng)
+	}
+}
+
+/// A distribution defined by a closure.
+///
+/// The closure receives the generator as `&mut Random<dyn Rng>` and can use the full [`Random`](Random) API,
+/// this makes it quick to define one-off distributions without declaring a struct.
+///
+/// The generic generator passed to [`sample`](Distribution::sample) is erased behind a `dyn Rng` forwarding to it,
+/// the closure sees exactly the same values as a hand-written distribution would at the cost of dynamic dispatch.
+///
+/// `FromFn` is `Clone` and `Copy` whenever the closure is.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::FromFn;
+///
+/// let mut rng = urandom::new();
+/// let distr = FromFn::new(|r| r.range(0..10) * 3);
+/// let value = rng.sample(&distr);
+/// assert!(value % 3 == 0 && value < 30);
+/// ```
+#[derive(Copy, Clone)]
+pub struct FromFn<F> {
+	f: F,
+}
+impl<F> fmt::Debug for FromFn<F> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("FromFn").finish_non_exhaustive()
+	}
+}
+impl<F> FromFn<F> {
+	/// Creates a distribution from the closure.
+	#[inline]
+	pub const fn new<T>(f: F) -> FromFn<F> where F: Fn(&mut Random<dyn Rng + '_>) -> T {
+		FromFn { f }
+	}
+}
+impl<F: Fn(&mut Random<dyn Rng + '_>) -> T, T> Distribution<T> for FromFn<F> {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> T {
+		(self.f)(&mut Random(Forward(&mut rng.0)))
+	}
+}
+
+// Forwards to a possibly unsized generator so that it can be erased as `dyn Rng`
+struct Forward<'a, R: ?Sized>(&'a mut R);
+impl<R: Rng + ?Sized> Rng for Forward<'_, R> {
+	#[inline]
+	fn next_u32(&mut self) -> u32 {
+		self.0.next_u32()
+	}
+	#[inline]
+	fn next_u64(&mut self) -> u64 {
+		self.0.next_u64()
+	}
+	#[inline]
+	fn next_f32(&mut self) -> f32 {
+		self.0.next_f32()
+	}
+	#[inline]
+	fn next_f64(&mut self) -> f64 {
+		self.0.next_f64()
+	}
+	#[inline]
+	fn fill_u32(&mut self, buffer: &mut [u32]) {
+		self.0.fill_u32(buffer)
+	}
+	#[inline]
+	fn fill_u64(&mut self, buffer: &mut [u64]) {
+		self.0.fill_u64(buffer)
+	}
+	#[inline]
+	fn fill_bytes(&mut self, buffer: &mut [u8]) {
+		self.0.fill_bytes(buffer)
+	}
+	#[inline]
+	fn jump(&mut self) {
+		self.0.jump()
+	}
+	#[inline]
+	fn jump_support(&self) -> JumpStrength {
+		self.0.jump_support()
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_filter_evens() {
+	use crate::distributions::{Standard, Uniform};
+	let mut rng = crate::seeded(42);
+	let evens = Distribution::<u8>::filter(Standard, |&x: &u8| x % 2 == 0);
+	let reference = Uniform::from(0u8..128).map(|x: u8| x * 2);
+	const N: usize = 128000;
+	let mut counts = [0i32; 256];
+	let mut reference_counts = [0i32; 256];
+	for _ in 0..N {
+		counts[rng.sample(&evens) as usize] += 1;
+		reference_counts[rng.sample(&reference) as usize] += 1;
+	}
+	for i in 0..256 {
+		if i % 2 == 1 {
+			assert_eq!(counts[i], 0);
+			assert_eq!(reference_counts[i], 0);
+		}
+		else {
+			assert!((counts[i] - 1000).abs() < 150, "value: {} count: {}", i, counts[i]);
+			assert!((reference_counts[i] - 1000).abs() < 150, "value: {} count: {}", i, reference_counts[i]);
+		}
+	}
+}
+
+#[test]
+fn test_filter_map() {
+	use crate::distributions::Uniform;
+	let mut rng = crate::new();
+	let distr = Uniform::from(0i32..100).filter_map(|x| u8::try_from(x - 50).ok());
+	for _ in 0..1000 {
+		let value: u8 = rng.sample(&distr);
+		assert!(value < 50);
+	}
+}
+
+#[test]
+fn test_compose() {
+	use crate::distributions::Uniform;
+	let mut rng = crate::new();
+	let distr = Uniform::from(0u32..1000)
+		.filter(|x| x % 3 == 0)
+		.map(|x| x as f64 / 3.0)
+		.filter_map(|x| if x >= 100.0 { Some(x as u64) } else { None });
+	for _ in 0..1000 {
+		let value: u64 = rng.sample(&distr);
+		assert!((100..=333).contains(&value), "value: {}", value);
+	}
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "acceptance probability")]
+fn test_never_accepts() {
+	use crate::distributions::Standard;
+	let distr = Distribution::<u64>::filter(Standard, |_| false);
+	let _: u64 = crate::new().sample(&distr);
+}
+
+#[test]
+fn test_from_fn() {
+	use crate::distributions::Uniform;
+
+	// Matches the equivalent hand-written distribution
+	struct Scaled(Uniform<i32>);
+	impl Distribution<i32> for Scaled {
+		fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> i32 {
+			self.0.sample(rng) * 3 + (rng.next_u32() % 2) as i32
+		}
+	}
+	let distr = FromFn::new(|r| r.range(0..10) * 3 + (r.next_u32() % 2) as i32);
+	let reference = Scaled(Uniform::from(0..10));
+	let mut a = crate::seeded(42);
+	let mut b = crate::seeded(42);
+	for _ in 0..1000 {
+		assert_eq!(a.sample(&distr), b.sample(&reference));
+	}
+
+	// Composes with the adapters and the sample iterators
+	let copy = distr;
+	let values: Vec<i32> = a.samples(copy.map(|x| -x)).take(100).collect();
+	assert!(values.iter().all(|&x| x <= 0 && x > -30));
+
+	// Works with unsized generators
+	let mut rng = crate::new();
+	let dyn_rng: &mut Random<dyn Rng> = &mut rng;
+	let pair = FromFn::new(|r| (r.coin_flip(), r.index(5)));
+	let (_, index) = dyn_rng.sample(&pair);
+	assert!(index < 5);
+}
+
+#[test]
+fn test_compound() {
+	use crate::distributions::Uniform;
+
+	// Matches sampling the parameter and the value by hand
+	let distr = Uniform::from(1u32..=6).and_then(|n| Uniform::from(0..n * 10));
+	let mut a = crate::seeded(42);
+	let mut b = crate::seeded(42);
+	for _ in 0..1000 {
+		let n = b.range(1u32..=6);
+		assert_eq!(a.sample(&distr), b.range(0..n * 10));
+	}
+
+	// Three levels deep
+	let nested = Compound::new(distr, |m| Uniform::from(m..=m + 1)).and_then(|x| Uniform::from(x * 2..=x * 2));
+	let mut c = crate::seeded(7);
+	let mut d = crate::seeded(7);
+	for _ in 0..1000 {
+		let value = c.sample(&nested);
+		assert!(value.is_multiple_of(2) && value <= 120, "value: {}", value);
+		assert_eq!(value, d.sample(&nested));
+	}
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_compound_variance() {
+	use crate::distributions::gaussian::standard_normal_pair;
+
+	struct Normal(f64);
+	impl Distribution<f64> for Normal {
+		fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> f64 {
+			standard_normal_pair(rng).0 * self.0
+		}
+	}
+
+	// Normal with a log-normal standard deviation exp(N(0, 0.5²))
+	let sigma = FromFn::new(|r| (standard_normal_pair(r).0 * 0.5).exp());
+	let distr = sigma.and_then(Normal);
+	let mut rng = crate::seeded(42);
+	const N: usize = 200000;
+	let (mut sum, mut sum_sq) = (0.0, 0.0);
+	for _ in 0..N {
+		let x = rng.sample(&distr);
+		sum += x;
+		sum_sq += x * x;
+	}
+	let mean = sum / N as f64;
+	let variance = sum_sq / N as f64 - mean * mean;
+	// Law of total variance: Var(X) = E[σ²] + Var(E[X | σ]) = E[σ²] = exp(2 · 0.5²)
+	let expected = f64::exp(0.5);
+	assert!(mean.abs() < 0.02, "mean: {}", mean);
+	assert!((variance - expected).abs() < 0.03 * expected, "variance: {} expected: {}", variance, expected);
+}
@@ -12,7 +12,7 @@ const ALPHANUMERIC: &[u8; 62] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghij
 /// let chars: String = rng.samples(Alphanumeric).take(7).collect();
 /// println!("Random chars: {}", chars);
 /// ```
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub struct Alphanumeric;
 
 impl Distribution<char> for Alphanumeric {
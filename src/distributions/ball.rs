@@ -0,0 +1,60 @@
+use crate::{Distribution, Random, Rng};
+use crate::distributions::Float01;
+
+/// Sample points uniformly distributed inside the unit ball in `N` dimensions.
+///
+/// # Implementation notes
+///
+/// A standard normal vector is normalized to a uniformly distributed direction on the unit sphere,
+/// then scaled by the radius `u^(1/N)` with `u` uniform in `(0, 1)` to correct for the volume growing as `r^N`.
+///
+/// The standard normal samples are generated in pairs with the Marsaglia polar method.
+///
+/// For 2 and 3 dimensions [`UnitDisc`](super::UnitDisc) and [`UnitBall`](super::UnitBall) are faster.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::Ball;
+///
+/// let mut rng = urandom::new();
+/// let point = rng.sample(&Ball::<8>);
+/// assert!(point.iter().map(|x| x * x).sum::<f64>() <= 1.0);
+/// ```
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Ball<const N: usize>;
+
+impl<const N: usize> Distribution<[f64; N]> for Ball<N> {
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> [f64; N] {
+		let mut point = [0.0; N];
+		if N == 0 {
+			return point;
+		}
+		let norm2 = loop {
+			super::gaussian::fill_standard_normal(rng, &mut point);
+			let norm2: f64 = point.iter().map(|x| x * x).sum();
+			if norm2 > 0.0 {
+				break norm2;
+			}
+		};
+		let u: f64 = Float01.sample(rng);
+		let scale = u.powf(1.0 / N as f64) / norm2.sqrt();
+		for x in &mut point {
+			*x *= scale;
+		}
+		point
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_ball() {
+	use super::unit_ball::check_ball;
+	check_ball(&Ball::<1>);
+	check_ball(&Ball::<2>);
+	check_ball(&Ball::<3>);
+	check_ball(&Ball::<5>);
+	check_ball(&Ball::<10>);
+	assert_eq!(crate::new().sample(&Ball::<0>), []);
+}
@@ -1,6 +1,12 @@
+use core::hash;
 use crate::{Distribution, Random, Rng};
 
 /// Sample from the [Bernoulli distribution](https://en.wikipedia.org/wiki/Bernoulli_distribution).
+///
+/// # Equality
+///
+/// The probability is normalized at construction: values `<= 0.0` (including `-0.0` and `NaN`) are stored as `0.0` and values `>= 1.0` as `1.0`.
+/// Distributions which behave the same compare and hash equal, which makes `Bernoulli` usable as a key in hash maps.
 #[derive(Copy, Clone, Debug)]
 pub struct Bernoulli {
 	p: f64,
@@ -13,10 +19,43 @@ impl Bernoulli {
 	///
 	/// For p >= 1.0, the resulting distribution will always generate true.
 	/// For p <= 0.0, the resulting distribution will always generate false.
+	///
+	/// # Examples
+	///
+	/// The probability is stored as is without any conversion other than the normalization above, the constructor can be used in const contexts:
+	///
+	/// ```
+	/// use urandom::distributions::Bernoulli;
+	///
+	/// static DROP: Bernoulli = Bernoulli::new(0.0625);
+	///
+	/// let mut rng = urandom::new();
+	/// let dropped: bool = rng.sample(&DROP);
+	/// ```
 	#[inline]
 	pub const fn new(p: f64) -> Bernoulli {
+		let p = if p >= 1.0 { 1.0 } else if p > 0.0 { p } else { 0.0 };
 		Bernoulli { p }
 	}
+	/// Returns the probability of success.
+	#[inline]
+	pub const fn p(&self) -> f64 {
+		self.p
+	}
+}
+
+impl PartialEq for Bernoulli {
+	#[inline]
+	fn eq(&self, other: &Bernoulli) -> bool {
+		self.p.to_bits() == other.p.to_bits()
+	}
+}
+impl Eq for Bernoulli {}
+impl hash::Hash for Bernoulli {
+	#[inline]
+	fn hash<H: hash::Hasher>(&self, state: &mut H) {
+		self.p.to_bits().hash(state);
+	}
 }
 
 impl Distribution<bool> for Bernoulli {
@@ -26,6 +65,33 @@ impl Distribution<bool> for Bernoulli {
 	}
 }
 
+//----------------------------------------------------------------
+
+#[test]
+fn test_const() {
+	static DROP: Bernoulli = Bernoulli::new(1.0 / 16.0);
+	const PROBABILITIES: [f64; 6] = [0.0, f64::MIN_POSITIVE, 0.1, 0.0625, 1.0 - f64::EPSILON / 2.0, 1.0];
+	for &p in &PROBABILITIES {
+		let distr = Bernoulli::new(p);
+		assert_eq!(distr.p.to_bits(), p.to_bits());
+	}
+	assert_eq!(DROP.p.to_bits(), 0.0625f64.to_bits());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_frequencies() {
+	let mut rng = crate::seeded(42);
+	for &p in &[0.001, 0.1, 0.25, 0.5, 0.9, 0.999] {
+		let distr = Bernoulli::new(p);
+		let mut counts = [0u64; 2];
+		for _ in 0..100000 {
+			counts[rng.sample::<bool, _>(&distr) as usize] += 1;
+		}
+		crate::stats::assert_counts(&counts, &[1.0 - p, p], 1e-4);
+	}
+}
+
 #[test]
 fn test_trivial() {
 	let mut rng = crate::new();
@@ -38,3 +104,35 @@ fn test_trivial() {
 		assert_eq!(Distribution::<bool>::sample(&always_true, &mut rng), true);
 	}
 }
+
+#[test]
+fn test_normalized() {
+	assert_eq!(Bernoulli::new(0.25), Bernoulli::new(1.0 / 4.0));
+	assert_eq!(Bernoulli::new(0.25), Bernoulli::new(0.5 * 0.5));
+	assert_eq!(Bernoulli::new(-0.0), Bernoulli::new(0.0));
+	assert_eq!(Bernoulli::new(f64::NAN), Bernoulli::new(0.0));
+	assert_eq!(Bernoulli::new(-3.0), Bernoulli::new(0.0));
+	assert_eq!(Bernoulli::new(2.0), Bernoulli::new(1.0));
+	assert_eq!(Bernoulli::new(f64::INFINITY).p(), 1.0);
+	assert_ne!(Bernoulli::new(0.25), Bernoulli::new(0.5));
+	assert_eq!(Bernoulli::new(-0.0).p().to_bits(), 0.0f64.to_bits());
+
+	// Normalization preserves the behavior
+	let mut rng = crate::new();
+	for _ in 0..100 {
+		assert!(!rng.sample::<bool, _>(&Bernoulli::new(f64::NAN)));
+		assert!(rng.sample::<bool, _>(&Bernoulli::new(2.0)));
+	}
+}
+
+#[test]
+fn test_hash_map() {
+	use std::collections::HashMap;
+	let mut map = HashMap::new();
+	map.insert(Bernoulli::new(0.25), "quarter");
+	map.insert(Bernoulli::new(-1.0), "never");
+	assert_eq!(map.get(&Bernoulli::new(1.0 / 4.0)), Some(&"quarter"));
+	assert_eq!(map.get(&Bernoulli::new(-0.0)), Some(&"never"));
+	assert_eq!(map.get(&Bernoulli::new(f64::NAN)), Some(&"never"));
+	assert_eq!(map.get(&Bernoulli::new(0.5)), None);
+}
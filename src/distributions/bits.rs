@@ -0,0 +1,92 @@
+use crate::{Distribution, Random, Rng};
+
+/// Uniformly random integers with the given number of bits.
+///
+/// Samples multi-word integers as arrays of `u64` limbs in little-endian limb order (the first limb is the least significant).
+/// The result is uniformly distributed over `0..2^bits`, the bits above the requested number of bits are zero.
+///
+/// This is a building block for sampling large integers such as 256-bit scalars without a bignum dependency.
+/// See [`Random::below_bytes`](Random::below_bytes) for unbiased sampling below an arbitrary modulus.
+///
+/// # Panics
+///
+/// Sampling panics if `bits` is larger than the number of bits in the output array.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::UniformBits;
+///
+/// let mut rng = urandom::new();
+/// let value: [u64; 4] = rng.sample(&UniformBits::new(255));
+/// assert!(value[3] < 1 << 63);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct UniformBits {
+	bits: u32,
+}
+
+impl UniformBits {
+	/// Constructs a new distribution with the given number of bits.
+	#[inline]
+	pub const fn new(bits: u32) -> UniformBits {
+		UniformBits { bits }
+	}
+
+	/// Returns the number of bits.
+	#[inline]
+	pub const fn bits(&self) -> u32 {
+		self.bits
+	}
+}
+
+impl<const N: usize> Distribution<[u64; N]> for UniformBits {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> [u64; N] {
+		if self.bits as usize > N * 64 {
+			uniform_bits_error(self.bits, N);
+		}
+		let mut value = [0u64; N];
+		let full = self.bits as usize / 64;
+		let rem = self.bits % 64;
+		rng.fill_u64(&mut value[..full]);
+		if rem > 0 {
+			value[full] = rng.next_u64() >> (64 - rem);
+		}
+		value
+	}
+}
+
+#[cold]
+fn uniform_bits_error(bits: u32, n: usize) -> ! {
+	panic!("UniformBits bits (is {}) should be <= the bits in [u64; {}]", bits, n);
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_bits() {
+	let mut rng = crate::new();
+	let mut seen = [0u64; 4];
+	for _ in 0..1000 {
+		let value: [u64; 4] = rng.sample(&UniformBits::new(130));
+		assert_eq!(value[2] >> 2, 0);
+		assert_eq!(value[3], 0);
+		for (seen, value) in seen.iter_mut().zip(value) {
+			*seen |= value;
+		}
+	}
+	assert_eq!(seen, [!0, !0, 3, 0]);
+
+	let value: [u64; 2] = rng.sample(&UniformBits::new(0));
+	assert_eq!(value, [0, 0]);
+	let value: [u64; 0] = rng.sample(&UniformBits::new(0));
+	assert_eq!(value, []);
+	let _: [u64; 2] = rng.sample(&UniformBits::new(128));
+}
+
+#[test]
+#[should_panic]
+fn test_bits_too_large() {
+	let _: [u64; 2] = crate::new().sample(&UniformBits::new(129));
+}
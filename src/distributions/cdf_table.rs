@@ -0,0 +1,197 @@
+use core::fmt;
+use alloc::vec::Vec;
+use crate::{Distribution, Random, Rng};
+use crate::distributions::Float01;
+
+/// Inverse transform sampling from a table of the cumulative distribution function.
+///
+/// The table consists of `(value, cumulative_probability)` knots sorted by value.
+/// The CDF is linearly interpolated between the knots.
+///
+/// The probability mass below the first knot is assigned to the value of the first knot
+/// and any probability mass missing after the last knot is assigned to the value of the last knot.
+///
+/// # Examples
+///
+/// A triangular distribution on `[0, 2]` with a flat top on `[0.5, 1.5]`:
+///
+/// ```
+/// use urandom::distributions::FromCdfTable;
+///
+/// let mut rng = urandom::new();
+/// let distr = FromCdfTable::new(vec![(0.0, 0.0), (0.5, 0.125), (1.5, 0.875), (2.0, 1.0)]);
+/// let value = rng.sample(&distr);
+/// assert!(value >= 0.0 && value <= 2.0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct FromCdfTable {
+	table: Vec<(f64, f64)>,
+}
+
+/// Error type returned from [`FromCdfTable`](FromCdfTable) constructors.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CdfTableError {
+	/// The table has no knots.
+	Empty,
+	/// A value or probability is not finite.
+	NotFinite,
+	/// The values are not sorted in non-decreasing order.
+	ValuesNotSorted,
+	/// The cumulative probabilities are not non-decreasing.
+	ProbabilitiesDecreasing,
+	/// The first cumulative probability is negative.
+	StartNegative,
+	/// The last cumulative probability is not approximately `1.0`.
+	EndNotOne,
+}
+
+impl fmt::Display for CdfTableError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match self {
+			CdfTableError::Empty => "CDF table has no knots",
+			CdfTableError::NotFinite => "CDF table contains a value or probability which is not finite",
+			CdfTableError::ValuesNotSorted => "CDF table values are not sorted",
+			CdfTableError::ProbabilitiesDecreasing => "CDF table probabilities are decreasing",
+			CdfTableError::StartNegative => "CDF table starts with a negative probability",
+			CdfTableError::EndNotOne => "CDF table does not end with a probability of one",
+		})
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CdfTableError {}
+
+impl FromCdfTable {
+	/// Tolerance on the last cumulative probability.
+	pub const END_TOLERANCE: f64 = 1e-6;
+
+	/// Constructs a new distribution from the table of `(value, cumulative_probability)` knots.
+	///
+	/// # Panics
+	///
+	/// Panics if the table is invalid, see [`try_new`](FromCdfTable::try_new).
+	#[inline]
+	pub fn new(table: Vec<(f64, f64)>) -> FromCdfTable {
+		match FromCdfTable::try_new(table) {
+			Ok(distr) => distr,
+			Err(err) => cdf_table_error(err),
+		}
+	}
+
+	/// Constructs a new distribution from the table of `(value, cumulative_probability)` knots.
+	///
+	/// Returns an error if the table is empty, contains non-finite numbers, its values are not sorted,
+	/// its probabilities are decreasing, start below `0.0` or do not end within [`END_TOLERANCE`](FromCdfTable::END_TOLERANCE) of `1.0`.
+	pub fn try_new(table: Vec<(f64, f64)>) -> Result<FromCdfTable, CdfTableError> {
+		let (first, last) = match (table.first(), table.last()) {
+			(Some(&first), Some(&last)) => (first, last),
+			_ => return Err(CdfTableError::Empty),
+		};
+		if !table.iter().all(|&(value, p)| value.is_finite() && p.is_finite()) {
+			return Err(CdfTableError::NotFinite);
+		}
+		if !table.windows(2).all(|w| w[0].0 <= w[1].0) {
+			return Err(CdfTableError::ValuesNotSorted);
+		}
+		if !table.windows(2).all(|w| w[0].1 <= w[1].1) {
+			return Err(CdfTableError::ProbabilitiesDecreasing);
+		}
+		if first.1 < 0.0 {
+			return Err(CdfTableError::StartNegative);
+		}
+		if (last.1 - 1.0).abs() > FromCdfTable::END_TOLERANCE {
+			return Err(CdfTableError::EndNotOne);
+		}
+		Ok(FromCdfTable { table })
+	}
+
+	/// Returns the knots of the table.
+	#[inline]
+	pub fn table(&self) -> &[(f64, f64)] {
+		&self.table
+	}
+
+	/// Evaluates the quantile function at probability `p` by interpolating the table.
+	///
+	/// Probabilities below the first knot return the first value and probabilities beyond the last knot return the last value.
+	pub fn quantile(&self, p: f64) -> f64 {
+		// Find the first knot with cumulative probability not less than p
+		let index = self.table.partition_point(|&(_, cp)| cp < p);
+		if index == 0 {
+			return self.table[0].0;
+		}
+		if index >= self.table.len() {
+			return self.table[self.table.len() - 1].0;
+		}
+		let (x0, p0) = self.table[index - 1];
+		let (x1, p1) = self.table[index];
+		// Because p0 < p <= p1 the denominator is never zero
+		x0 + (x1 - x0) * ((p - p0) / (p1 - p0))
+	}
+}
+
+impl Distribution<f64> for FromCdfTable {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> f64 {
+		let p: f64 = Float01.sample(rng);
+		self.quantile(p)
+	}
+}
+
+#[cold]
+fn cdf_table_error(err: CdfTableError) -> ! {
+	panic!("FromCdfTable::new called with invalid table: {}", err)
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_triangular() {
+	// Triangular distribution on [0, 2] with its mode at 1
+	let cdf = |x: f64| if x <= 1.0 { x * x / 2.0 } else { 1.0 - (2.0 - x) * (2.0 - x) / 2.0 };
+	let table = (0..=100).map(|i| { let x = i as f64 / 50.0; (x, cdf(x)) }).collect();
+	let distr = FromCdfTable::new(table);
+
+	let mut rng = crate::seeded(42);
+	const N: usize = 100000;
+	let mut counts = [0usize; 3];
+	for _ in 0..N {
+		let value = rng.sample(&distr);
+		assert!(value >= 0.0 && value <= 2.0);
+		for (i, &x) in [0.5, 1.0, 1.5].iter().enumerate() {
+			if value <= x {
+				counts[i] += 1;
+			}
+		}
+	}
+	for (i, &expected) in [0.125, 0.5, 0.875].iter().enumerate() {
+		let p = counts[i] as f64 / N as f64;
+		assert!((p - expected).abs() < 0.01, "P(X <= {}) = {} expected {}", [0.5, 1.0, 1.5][i], p, expected);
+	}
+}
+
+#[test]
+fn test_table_edges() {
+	let distr = FromCdfTable::new(vec![(1.0, 0.25), (2.0, 0.5), (2.0, 0.5), (4.0, 0.999_999_5)]);
+	// Below the first knot
+	assert_eq!(distr.quantile(0.1), 1.0);
+	// Exactly on the knots
+	assert_eq!(distr.quantile(0.25), 1.0);
+	assert_eq!(distr.quantile(0.5), 2.0);
+	// Interpolated
+	assert_eq!(distr.quantile(0.375), 1.5);
+	// Beyond the last knot
+	assert_eq!(distr.quantile(0.999_999_9), 4.0);
+	assert_eq!(distr.quantile(1.0), 4.0);
+}
+
+#[test]
+fn test_table_validation() {
+	assert_eq!(FromCdfTable::try_new(vec![]).unwrap_err(), CdfTableError::Empty);
+	assert_eq!(FromCdfTable::try_new(vec![(f64::NAN, 1.0)]).unwrap_err(), CdfTableError::NotFinite);
+	assert_eq!(FromCdfTable::try_new(vec![(1.0, 0.0), (0.0, 1.0)]).unwrap_err(), CdfTableError::ValuesNotSorted);
+	assert_eq!(FromCdfTable::try_new(vec![(0.0, 0.5), (1.0, 0.4), (2.0, 1.0)]).unwrap_err(), CdfTableError::ProbabilitiesDecreasing);
+	assert_eq!(FromCdfTable::try_new(vec![(0.0, -0.1), (1.0, 1.0)]).unwrap_err(), CdfTableError::StartNegative);
+	assert_eq!(FromCdfTable::try_new(vec![(0.0, 0.0), (1.0, 0.9)]).unwrap_err(), CdfTableError::EndNotOne);
+	assert!(FromCdfTable::try_new(vec![(3.0, 1.0)]).is_ok());
+}
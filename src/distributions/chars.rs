@@ -0,0 +1,150 @@
+use crate::{Distribution, Random, Rng};
+use crate::distributions::UniformInt;
+
+mod graphic;
+
+/// Sample a `char`, uniformly distributed over the printable ASCII characters `' '..='~'` (`0x20..=0x7E`).
+///
+/// This includes the space character, see [`Alphanumeric`](super::Alphanumeric) for letters and numbers only.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::AsciiPrintable;
+/// let mut rng = urandom::new();
+/// let text: String = rng.samples(AsciiPrintable).take(12).collect();
+/// assert!(text.chars().all(|c| c == ' ' || c.is_ascii_graphic()));
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct AsciiPrintable;
+
+impl Distribution<char> for AsciiPrintable {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> char {
+		UniformInt::constant(0x20u8, 0x7F - 0x20).sample(rng) as char
+	}
+}
+
+/// Sample a `char`, uniformly distributed over the Basic Multilingual Plane (`0..0x1_0000`) excluding the surrogate code points (`0xD800..0xE000`).
+///
+/// This includes unassigned, control, combining and private use code points.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::Bmp;
+/// let c = urandom::new().sample(&Bmp);
+/// assert!((c as u32) < 0x1_0000);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Bmp;
+
+impl Distribution<char> for Bmp {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> char {
+		const GAP_SIZE: u32 = 0xE000 - 0xD800;
+		let mut n = UniformInt::constant(0u32, 0x1_0000 - GAP_SIZE).sample(rng);
+		if n >= 0xD800 {
+			n += GAP_SIZE;
+		}
+		to_char(n)
+	}
+}
+
+/// Sample a `char`, uniformly distributed over the assigned graphic Unicode scalar values.
+///
+/// Graphic characters are the code points in the general categories Letter (L), Number (N), Punctuation (P), Symbol (S) and Space Separator (Zs).
+/// This excludes unassigned, control, format, private use and surrogate code points as well as combining marks and line and paragraph separators.
+///
+/// The set of characters is embedded as a table of code point ranges generated from the Unicode Character Database version 14.0.0.
+/// Updating the table to newer versions of Unicode changes the sampled values and is considered a breaking change.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::GraphicChar;
+/// let mut rng = urandom::new();
+/// let text: String = rng.samples(GraphicChar).take(12).collect();
+/// assert!(text.chars().all(|c| !c.is_control()));
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct GraphicChar;
+
+static GRAPHIC_RANGES: [(u32, u32); graphic::GRAPHIC_RANGES.len()] = graphic::GRAPHIC_RANGES;
+static GRAPHIC_OFFSETS: [u32; graphic::GRAPHIC_RANGES.len()] = graphic_offsets();
+const GRAPHIC_TOTAL: u32 = {
+	let (_, len) = graphic::GRAPHIC_RANGES[graphic::GRAPHIC_RANGES.len() - 1];
+	graphic_offsets()[graphic::GRAPHIC_RANGES.len() - 1] + len
+};
+
+// Cumulative number of characters before each range
+const fn graphic_offsets() -> [u32; graphic::GRAPHIC_RANGES.len()] {
+	let mut offsets = [0; graphic::GRAPHIC_RANGES.len()];
+	let mut i = 1;
+	while i < offsets.len() {
+		offsets[i] = offsets[i - 1] + graphic::GRAPHIC_RANGES[i - 1].1;
+		i += 1;
+	}
+	offsets
+}
+
+impl Distribution<char> for GraphicChar {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> char {
+		let n = UniformInt::constant(0u32, GRAPHIC_TOTAL).sample(rng);
+		let index = GRAPHIC_OFFSETS.partition_point(|&offset| offset <= n) - 1;
+		to_char(GRAPHIC_RANGES[index].0 + (n - GRAPHIC_OFFSETS[index]))
+	}
+}
+
+#[inline]
+fn to_char(n: u32) -> char {
+	// Let's be safe and provide a checked implementation for debugging
+	#[cfg(debug_assertions)]
+	return char::from_u32(n).unwrap();
+	// Safety depends on proper implementation of `UniformInt` and the tables
+	#[cfg(not(debug_assertions))]
+	#[allow(unsafe_code)]
+	return unsafe { char::from_u32_unchecked(n) };
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_ascii_printable() {
+	let mut rng = crate::new();
+	let mut buckets = [0i32; 95];
+	const N: i32 = 95000;
+	for c in rng.samples(AsciiPrintable).take(N as usize) {
+		let c: char = c;
+		assert!(c == ' ' || c.is_ascii_graphic(), "{:?}", c);
+		buckets[c as usize - 0x20] += 1;
+	}
+	assert!(buckets.iter().all(|&count| (count - 1000).abs() < 150), "{:?}", buckets);
+}
+
+#[test]
+fn test_bmp() {
+	let mut rng = crate::new();
+	for c in rng.samples(Bmp).take(10000) {
+		let c: char = c;
+		assert!((c as u32) < 0x1_0000, "{:?}", c);
+	}
+}
+
+#[test]
+fn test_graphic() {
+	// Check the table is sorted and the ranges do not overlap
+	for w in GRAPHIC_RANGES.windows(2) {
+		assert!(w[0].0 + w[0].1 < w[1].0, "{:x?}", w);
+	}
+	let mut rng = crate::new();
+	for c in rng.samples(GraphicChar).take(10000) {
+		let c: char = c;
+		assert!(!c.is_control(), "{:?}", c);
+		assert!(c == ' ' || !c.is_whitespace() || (c as u32) >= 0xA0, "{:?}", c);
+	}
+	let last = GRAPHIC_RANGES.len() - 1;
+	assert_eq!(GRAPHIC_TOTAL, GRAPHIC_OFFSETS[last] + GRAPHIC_RANGES[last].1);
+	assert_eq!(GRAPHIC_TOTAL, GRAPHIC_RANGES.iter().map(|&(_, len)| len).sum::<u32>());
+}
@@ -0,0 +1,132 @@
+// Generated from the Unicode Character Database version 14.0.0.
+// Contains the assigned code points in the general categories Letter (L), Number (N), Punctuation (P), Symbol (S) and Space Separator (Zs).
+// Listed as `(start, len)` pairs of code point ranges sorted by start.
+
+pub const GRAPHIC_RANGES: [(u32, u32); 756] = [
+	(0x20, 95), (0xA0, 13), (0xAE, 594), (0x370, 8), (0x37A, 6), (0x384, 7),
+	(0x38C, 1), (0x38E, 20), (0x3A3, 224), (0x48A, 166), (0x531, 38), (0x559, 50),
+	(0x58D, 3), (0x5BE, 1), (0x5C0, 1), (0x5C3, 1), (0x5C6, 1), (0x5D0, 27),
+	(0x5EF, 6), (0x606, 10), (0x61B, 1), (0x61D, 46), (0x660, 16), (0x671, 101),
+	(0x6DE, 1), (0x6E5, 2), (0x6E9, 1), (0x6EE, 32), (0x710, 1), (0x712, 30),
+	(0x74D, 89), (0x7B1, 1), (0x7C0, 43), (0x7F4, 7), (0x7FE, 24), (0x81A, 1),
+	(0x824, 1), (0x828, 1), (0x830, 15), (0x840, 25), (0x85E, 1), (0x860, 11),
+	(0x870, 31), (0x8A0, 42), (0x904, 54), (0x93D, 1), (0x950, 1), (0x958, 10),
+	(0x964, 29), (0x985, 8), (0x98F, 2), (0x993, 22), (0x9AA, 7), (0x9B2, 1),
+	(0x9B6, 4), (0x9BD, 1), (0x9CE, 1), (0x9DC, 2), (0x9DF, 3), (0x9E6, 24),
+	(0xA05, 6), (0xA0F, 2), (0xA13, 22), (0xA2A, 7), (0xA32, 2), (0xA35, 2),
+	(0xA38, 2), (0xA59, 4), (0xA5E, 1), (0xA66, 10), (0xA72, 3), (0xA76, 1),
+	(0xA85, 9), (0xA8F, 3), (0xA93, 22), (0xAAA, 7), (0xAB2, 2), (0xAB5, 5),
+	(0xABD, 1), (0xAD0, 1), (0xAE0, 2), (0xAE6, 12), (0xAF9, 1), (0xB05, 8),
+	(0xB0F, 2), (0xB13, 22), (0xB2A, 7), (0xB32, 2), (0xB35, 5), (0xB3D, 1),
+	(0xB5C, 2), (0xB5F, 3), (0xB66, 18), (0xB83, 1), (0xB85, 6), (0xB8E, 3),
+	(0xB92, 4), (0xB99, 2), (0xB9C, 1), (0xB9E, 2), (0xBA3, 2), (0xBA8, 3),
+	(0xBAE, 12), (0xBD0, 1), (0xBE6, 21), (0xC05, 8), (0xC0E, 3), (0xC12, 23),
+	(0xC2A, 16), (0xC3D, 1), (0xC58, 3), (0xC5D, 1), (0xC60, 2), (0xC66, 10),
+	(0xC77, 10), (0xC84, 9), (0xC8E, 3), (0xC92, 23), (0xCAA, 10), (0xCB5, 5),
+	(0xCBD, 1), (0xCDD, 2), (0xCE0, 2), (0xCE6, 10), (0xCF1, 2), (0xD04, 9),
+	(0xD0E, 3), (0xD12, 41), (0xD3D, 1), (0xD4E, 2), (0xD54, 3), (0xD58, 10),
+	(0xD66, 26), (0xD85, 18), (0xD9A, 24), (0xDB3, 9), (0xDBD, 1), (0xDC0, 7),
+	(0xDE6, 10), (0xDF4, 1), (0xE01, 48), (0xE32, 2), (0xE3F, 8), (0xE4F, 13),
+	(0xE81, 2), (0xE84, 1), (0xE86, 5), (0xE8C, 24), (0xEA5, 1), (0xEA7, 10),
+	(0xEB2, 2), (0xEBD, 1), (0xEC0, 5), (0xEC6, 1), (0xED0, 10), (0xEDC, 4),
+	(0xF00, 24), (0xF1A, 27), (0xF36, 1), (0xF38, 1), (0xF3A, 4), (0xF40, 8),
+	(0xF49, 36), (0xF85, 1), (0xF88, 5), (0xFBE, 8), (0xFC7, 6), (0xFCE, 13),
+	(0x1000, 43), (0x103F, 23), (0x105A, 4), (0x1061, 1), (0x1065, 2), (0x106E, 3),
+	(0x1075, 13), (0x108E, 1), (0x1090, 10), (0x109E, 40), (0x10C7, 1), (0x10CD, 1),
+	(0x10D0, 377), (0x124A, 4), (0x1250, 7), (0x1258, 1), (0x125A, 4), (0x1260, 41),
+	(0x128A, 4), (0x1290, 33), (0x12B2, 4), (0x12B8, 7), (0x12C0, 1), (0x12C2, 4),
+	(0x12C8, 15), (0x12D8, 57), (0x1312, 4), (0x1318, 67), (0x1360, 29), (0x1380, 26),
+	(0x13A0, 86), (0x13F8, 6), (0x1400, 669), (0x16A0, 89), (0x1700, 18), (0x171F, 19),
+	(0x1735, 2), (0x1740, 18), (0x1760, 13), (0x176E, 3), (0x1780, 52), (0x17D4, 9),
+	(0x17E0, 10), (0x17F0, 10), (0x1800, 11), (0x1810, 10), (0x1820, 89), (0x1880, 5),
+	(0x1887, 34), (0x18AA, 1), (0x18B0, 70), (0x1900, 31), (0x1940, 1), (0x1944, 42),
+	(0x1970, 5), (0x1980, 44), (0x19B0, 26), (0x19D0, 11), (0x19DE, 57), (0x1A1E, 55),
+	(0x1A80, 10), (0x1A90, 10), (0x1AA0, 14), (0x1B05, 47), (0x1B45, 8), (0x1B50, 27),
+	(0x1B74, 11), (0x1B83, 30), (0x1BAE, 56), (0x1BFC, 40), (0x1C3B, 15), (0x1C4D, 60),
+	(0x1C90, 43), (0x1CBD, 11), (0x1CD3, 1), (0x1CE9, 4), (0x1CEE, 6), (0x1CF5, 2),
+	(0x1CFA, 1), (0x1D00, 192), (0x1E00, 278), (0x1F18, 6), (0x1F20, 38), (0x1F48, 6),
+	(0x1F50, 8), (0x1F59, 1), (0x1F5B, 1), (0x1F5D, 1), (0x1F5F, 31), (0x1F80, 53),
+	(0x1FB6, 15), (0x1FC6, 14), (0x1FD6, 6), (0x1FDD, 19), (0x1FF2, 3), (0x1FF6, 9),
+	(0x2000, 11), (0x2010, 24), (0x202F, 49), (0x2070, 2), (0x2074, 27), (0x2090, 13),
+	(0x20A0, 33), (0x2100, 140), (0x2190, 663), (0x2440, 11), (0x2460, 1812), (0x2B76, 32),
+	(0x2B97, 344), (0x2CF2, 2), (0x2CF9, 45), (0x2D27, 1), (0x2D2D, 1), (0x2D30, 56),
+	(0x2D6F, 2), (0x2D80, 23), (0x2DA0, 7), (0x2DA8, 7), (0x2DB0, 7), (0x2DB8, 7),
+	(0x2DC0, 7), (0x2DC8, 7), (0x2DD0, 7), (0x2DD8, 7), (0x2E00, 94), (0x2E80, 26),
+	(0x2E9B, 89), (0x2F00, 214), (0x2FF0, 12), (0x3000, 42), (0x3030, 16), (0x3041, 86),
+	(0x309B, 101), (0x3105, 43), (0x3131, 94), (0x3190, 84), (0x31F0, 47), (0x3220, 29293),
+	(0xA490, 55), (0xA4D0, 348), (0xA640, 47), (0xA673, 1), (0xA67E, 32), (0xA6A0, 80),
+	(0xA6F2, 6), (0xA700, 203), (0xA7D0, 2), (0xA7D3, 1), (0xA7D5, 5), (0xA7F2, 16),
+	(0xA803, 3), (0xA807, 4), (0xA80C, 23), (0xA828, 4), (0xA830, 10), (0xA840, 56),
+	(0xA882, 50), (0xA8CE, 12), (0xA8F2, 13), (0xA900, 38), (0xA92E, 25), (0xA95F, 30),
+	(0xA984, 47), (0xA9C1, 13), (0xA9CF, 11), (0xA9DE, 7), (0xA9E6, 25), (0xAA00, 41),
+	(0xAA40, 3), (0xAA44, 8), (0xAA50, 10), (0xAA5C, 31), (0xAA7E, 50), (0xAAB1, 1),
+	(0xAAB5, 2), (0xAAB9, 5), (0xAAC0, 1), (0xAAC2, 1), (0xAADB, 16), (0xAAF0, 5),
+	(0xAB01, 6), (0xAB09, 6), (0xAB11, 6), (0xAB20, 7), (0xAB28, 7), (0xAB30, 60),
+	(0xAB70, 115), (0xABEB, 1), (0xABF0, 10), (0xAC00, 11172), (0xD7B0, 23), (0xD7CB, 49),
+	(0xF900, 366), (0xFA70, 106), (0xFB00, 7), (0xFB13, 5), (0xFB1D, 1), (0xFB1F, 24),
+	(0xFB38, 5), (0xFB3E, 1), (0xFB40, 2), (0xFB43, 2), (0xFB46, 125), (0xFBD3, 445),
+	(0xFD92, 54), (0xFDCF, 1), (0xFDF0, 16), (0xFE10, 10), (0xFE30, 35), (0xFE54, 19),
+	(0xFE68, 4), (0xFE70, 5), (0xFE76, 135), (0xFF01, 190), (0xFFC2, 6), (0xFFCA, 6),
+	(0xFFD2, 6), (0xFFDA, 3), (0xFFE0, 7), (0xFFE8, 7), (0xFFFC, 2), (0x10000, 12),
+	(0x1000D, 26), (0x10028, 19), (0x1003C, 2), (0x1003F, 15), (0x10050, 14), (0x10080, 123),
+	(0x10100, 3), (0x10107, 45), (0x10137, 88), (0x10190, 13), (0x101A0, 1), (0x101D0, 45),
+	(0x10280, 29), (0x102A0, 49), (0x102E1, 27), (0x10300, 36), (0x1032D, 30), (0x10350, 38),
+	(0x10380, 30), (0x1039F, 37), (0x103C8, 14), (0x10400, 158), (0x104A0, 10), (0x104B0, 36),
+	(0x104D8, 36), (0x10500, 40), (0x10530, 52), (0x1056F, 12), (0x1057C, 15), (0x1058C, 7),
+	(0x10594, 2), (0x10597, 11), (0x105A3, 15), (0x105B3, 7), (0x105BB, 2), (0x10600, 311),
+	(0x10740, 22), (0x10760, 8), (0x10780, 6), (0x10787, 42), (0x107B2, 9), (0x10800, 6),
+	(0x10808, 1), (0x1080A, 44), (0x10837, 2), (0x1083C, 1), (0x1083F, 23), (0x10857, 72),
+	(0x108A7, 9), (0x108E0, 19), (0x108F4, 2), (0x108FB, 33), (0x1091F, 27), (0x1093F, 1),
+	(0x10980, 56), (0x109BC, 20), (0x109D2, 47), (0x10A10, 4), (0x10A15, 3), (0x10A19, 29),
+	(0x10A40, 9), (0x10A50, 9), (0x10A60, 64), (0x10AC0, 37), (0x10AEB, 12), (0x10B00, 54),
+	(0x10B39, 29), (0x10B58, 27), (0x10B78, 26), (0x10B99, 4), (0x10BA9, 7), (0x10C00, 73),
+	(0x10C80, 51), (0x10CC0, 51), (0x10CFA, 42), (0x10D30, 10), (0x10E60, 31), (0x10E80, 42),
+	(0x10EAD, 1), (0x10EB0, 2), (0x10F00, 40), (0x10F30, 22), (0x10F51, 9), (0x10F70, 18),
+	(0x10F86, 4), (0x10FB0, 28), (0x10FE0, 23), (0x11003, 53), (0x11047, 7), (0x11052, 30),
+	(0x11071, 2), (0x11075, 1), (0x11083, 45), (0x110BB, 2), (0x110BE, 4), (0x110D0, 25),
+	(0x110F0, 10), (0x11103, 36), (0x11136, 15), (0x11147, 1), (0x11150, 35), (0x11174, 3),
+	(0x11183, 48), (0x111C1, 8), (0x111CD, 1), (0x111D0, 16), (0x111E1, 20), (0x11200, 18),
+	(0x11213, 25), (0x11238, 6), (0x11280, 7), (0x11288, 1), (0x1128A, 4), (0x1128F, 15),
+	(0x1129F, 11), (0x112B0, 47), (0x112F0, 10), (0x11305, 8), (0x1130F, 2), (0x11313, 22),
+	(0x1132A, 7), (0x11332, 2), (0x11335, 5), (0x1133D, 1), (0x11350, 1), (0x1135D, 5),
+	(0x11400, 53), (0x11447, 21), (0x1145D, 1), (0x1145F, 3), (0x11480, 48), (0x114C4, 4),
+	(0x114D0, 10), (0x11580, 47), (0x115C1, 27), (0x11600, 48), (0x11641, 4), (0x11650, 10),
+	(0x11660, 13), (0x11680, 43), (0x116B8, 2), (0x116C0, 10), (0x11700, 27), (0x11730, 23),
+	(0x11800, 44), (0x1183B, 1), (0x118A0, 83), (0x118FF, 8), (0x11909, 1), (0x1190C, 8),
+	(0x11915, 2), (0x11918, 24), (0x1193F, 1), (0x11941, 1), (0x11944, 3), (0x11950, 10),
+	(0x119A0, 8), (0x119AA, 39), (0x119E1, 3), (0x11A00, 1), (0x11A0B, 40), (0x11A3A, 1),
+	(0x11A3F, 8), (0x11A50, 1), (0x11A5C, 46), (0x11A9A, 9), (0x11AB0, 73), (0x11C00, 9),
+	(0x11C0A, 37), (0x11C40, 6), (0x11C50, 29), (0x11C70, 32), (0x11D00, 7), (0x11D08, 2),
+	(0x11D0B, 38), (0x11D46, 1), (0x11D50, 10), (0x11D60, 6), (0x11D67, 2), (0x11D6A, 32),
+	(0x11D98, 1), (0x11DA0, 10), (0x11EE0, 19), (0x11EF7, 2), (0x11FB0, 1), (0x11FC0, 50),
+	(0x11FFF, 923), (0x12400, 111), (0x12470, 5), (0x12480, 196), (0x12F90, 99), (0x13000, 1071),
+	(0x14400, 583), (0x16800, 569), (0x16A40, 31), (0x16A60, 10), (0x16A6E, 81), (0x16AC0, 10),
+	(0x16AD0, 30), (0x16AF5, 1), (0x16B00, 48), (0x16B37, 15), (0x16B50, 10), (0x16B5B, 7),
+	(0x16B63, 21), (0x16B7D, 19), (0x16E40, 91), (0x16F00, 75), (0x16F50, 1), (0x16F93, 13),
+	(0x16FE0, 4), (0x17000, 6136), (0x18800, 1238), (0x18D00, 9), (0x1AFF0, 4), (0x1AFF5, 7),
+	(0x1AFFD, 2), (0x1B000, 291), (0x1B150, 3), (0x1B164, 4), (0x1B170, 396), (0x1BC00, 107),
+	(0x1BC70, 13), (0x1BC80, 9), (0x1BC90, 10), (0x1BC9C, 1), (0x1BC9F, 1), (0x1CF50, 116),
+	(0x1D000, 246), (0x1D100, 39), (0x1D129, 60), (0x1D16A, 3), (0x1D183, 2), (0x1D18C, 30),
+	(0x1D1AE, 61), (0x1D200, 66), (0x1D245, 1), (0x1D2E0, 20), (0x1D300, 87), (0x1D360, 25),
+	(0x1D400, 85), (0x1D456, 71), (0x1D49E, 2), (0x1D4A2, 1), (0x1D4A5, 2), (0x1D4A9, 4),
+	(0x1D4AE, 12), (0x1D4BB, 1), (0x1D4BD, 7), (0x1D4C5, 65), (0x1D507, 4), (0x1D50D, 8),
+	(0x1D516, 7), (0x1D51E, 28), (0x1D53B, 4), (0x1D540, 5), (0x1D546, 1), (0x1D54A, 7),
+	(0x1D552, 340), (0x1D6A8, 292), (0x1D7CE, 562), (0x1DA37, 4), (0x1DA6D, 8), (0x1DA76, 14),
+	(0x1DA85, 7), (0x1DF00, 31), (0x1E100, 45), (0x1E137, 7), (0x1E140, 10), (0x1E14E, 2),
+	(0x1E290, 30), (0x1E2C0, 44), (0x1E2F0, 10), (0x1E2FF, 1), (0x1E7E0, 7), (0x1E7E8, 4),
+	(0x1E7ED, 2), (0x1E7F0, 15), (0x1E800, 197), (0x1E8C7, 9), (0x1E900, 68), (0x1E94B, 1),
+	(0x1E950, 10), (0x1E95E, 2), (0x1EC71, 68), (0x1ED01, 61), (0x1EE00, 4), (0x1EE05, 27),
+	(0x1EE21, 2), (0x1EE24, 1), (0x1EE27, 1), (0x1EE29, 10), (0x1EE34, 4), (0x1EE39, 1),
+	(0x1EE3B, 1), (0x1EE42, 1), (0x1EE47, 1), (0x1EE49, 1), (0x1EE4B, 1), (0x1EE4D, 3),
+	(0x1EE51, 2), (0x1EE54, 1), (0x1EE57, 1), (0x1EE59, 1), (0x1EE5B, 1), (0x1EE5D, 1),
+	(0x1EE5F, 1), (0x1EE61, 2), (0x1EE64, 1), (0x1EE67, 4), (0x1EE6C, 7), (0x1EE74, 4),
+	(0x1EE79, 4), (0x1EE7E, 1), (0x1EE80, 10), (0x1EE8B, 17), (0x1EEA1, 3), (0x1EEA5, 5),
+	(0x1EEAB, 17), (0x1EEF0, 2), (0x1F000, 44), (0x1F030, 100), (0x1F0A0, 15), (0x1F0B1, 15),
+	(0x1F0C1, 15), (0x1F0D1, 37), (0x1F100, 174), (0x1F1E6, 29), (0x1F210, 44), (0x1F240, 9),
+	(0x1F250, 2), (0x1F260, 6), (0x1F300, 984), (0x1F6DD, 16), (0x1F6F0, 13), (0x1F700, 116),
+	(0x1F780, 89), (0x1F7E0, 12), (0x1F7F0, 1), (0x1F800, 12), (0x1F810, 56), (0x1F850, 10),
+	(0x1F860, 40), (0x1F890, 30), (0x1F8B0, 2), (0x1F900, 340), (0x1FA60, 14), (0x1FA70, 5),
+	(0x1FA78, 5), (0x1FA80, 7), (0x1FA90, 29), (0x1FAB0, 11), (0x1FAC0, 6), (0x1FAD0, 10),
+	(0x1FAE0, 8), (0x1FAF0, 7), (0x1FB00, 147), (0x1FB94, 55), (0x1FBF0, 10), (0x20000, 42720),
+	(0x2A700, 4153), (0x2B740, 222), (0x2B820, 5762), (0x2CEB0, 7473), (0x2F800, 542), (0x30000, 4939),
+];
@@ -0,0 +1,226 @@
+use alloc::{string::String, vec::Vec};
+use crate::{Distribution, Random, Rng};
+
+/// Sample vectors with a random length and random elements.
+///
+/// The length is sampled from the length distribution, then every element is sampled from the element distribution.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::{Standard, Uniform, VecOf};
+///
+/// let mut rng = urandom::new();
+/// let distr = VecOf::new(Uniform::from(0..=20), Standard);
+/// for vec in rng.samples::<Vec<u32>, _>(distr).take(100) {
+/// 	assert!(vec.len() <= 20);
+/// }
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct VecOf<L, D> {
+	len_distr: L,
+	elem_distr: D,
+}
+
+impl<L, D> VecOf<L, D> {
+	/// Constructs a new `VecOf` distribution from the length and element distributions.
+	#[inline]
+	pub const fn new(len_distr: L, elem_distr: D) -> VecOf<L, D> {
+		VecOf { len_distr, elem_distr }
+	}
+}
+
+impl<L: Distribution<usize>, D: Distribution<T>, T> Distribution<Vec<T>> for VecOf<L, D> {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> Vec<T> {
+		let len = self.len_distr.sample(rng);
+		let mut vec = Vec::with_capacity(len);
+		for _ in 0..len {
+			vec.push(self.elem_distr.sample(rng));
+		}
+		vec
+	}
+}
+
+/// Sample strings with a random length and random characters.
+///
+/// The length in characters is sampled from the length distribution, then every character is sampled from the character distribution.
+/// The length of the string in bytes depends on the characters sampled.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::{Alphanumeric, StringOf, Uniform};
+///
+/// let mut rng = urandom::new();
+/// let distr = StringOf::new(Uniform::from(8..=16), Alphanumeric);
+/// let name = rng.sample(&distr);
+/// assert!(name.len() >= 8 && name.len() <= 16);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct StringOf<L, D> {
+	len_distr: L,
+	char_distr: D,
+}
+
+impl<L, D> StringOf<L, D> {
+	/// Constructs a new `StringOf` distribution from the length and character distributions.
+	#[inline]
+	pub const fn new(len_distr: L, char_distr: D) -> StringOf<L, D> {
+		StringOf { len_distr, char_distr }
+	}
+}
+
+impl<L: Distribution<usize>, D: Distribution<char>> Distribution<String> for StringOf<L, D> {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> String {
+		let len = self.len_distr.sample(rng);
+		let mut string = String::with_capacity(len);
+		for _ in 0..len {
+			string.push(self.char_distr.sample(rng));
+		}
+		string
+	}
+}
+
+/// Sample hash maps with a random number of random entries.
+///
+/// The number of entries is sampled from the length distribution, then every key and value is sampled from the key and value distributions.
+///
+/// When a key is sampled more than once the last value is kept, the map then contains fewer entries than the sampled length.
+/// Key distributions with few distinct keys produce maps smaller than requested, the number of entries never exceeds the number of distinct keys.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::{Alphanumeric, MapOf, Standard, StringOf, Uniform};
+///
+/// let mut rng = urandom::new();
+/// let distr = MapOf::new(Uniform::from(0..50), StringOf::new(Uniform::from(4..8), Alphanumeric), Standard);
+/// let map: std::collections::HashMap<String, u64> = rng.sample(&distr);
+/// assert!(map.len() < 50);
+/// ```
+#[cfg(feature = "std")]
+#[derive(Copy, Clone, Debug)]
+pub struct MapOf<L, K, V> {
+	len_distr: L,
+	key_distr: K,
+	val_distr: V,
+}
+
+#[cfg(feature = "std")]
+impl<L, K, V> MapOf<L, K, V> {
+	/// Constructs a new `MapOf` distribution from the length, key and value distributions.
+	#[inline]
+	pub const fn new(len_distr: L, key_distr: K, val_distr: V) -> MapOf<L, K, V> {
+		MapOf { len_distr, key_distr, val_distr }
+	}
+}
+
+#[cfg(feature = "std")]
+impl<L, KD, VD, K, V> Distribution<std::collections::HashMap<K, V>> for MapOf<L, KD, VD>
+	where L: Distribution<usize>, KD: Distribution<K>, VD: Distribution<V>, K: Eq + core::hash::Hash
+{
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> std::collections::HashMap<K, V> {
+		let len = self.len_distr.sample(rng);
+		let mut map = std::collections::HashMap::with_capacity(len);
+		for _ in 0..len {
+			let key = self.key_distr.sample(rng);
+			let value = self.val_distr.sample(rng);
+			map.insert(key, value);
+		}
+		map
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_vec_of() {
+	use crate::distributions::{Dice, Uniform};
+	let mut rng = crate::seeded(42);
+	let distr = VecOf::new(Uniform::from(0..=20), Dice::new(6));
+	let mut lens = [0; 21];
+	for vec in rng.samples::<Vec<i32>, _>(distr).take(21000) {
+		assert!(vec.iter().all(|&x| x >= 1 && x <= 6));
+		lens[vec.len()] += 1;
+	}
+	// Every length is equally likely
+	assert!(lens.iter().all(|&count| (count - 1000i32).abs() < 150), "{:?}", lens);
+
+	// Element frequencies
+	let vec: Vec<i32> = rng.sample(&VecOf::new(Uniform::from(60000..=60000), Dice::new(6)));
+	assert_eq!(vec.len(), 60000);
+	for face in 1..=6 {
+		let count = vec.iter().filter(|&&x| x == face).count() as i32;
+		assert!((count - 10000).abs() < 400, "face: {} count: {}", face, count);
+	}
+}
+
+#[test]
+fn test_vec_of_edges() {
+	use crate::distributions::{Standard, Uniform};
+	let mut rng = crate::seeded(42);
+	let vec: Vec<u64> = rng.sample(&VecOf::new(Uniform::from(0..=0), Standard));
+	assert!(vec.is_empty());
+
+	let vec: Vec<u8> = rng.sample(&VecOf::new(Uniform::from(1 << 24..=1 << 24), Standard));
+	assert_eq!(vec.len(), 1 << 24);
+	assert!(vec.contains(&0) && vec.contains(&255));
+}
+
+#[test]
+fn test_string_of() {
+	use crate::distributions::{Alphanumeric, Bmp, Uniform};
+	let mut rng = crate::seeded(42);
+	let distr = StringOf::new(Uniform::from(3..6), Alphanumeric);
+	for _ in 0..1000 {
+		let string = rng.sample(&distr);
+		assert!(string.len() >= 3 && string.len() < 6, "{:?}", string);
+		assert!(string.bytes().all(|b| b.is_ascii_alphanumeric()), "{:?}", string);
+	}
+
+	// Length is measured in characters
+	let distr = StringOf::new(Uniform::from(10..=10), Bmp);
+	for _ in 0..100 {
+		let string = rng.sample(&distr);
+		assert_eq!(string.chars().count(), 10);
+	}
+
+	assert_eq!(rng.sample(&StringOf::new(Uniform::from(0..=0), Alphanumeric)), "");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_map_of() {
+	use std::collections::HashMap;
+	use crate::distributions::{Standard, Uniform};
+	let mut rng = crate::seeded(42);
+
+	// Distinct keys are all but guaranteed
+	let distr = MapOf::new(Uniform::from(100..=100), Standard, Uniform::from(0..10));
+	let map: HashMap<u64, u32> = rng.sample(&distr);
+	assert_eq!(map.len(), 100);
+	assert!(map.values().all(|&v| v < 10));
+
+	// Duplicate keys reduce the size
+	let distr = MapOf::new(Uniform::from(1000..=1000), Uniform::from(0..5), Standard);
+	let map: HashMap<u8, u32> = rng.sample(&distr);
+	assert_eq!(map.len(), 5);
+
+	// The last value for a repeated key is kept
+	struct Counter(core::cell::Cell<u32>);
+	impl Distribution<u32> for Counter {
+		fn sample<R: Rng + ?Sized>(&self, _rng: &mut Random<R>) -> u32 {
+			self.0.set(self.0.get() + 1);
+			self.0.get()
+		}
+	}
+	let distr = MapOf::new(Uniform::from(3..=3), Uniform::from(0..=0u32), Counter(core::cell::Cell::new(0)));
+	let map: HashMap<u32, u32> = rng.sample(&distr);
+	assert_eq!(map, HashMap::from([(0, 3)]));
+
+	let map: HashMap<u32, u32> = crate::new().sample(&MapOf::new(Uniform::from(0..=0), Standard, Standard));
+	assert!(map.is_empty());
+}
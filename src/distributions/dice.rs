@@ -12,7 +12,7 @@ use crate::distributions::{UniformInt, UniformSampler};
 /// let sum: i32 = rng.samples(Dice::D6).take(2).sum();
 /// assert!(sum >= 1 && sum <= 12);
 /// ```
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub struct Dice(UniformInt<u8>);
 
 impl Dice {
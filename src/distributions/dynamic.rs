@@ -0,0 +1,376 @@
+use std::fmt;
+use std::string::String;
+use std::vec::Vec;
+use crate::{Distribution, Random, Rng};
+use crate::distributions::{Bernoulli, Float01, UniformFloat, UniformSampler, WeightedIndex};
+use super::gaussian::standard_normal_pair;
+
+/// A distribution of `f64` described by data, eg. loaded from a configuration file.
+///
+/// Data-driven games and simulations describe their randomness in data files such as `{"distr": "normal", "mean": 12, "std_dev": 3}`.
+/// `Dynamic` is the common target for such descriptions: parse the file with any format and build the matching variant.
+///
+/// Call [`build`](Dynamic::build) after loading, it validates the description once and returns a [`DynamicSampler`](DynamicSampler).
+/// The error names the offending field, configuration errors are caught at load time and sampling never panics.
+///
+/// # Sampling
+///
+/// The variants sample as follows, this is guaranteed to never change:
+///
+/// * `Uniform`: [`Random::range(low..high)`](Random::range).
+/// * `Normal`: `mean + std_dev·z` where `z` is a standard normal value from the Marsaglia polar method.
+/// * `LogNormal`: `exp(mu + sigma·z)` with `z` as above.
+/// * `Exp`: `−ln(u) / lambda` where `u` is sampled from [`Float01`](Float01).
+/// * `Bernoulli`: `1.0` with probability `p`, otherwise `0.0`, sampled from [`Bernoulli`](Bernoulli).
+/// * `Constant`: the value, consumes no randomness.
+/// * `Weighted`: picks an entry with [`WeightedIndex`](WeightedIndex) over the weights, then samples the entry.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::Dynamic;
+///
+/// // Eg. parsed from {"distr": "weighted", "entries": [[9, {"distr": "normal", ...}], [1, {"distr": "constant", ...}]]}
+/// let damage = Dynamic::Weighted(vec![
+/// 	(9.0, Dynamic::Normal { mean: 12.0, std_dev: 3.0 }),
+/// 	(1.0, Dynamic::Constant { value: 50.0 }),
+/// ]);
+/// assert_eq!(damage.to_string(), "weighted(9: normal(mean: 12, std_dev: 3), 1: constant(value: 50))");
+/// let sampler = damage.build().unwrap();
+///
+/// let mut rng = urandom::new();
+/// let value = rng.sample(&sampler);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum Dynamic {
+	/// Uniform in the half-open interval `[low, high)`.
+	Uniform {
+		/// The low bound, finite.
+		low: f64,
+		/// The high bound, finite and greater than `low`.
+		high: f64,
+	},
+	/// The normal distribution.
+	Normal {
+		/// The mean, finite.
+		mean: f64,
+		/// The standard deviation, non-negative and finite.
+		std_dev: f64,
+	},
+	/// The log-normal distribution, the exponential of a normal distribution.
+	LogNormal {
+		/// The mean of the logarithm, finite.
+		mu: f64,
+		/// The standard deviation of the logarithm, non-negative and finite.
+		sigma: f64,
+	},
+	/// The exponential distribution.
+	Exp {
+		/// The rate, positive and finite.
+		lambda: f64,
+	},
+	/// The Bernoulli distribution as `0.0` or `1.0`.
+	Bernoulli {
+		/// The probability of `1.0`, between zero and one.
+		p: f64,
+	},
+	/// Always the same value.
+	Constant {
+		/// The value, finite.
+		value: f64,
+	},
+	/// A weighted mixture of distributions.
+	///
+	/// The weights are non-negative and finite with a positive sum.
+	Weighted(Vec<(f64, Dynamic)>),
+}
+
+/// Error type returned from [`Dynamic::validate`](Dynamic::validate) and [`Dynamic::build`](Dynamic::build).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DynamicError {
+	/// Path to the invalid distribution, eg. `weighted[1]`, empty for the top level.
+	pub path: String,
+	/// Name of the invalid field.
+	pub field: &'static str,
+	/// The requirement which the field does not meet.
+	pub reason: &'static str,
+}
+
+impl fmt::Display for DynamicError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if self.path.is_empty() {
+			write!(f, "invalid field `{}` in Dynamic distribution: {}", self.field, self.reason)
+		}
+		else {
+			write!(f, "invalid field `{}` at {} in Dynamic distribution: {}", self.field, self.path, self.reason)
+		}
+	}
+}
+
+impl std::error::Error for DynamicError {}
+
+const FINITE: &str = "must be finite";
+const NON_NEGATIVE: &str = "must be non-negative and finite";
+
+/// Prepared sampler of a [`Dynamic`](Dynamic) distribution, returned from [`Dynamic::build`](Dynamic::build).
+///
+/// The samplers of the variants are constructed once, sampling does not validate or allocate.
+#[derive(Clone, Debug)]
+pub struct DynamicSampler {
+	inner: Prepared,
+}
+
+#[derive(Clone, Debug)]
+enum Prepared {
+	Uniform(UniformFloat<f64>),
+	Normal { mean: f64, std_dev: f64 },
+	LogNormal { mu: f64, sigma: f64 },
+	Exp { lambda: f64 },
+	Bernoulli(Bernoulli),
+	Constant(f64),
+	Weighted(WeightedIndex<f64>, Vec<DynamicSampler>),
+}
+
+impl Dynamic {
+	/// Validates the distribution and its nested distributions and constructs their samplers.
+	///
+	/// Returns an error naming the first invalid field, see [`validate`](Dynamic::validate).
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use urandom::distributions::Dynamic;
+	///
+	/// let sampler = Dynamic::Exp { lambda: 2.0 }.build().unwrap();
+	/// let value = urandom::new().sample(&sampler);
+	/// assert!(value >= 0.0);
+	///
+	/// assert!(Dynamic::Uniform { low: 1.0, high: 0.0 }.build().is_err());
+	/// ```
+	pub fn build(&self) -> Result<DynamicSampler, DynamicError> {
+		self.validate()?;
+		Ok(self.prepare())
+	}
+
+	// Constructs the samplers of a validated distribution
+	fn prepare(&self) -> DynamicSampler {
+		let inner = match *self {
+			Dynamic::Uniform { low, high } => Prepared::Uniform(UniformFloat::new(low, high)),
+			Dynamic::Normal { mean, std_dev } => Prepared::Normal { mean, std_dev },
+			Dynamic::LogNormal { mu, sigma } => Prepared::LogNormal { mu, sigma },
+			Dynamic::Exp { lambda } => Prepared::Exp { lambda },
+			Dynamic::Bernoulli { p } => Prepared::Bernoulli(Bernoulli::new(p)),
+			Dynamic::Constant { value } => Prepared::Constant(value),
+			Dynamic::Weighted(ref entries) => {
+				let index = WeightedIndex::new(entries.iter().map(|&(weight, _)| weight));
+				Prepared::Weighted(index, entries.iter().map(|(_, distr)| distr.prepare()).collect())
+			}
+		};
+		DynamicSampler { inner }
+	}
+
+	/// Checks the parameters of the distribution and its nested distributions.
+	///
+	/// Returns an error naming the first invalid field.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use urandom::distributions::Dynamic;
+	///
+	/// let distr = Dynamic::Weighted(vec![(1.0, Dynamic::Exp { lambda: 0.0 })]);
+	/// let err = distr.validate().unwrap_err();
+	/// assert_eq!((err.path.as_str(), err.field), ("weighted[0]", "lambda"));
+	/// ```
+	pub fn validate(&self) -> Result<(), DynamicError> {
+		if let Err((field, reason)) = self.check() {
+			return Err(DynamicError { path: String::new(), field, reason });
+		}
+		if let Dynamic::Weighted(entries) = self {
+			for (i, (_, distr)) in entries.iter().enumerate() {
+				if let Err(mut err) = distr.validate() {
+					err.path = if err.path.is_empty() { format!("weighted[{}]", i) } else { format!("weighted[{}].{}", i, err.path) };
+					return Err(err);
+				}
+			}
+		}
+		Ok(())
+	}
+
+	// Checks the parameters of this distribution without the nested distributions
+	fn check(&self) -> Result<(), (&'static str, &'static str)> {
+		match *self {
+			Dynamic::Uniform { low, high } => {
+				if !low.is_finite() {
+					return Err(("low", FINITE));
+				}
+				if !(high.is_finite() && high > low) {
+					return Err(("high", "must be finite and greater than low"));
+				}
+			}
+			Dynamic::Normal { mean, std_dev } => {
+				if !mean.is_finite() {
+					return Err(("mean", FINITE));
+				}
+				if !(std_dev >= 0.0 && std_dev.is_finite()) {
+					return Err(("std_dev", NON_NEGATIVE));
+				}
+			}
+			Dynamic::LogNormal { mu, sigma } => {
+				if !mu.is_finite() {
+					return Err(("mu", FINITE));
+				}
+				if !(sigma >= 0.0 && sigma.is_finite()) {
+					return Err(("sigma", NON_NEGATIVE));
+				}
+			}
+			Dynamic::Exp { lambda } => {
+				if !(lambda > 0.0 && lambda.is_finite()) {
+					return Err(("lambda", "must be positive and finite"));
+				}
+			}
+			Dynamic::Bernoulli { p } => {
+				if !(p >= 0.0 && p <= 1.0) {
+					return Err(("p", "must be between zero and one"));
+				}
+			}
+			Dynamic::Constant { value } => {
+				if !value.is_finite() {
+					return Err(("value", FINITE));
+				}
+			}
+			Dynamic::Weighted(ref entries) => {
+				let mut total = 0.0;
+				for &(weight, _) in entries {
+					if !(weight >= 0.0 && weight.is_finite()) {
+						return Err(("weight", NON_NEGATIVE));
+					}
+					total += weight;
+				}
+				if !(total > 0.0 && total.is_finite()) {
+					return Err(("weight", "the sum must be positive and finite"));
+				}
+			}
+		}
+		Ok(())
+	}
+}
+
+impl Distribution<f64> for DynamicSampler {
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> f64 {
+		match self.inner {
+			Prepared::Uniform(ref distr) => distr.sample(rng),
+			Prepared::Normal { mean, std_dev } => mean + std_dev * standard_normal_pair(rng).0,
+			Prepared::LogNormal { mu, sigma } => f64::exp(mu + sigma * standard_normal_pair(rng).0),
+			Prepared::Exp { lambda } => {
+				let u: f64 = Float01.sample(rng);
+				-u.ln() / lambda
+			}
+			Prepared::Bernoulli(ref distr) => if distr.sample(rng) { 1.0 } else { 0.0 },
+			Prepared::Constant(value) => value,
+			Prepared::Weighted(ref index, ref entries) => entries[index.sample(rng)].sample(rng),
+		}
+	}
+}
+
+impl fmt::Display for Dynamic {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Dynamic::Uniform { low, high } => write!(f, "uniform(low: {}, high: {})", low, high),
+			Dynamic::Normal { mean, std_dev } => write!(f, "normal(mean: {}, std_dev: {})", mean, std_dev),
+			Dynamic::LogNormal { mu, sigma } => write!(f, "log_normal(mu: {}, sigma: {})", mu, sigma),
+			Dynamic::Exp { lambda } => write!(f, "exp(lambda: {})", lambda),
+			Dynamic::Bernoulli { p } => write!(f, "bernoulli(p: {})", p),
+			Dynamic::Constant { value } => write!(f, "constant(value: {})", value),
+			Dynamic::Weighted(entries) => {
+				f.write_str("weighted(")?;
+				for (i, (weight, distr)) in entries.iter().enumerate() {
+					if i > 0 {
+						f.write_str(", ")?;
+					}
+					write!(f, "{}: {}", weight, distr)?;
+				}
+				f.write_str(")")
+			}
+		}
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_matches_direct() {
+	let cases = [
+		Dynamic::Uniform { low: -2.0, high: 5.0 },
+		Dynamic::Normal { mean: 12.0, std_dev: 3.0 },
+		Dynamic::LogNormal { mu: 0.5, sigma: 0.25 },
+		Dynamic::Exp { lambda: 2.0 },
+		Dynamic::Bernoulli { p: 0.3 },
+		Dynamic::Constant { value: 7.0 },
+	];
+	for distr in &cases {
+		let sampler = distr.build().unwrap();
+		let mut a = crate::seeded(42);
+		let mut b = crate::seeded(42);
+		for _ in 0..100 {
+			let expected = match *distr {
+				Dynamic::Uniform { low, high } => b.range(low..high),
+				Dynamic::Normal { mean, std_dev } => mean + std_dev * standard_normal_pair(&mut b).0,
+				Dynamic::LogNormal { mu, sigma } => (mu + sigma * standard_normal_pair(&mut b).0).exp(),
+				Dynamic::Exp { lambda } => -b.sample::<f64, _>(&Float01).ln() / lambda,
+				Dynamic::Bernoulli { p } => b.sample(&Bernoulli::new(p)) as i32 as f64,
+				Dynamic::Constant { value } => value,
+				Dynamic::Weighted(_) => unreachable!(),
+			};
+			assert_eq!(a.sample(&sampler), expected, "{}", distr);
+		}
+	}
+}
+
+#[test]
+fn test_weighted() {
+	let distr = Dynamic::Weighted(vec![
+		(1.0, Dynamic::Constant { value: 0.0 }),
+		(0.0, Dynamic::Constant { value: 1.0 }),
+		(3.0, Dynamic::Constant { value: 2.0 }),
+	]);
+	let sampler = distr.build().unwrap();
+	let mut rng = crate::seeded(42);
+	let mut counts = [0u64; 3];
+	for _ in 0..40000 {
+		counts[rng.sample(&sampler) as usize] += 1;
+	}
+	assert_eq!(counts[1], 0);
+	crate::stats::assert_counts(&counts, &[1.0, 0.0, 3.0], 1e-4);
+
+	// Nested mixtures
+	let nested = Dynamic::Weighted(vec![(1.0, distr), (1.0, Dynamic::Uniform { low: 10.0, high: 11.0 })]);
+	let sampler = nested.build().unwrap();
+	let high = (0..1000).filter(|_| rng.sample(&sampler) >= 10.0).count();
+	assert!(high > 400 && high < 600, "high: {}", high);
+}
+
+#[test]
+fn test_validate() {
+	let check = |distr: Dynamic, path: &str, field: &str| {
+		let err = distr.validate().unwrap_err();
+		assert_eq!((err.path.as_str(), err.field), (path, field), "{}", err);
+		assert_eq!(distr.build().unwrap_err(), err);
+	};
+	check(Dynamic::Uniform { low: 1.0, high: 1.0 }, "", "high");
+	check(Dynamic::Uniform { low: f64::NEG_INFINITY, high: 1.0 }, "", "low");
+	check(Dynamic::Normal { mean: 0.0, std_dev: -1.0 }, "", "std_dev");
+	check(Dynamic::Normal { mean: f64::NAN, std_dev: 1.0 }, "", "mean");
+	check(Dynamic::LogNormal { mu: 0.0, sigma: f64::INFINITY }, "", "sigma");
+	check(Dynamic::Exp { lambda: 0.0 }, "", "lambda");
+	check(Dynamic::Bernoulli { p: 1.5 }, "", "p");
+	check(Dynamic::Constant { value: f64::NAN }, "", "value");
+	check(Dynamic::Weighted(vec![]), "", "weight");
+	check(Dynamic::Weighted(vec![(-1.0, Dynamic::Constant { value: 0.0 })]), "", "weight");
+	let nested = Dynamic::Weighted(vec![
+		(1.0, Dynamic::Constant { value: 0.0 }),
+		(1.0, Dynamic::Weighted(vec![(1.0, Dynamic::Normal { mean: 0.0, std_dev: -3.0 })])),
+	]);
+	check(nested.clone(), "weighted[1].weighted[0]", "std_dev");
+	assert_eq!(nested.validate().unwrap_err().to_string(), "invalid field `std_dev` at weighted[1].weighted[0] in Dynamic distribution: must be non-negative and finite");
+}
@@ -0,0 +1,201 @@
+use core::fmt;
+use alloc::vec::Vec;
+use crate::{Distribution, Random, Rng};
+use crate::distributions::Float01;
+
+/// Empirical distribution of observed data.
+///
+/// Samples mimic a recorded dataset, eg. to generate synthetic load resembling recorded latencies.
+/// The observations are sorted and stored on construction.
+///
+/// * [`new`](Empirical::new) constructs the discrete bootstrap distribution: samples are the observations picked uniformly at random.
+/// * [`interpolated`](Empirical::interpolated) constructs a smoothed distribution: samples are linearly interpolated between adjacent order statistics.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::Empirical;
+///
+/// let latencies = [12.0, 15.0, 11.0, 230.0, 14.0, 13.0];
+/// let distr = Empirical::interpolated(&latencies);
+///
+/// let mut rng = urandom::new();
+/// let value = rng.sample(&distr);
+/// assert!(value >= distr.min() && value <= distr.max());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Empirical {
+	data: Vec<f64>,
+	interpolate: bool,
+}
+
+/// Error type returned from [`Empirical`](Empirical) constructors.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum EmpiricalError {
+	/// There are no observations.
+	Empty,
+	/// An observation is NaN or infinite.
+	NotFinite,
+}
+
+impl fmt::Display for EmpiricalError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match self {
+			EmpiricalError::Empty => "empirical distribution has no observations",
+			EmpiricalError::NotFinite => "empirical distribution contains an observation which is not finite",
+		})
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EmpiricalError {}
+
+#[allow(clippy::len_without_is_empty)]
+impl Empirical {
+	/// Constructs the discrete bootstrap distribution of the observations.
+	///
+	/// # Panics
+	///
+	/// Panics if the observations are invalid, see [`try_new`](Empirical::try_new).
+	#[inline]
+	pub fn new(samples: &[f64]) -> Empirical {
+		match Empirical::try_new(samples) {
+			Ok(distr) => distr,
+			Err(err) => empirical_error(err),
+		}
+	}
+
+	/// Constructs the discrete bootstrap distribution of the observations.
+	///
+	/// Returns an error if there are no observations or if any observation is not finite.
+	#[inline]
+	pub fn try_new(samples: &[f64]) -> Result<Empirical, EmpiricalError> {
+		Empirical::from_samples(samples, false)
+	}
+
+	/// Constructs the linearly interpolated distribution of the observations.
+	///
+	/// # Panics
+	///
+	/// Panics if the observations are invalid, see [`try_new`](Empirical::try_new).
+	#[inline]
+	pub fn interpolated(samples: &[f64]) -> Empirical {
+		match Empirical::try_interpolated(samples) {
+			Ok(distr) => distr,
+			Err(err) => empirical_error(err),
+		}
+	}
+
+	/// Constructs the linearly interpolated distribution of the observations.
+	///
+	/// Returns an error if there are no observations or if any observation is not finite.
+	#[inline]
+	pub fn try_interpolated(samples: &[f64]) -> Result<Empirical, EmpiricalError> {
+		Empirical::from_samples(samples, true)
+	}
+
+	fn from_samples(samples: &[f64], interpolate: bool) -> Result<Empirical, EmpiricalError> {
+		if samples.is_empty() {
+			return Err(EmpiricalError::Empty);
+		}
+		if !samples.iter().all(|value| value.is_finite()) {
+			return Err(EmpiricalError::NotFinite);
+		}
+		let mut data = samples.to_vec();
+		data.sort_unstable_by(f64::total_cmp);
+		Ok(Empirical { data, interpolate })
+	}
+
+	/// Returns the smallest observation.
+	#[inline]
+	pub fn min(&self) -> f64 {
+		self.data[0]
+	}
+
+	/// Returns the largest observation.
+	#[inline]
+	pub fn max(&self) -> f64 {
+		self.data[self.data.len() - 1]
+	}
+
+	/// Returns the number of observations.
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.data.len()
+	}
+}
+
+impl Distribution<f64> for Empirical {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> f64 {
+		if !self.interpolate || self.data.len() == 1 {
+			return self.data[rng.index(self.data.len())];
+		}
+		// Float01 excludes 1.0 so the upper order statistic is always inbounds
+		let u: f64 = Float01.sample(rng);
+		let position = u * (self.data.len() - 1) as f64;
+		let index = position as usize;
+		let (x0, x1) = (self.data[index], self.data[index + 1]);
+		f64::min(x0 + (x1 - x0) * (position - index as f64), x1)
+	}
+}
+
+#[cold]
+fn empirical_error(err: EmpiricalError) -> ! {
+	panic!("Empirical called with invalid observations: {}", err)
+}
+
+//----------------------------------------------------------------
+
+#[cfg(test)]
+fn bimodal(rng: &mut Random<impl Rng>, n: usize) -> Vec<f64> {
+	(0..n).map(|i| if i % 3 == 0 { rng.range(0.0..1.0) } else { rng.range(5.0..6.0) }).collect()
+}
+
+#[test]
+fn test_discrete() {
+	let mut rng = crate::seeded(42);
+	let data = [3.0, -1.0, 3.0, 7.5, 0.25];
+	let distr = Empirical::new(&data);
+	assert_eq!(distr.len(), 5);
+	assert_eq!(distr.min(), -1.0);
+	assert_eq!(distr.max(), 7.5);
+	for _ in 0..1000 {
+		let value = rng.sample(&distr);
+		assert!(data.contains(&value), "value: {}", value);
+	}
+}
+
+#[test]
+fn test_interpolated() {
+	let mut rng = crate::seeded(42);
+	let data = bimodal(&mut rng, 100);
+	let distr = Empirical::interpolated(&data);
+	for _ in 0..10000 {
+		let value = rng.sample(&distr);
+		assert!(value >= distr.min() && value <= distr.max(), "value: {}", value);
+	}
+	let single = Empirical::interpolated(&[4.0]);
+	assert_eq!(rng.sample(&single), 4.0);
+}
+
+#[test]
+fn test_cdf() {
+	let mut rng = crate::seeded(42);
+	let data = bimodal(&mut rng, 1000);
+	let cdf = |values: &[f64], x: f64| values.iter().filter(|&&value| value <= x).count() as f64 / values.len() as f64;
+	for distr in [Empirical::new(&data), Empirical::interpolated(&data)] {
+		let samples: Vec<f64> = (0..20000).map(|_| rng.sample(&distr)).collect();
+		// Kolmogorov–Smirnov statistic evaluated on a grid
+		let ks = (0..=120).map(|i| i as f64 * 0.05).map(|x| (cdf(&samples, x) - cdf(&data, x)).abs()).fold(0.0, f64::max);
+		assert!(ks < 0.02, "ks: {}", ks);
+	}
+}
+
+#[test]
+fn test_validation() {
+	assert_eq!(Empirical::try_new(&[]).unwrap_err(), EmpiricalError::Empty);
+	assert_eq!(Empirical::try_interpolated(&[]).unwrap_err(), EmpiricalError::Empty);
+	assert_eq!(Empirical::try_new(&[1.0, f64::NAN]).unwrap_err(), EmpiricalError::NotFinite);
+	assert_eq!(Empirical::try_interpolated(&[f64::INFINITY]).unwrap_err(), EmpiricalError::NotFinite);
+}
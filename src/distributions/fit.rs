@@ -0,0 +1,128 @@
+/*!
+Method-of-moments parameter fitting.
+
+These estimators compute the parameters of a distribution from observed samples.
+They are useful to sanity check generated data and to round-trip distributions in statistical tests.
+
+# Examples
+
+```
+use urandom::distributions::{fit, Laplace};
+
+let mut rng = urandom::seeded(42);
+let samples: Vec<f64> = rng.samples(Laplace::new(3.0, 2.0)).take(10000).collect();
+
+let fitted = fit::laplace(&samples).unwrap();
+assert!((fitted.location() - 3.0).abs() < 0.1);
+assert!((fitted.scale() - 2.0).abs() < 0.1);
+```
+*/
+
+use core::fmt;
+use crate::distributions::{Laplace, Uniform};
+
+/// Error type returned from the fitting functions.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum FitError {
+	/// There are no samples.
+	Empty,
+	/// A sample is NaN or infinite.
+	NotFinite,
+	/// The samples have no spread, the fitted distribution would be degenerate.
+	Degenerate,
+}
+
+impl fmt::Display for FitError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match self {
+			FitError::Empty => "cannot fit a distribution to no samples",
+			FitError::NotFinite => "cannot fit a distribution to samples which are not finite",
+			FitError::Degenerate => "cannot fit a distribution to samples without spread",
+		})
+	}
+}
+
+impl std::error::Error for FitError {}
+
+/// Returns the sample mean and the (biased) sample variance.
+///
+/// Returns an error if there are no samples or any sample is not finite.
+pub fn moments(samples: &[f64]) -> Result<(f64, f64), FitError> {
+	if samples.is_empty() {
+		return Err(FitError::Empty);
+	}
+	if !samples.iter().all(|value| value.is_finite()) {
+		return Err(FitError::NotFinite);
+	}
+	let n = samples.len() as f64;
+	let mean = samples.iter().sum::<f64>() / n;
+	// Two-pass algorithm for numerical stability
+	let variance = samples.iter().map(|&value| (value - mean) * (value - mean)).sum::<f64>() / n;
+	Ok((mean, variance))
+}
+
+/// Fits a uniform distribution to the samples.
+///
+/// The bounds are the smallest and largest sample.
+///
+/// Returns an error if there are no samples, any sample is not finite or all samples are equal.
+pub fn uniform(samples: &[f64]) -> Result<Uniform<f64>, FitError> {
+	if samples.is_empty() {
+		return Err(FitError::Empty);
+	}
+	if !samples.iter().all(|value| value.is_finite()) {
+		return Err(FitError::NotFinite);
+	}
+	let min = samples.iter().copied().fold(f64::INFINITY, f64::min);
+	let max = samples.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+	if min >= max {
+		return Err(FitError::Degenerate);
+	}
+	Ok(Uniform::from(min..max))
+}
+
+/// Fits a Laplace distribution to the samples.
+///
+/// The location is the sample mean and the scale is derived from the sample variance `2b²`.
+///
+/// Returns an error if there are no samples, any sample is not finite or all samples are equal.
+pub fn laplace(samples: &[f64]) -> Result<Laplace, FitError> {
+	let (mean, variance) = moments(samples)?;
+	Laplace::try_new(mean, (variance / 2.0).sqrt()).map_err(|_| FitError::Degenerate)
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_uniform() {
+	let mut rng = crate::seeded(42);
+	let samples: Vec<f64> = (0..10000).map(|_| rng.range(-2.0..5.0)).collect();
+	let distr = uniform(&samples).unwrap();
+	let (mean, variance) = moments(&samples).unwrap();
+	assert!((mean - 1.5).abs() < 0.1, "mean: {}", mean);
+	assert!((variance - 49.0 / 12.0).abs() < 0.1, "variance: {}", variance);
+	for _ in 0..1000 {
+		let value: f64 = rng.sample(&distr);
+		assert!(value >= -2.0 && value < 5.0, "value: {}", value);
+	}
+}
+
+#[test]
+fn test_laplace() {
+	let mut rng = crate::seeded(42);
+	let samples: Vec<f64> = rng.samples(Laplace::new(-1.0, 0.5)).take(100000).collect();
+	let distr = laplace(&samples).unwrap();
+	assert!((distr.location() + 1.0).abs() < 0.01, "location: {}", distr.location());
+	assert!((distr.scale() - 0.5).abs() < 0.01, "scale: {}", distr.scale());
+}
+
+#[test]
+fn test_errors() {
+	assert_eq!(moments(&[]).unwrap_err(), FitError::Empty);
+	assert_eq!(uniform(&[]).unwrap_err(), FitError::Empty);
+	assert_eq!(laplace(&[]).unwrap_err(), FitError::Empty);
+	assert_eq!(moments(&[1.0, f64::NAN]).unwrap_err(), FitError::NotFinite);
+	assert_eq!(uniform(&[f64::INFINITY, 1.0]).unwrap_err(), FitError::NotFinite);
+	assert_eq!(uniform(&[3.0, 3.0]).unwrap_err(), FitError::Degenerate);
+	assert_eq!(laplace(&[3.0]).unwrap_err(), FitError::Degenerate);
+}
@@ -25,7 +25,12 @@ use crate::{Distribution, Rng, Random};
 /// There is a small bias in case the Rng outputs all zeros but in practice this should never happen unless your PRNG is broken.
 ///
 /// The result is two calls to the Rng, one for generating 64 bits worth of coin flips and one for generating the mantissa of the resulting float.
-#[derive(Copy, Clone, Debug)]
+///
+/// The smallest exponent reachable this way is `-65` which keeps the result well clear of the subnormal range for `f32` and `f64`.
+///
+/// With the `half` feature `half::f16` is supported as well. Its exponent range is too narrow to reach with 64 coin flips,
+/// values which would fall in the subnormal range of `f16` are rejected and resampled.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub struct Float01;
 
 impl Distribution<f32> for Float01 {
@@ -45,6 +50,21 @@ impl Distribution<f64> for Float01 {
 	}
 }
 
+#[cfg(feature = "half")]
+impl Distribution<half::f16> for Float01 {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> half::f16 {
+		loop {
+			let zeros = rng.next_u32().leading_zeros() as u16;
+			if zeros < 14 {
+				let exp = 0b0_01110 - zeros;
+				let mantissa = (rng.next_u32() >> 22) as u16;
+				break half::f16::from_bits(exp << 10 | mantissa);
+			}
+		}
+	}
+}
+
 #[test]
 fn test_yolo() {
 	for float in crate::new().samples(Float01).take(1000) {
@@ -61,3 +81,18 @@ fn test_edges() {
 	assert!(low > 0.0 && low < 1.0, "double({}) bits({:#x})", low, low.to_bits());
 	assert!(high > 0.0 && high < 1.0, "double({}) bits({:#x})", high, high.to_bits());
 }
+
+#[cfg(feature = "half")]
+#[test]
+fn test_f16() {
+	use half::f16;
+	for float in crate::new().samples(Float01).take(10000) {
+		let float: f16 = float;
+		assert!(float > f16::ZERO && float < f16::ONE && float.is_normal(), "float({}) bits({:#x})", float, float.to_bits());
+	}
+	let mut rng = crate::rng::MockRng::slice(&[0, 1 << 18, 0, !0, !0]);
+	let low: f16 = rng.sample(&Float01);
+	let high: f16 = rng.sample(&Float01);
+	assert_eq!(low, f16::MIN_POSITIVE);
+	assert_eq!(high, f16::ONE - f16::EPSILON / f16::from_f32(2.0));
+}
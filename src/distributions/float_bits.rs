@@ -0,0 +1,265 @@
+use core::fmt;
+use crate::{Distribution, Random, Rng};
+use crate::distributions::{UniformInt, UniformSampler};
+
+/// Sample floats uniformly over their representable values.
+///
+/// Every distinct float in `[low, high]` is equally likely, unlike [`UniformFloat`](super::UniformFloat) which is uniform over the real interval.
+/// Most samples have a small magnitude: half of the floats in `[0.0, 1.0]` are smaller than `2^-511` for `f64`.
+///
+/// Useful for numerical robustness fuzzing where exponent boundaries and subnormals must be exercised,
+/// real uniform sampling practically never produces them.
+///
+/// # Special values
+///
+/// * NaN is never sampled and not accepted as a bound.
+/// * Infinities are sampled only if given as a bound, they are the extreme values of the order.
+/// * `-0.0` and `+0.0` are distinct adjacent values, `-0.0` orders before `+0.0`.
+///   A range which contains zero samples both, `[-0.0, 0.0]` samples exactly these two values.
+///
+/// # Implementation notes
+///
+/// The floats are mapped to unsigned integers of the same size which preserve the order of the floats:
+/// positive floats set the sign bit and negative floats invert all their bits.
+/// An integer is sampled with [`UniformInt`](UniformInt) between the mapped bounds and mapped back.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::FloatBits;
+///
+/// let mut rng = urandom::new();
+/// let distr = FloatBits::new(-1.0f64, 1.0);
+/// let value = rng.sample(&distr);
+/// assert!(value >= -1.0 && value <= 1.0);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FloatBits<T: SampleFloatBits> {
+	low: T,
+	high: T,
+	index: UniformInt<T::Bits>,
+}
+
+/// Error type returned from [`FloatBits::try_new`](FloatBits::try_new).
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum FloatBitsError {
+	/// A bound is NaN.
+	NaN,
+	/// The low bound orders after the high bound.
+	EmptyRange,
+}
+
+impl fmt::Display for FloatBitsError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match self {
+			FloatBitsError::NaN => "bound is NaN in FloatBits distribution",
+			FloatBitsError::EmptyRange => "low is greater than high in FloatBits distribution",
+		})
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FloatBitsError {}
+
+/// Float types which can be sampled from the [`FloatBits`](FloatBits) distribution.
+pub trait SampleFloatBits: Copy + fmt::Debug {
+	/// The unsigned integer type of the same size.
+	type Bits: Copy + Ord + fmt::Debug;
+	/// Maps the float to an unsigned integer with the same total order.
+	fn to_ordered(self) -> Self::Bits;
+	/// Maps the integer back to the float, the inverse of [`to_ordered`](SampleFloatBits::to_ordered).
+	fn from_ordered(bits: Self::Bits) -> Self;
+	/// Returns true if the float is NaN.
+	fn is_nan(self) -> bool;
+}
+
+macro_rules! impl_sample_float_bits {
+	($ty:ty, $bits:ty) => {
+		impl SampleFloatBits for $ty {
+			type Bits = $bits;
+			#[inline]
+			fn to_ordered(self) -> $bits {
+				const SIGN: $bits = 1 << (<$bits>::BITS - 1);
+				let bits = self.to_bits();
+				if bits & SIGN != 0 { !bits } else { bits | SIGN }
+			}
+			#[inline]
+			fn from_ordered(bits: $bits) -> $ty {
+				const SIGN: $bits = 1 << (<$bits>::BITS - 1);
+				<$ty>::from_bits(if bits & SIGN != 0 { bits ^ SIGN } else { !bits })
+			}
+			#[inline]
+			fn is_nan(self) -> bool {
+				<$ty>::is_nan(self)
+			}
+		}
+	};
+}
+
+impl_sample_float_bits!(f32, u32);
+impl_sample_float_bits!(f64, u64);
+
+impl<T: SampleFloatBits> FloatBits<T> where UniformInt<T::Bits>: UniformSampler<T::Bits> {
+	/// Constructs a new `FloatBits` distribution over the floats in `[low, high]`.
+	///
+	/// # Panics
+	///
+	/// Panics if a bound is NaN or `low` orders after `high`.
+	#[inline]
+	pub fn new(low: T, high: T) -> FloatBits<T> {
+		match FloatBits::try_new(low, high) {
+			Ok(distr) => distr,
+			Err(err) => float_bits_error(err),
+		}
+	}
+	/// Constructs a new `FloatBits` distribution over the floats in `[low, high]`.
+	///
+	/// Returns an error if a bound is NaN or `low` orders after `high`, note that `0.0` orders after `-0.0`.
+	#[inline]
+	pub fn try_new(low: T, high: T) -> Result<FloatBits<T>, FloatBitsError> {
+		if low.is_nan() || high.is_nan() {
+			return Err(FloatBitsError::NaN);
+		}
+		let (start, end) = (low.to_ordered(), high.to_ordered());
+		if start > end {
+			return Err(FloatBitsError::EmptyRange);
+		}
+		let index = UniformInt::new_inclusive(start, end);
+		Ok(FloatBits { low, high, index })
+	}
+	/// Returns the low bound.
+	#[inline]
+	pub fn low(&self) -> T {
+		self.low
+	}
+	/// Returns the high bound.
+	#[inline]
+	pub fn high(&self) -> T {
+		self.high
+	}
+}
+
+#[cold]
+fn float_bits_error(err: FloatBitsError) -> ! {
+	panic!("{}", err)
+}
+
+impl<T: SampleFloatBits> Distribution<T> for FloatBits<T> where UniformInt<T::Bits>: Distribution<T::Bits> {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> T {
+		T::from_ordered(self.index.sample(rng))
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_ordered() {
+	// Round-trips and preserves the order over a sample of all f32 bit patterns
+	let mut prev: Option<f32> = None;
+	for i in 0..=u32::MAX / 65521 {
+		let bits = i * 65521;
+		let ordered = f32::from_ordered(bits);
+		assert_eq!(ordered.to_ordered(), bits);
+		let value = f32::from_bits(bits);
+		assert_eq!(f32::from_ordered(value.to_ordered()).to_bits(), bits);
+		if !ordered.is_nan() {
+			if let Some(prev) = prev {
+				assert!(prev < ordered || prev == 0.0 && ordered == 0.0, "{} {}", prev, ordered);
+			}
+			prev = Some(ordered);
+		}
+	}
+
+	// Neighbors across the sign and exponent boundaries
+	assert_eq!((-0.0f64).to_ordered() + 1, 0.0f64.to_ordered());
+	assert_eq!(f64::from_ordered(0.0f64.to_ordered() + 1), f64::from_bits(1));
+	assert_eq!(f64::from_ordered(1.0f64.to_ordered() - 1), 1.0 - f64::EPSILON / 2.0);
+	assert_eq!(f64::NEG_INFINITY.to_ordered() + 1, f64::MIN.to_ordered());
+	assert_eq!(f64::INFINITY.to_ordered() - 1, f64::MAX.to_ordered());
+}
+
+#[test]
+fn test_bounds() {
+	let mut rng = crate::seeded(42);
+	let distr = FloatBits::new(-1.0e10f64, 3.0);
+	for _ in 0..10000 {
+		let value = rng.sample(&distr);
+		assert!(value.is_finite() && value >= -1.0e10 && value <= 3.0, "{}", value);
+	}
+	let distr = FloatBits::new(f32::MIN, f32::MAX);
+	for _ in 0..10000 {
+		let value = rng.sample(&distr);
+		assert!(value.is_finite());
+	}
+
+	// Infinities only when given as bounds
+	let distr = FloatBits::new(f32::NEG_INFINITY, f32::INFINITY);
+	for _ in 0..10000 {
+		assert!(!rng.sample(&distr).is_nan());
+	}
+	let distr = FloatBits::new(f64::MAX, f64::INFINITY);
+	let mut infinite = 0;
+	for _ in 0..1000 {
+		let value = rng.sample(&distr);
+		assert!(value == f64::MAX || value == f64::INFINITY);
+		infinite += value.is_infinite() as i32;
+	}
+	assert!(infinite > 400 && infinite < 600, "{}", infinite);
+
+	// Both zeros are reachable
+	let distr = FloatBits::new(-0.0f64, 0.0);
+	let mut negative = 0;
+	for _ in 0..1000 {
+		let value = rng.sample(&distr);
+		assert_eq!(value, 0.0);
+		negative += value.is_sign_negative() as i32;
+	}
+	assert!(negative > 400 && negative < 600, "{}", negative);
+
+	assert_eq!(FloatBits::try_new(f64::NAN, 1.0), Err(FloatBitsError::NaN));
+	assert_eq!(FloatBits::try_new(0.0f32, f32::NAN), Err(FloatBitsError::NaN));
+	assert_eq!(FloatBits::try_new(1.0f64, -1.0), Err(FloatBitsError::EmptyRange));
+	assert_eq!(FloatBits::try_new(0.0f64, -0.0), Err(FloatBitsError::EmptyRange));
+	assert_eq!(FloatBits::new(2.0f32, 2.0).low(), 2.0);
+}
+
+#[test]
+fn test_neighbors() {
+	let mut rng = crate::seeded(42);
+	let below = 1.0 - f64::EPSILON / 2.0;
+	let above = 1.0 + f64::EPSILON;
+	let distr = FloatBits::new(below, above);
+	let mut counts = [0; 3];
+	for _ in 0..3000 {
+		let value = rng.sample(&distr);
+		let index = [below, 1.0, above].iter().position(|&x| x == value).unwrap();
+		counts[index] += 1;
+	}
+	for &count in &counts {
+		assert!(count > 800 && count < 1200, "{:?}", counts);
+	}
+}
+
+#[test]
+fn test_subnormal() {
+	let mut rng = crate::seeded(42);
+	let distr = FloatBits::new(-f32::MIN_POSITIVE, f32::MIN_POSITIVE);
+	let mut subnormal = 0;
+	for _ in 0..10000 {
+		let value: f32 = rng.sample(&distr);
+		assert!(value.abs() <= f32::MIN_POSITIVE);
+		subnormal += (value != 0.0 && value.abs() < f32::MIN_POSITIVE) as i32;
+	}
+	assert!(subnormal > 9990, "{}", subnormal);
+
+	let distr = FloatBits::new(0.0f64, 1.0);
+	let mut tiny = 0;
+	for _ in 0..10000 {
+		let value = rng.sample(&distr);
+		assert!(value >= 0.0 && value <= 1.0);
+		tiny += (value < 1.0e-100) as i32;
+	}
+	// Roughly two thirds of the floats in [0, 1] are smaller than 1e-100
+	assert!(tiny > 6000, "{}", tiny);
+}
@@ -0,0 +1,47 @@
+use crate::{Distribution, Random, Rng};
+
+/// Samples a pair of independent standard normal values with the [Marsaglia polar method](https://en.wikipedia.org/wiki/Marsaglia_polar_method).
+#[inline]
+pub(crate) fn standard_normal_pair<R: Rng + ?Sized>(rng: &mut Random<R>) -> (f64, f64) {
+	loop {
+		let u = rng.next_f64() * 2.0 - 3.0;
+		let v = rng.next_f64() * 2.0 - 3.0;
+		let s = u * u + v * v;
+		if s < 1.0 && s > 0.0 {
+			let factor = (-2.0 * s.ln() / s).sqrt();
+			return (u * factor, v * factor);
+		}
+	}
+}
+
+/// Fills the slice with independent standard normal values.
+#[inline]
+pub(crate) fn fill_standard_normal<R: Rng + ?Sized>(rng: &mut Random<R>, values: &mut [f64]) {
+	for pair in values.chunks_mut(2) {
+		let (a, b) = standard_normal_pair(rng);
+		pair[0] = a;
+		if let Some(slot) = pair.get_mut(1) {
+			*slot = b;
+		}
+	}
+}
+
+/// Samples from the standard gamma distribution with the given shape `>= 1` with the [Marsaglia–Tsang method](https://doi.org/10.1145/358407.358414).
+#[inline]
+pub(crate) fn standard_gamma<R: Rng + ?Sized>(rng: &mut Random<R>, shape: f64) -> f64 {
+	debug_assert!(shape >= 1.0);
+	let d = shape - 1.0 / 3.0;
+	let c = 1.0 / (9.0 * d).sqrt();
+	loop {
+		let (x, _) = standard_normal_pair(rng);
+		let v = 1.0 + c * x;
+		if v <= 0.0 {
+			continue;
+		}
+		let v = v * v * v;
+		let u: f64 = crate::distributions::Float01.sample(rng);
+		if u.ln() < 0.5 * x * x + d - d * v + d * v.ln() {
+			return d * v;
+		}
+	}
+}
@@ -0,0 +1,172 @@
+use core::fmt;
+use crate::{Distribution, Random, Rng};
+use crate::distributions::Float01;
+
+/// Sample from the [Kumaraswamy distribution](https://en.wikipedia.org/wiki/Kumaraswamy_distribution).
+///
+/// The distribution is parameterized by two positive shape parameters `a` and `b` and is supported on the open interval `(0, 1)`.
+///
+/// It resembles the Beta distribution and covers the same family of shapes (uniform, unimodal, U-shaped, J-shaped),
+/// but has a closed-form CDF and inverse CDF making it very cheap to sample.
+/// Prefer Kumaraswamy for procedural generation and blending where only the shape matters.
+/// Prefer Beta when the exact statistical properties matter, eg. as a conjugate prior in Bayesian statistics.
+/// The two coincide when either parameter is `1`: `Kumaraswamy(1, b) = Beta(1, b)` and `Kumaraswamy(a, 1) = Beta(a, 1)`.
+///
+/// The mean is `b·B(1 + 1/a, b)` where `B` is the Beta function.
+///
+/// # Implementation notes
+///
+/// Samples are generated by the inverse CDF from a uniform `u` in the open interval `(0, 1)` sampled with [`Float01`](Float01):
+/// `(1 - (1 - u)^(1/b))^(1/a)`.
+///
+/// For extreme parameters the samples may round to exactly `0.0` or `1.0`.
+///
+/// The parameters are stored as `f64`, when sampling `f32` they are first converted to `f32`.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::Kumaraswamy;
+///
+/// let mut rng = urandom::new();
+/// let distr = Kumaraswamy::new(2.0, 5.0);
+/// let blend: f64 = rng.sample(&distr);
+/// assert!(blend > 0.0 && blend < 1.0);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Kumaraswamy {
+	a: f64,
+	b: f64,
+}
+
+/// Error type returned from [`Kumaraswamy`](Kumaraswamy) constructors.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum KumaraswamyError {
+	/// The shape parameter `a` is not positive or not finite.
+	ANotPositive,
+	/// The shape parameter `b` is not positive or not finite.
+	BNotPositive,
+}
+
+impl fmt::Display for KumaraswamyError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match self {
+			KumaraswamyError::ANotPositive => "a is not positive and finite in Kumaraswamy distribution",
+			KumaraswamyError::BNotPositive => "b is not positive and finite in Kumaraswamy distribution",
+		})
+	}
+}
+
+impl std::error::Error for KumaraswamyError {}
+
+impl Kumaraswamy {
+	/// Constructs a new `Kumaraswamy` distribution with the given shape parameters.
+	///
+	/// # Panics
+	///
+	/// Panics if either parameter is not positive and finite.
+	#[inline]
+	pub fn new(a: f64, b: f64) -> Kumaraswamy {
+		match Kumaraswamy::try_new(a, b) {
+			Ok(distr) => distr,
+			Err(err) => kumaraswamy_error(err),
+		}
+	}
+	/// Constructs a new `Kumaraswamy` distribution with the given shape parameters.
+	///
+	/// Returns an error if either parameter is not positive and finite.
+	#[inline]
+	pub fn try_new(a: f64, b: f64) -> Result<Kumaraswamy, KumaraswamyError> {
+		if !(a > 0.0 && a.is_finite()) {
+			return Err(KumaraswamyError::ANotPositive);
+		}
+		if !(b > 0.0 && b.is_finite()) {
+			return Err(KumaraswamyError::BNotPositive);
+		}
+		Ok(Kumaraswamy { a, b })
+	}
+	/// Returns the shape parameter `a`.
+	#[inline]
+	pub const fn a(&self) -> f64 {
+		self.a
+	}
+	/// Returns the shape parameter `b`.
+	#[inline]
+	pub const fn b(&self) -> f64 {
+		self.b
+	}
+}
+
+impl Distribution<f64> for Kumaraswamy {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> f64 {
+		let u: f64 = Float01.sample(rng);
+		(1.0 - (1.0 - u).powf(1.0 / self.b)).powf(1.0 / self.a)
+	}
+}
+impl Distribution<f32> for Kumaraswamy {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> f32 {
+		let u: f32 = Float01.sample(rng);
+		let a = self.a as f32;
+		let b = self.b as f32;
+		(1.0 - (1.0 - u).powf(1.0 / b)).powf(1.0 / a)
+	}
+}
+
+#[cold]
+fn kumaraswamy_error(err: KumaraswamyError) -> ! {
+	panic!("Kumaraswamy::new called with invalid parameters: {}", err)
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_support() {
+	let mut rng = crate::seeded(42);
+	for &(a, b) in &[(0.5, 0.5), (1.0, 3.0), (2.0, 5.0), (5.0, 1.0), (0.2, 8.0)] {
+		let distr = Kumaraswamy::new(a, b);
+		for _ in 0..10000 {
+			let value: f64 = rng.sample(&distr);
+			assert!(value > 0.0 && value < 1.0, "a: {} b: {} value: {}", a, b, value);
+			let value: f32 = rng.sample(&distr);
+			assert!(value >= 0.0 && value <= 1.0, "a: {} b: {} value: {}", a, b, value);
+		}
+	}
+}
+
+#[test]
+fn test_uniform() {
+	let mut rng = crate::seeded(42);
+	let distr = Kumaraswamy::new(1.0, 1.0);
+	const N: usize = 100000;
+	let samples: Vec<f64> = rng.samples(distr).take(N).collect();
+	let mut buckets = [0i32; 10];
+	for &value in &samples {
+		buckets[(value * 10.0) as usize] += 1;
+	}
+	assert!(buckets.iter().all(|&count| (count - 10000).abs() < 400), "buckets: {:?}", buckets);
+	let (mean, variance) = super::fit::moments(&samples).unwrap();
+	assert!((mean - 0.5).abs() < 0.01, "mean: {}", mean);
+	assert!((variance - 1.0 / 12.0).abs() < 0.002, "variance: {}", variance);
+}
+
+#[test]
+fn test_mean() {
+	let mut rng = crate::seeded(42);
+	let samples: Vec<f64> = rng.samples(Kumaraswamy::new(2.0, 5.0)).take(100000).collect();
+	let (mean, _) = super::fit::moments(&samples).unwrap();
+	// b·B(1 + 1/a, b) = 5·Γ(1.5)·Γ(5)/Γ(6.5)
+	let expected = 0.36940836940836935;
+	assert!((mean - expected).abs() < 0.005, "mean: {} expected: {}", mean, expected);
+}
+
+#[test]
+fn test_validation() {
+	assert_eq!(Kumaraswamy::try_new(0.0, 1.0).unwrap_err(), KumaraswamyError::ANotPositive);
+	assert_eq!(Kumaraswamy::try_new(f64::NAN, 1.0).unwrap_err(), KumaraswamyError::ANotPositive);
+	assert_eq!(Kumaraswamy::try_new(1.0, -1.0).unwrap_err(), KumaraswamyError::BNotPositive);
+	assert_eq!(Kumaraswamy::try_new(1.0, f64::INFINITY).unwrap_err(), KumaraswamyError::BNotPositive);
+	let distr = Kumaraswamy::new(2.0, 3.0);
+	assert_eq!((distr.a(), distr.b()), (2.0, 3.0));
+}
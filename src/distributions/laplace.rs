@@ -0,0 +1,205 @@
+use core::fmt;
+use crate::{Distribution, Random, Rng};
+use crate::distributions::Float01;
+
+/// Sample from the [Laplace distribution](https://en.wikipedia.org/wiki/Laplace_distribution), also known as the double exponential distribution.
+///
+/// The distribution is parameterized by its location `μ` and scale `b`.
+/// Its mean is `μ` and its variance is `2b²`.
+///
+/// # Implementation notes
+///
+/// Samples are generated by the inverse CDF from a uniform `u` in the open interval `(0, 1)` sampled with [`Float01`](Float01):
+/// `μ - b·sign(u - ½)·ln(1 - 2|u - ½|)`.
+///
+/// Both halves of the distribution are computed separately so that no sample is ever infinite.
+///
+/// The parameters are stored as `f64`, when sampling `f32` they are first converted to `f32`.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::Laplace;
+///
+/// let mut rng = urandom::new();
+/// let distr = Laplace::new(2.0, 3.0);
+/// let value: f64 = rng.sample(&distr);
+/// assert!(value.is_finite());
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Laplace {
+	location: f64,
+	scale: f64,
+}
+
+/// Error type returned from [`Laplace`](Laplace) constructors.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum LaplaceError {
+	/// The location is not finite.
+	LocationNotFinite,
+	/// The scale is not positive or not finite.
+	ScaleNotPositive,
+	/// The sensitivity is not positive or not finite.
+	SensitivityNotPositive,
+	/// Epsilon is not positive or not finite.
+	EpsilonNotPositive,
+}
+
+impl fmt::Display for LaplaceError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match self {
+			LaplaceError::LocationNotFinite => "location is not finite in Laplace distribution",
+			LaplaceError::ScaleNotPositive => "scale is not positive and finite in Laplace distribution",
+			LaplaceError::SensitivityNotPositive => "sensitivity is not positive and finite in Laplace distribution",
+			LaplaceError::EpsilonNotPositive => "epsilon is not positive and finite in Laplace distribution",
+		})
+	}
+}
+
+impl std::error::Error for LaplaceError {}
+
+impl Laplace {
+	/// Constructs a new `Laplace` distribution with the given location and scale.
+	///
+	/// # Panics
+	///
+	/// Panics if the location is not finite or the scale is not positive and finite.
+	#[inline]
+	pub fn new(location: f64, scale: f64) -> Laplace {
+		match Laplace::try_new(location, scale) {
+			Ok(distr) => distr,
+			Err(err) => laplace_error(err),
+		}
+	}
+	/// Constructs a new `Laplace` distribution with the given location and scale.
+	///
+	/// Returns an error if the location is not finite or the scale is not positive and finite.
+	#[inline]
+	pub fn try_new(location: f64, scale: f64) -> Result<Laplace, LaplaceError> {
+		if !location.is_finite() {
+			return Err(LaplaceError::LocationNotFinite);
+		}
+		if !(scale > 0.0 && scale.is_finite()) {
+			return Err(LaplaceError::ScaleNotPositive);
+		}
+		Ok(Laplace { location, scale })
+	}
+	/// Constructs a zero centered `Laplace` distribution for the [Laplace mechanism](https://en.wikipedia.org/wiki/Additive_noise_differential_privacy_mechanisms#Laplace_Mechanism) in differential privacy.
+	///
+	/// The scale is `sensitivity / epsilon`.
+	///
+	/// Returns an error if either argument is not positive and finite or if the resulting scale is not positive and finite.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use urandom::distributions::Laplace;
+	///
+	/// let mut rng = urandom::new();
+	/// let distr = Laplace::from_sensitivity_epsilon(1.0, 0.5).unwrap();
+	/// let count = 42.0;
+	/// let noise: f64 = rng.sample(&distr);
+	/// let noisy = count + noise;
+	/// ```
+	#[inline]
+	pub fn from_sensitivity_epsilon(sensitivity: f64, epsilon: f64) -> Result<Laplace, LaplaceError> {
+		if !(sensitivity > 0.0 && sensitivity.is_finite()) {
+			return Err(LaplaceError::SensitivityNotPositive);
+		}
+		if !(epsilon > 0.0 && epsilon.is_finite()) {
+			return Err(LaplaceError::EpsilonNotPositive);
+		}
+		Laplace::try_new(0.0, sensitivity / epsilon)
+	}
+	/// Returns the location of the distribution.
+	#[inline]
+	pub const fn location(&self) -> f64 {
+		self.location
+	}
+	/// Returns the scale of the distribution.
+	#[inline]
+	pub const fn scale(&self) -> f64 {
+		self.scale
+	}
+}
+
+impl Distribution<f64> for Laplace {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> f64 {
+		let u: f64 = Float01.sample(rng);
+		if u < 0.5 {
+			self.location + self.scale * (2.0 * u).ln()
+		}
+		else {
+			self.location - self.scale * (2.0 * (1.0 - u)).ln()
+		}
+	}
+}
+impl Distribution<f32> for Laplace {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> f32 {
+		let u: f32 = Float01.sample(rng);
+		let location = self.location as f32;
+		let scale = self.scale as f32;
+		if u < 0.5 {
+			location + scale * (2.0 * u).ln()
+		}
+		else {
+			location - scale * (2.0 * (1.0 - u)).ln()
+		}
+	}
+}
+
+#[cold]
+fn laplace_error(err: LaplaceError) -> ! {
+	panic!("Laplace::new called with invalid parameters: {}", err)
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_moments() {
+	let mut rng = crate::seeded(42);
+	let distr = Laplace::new(3.0, 2.0);
+	let samples: Vec<f64> = rng.samples(distr).take(100000).collect();
+	let (mean, variance) = super::fit::moments(&samples).unwrap();
+	assert!((mean - 3.0).abs() < 0.05, "mean: {}", mean);
+	assert!((variance - 8.0).abs() < 0.3, "variance: {}", variance);
+}
+
+#[test]
+fn test_symmetry() {
+	let mut rng = crate::seeded(42);
+	let distr = Laplace::new(-1.5, 0.5);
+	const N: i32 = 10000;
+
+	let mut above = 0;
+	for _ in 0..N {
+		if rng.sample::<f32, _>(&distr) > -1.5 {
+			above += 1;
+		}
+	}
+	assert!((above - N / 2).abs() < 300, "above: {} out of {}", above, N);
+}
+
+#[test]
+fn test_edges() {
+	let distr = Laplace::new(0.0, 1.0);
+	let mut rng = crate::rng::MockRng::slice(&[0, 0, !0, !0]);
+	let low: f64 = rng.sample(&distr);
+	let high: f64 = rng.sample(&distr);
+	assert!(low.is_finite() && low < 0.0, "low: {}", low);
+	assert!(high.is_finite() && high > 0.0, "high: {}", high);
+}
+
+#[test]
+fn test_validation() {
+	assert_eq!(Laplace::try_new(f64::NAN, 1.0).unwrap_err(), LaplaceError::LocationNotFinite);
+	assert_eq!(Laplace::try_new(0.0, 0.0).unwrap_err(), LaplaceError::ScaleNotPositive);
+	assert_eq!(Laplace::try_new(0.0, -1.0).unwrap_err(), LaplaceError::ScaleNotPositive);
+	assert_eq!(Laplace::try_new(0.0, f64::INFINITY).unwrap_err(), LaplaceError::ScaleNotPositive);
+	assert_eq!(Laplace::from_sensitivity_epsilon(0.0, 1.0).unwrap_err(), LaplaceError::SensitivityNotPositive);
+	assert_eq!(Laplace::from_sensitivity_epsilon(1.0, f64::NAN).unwrap_err(), LaplaceError::EpsilonNotPositive);
+	assert_eq!(Laplace::from_sensitivity_epsilon(f64::MAX, f64::MIN_POSITIVE).unwrap_err(), LaplaceError::ScaleNotPositive);
+	assert_eq!(Laplace::from_sensitivity_epsilon(2.0, 0.5).unwrap().scale(), 4.0);
+}
@@ -0,0 +1,237 @@
+use core::fmt;
+use crate::{Distribution, Random, Rng};
+use crate::distributions::Float01;
+
+/// Sample values uniformly distributed in log space, also known as the [reciprocal distribution](https://en.wikipedia.org/wiki/Reciprocal_distribution).
+///
+/// Every order of magnitude between `low` and `high` is equally likely, which makes it suitable for generating sizes and timeouts.
+///
+/// # Implementation notes
+///
+/// For floating point types the sample is `exp(ln(low) + u·(ln(high) - ln(low)))` with `u` uniform in `(0, 1)`, clamped to `[low, high]` to absorb rounding errors.
+///
+/// For integer types the range `[low, high]` is inclusive.
+/// The sample is the floor of a continuous log-uniform sample from the half-open interval `[low, high + 1)`,
+/// so that the integer `k` is sampled with probability `ln((k + 1) / k) / ln((high + 1) / low)`.
+/// Both endpoints are reachable and every decade `[10ⁿ, 10ⁿ⁺¹)` fully inside the range receives exactly the same probability.
+/// Naively rounding a sample from `[low, high]` to the nearest integer would halve the probability of both endpoints.
+///
+/// The continuous sample has the precision of `f64`, integers above `2⁵³` cannot all be sampled.
+///
+/// The logarithms are stored as `f64`.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::LogUniform;
+///
+/// let mut rng = urandom::new();
+/// let size = rng.sample(&LogUniform::new(1u64, 1_000_000_000));
+/// assert!(size >= 1 && size <= 1_000_000_000);
+///
+/// let timeout = rng.sample(&LogUniform::new(0.001, 10.0));
+/// assert!(timeout >= 0.001 && timeout <= 10.0);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct LogUniform<T> {
+	low: T,
+	high: T,
+	ln_low: f64,
+	ln_range: f64,
+}
+
+/// Error type returned from [`LogUniform`](LogUniform) constructors.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum LogUniformError {
+	/// The low bound is not positive.
+	LowNotPositive,
+	/// The low bound is not less than the high bound.
+	EmptyRange,
+	/// The high bound is not finite.
+	NotFinite,
+}
+
+impl fmt::Display for LogUniformError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match self {
+			LogUniformError::LowNotPositive => "low is not positive in LogUniform distribution",
+			LogUniformError::EmptyRange => "low is not less than high in LogUniform distribution",
+			LogUniformError::NotFinite => "high is not finite in LogUniform distribution",
+		})
+	}
+}
+
+impl std::error::Error for LogUniformError {}
+
+/// Types which can be sampled from the [`LogUniform`](LogUniform) distribution.
+pub trait SampleLogUniform: Copy + PartialOrd {
+	/// Returns the exclusive upper end of the continuous range in log space: `high` for floats and `high + 1` for integers.
+	fn log_end(high: Self) -> f64;
+	/// Converts a continuous sample to `Self`, truncating integers.
+	fn from_log_sample(x: f64) -> Self;
+	/// Returns `true` if the value is greater than zero.
+	fn is_positive(self) -> bool;
+	/// Converts the value to `f64`.
+	fn to_f64(self) -> f64;
+}
+
+impl<T: SampleLogUniform> LogUniform<T> {
+	/// Constructs a new `LogUniform` distribution between `low` and `high`.
+	///
+	/// # Panics
+	///
+	/// Panics if `low` is not positive, `low` is not less than `high` or `high` is not finite.
+	#[inline]
+	pub fn new(low: T, high: T) -> LogUniform<T> {
+		match LogUniform::try_new(low, high) {
+			Ok(distr) => distr,
+			Err(err) => log_uniform_error(err),
+		}
+	}
+	/// Constructs a new `LogUniform` distribution between `low` and `high`.
+	///
+	/// Returns an error if `low` is not positive, `low` is not less than `high` or `high` is not finite.
+	#[inline]
+	pub fn try_new(low: T, high: T) -> Result<LogUniform<T>, LogUniformError> {
+		if !low.is_positive() {
+			return Err(LogUniformError::LowNotPositive);
+		}
+		if low.partial_cmp(&high) != Some(core::cmp::Ordering::Less) {
+			return Err(LogUniformError::EmptyRange);
+		}
+		if !high.to_f64().is_finite() {
+			return Err(LogUniformError::NotFinite);
+		}
+		let ln_low = low.to_f64().ln();
+		let ln_range = T::log_end(high).ln() - ln_low;
+		Ok(LogUniform { low, high, ln_low, ln_range })
+	}
+	/// Returns the low bound.
+	#[inline]
+	pub fn low(&self) -> T {
+		self.low
+	}
+	/// Returns the high bound.
+	#[inline]
+	pub fn high(&self) -> T {
+		self.high
+	}
+}
+
+impl<T: SampleLogUniform> Distribution<T> for LogUniform<T> {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> T {
+		let u: f64 = Float01.sample(rng);
+		let value = T::from_log_sample((self.ln_low + u * self.ln_range).exp());
+		if value < self.low { self.low } else if value > self.high { self.high } else { value }
+	}
+}
+
+macro_rules! impl_sample_log_uniform {
+	($ty:ty, $end:expr, $sample:expr) => {
+		impl SampleLogUniform for $ty {
+			#[inline]
+			fn log_end(high: $ty) -> f64 {
+				let end: fn($ty) -> f64 = $end;
+				end(high)
+			}
+			#[inline]
+			fn from_log_sample(x: f64) -> $ty {
+				let sample: fn(f64) -> $ty = $sample;
+				sample(x)
+			}
+			#[allow(unused_comparisons)]
+			#[inline]
+			fn is_positive(self) -> bool {
+				self > 0 as $ty
+			}
+			#[inline]
+			fn to_f64(self) -> f64 {
+				self as f64
+			}
+		}
+	};
+}
+
+impl_sample_log_uniform!(f32, |high| high as f64, |x| x as f32);
+impl_sample_log_uniform!(f64, |high| high, |x| x);
+
+macro_rules! impl_sample_log_uniform_int {
+	($($ty:ty),*) => {$(
+		// The float to int cast saturates, the result is clamped to the range afterwards
+		impl_sample_log_uniform!($ty, |high| high as f64 + 1.0, |x| x.floor() as $ty);
+	)*};
+}
+impl_sample_log_uniform_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+#[cold]
+fn log_uniform_error(err: LogUniformError) -> ! {
+	panic!("LogUniform::new called with invalid parameters: {}", err)
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_decades() {
+	let mut rng = crate::seeded(42);
+	const N: usize = 60000;
+
+	let float = LogUniform::new(1.0, 1e6);
+	let int = LogUniform::new(1u32, 999_999);
+	let mut float_counts = [0i32; 6];
+	let mut int_counts = [0i32; 6];
+	for _ in 0..N {
+		let x: f64 = rng.sample(&float);
+		assert!((1.0..=1e6).contains(&x), "x: {}", x);
+		float_counts[usize::min(x.log10() as usize, 5)] += 1;
+
+		let k: u32 = rng.sample(&int);
+		assert!((1..=999_999).contains(&k), "k: {}", k);
+		int_counts[(k as f64).log10() as usize] += 1;
+	}
+	for counts in [float_counts, int_counts] {
+		assert!(counts.iter().all(|&count| (count - 10000).abs() < 400), "{:?}", counts);
+	}
+}
+
+#[test]
+fn test_endpoints() {
+	let mut rng = crate::seeded(42);
+	let distr = LogUniform::new(3u8, 10);
+	let mut counts = [0i32; 11];
+	const N: i32 = 100000;
+	for _ in 0..N {
+		counts[rng.sample(&distr) as usize] += 1;
+	}
+	assert_eq!(&counts[..3], &[0, 0, 0]);
+	let total = (11.0f64 / 3.0).ln();
+	for k in 3..=10 {
+		let expected = ((k as f64 + 1.0) / k as f64).ln() / total * N as f64;
+		assert!((counts[k] as f64 - expected).abs() < 0.05 * expected, "k: {} count: {} expected: {}", k, counts[k], expected);
+	}
+
+	// Extreme uniform samples stay in range
+	let distr = LogUniform::new(7i64, 1000);
+	let mut rng = crate::rng::MockRng::slice(&[0, 0, !0, !0]);
+	assert_eq!(rng.sample(&distr), 7);
+	assert_eq!(rng.sample(&distr), 1000);
+	let distr = LogUniform::new(1u32, u32::MAX);
+	let mut rng = crate::rng::MockRng::slice(&[!0, !0]);
+	assert_eq!(rng.sample(&distr), u32::MAX);
+	let distr = LogUniform::new(0.5f32, 2.0);
+	let mut rng = crate::rng::MockRng::slice(&[0, 0, !0, !0]);
+	let low = rng.sample(&distr);
+	let high = rng.sample(&distr);
+	assert!(low >= 0.5 && high <= 2.0, "low: {} high: {}", low, high);
+}
+
+#[test]
+fn test_validation() {
+	assert_eq!(LogUniform::try_new(0u32, 10).unwrap_err(), LogUniformError::LowNotPositive);
+	assert_eq!(LogUniform::try_new(-1i32, 10).unwrap_err(), LogUniformError::LowNotPositive);
+	assert_eq!(LogUniform::try_new(f64::NAN, 10.0).unwrap_err(), LogUniformError::LowNotPositive);
+	assert_eq!(LogUniform::try_new(10u32, 10).unwrap_err(), LogUniformError::EmptyRange);
+	assert_eq!(LogUniform::try_new(2.0, 1.0).unwrap_err(), LogUniformError::EmptyRange);
+	assert_eq!(LogUniform::try_new(1.0, f64::INFINITY).unwrap_err(), LogUniformError::NotFinite);
+	assert_eq!(LogUniform::new(1u8, 255).high(), 255);
+}
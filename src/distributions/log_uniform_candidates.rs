@@ -0,0 +1,259 @@
+use core::fmt;
+use crate::{Distribution, Random, Rng};
+use crate::distributions::Float01;
+
+/// Log-uniform candidate sampler for sampled softmax and negative sampling.
+///
+/// Samples ids in `[0, vocab_size)` where the id `k` has probability `ln((k + 2) / (k + 1)) / ln(vocab_size + 1)`.
+/// Vocabularies sorted by decreasing frequency approximately follow this Zipfian distribution, making it a good proposal for negative sampling.
+///
+/// The semantics match TensorFlow's `log_uniform_candidate_sampler` so that sampled losses can be corrected with [`expected_count`](LogUniformCandidates::expected_count).
+///
+/// # Implementation notes
+///
+/// Uses the closed-form inverse CDF `floor(exp(u·ln(vocab_size + 1))) - 1` with `u` uniform in `(0, 1)`.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::LogUniformCandidates;
+///
+/// let mut rng = urandom::new();
+/// let candidates = LogUniformCandidates::new(50_000);
+///
+/// let positive = 42;
+/// let mut negatives = Vec::new();
+/// let num_tries = candidates.sample_batch(&mut rng, 64, &[positive], true, &mut negatives);
+/// assert_eq!(negatives.len(), 64);
+/// assert!(!negatives.contains(&positive));
+///
+/// let correction = candidates.expected_count(negatives[0], num_tries);
+/// assert!(correction > 0.0 && correction <= 1.0);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct LogUniformCandidates {
+	vocab_size: u64,
+	ln_range: f64,
+}
+
+/// Error type returned from [`LogUniformCandidates`](LogUniformCandidates) constructors.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum LogUniformCandidatesError {
+	/// The vocabulary is empty.
+	EmptyVocab,
+}
+
+impl fmt::Display for LogUniformCandidatesError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match self {
+			LogUniformCandidatesError::EmptyVocab => "vocab_size is zero in LogUniformCandidates distribution",
+		})
+	}
+}
+
+impl std::error::Error for LogUniformCandidatesError {}
+
+impl LogUniformCandidates {
+	/// Constructs a new `LogUniformCandidates` distribution over the ids `[0, vocab_size)`.
+	///
+	/// # Panics
+	///
+	/// Panics if `vocab_size` is zero.
+	#[inline]
+	pub fn new(vocab_size: u64) -> LogUniformCandidates {
+		match LogUniformCandidates::try_new(vocab_size) {
+			Ok(distr) => distr,
+			Err(err) => log_uniform_candidates_error(err),
+		}
+	}
+	/// Constructs a new `LogUniformCandidates` distribution over the ids `[0, vocab_size)`.
+	///
+	/// Returns an error if `vocab_size` is zero.
+	#[inline]
+	pub fn try_new(vocab_size: u64) -> Result<LogUniformCandidates, LogUniformCandidatesError> {
+		if vocab_size == 0 {
+			return Err(LogUniformCandidatesError::EmptyVocab);
+		}
+		let ln_range = (vocab_size as f64 + 1.0).ln();
+		Ok(LogUniformCandidates { vocab_size, ln_range })
+	}
+	/// Returns the size of the vocabulary.
+	#[inline]
+	pub fn vocab_size(&self) -> u64 {
+		self.vocab_size
+	}
+	/// Returns the probability of sampling the id in a single try.
+	///
+	/// Returns zero for ids outside the vocabulary.
+	#[inline]
+	pub fn probability(&self, id: u64) -> f64 {
+		if id >= self.vocab_size {
+			return 0.0;
+		}
+		(1.0 / (id as f64 + 1.0)).ln_1p() / self.ln_range
+	}
+	/// Returns the expected count of the id in a batch which took `num_tries` tries to sample.
+	///
+	/// This is the correction term for sampled losses, matching TensorFlow:
+	/// the probability that the id was drawn at least once in `num_tries` independent tries, `1 - (1 - p)^num_tries`.
+	///
+	/// For batches sampled without the `unique` flag the number of tries equals the batch size `k`.
+	/// TensorFlow uses the exact expectation `p·k` for those batches instead, which is [`probability`](Self::probability) times `k`.
+	#[inline]
+	pub fn expected_count(&self, id: u64, num_tries: usize) -> f64 {
+		let p = self.probability(id);
+		-(num_tries as f64 * (-p).ln_1p()).exp_m1()
+	}
+	/// Samples a batch of `k` candidate ids into `out` and returns the number of tries.
+	///
+	/// The vector `out` is cleared first. Ids in `exclude`, such as the positive ids, are rejected and sampled again.
+	/// If `unique` is set duplicate ids are also rejected and every id in the batch is distinct.
+	///
+	/// Each rejected sample counts as a try, the returned number of tries is the argument for [`expected_count`](Self::expected_count).
+	///
+	/// # Panics
+	///
+	/// Panics if there are not enough ids left after exclusion to complete the batch.
+	pub fn sample_batch<R: Rng + ?Sized>(&self, rand: &mut Random<R>, k: usize, exclude: &[u64], unique: bool, out: &mut Vec<u64>) -> usize {
+		out.clear();
+		if k == 0 {
+			return 0;
+		}
+		let mut excluded: Vec<u64> = exclude.iter().copied().filter(|&id| id < self.vocab_size).collect();
+		excluded.sort_unstable();
+		excluded.dedup();
+		let available = self.vocab_size - excluded.len() as u64;
+		if available == 0 || unique && (k as u64) > available {
+			sample_batch_error(k, available);
+		}
+		out.reserve(k);
+		let mut num_tries = 0;
+		while out.len() < k {
+			let id = self.sample(rand);
+			num_tries += 1;
+			if excluded.binary_search(&id).is_ok() || unique && out.contains(&id) {
+				continue;
+			}
+			out.push(id);
+		}
+		num_tries
+	}
+}
+
+impl Distribution<u64> for LogUniformCandidates {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> u64 {
+		let u: f64 = Float01.sample(rng);
+		// The float to int cast saturates, clamp to absorb rounding at the upper end
+		let id = (u * self.ln_range).exp().floor() as u64;
+		u64::min(id.saturating_sub(1), self.vocab_size - 1)
+	}
+}
+
+#[cold]
+fn log_uniform_candidates_error(err: LogUniformCandidatesError) -> ! {
+	panic!("LogUniformCandidates::new called with invalid parameters: {}", err)
+}
+
+#[cold]
+fn sample_batch_error(k: usize, available: u64) -> ! {
+	panic!("sample_batch k (is {}) cannot be sampled from {} available ids", k, available)
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_frequencies() {
+	let mut rng = crate::seeded(42);
+	let distr = LogUniformCandidates::new(100_000);
+	const N: usize = 400_000;
+
+	// Buckets for the first few ids and every decade after
+	let bounds = [0, 1, 2, 3, 10, 100, 1000, 10_000, 100_000];
+	let mut counts = [0usize; 8];
+	for _ in 0..N {
+		let id = distr.sample(&mut rng);
+		assert!(id < 100_000);
+		counts[bounds.iter().rposition(|&b| b <= id).unwrap()] += 1;
+	}
+	for i in 0..8 {
+		let expected: f64 = (bounds[i]..bounds[i + 1]).map(|id| distr.probability(id)).sum::<f64>() * N as f64;
+		let count = counts[i] as f64;
+		assert!((count - expected).abs() < 4.0 * expected.sqrt() + 1.0, "bucket: {} count: {} expected: {}", i, count, expected);
+	}
+
+	let total: f64 = (0..100_000).map(|id| distr.probability(id)).sum();
+	assert!((total - 1.0).abs() < 1e-9, "total: {}", total);
+	assert_eq!(distr.probability(100_000), 0.0);
+}
+
+#[test]
+fn test_sample_batch() {
+	let mut rng = crate::seeded(42);
+	let distr = LogUniformCandidates::new(20);
+	let mut out = vec![99];
+	for _ in 0..1000 {
+		let num_tries = distr.sample_batch(&mut rng, 10, &[0, 3, 3, 1000], true, &mut out);
+		assert_eq!(out.len(), 10);
+		assert!(num_tries >= 10);
+		assert!(!out.contains(&0) && !out.contains(&3));
+		let mut sorted = out.clone();
+		sorted.sort();
+		sorted.dedup();
+		assert_eq!(sorted.len(), 10);
+	}
+
+	// Without the unique flag duplicates are allowed but exclusion is honored
+	let num_tries = distr.sample_batch(&mut rng, 500, &[0, 1], false, &mut out);
+	assert!(num_tries > 500);
+	assert!(out.iter().all(|&id| id >= 2 && id < 20));
+	out.sort();
+	out.dedup();
+	assert_eq!(out.len(), 18);
+
+	// Exactly the available ids
+	distr.sample_batch(&mut rng, 18, &[5, 7], true, &mut out);
+	out.sort();
+	assert_eq!(out, (0..20).filter(|&id| id != 5 && id != 7).collect::<Vec<u64>>());
+	assert_eq!(distr.sample_batch(&mut rng, 0, &[], true, &mut out), 0);
+	assert!(out.is_empty());
+}
+
+#[test]
+#[should_panic]
+fn test_sample_batch_exhausted() {
+	LogUniformCandidates::new(3).sample_batch(&mut crate::new(), 3, &[1], true, &mut Vec::new());
+}
+
+#[test]
+fn test_expected_count() {
+	let mut rng = crate::seeded(42);
+	let distr = LogUniformCandidates::new(5);
+	const NUM_TRIES: usize = 4;
+	const RUNS: usize = 100_000;
+	let mut present = [0usize; 5];
+	for _ in 0..RUNS {
+		let mut seen = [false; 5];
+		for _ in 0..NUM_TRIES {
+			seen[distr.sample(&mut rng) as usize] = true;
+		}
+		for id in 0..5 {
+			present[id] += seen[id] as usize;
+		}
+	}
+	for id in 0..5 {
+		let frequency = present[id] as f64 / RUNS as f64;
+		let expected = distr.expected_count(id as u64, NUM_TRIES);
+		assert!((frequency - expected).abs() < 0.005, "id: {} frequency: {} expected: {}", id, frequency, expected);
+	}
+	assert!((distr.expected_count(0, 1) - distr.probability(0)).abs() < 1e-15);
+	assert!((distr.probability(0) - 2f64.ln() / 6f64.ln()).abs() < 1e-15);
+}
+
+#[test]
+fn test_validation() {
+	assert_eq!(LogUniformCandidates::try_new(0).unwrap_err(), LogUniformCandidatesError::EmptyVocab);
+	let distr = LogUniformCandidates::new(1);
+	let mut rng = crate::seeded(42);
+	assert!((0..100).all(|_| distr.sample(&mut rng) == 0));
+}
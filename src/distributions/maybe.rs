@@ -0,0 +1,136 @@
+use crate::{Distribution, Random, Rng};
+use crate::distributions::Bernoulli;
+
+/// Sample `Some` value from the inner distribution with the given probability, otherwise `None`.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::{Dice, Maybe};
+///
+/// let mut rng = urandom::new();
+/// let distr = Maybe::new(0.9, Dice::D6);
+/// let fixtures: Vec<Option<i32>> = rng.samples(distr).take(10).collect();
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Maybe<D> {
+	some: Bernoulli,
+	inner: D,
+}
+
+impl<D> Maybe<D> {
+	/// Constructs a new distribution returning `Some` with probability `p_some`.
+	///
+	/// See [`Bernoulli::new`](Bernoulli::new) for the handling of probabilities outside of `[0.0, 1.0]`.
+	#[inline]
+	pub const fn new(p_some: f64, inner: D) -> Maybe<D> {
+		Maybe { some: Bernoulli::new(p_some), inner }
+	}
+}
+
+impl<T, D: Distribution<T>> Distribution<Option<T>> for Maybe<D> {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> Option<T> {
+		if self.some.sample(rng) {
+			Some(self.inner.sample(rng))
+		}
+		else {
+			None
+		}
+	}
+}
+
+/// Sample `Ok` from the first distribution with the given probability, otherwise `Err` from the second distribution.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::{Alphanumeric, Dice, OrElse};
+///
+/// let mut rng = urandom::new();
+/// let distr = OrElse::new(0.9, Dice::D20, Alphanumeric);
+/// let fixtures: Vec<Result<i32, char>> = rng.samples(distr).take(10).collect();
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct OrElse<D1, D2> {
+	first: Bernoulli,
+	ok: D1,
+	err: D2,
+}
+
+impl<D1, D2> OrElse<D1, D2> {
+	/// Constructs a new distribution returning `Ok` with probability `p_first`.
+	///
+	/// See [`Bernoulli::new`](Bernoulli::new) for the handling of probabilities outside of `[0.0, 1.0]`.
+	#[inline]
+	pub const fn new(p_first: f64, first: D1, second: D2) -> OrElse<D1, D2> {
+		OrElse { first: Bernoulli::new(p_first), ok: first, err: second }
+	}
+}
+
+impl<T, E, D1: Distribution<T>, D2: Distribution<E>> Distribution<Result<T, E>> for OrElse<D1, D2> {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> Result<T, E> {
+		if self.first.sample(rng) {
+			Ok(self.ok.sample(rng))
+		}
+		else {
+			Err(self.err.sample(rng))
+		}
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_maybe() {
+	use crate::distributions::Dice;
+	let mut rng = crate::new();
+	let distr = Maybe::new(0.25, Dice::D6);
+	const N: usize = 40000;
+	let mut none = 0;
+	let mut faces = [0i32; 6];
+	for value in rng.samples(distr).take(N) {
+		let value: Option<i32> = value;
+		match value {
+			Some(face) => faces[face as usize - 1] += 1,
+			None => none += 1,
+		}
+	}
+	assert!((none as f64 / N as f64 - 0.75).abs() < 0.01, "none: {}", none);
+	assert!(faces.iter().all(|&count| (count - 10000 / 6).abs() < 150), "faces: {:?}", faces);
+
+	for _ in 0..100 {
+		let value: Option<i32> = rng.sample(&Maybe::new(0.0, Dice::D6));
+		assert_eq!(value, None);
+		let value: Option<i32> = rng.sample(&Maybe::new(1.0, Dice::D6));
+		assert!(value.is_some());
+	}
+}
+
+#[test]
+fn test_or_else() {
+	use crate::distributions::{Alphanumeric, Dice};
+	let mut rng = crate::new();
+	let distr = OrElse::new(0.9, Dice::D4, Alphanumeric);
+	const N: usize = 40000;
+	let mut ok = 0;
+	for value in rng.samples(distr).take(N) {
+		let value: Result<i32, char> = value;
+		match value {
+			Ok(face) => {
+				assert!(face >= 1 && face <= 4);
+				ok += 1;
+			},
+			Err(c) => assert!(c.is_ascii_alphanumeric()),
+		}
+	}
+	assert!((ok as f64 / N as f64 - 0.9).abs() < 0.01, "ok: {}", ok);
+
+	for _ in 0..100 {
+		let value: Result<i32, char> = rng.sample(&OrElse::new(0.0, Dice::D4, Alphanumeric));
+		assert!(value.is_err());
+		let value: Result<i32, char> = rng.sample(&OrElse::new(1.0, Dice::D4, Alphanumeric));
+		assert!(value.is_ok());
+	}
+}
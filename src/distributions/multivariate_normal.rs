@@ -0,0 +1,204 @@
+use core::fmt;
+use crate::{Distribution, Random, Rng};
+
+/// Sample from the [multivariate normal distribution](https://en.wikipedia.org/wiki/Multivariate_normal_distribution) in `N` dimensions.
+///
+/// The distribution is parameterized by its mean vector `μ` and its symmetric positive-definite covariance matrix `Σ`.
+///
+/// # Implementation notes
+///
+/// The covariance matrix is decomposed as `Σ = L·Lᵀ` with the [Cholesky decomposition](https://en.wikipedia.org/wiki/Cholesky_decomposition) at construction.
+/// Samples are `μ + L·z` where `z` is a vector of `N` independent standard normal values generated in pairs with the Marsaglia polar method.
+///
+/// The decomposition takes `O(N³)` operations and sampling `O(N²)`, which is intended for small `N`.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::MultivariateNormal;
+///
+/// let mut rng = urandom::new();
+/// let distr = MultivariateNormal::new([0.0, 1.0], [[1.0, 0.8], [0.8, 1.0]]);
+/// let [x, y] = rng.sample(&distr);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct MultivariateNormal<const N: usize> {
+	mean: [f64; N],
+	factor: [[f64; N]; N],
+}
+
+/// Error type returned from [`MultivariateNormal`](MultivariateNormal) constructors.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MultivariateNormalError {
+	/// The mean or covariance contains values which are not finite.
+	NotFinite,
+	/// The covariance matrix is not symmetric.
+	NotSymmetric,
+	/// The covariance matrix is not positive-definite.
+	NotPositiveDefinite,
+}
+
+impl fmt::Display for MultivariateNormalError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match self {
+			MultivariateNormalError::NotFinite => "mean or covariance is not finite in multivariate normal distribution",
+			MultivariateNormalError::NotSymmetric => "covariance is not symmetric in multivariate normal distribution",
+			MultivariateNormalError::NotPositiveDefinite => "covariance is not positive-definite in multivariate normal distribution",
+		})
+	}
+}
+
+impl std::error::Error for MultivariateNormalError {}
+
+impl<const N: usize> MultivariateNormal<N> {
+	/// Constructs a new `MultivariateNormal` distribution with the given mean and covariance.
+	///
+	/// # Panics
+	///
+	/// Panics if any value is not finite or the covariance is not symmetric and positive-definite.
+	#[inline]
+	pub fn new(mean: [f64; N], covariance: [[f64; N]; N]) -> MultivariateNormal<N> {
+		match MultivariateNormal::try_new(mean, covariance) {
+			Ok(distr) => distr,
+			Err(err) => multivariate_normal_error(err),
+		}
+	}
+	/// Constructs a new `MultivariateNormal` distribution with the given mean and covariance.
+	///
+	/// Returns an error if any value is not finite or the covariance is not symmetric and positive-definite.
+	/// Symmetry is checked with a relative tolerance of `1e-10` to allow for rounding errors in computed covariances.
+	pub fn try_new(mean: [f64; N], covariance: [[f64; N]; N]) -> Result<MultivariateNormal<N>, MultivariateNormalError> {
+		if !mean.iter().chain(covariance.iter().flatten()).all(|x| x.is_finite()) {
+			return Err(MultivariateNormalError::NotFinite);
+		}
+		for i in 0..N {
+			for j in 0..i {
+				let (a, b) = (covariance[i][j], covariance[j][i]);
+				if (a - b).abs() > 1e-10 * f64::max(a.abs(), b.abs()) {
+					return Err(MultivariateNormalError::NotSymmetric);
+				}
+			}
+		}
+		let factor = cholesky(&covariance).ok_or(MultivariateNormalError::NotPositiveDefinite)?;
+		Ok(MultivariateNormal { mean, factor })
+	}
+	/// Returns the mean of the distribution.
+	#[inline]
+	pub const fn mean(&self) -> &[f64; N] {
+		&self.mean
+	}
+	/// Returns the lower triangular Cholesky factor `L` of the covariance `Σ = L·Lᵀ`.
+	#[inline]
+	pub const fn cholesky_factor(&self) -> &[[f64; N]; N] {
+		&self.factor
+	}
+}
+
+// Cholesky–Banachiewicz algorithm, returns None if the matrix is not positive-definite
+fn cholesky<const N: usize>(a: &[[f64; N]; N]) -> Option<[[f64; N]; N]> {
+	let mut l = [[0.0; N]; N];
+	for i in 0..N {
+		for j in 0..=i {
+			let mut sum = a[i][j];
+			for k in 0..j {
+				sum -= l[i][k] * l[j][k];
+			}
+			if i == j {
+				if sum.is_nan() || sum <= 0.0 {
+					return None;
+				}
+				l[i][j] = sum.sqrt();
+			}
+			else {
+				l[i][j] = sum / l[j][j];
+			}
+		}
+	}
+	Some(l)
+}
+
+impl<const N: usize> Distribution<[f64; N]> for MultivariateNormal<N> {
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> [f64; N] {
+		let mut z = [0.0; N];
+		super::gaussian::fill_standard_normal(rng, &mut z);
+		let mut x = self.mean;
+		for i in 0..N {
+			for j in 0..=i {
+				x[i] += self.factor[i][j] * z[j];
+			}
+		}
+		x
+	}
+}
+
+#[cold]
+fn multivariate_normal_error(err: MultivariateNormalError) -> ! {
+	panic!("MultivariateNormal::new called with invalid parameters: {}", err)
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_moments() {
+	let mut rng = crate::seeded(42);
+	let mean = [1.0, -2.0, 0.5];
+	let covariance = [
+		[4.0, 1.2, -0.6],
+		[1.2, 1.0, 0.3],
+		[-0.6, 0.3, 0.5],
+	];
+	let distr = MultivariateNormal::new(mean, covariance);
+
+	const SAMPLES: usize = 200000;
+	let samples: Vec<[f64; 3]> = (0..SAMPLES).map(|_| rng.sample(&distr)).collect();
+	let mut sample_mean = [0.0; 3];
+	for x in &samples {
+		for i in 0..3 {
+			sample_mean[i] += x[i] / SAMPLES as f64;
+		}
+	}
+	let mut sample_covariance = [[0.0; 3]; 3];
+	for x in &samples {
+		for i in 0..3 {
+			for j in 0..3 {
+				sample_covariance[i][j] += (x[i] - sample_mean[i]) * (x[j] - sample_mean[j]) / (SAMPLES - 1) as f64;
+			}
+		}
+	}
+	for i in 0..3 {
+		assert!((sample_mean[i] - mean[i]).abs() < 0.02, "mean: {:?}", sample_mean);
+		for j in 0..3 {
+			assert!((sample_covariance[i][j] - covariance[i][j]).abs() < 0.05, "covariance: {:?}", sample_covariance);
+		}
+	}
+}
+
+#[test]
+fn test_cholesky() {
+	let distr = MultivariateNormal::new([0.0; 2], [[4.0, 2.0], [2.0, 5.0]]);
+	assert_eq!(distr.cholesky_factor(), &[[2.0, 0.0], [1.0, 2.0]]);
+}
+
+#[test]
+fn test_univariate() {
+	// One dimension is the univariate normal distribution with the standard deviation as the factor
+	let distr = MultivariateNormal::new([3.0], [[2.25]]);
+	assert_eq!(distr.cholesky_factor(), &[[1.5]]);
+	let mut a = crate::seeded(42);
+	let mut b = crate::seeded(42);
+	for _ in 0..100 {
+		let [x] = a.sample(&distr);
+		let (z, _) = super::gaussian::standard_normal_pair(&mut b);
+		assert_eq!(x, 3.0 + 1.5 * z);
+	}
+}
+
+#[test]
+fn test_validation() {
+	assert_eq!(MultivariateNormal::try_new([0.0; 2], [[-1.0, 0.0], [0.0, -1.0]]).unwrap_err(), MultivariateNormalError::NotPositiveDefinite);
+	assert_eq!(MultivariateNormal::try_new([0.0; 2], [[1.0, 2.0], [2.0, 1.0]]).unwrap_err(), MultivariateNormalError::NotPositiveDefinite);
+	assert_eq!(MultivariateNormal::try_new([0.0; 2], [[1.0, 0.5], [0.4, 1.0]]).unwrap_err(), MultivariateNormalError::NotSymmetric);
+	assert_eq!(MultivariateNormal::try_new([f64::NAN; 2], [[1.0, 0.0], [0.0, 1.0]]).unwrap_err(), MultivariateNormalError::NotFinite);
+	assert_eq!(MultivariateNormal::try_new([0.0], [[f64::INFINITY]]).unwrap_err(), MultivariateNormalError::NotFinite);
+	assert!(MultivariateNormal::try_new([0.0; 2], [[1.0, 0.1 + 0.2], [0.3, 1.0]]).is_ok());
+}
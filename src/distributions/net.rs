@@ -0,0 +1,249 @@
+use core::fmt;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use crate::{Distribution, Random, Rng};
+use crate::distributions::{SampleUniform, Standard, UniformInt, UniformSampler};
+
+impl Distribution<Ipv4Addr> for Standard {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> Ipv4Addr {
+		Ipv4Addr::from(rng.next_u32())
+	}
+}
+impl Distribution<Ipv6Addr> for Standard {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> Ipv6Addr {
+		Ipv6Addr::from(rng.next_u128())
+	}
+}
+
+/// Uniform distribution over IPv4 addresses.
+///
+/// Addresses are sampled uniformly over their numerical `u32` representation.
+///
+/// # Examples
+///
+/// ```
+/// use std::net::Ipv4Addr;
+///
+/// let mut rng = urandom::new();
+/// let addr = rng.range(Ipv4Addr::new(10, 0, 0, 0)..=Ipv4Addr::new(10, 255, 255, 255));
+/// assert_eq!(addr.octets()[0], 10);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct UniformIpv4Addr(UniformInt<u32>);
+
+impl SampleUniform for Ipv4Addr {
+	type Sampler = UniformIpv4Addr;
+}
+impl UniformSampler<Ipv4Addr> for UniformIpv4Addr {
+	#[inline]
+	fn new(low: Ipv4Addr, high: Ipv4Addr) -> UniformIpv4Addr {
+		UniformIpv4Addr(UniformInt::new(u32::from(low), u32::from(high)))
+	}
+	#[inline]
+	fn new_inclusive(low: Ipv4Addr, high: Ipv4Addr) -> UniformIpv4Addr {
+		UniformIpv4Addr(UniformInt::new_inclusive(u32::from(low), u32::from(high)))
+	}
+}
+impl Distribution<Ipv4Addr> for UniformIpv4Addr {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> Ipv4Addr {
+		Ipv4Addr::from(self.0.sample(rng))
+	}
+}
+
+/// Uniform distribution over IPv6 addresses.
+///
+/// Addresses are sampled uniformly over their numerical `u128` representation.
+///
+/// # Examples
+///
+/// ```
+/// use std::net::Ipv6Addr;
+///
+/// let mut rng = urandom::new();
+/// let low = Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0);
+/// let high = Ipv6Addr::new(0x2001, 0xdb8, 0xffff, 0xffff, 0xffff, 0xffff, 0xffff, 0xffff);
+/// let addr = rng.range(low..=high);
+/// assert!(addr >= low && addr <= high);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct UniformIpv6Addr(UniformInt<u128>);
+
+impl SampleUniform for Ipv6Addr {
+	type Sampler = UniformIpv6Addr;
+}
+impl UniformSampler<Ipv6Addr> for UniformIpv6Addr {
+	#[inline]
+	fn new(low: Ipv6Addr, high: Ipv6Addr) -> UniformIpv6Addr {
+		UniformIpv6Addr(UniformInt::new(u128::from(low), u128::from(high)))
+	}
+	#[inline]
+	fn new_inclusive(low: Ipv6Addr, high: Ipv6Addr) -> UniformIpv6Addr {
+		UniformIpv6Addr(UniformInt::new_inclusive(u128::from(low), u128::from(high)))
+	}
+}
+impl Distribution<Ipv6Addr> for UniformIpv6Addr {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> Ipv6Addr {
+		Ipv6Addr::from(self.0.sample(rng))
+	}
+}
+
+/// Sample addresses uniformly within a subnet.
+///
+/// The subnet is given by an address and a prefix length, the host bits of the address are ignored.
+///
+/// When `exclude_ends` is set the lowest (network) and highest (broadcast) addresses of the subnet are never sampled.
+/// This has no effect on subnets with fewer than four addresses (eg. IPv4 `/31` and `/32`, IPv6 `/127` and `/128`)
+/// as these do not reserve such addresses.
+///
+/// # Examples
+///
+/// ```
+/// use std::net::Ipv4Addr;
+/// use urandom::distributions::Subnet;
+///
+/// let mut rng = urandom::new();
+/// let subnet = Subnet::v4(Ipv4Addr::new(192, 168, 1, 0), 24, true);
+/// let addr = rng.sample(&subnet);
+/// assert!(addr.octets()[3] != 0 && addr.octets()[3] != 255);
+/// ```
+pub struct Subnet<T: SampleUniform>(T::Sampler);
+
+impl<T: SampleUniform> Copy for Subnet<T> where T::Sampler: Copy {}
+impl<T: SampleUniform> Clone for Subnet<T> where T::Sampler: Clone {
+	#[inline]
+	fn clone(&self) -> Subnet<T> {
+		Subnet(self.0.clone())
+	}
+}
+impl<T: SampleUniform> fmt::Debug for Subnet<T> where T::Sampler: fmt::Debug {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_tuple("Subnet").field(&self.0).finish()
+	}
+}
+
+impl Subnet<Ipv4Addr> {
+	/// Constructs a new IPv4 subnet.
+	///
+	/// # Panics
+	///
+	/// Panics if `prefix_len` is greater than 32.
+	#[inline]
+	pub fn v4(addr: Ipv4Addr, prefix_len: u32, exclude_ends: bool) -> Subnet<Ipv4Addr> {
+		if prefix_len > 32 {
+			subnet_prefix_error(prefix_len, 32);
+		}
+		let mask = u32::MAX.checked_shl(32 - prefix_len).unwrap_or(0);
+		let mut low = u32::from(addr) & mask;
+		let mut high = low | !mask;
+		if exclude_ends && prefix_len < 31 {
+			low += 1;
+			high -= 1;
+		}
+		Subnet(UniformIpv4Addr::new_inclusive(Ipv4Addr::from(low), Ipv4Addr::from(high)))
+	}
+}
+impl Subnet<Ipv6Addr> {
+	/// Constructs a new IPv6 subnet.
+	///
+	/// # Panics
+	///
+	/// Panics if `prefix_len` is greater than 128.
+	#[inline]
+	pub fn v6(addr: Ipv6Addr, prefix_len: u32, exclude_ends: bool) -> Subnet<Ipv6Addr> {
+		if prefix_len > 128 {
+			subnet_prefix_error(prefix_len, 128);
+		}
+		let mask = u128::MAX.checked_shl(128 - prefix_len).unwrap_or(0);
+		let mut low = u128::from(addr) & mask;
+		let mut high = low | !mask;
+		if exclude_ends && prefix_len < 127 {
+			low += 1;
+			high -= 1;
+		}
+		Subnet(UniformIpv6Addr::new_inclusive(Ipv6Addr::from(low), Ipv6Addr::from(high)))
+	}
+}
+
+impl<T: SampleUniform> Distribution<T> for Subnet<T> {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> T {
+		self.0.sample(rng)
+	}
+}
+
+#[cold]
+fn subnet_prefix_error(prefix_len: u32, max: u32) -> ! {
+	panic!("Subnet constructed with prefix length {} greater than {}", prefix_len, max);
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_subnet_v4() {
+	let mut rng = crate::new();
+	let addr = Ipv4Addr::new(172, 16, 254, 77);
+	for prefix_len in [0, 1, 8, 12, 24, 30, 31, 32] {
+		let mask = u32::MAX.checked_shl(32 - prefix_len).unwrap_or(0);
+		let network = u32::from(addr) & mask;
+		let subnet = Subnet::v4(addr, prefix_len, false);
+		for _ in 0..1000 {
+			let value = u32::from(rng.sample(&subnet));
+			assert_eq!(value & mask, network, "prefix_len:{} value:{:#x}", prefix_len, value);
+		}
+	}
+
+	let single = Subnet::v4(addr, 32, true);
+	for _ in 0..100 {
+		assert_eq!(rng.sample(&single), addr);
+	}
+}
+
+#[test]
+fn test_subnet_v4_exclude() {
+	let mut rng = crate::new();
+	let subnet = Subnet::v4(Ipv4Addr::new(10, 1, 2, 3), 30, true);
+	for _ in 0..1000 {
+		let value = rng.sample(&subnet);
+		assert!(value == Ipv4Addr::new(10, 1, 2, 1) || value == Ipv4Addr::new(10, 1, 2, 2), "value: {}", value);
+	}
+	let subnet = Subnet::v4(Ipv4Addr::new(10, 1, 2, 3), 24, true);
+	for _ in 0..10000 {
+		let value = rng.sample(&subnet);
+		assert!(value != Ipv4Addr::new(10, 1, 2, 0) && value != Ipv4Addr::new(10, 1, 2, 255), "value: {}", value);
+	}
+}
+
+#[test]
+fn test_subnet_v6() {
+	let mut rng = crate::new();
+	let addr = Ipv6Addr::new(0x2001, 0xdb8, 0x85a3, 0, 0, 0x8a2e, 0x370, 0x7334);
+	for prefix_len in [0, 32, 64, 100, 126, 127, 128] {
+		let mask = u128::MAX.checked_shl(128 - prefix_len).unwrap_or(0);
+		let network = u128::from(addr) & mask;
+		let subnet = Subnet::v6(addr, prefix_len, false);
+		for _ in 0..1000 {
+			let value = u128::from(rng.sample(&subnet));
+			assert_eq!(value & mask, network, "prefix_len:{} value:{:#x}", prefix_len, value);
+		}
+	}
+
+	let subnet = Subnet::v6(addr, 126, true);
+	for _ in 0..1000 {
+		let value = u128::from(rng.sample(&subnet));
+		assert!(value & 3 == 1 || value & 3 == 2, "value: {:#x}", value);
+	}
+}
+
+#[test]
+fn test_standard() {
+	let mut rng = crate::seeded(42);
+	let a: Ipv4Addr = rng.next();
+	let b: Ipv4Addr = rng.next();
+	assert_ne!(a, b);
+	let c: Ipv6Addr = rng.next();
+	let d: Ipv6Addr = rng.next();
+	assert_ne!(c, d);
+}
@@ -0,0 +1,235 @@
+use core::fmt;
+use crate::{Distribution, Random, Rng};
+use crate::distributions::Float01;
+
+/// Sample the `k`-th smallest of `n` independent values directly, known as the [order statistic](https://en.wikipedia.org/wiki/Order_statistic).
+///
+/// Generating the order statistic directly costs the same regardless of `n`, instead of sampling and sorting `n` values.
+///
+/// # Implementation notes
+///
+/// The `k`-th smallest of `n` standard uniform values follows the Beta distribution `Beta(k, n + 1 - k)`.
+/// The minimum and maximum use the closed form inverse CDFs `1 - (1 - u)^(1/n)` and `u^(1/n)`.
+/// Other ranks are sampled as `X / (X + Y)` from the gamma variates `X ~ Gamma(k)` and `Y ~ Gamma(n + 1 - k)`,
+/// generated with the Marsaglia–Tsang method.
+///
+/// The `k`-th smallest of `n` exponential values with rate `λ` is the sum of the first `k` spacings `Eᵢ / (λ·(n - i + 1))`
+/// where `Eᵢ` are independent standard exponential values.
+/// For `k` greater than 16 the spacings are replaced by the inverse CDF `-ln(1 - U) / λ` of the uniform order statistic `U`.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::OrderStatistic;
+///
+/// let mut rng = urandom::new();
+/// let min = rng.sample(&OrderStatistic::uniform(1, 1_000_000));
+/// assert!(min > 0.0 && min < 1.0);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct OrderStatistic {
+	k: u64,
+	n: u64,
+	// Rate of the exponential distribution, or NaN for the uniform distribution
+	lambda: f64,
+}
+
+/// Error type returned from [`OrderStatistic`](OrderStatistic) constructors.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum OrderStatisticError {
+	/// The rank `k` is not in the closed interval `[1, n]`.
+	InvalidRank,
+	/// The rate is not positive and finite.
+	InvalidRate,
+}
+
+impl fmt::Display for OrderStatisticError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match self {
+			OrderStatisticError::InvalidRank => "k is not in [1, n] in order statistic",
+			OrderStatisticError::InvalidRate => "rate is not positive and finite in order statistic",
+		})
+	}
+}
+
+impl std::error::Error for OrderStatisticError {}
+
+impl OrderStatistic {
+	/// Constructs the distribution of the `k`-th smallest of `n` uniform values in `(0, 1)`.
+	///
+	/// # Panics
+	///
+	/// Panics if `k` is not in `[1, n]`.
+	#[inline]
+	pub fn uniform(k: u64, n: u64) -> OrderStatistic {
+		match OrderStatistic::try_uniform(k, n) {
+			Ok(distr) => distr,
+			Err(err) => order_statistic_error(err),
+		}
+	}
+	/// Constructs the distribution of the `k`-th smallest of `n` uniform values in `(0, 1)`.
+	///
+	/// Returns an error if `k` is not in `[1, n]`.
+	#[inline]
+	pub fn try_uniform(k: u64, n: u64) -> Result<OrderStatistic, OrderStatisticError> {
+		if k == 0 || k > n {
+			return Err(OrderStatisticError::InvalidRank);
+		}
+		Ok(OrderStatistic { k, n, lambda: f64::NAN })
+	}
+	/// Constructs the distribution of the `k`-th smallest of `n` exponential values with rate `lambda`.
+	///
+	/// # Panics
+	///
+	/// Panics if `k` is not in `[1, n]` or `lambda` is not positive and finite.
+	#[inline]
+	pub fn exponential(k: u64, n: u64, lambda: f64) -> OrderStatistic {
+		match OrderStatistic::try_exponential(k, n, lambda) {
+			Ok(distr) => distr,
+			Err(err) => order_statistic_error(err),
+		}
+	}
+	/// Constructs the distribution of the `k`-th smallest of `n` exponential values with rate `lambda`.
+	///
+	/// Returns an error if `k` is not in `[1, n]` or `lambda` is not positive and finite.
+	#[inline]
+	pub fn try_exponential(k: u64, n: u64, lambda: f64) -> Result<OrderStatistic, OrderStatisticError> {
+		if k == 0 || k > n {
+			return Err(OrderStatisticError::InvalidRank);
+		}
+		if !(lambda > 0.0 && lambda.is_finite()) {
+			return Err(OrderStatisticError::InvalidRate);
+		}
+		Ok(OrderStatistic { k, n, lambda })
+	}
+	/// Returns the rank `k`.
+	#[inline]
+	pub fn k(&self) -> u64 {
+		self.k
+	}
+	/// Returns the number of values `n`.
+	#[inline]
+	pub fn n(&self) -> u64 {
+		self.n
+	}
+
+	fn sample_uniform<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> f64 {
+		let n = self.n as f64;
+		if self.k == 1 {
+			let u: f64 = Float01.sample(rng);
+			// 1 - (1 - u)^(1/n) without cancellation for small results
+			-f64::exp_m1(f64::ln_1p(-u) / n)
+		}
+		else if self.k == self.n {
+			let u: f64 = Float01.sample(rng);
+			f64::exp(u.ln() / n)
+		}
+		else {
+			let x = super::gaussian::standard_gamma(rng, self.k as f64);
+			let y = super::gaussian::standard_gamma(rng, (self.n + 1 - self.k) as f64);
+			x / (x + y)
+		}
+	}
+}
+
+impl Distribution<f64> for OrderStatistic {
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> f64 {
+		if self.lambda.is_nan() {
+			return self.sample_uniform(rng);
+		}
+		if self.k <= 16 {
+			let mut sum = 0.0;
+			for i in 0..self.k {
+				let u: f64 = Float01.sample(rng);
+				sum -= u.ln() / (self.n - i) as f64;
+			}
+			sum / self.lambda
+		}
+		else {
+			-f64::ln_1p(-self.sample_uniform(rng)) / self.lambda
+		}
+	}
+}
+
+#[cold]
+fn order_statistic_error(err: OrderStatisticError) -> ! {
+	panic!("OrderStatistic called with invalid parameters: {}", err)
+}
+
+//----------------------------------------------------------------
+
+#[cfg(test)]
+fn check_cdf(distr: &OrderStatistic, cdf: impl Fn(f64) -> f64, points: &[f64]) {
+	let mut rng = crate::new();
+	const SAMPLES: usize = 100000;
+	let samples: Vec<f64> = (0..SAMPLES).map(|_| rng.sample(distr)).collect();
+	for &x in points {
+		let empirical = samples.iter().filter(|&&sample| sample <= x).count() as f64 / SAMPLES as f64;
+		assert!((empirical - cdf(x)).abs() < 0.01, "{:?} x: {} empirical: {} expected: {}", distr, x, empirical, cdf(x));
+	}
+}
+
+#[test]
+fn test_uniform_min() {
+	let n = 1000;
+	let distr = OrderStatistic::uniform(1, n);
+	check_cdf(&distr, |x| 1.0 - (1.0 - x).powi(n as i32), &[0.0001, 0.0005, 0.001, 0.002, 0.005]);
+
+	// A million values
+	let mut rng = crate::new();
+	let distr = OrderStatistic::uniform(1, 1_000_000);
+	let mean = (0..10000).map(|_| rng.sample(&distr)).sum::<f64>() / 10000.0;
+	assert!((mean * 1e6 - 1.0).abs() < 0.05, "mean: {}", mean);
+}
+
+#[test]
+fn test_uniform_max() {
+	let n = 50;
+	let distr = OrderStatistic::uniform(n, n);
+	check_cdf(&distr, |x| x.powi(n as i32), &[0.9, 0.95, 0.98, 0.99]);
+
+	// The maximum mirrors the minimum
+	let min = OrderStatistic::uniform(1, n);
+	check_cdf(&min, |x| 1.0 - (1.0 - x).powi(n as i32), &[0.01, 0.02, 0.05, 0.1]);
+}
+
+#[test]
+fn test_uniform_median() {
+	// Median of three: CDF is 3x² - 2x³
+	let distr = OrderStatistic::uniform(2, 3);
+	check_cdf(&distr, |x| 3.0 * x * x - 2.0 * x * x * x, &[0.1, 0.25, 0.5, 0.75, 0.9]);
+
+	// Mean of the k-th order statistic is k / (n + 1)
+	let mut rng = crate::new();
+	let distr = OrderStatistic::uniform(300, 1000);
+	let mean = (0..10000).map(|_| rng.sample(&distr)).sum::<f64>() / 10000.0;
+	assert!((mean - 300.0 / 1001.0).abs() < 0.002, "mean: {}", mean);
+}
+
+#[test]
+fn test_exponential() {
+	// The minimum of n exponentials is exponential with rate n·λ
+	let distr = OrderStatistic::exponential(1, 10, 2.0);
+	check_cdf(&distr, |x| 1.0 - f64::exp(-20.0 * x), &[0.01, 0.05, 0.1, 0.2]);
+
+	// Mean of the k-th order statistic is Σ 1 / (λ·(n - i)) for i in 0..k
+	let mut rng = crate::new();
+	for &k in &[5, 40] {
+		let distr = OrderStatistic::exponential(k, 50, 0.5);
+		let expected: f64 = (0..k).map(|i| 1.0 / (0.5 * (50 - i) as f64)).sum();
+		let mean = (0..20000).map(|_| rng.sample(&distr)).sum::<f64>() / 20000.0;
+		assert!((mean - expected).abs() < 0.02 * expected, "k: {} mean: {} expected: {}", k, mean, expected);
+	}
+}
+
+#[test]
+fn test_validation() {
+	assert_eq!(OrderStatistic::try_uniform(0, 10).unwrap_err(), OrderStatisticError::InvalidRank);
+	assert_eq!(OrderStatistic::try_uniform(11, 10).unwrap_err(), OrderStatisticError::InvalidRank);
+	assert_eq!(OrderStatistic::try_uniform(1, 0).unwrap_err(), OrderStatisticError::InvalidRank);
+	assert!(OrderStatistic::try_uniform(1, 1).is_ok());
+	assert_eq!(OrderStatistic::try_exponential(0, 10, 1.0).unwrap_err(), OrderStatisticError::InvalidRank);
+	assert_eq!(OrderStatistic::try_exponential(1, 10, 0.0).unwrap_err(), OrderStatisticError::InvalidRate);
+	assert_eq!(OrderStatistic::try_exponential(1, 10, f64::NAN).unwrap_err(), OrderStatisticError::InvalidRate);
+	assert_eq!(OrderStatistic::try_exponential(1, 10, f64::INFINITY).unwrap_err(), OrderStatisticError::InvalidRate);
+}
@@ -0,0 +1,143 @@
+use core::iter;
+use crate::hash::mix64;
+
+/// An iterator over all values in `0..n` exactly once in random order.
+///
+/// This struct is created by the [`Random::permuted_range`](crate::Random::permuted_range) method. See its documentation for more.
+///
+/// The permutation is a small-domain cipher: a 4-round Feistel network over the smallest even number of bits that can represent `n - 1`,
+/// with cycle-walking to map values outside of `0..n` back into the range.
+/// Each value is computed independently from its position which makes `nth` and iterating from the back efficient.
+///
+/// The permutation is not cryptographically secure.
+#[derive(Clone, Debug)]
+pub struct PermutedRange {
+	keys: [u64; 4],
+	half_bits: u32,
+	n: u64,
+	front: u64,
+	back: u64,
+}
+
+impl PermutedRange {
+	#[inline]
+	pub(crate) fn new(keys: [u64; 4], n: u64) -> PermutedRange {
+		let bits = 64 - n.saturating_sub(1).leading_zeros();
+		let half_bits = (bits + 1) / 2;
+		PermutedRange { keys, half_bits, n, front: 0, back: n }
+	}
+
+	// Feistel permutation over the domain of 2 * half_bits
+	#[inline]
+	fn feistel(&self, x: u64) -> u64 {
+		let mask = (1u64 << self.half_bits) - 1;
+		let mut left = x >> self.half_bits;
+		let mut right = x & mask;
+		for &key in &self.keys {
+			let next = left ^ (mix64(right ^ key) & mask);
+			left = right;
+			right = next;
+		}
+		left << self.half_bits | right
+	}
+
+	// Cycle-walk until the value lands in the range
+	#[inline]
+	fn permute(&self, index: u64) -> u64 {
+		let mut x = self.feistel(index);
+		while x >= self.n {
+			x = self.feistel(x);
+		}
+		x
+	}
+}
+
+impl Iterator for PermutedRange {
+	type Item = u64;
+	#[inline]
+	fn next(&mut self) -> Option<u64> {
+		if self.front >= self.back {
+			return None;
+		}
+		let value = self.permute(self.front);
+		self.front += 1;
+		Some(value)
+	}
+	#[inline]
+	fn nth(&mut self, n: usize) -> Option<u64> {
+		self.front = u64::min(self.front.saturating_add(n as u64), self.back);
+		self.next()
+	}
+	#[inline]
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		let len = self.back - self.front;
+		match usize::try_from(len) {
+			Ok(len) => (len, Some(len)),
+			Err(_) => (usize::MAX, None),
+		}
+	}
+	#[inline]
+	fn count(self) -> usize {
+		(self.back - self.front) as usize
+	}
+}
+
+impl DoubleEndedIterator for PermutedRange {
+	#[inline]
+	fn next_back(&mut self) -> Option<u64> {
+		if self.front >= self.back {
+			return None;
+		}
+		self.back -= 1;
+		Some(self.permute(self.back))
+	}
+}
+
+impl ExactSizeIterator for PermutedRange {}
+impl iter::FusedIterator for PermutedRange {}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_bijective() {
+	let mut rng = crate::new();
+	for n in (0..=70).chain([127, 128, 129, 1000, 1 << 12, (1 << 12) + 1, 100000]) {
+		let iter = rng.permuted_range(n);
+		assert_eq!(iter.len(), n as usize);
+		let mut values: Vec<u64> = iter.collect();
+		values.sort_unstable();
+		assert!(values.iter().copied().eq(0..n), "n: {}", n);
+	}
+}
+
+#[test]
+fn test_nth_and_back() {
+	let mut rng = crate::new();
+	let iter = rng.permuted_range(1000);
+	let values: Vec<u64> = iter.clone().collect();
+
+	let mut nth = iter.clone();
+	assert_eq!(nth.nth(10), Some(values[10]));
+	assert_eq!(nth.nth(500), Some(values[511]));
+	assert_eq!(nth.len(), 1000 - 512);
+	assert_eq!(nth.nth(1000), None);
+	assert_eq!(nth.next(), None);
+
+	let reversed: Vec<u64> = iter.clone().rev().collect();
+	assert!(reversed.iter().rev().eq(values.iter()));
+}
+
+#[test]
+fn test_first_uniform() {
+	let mut rng = crate::new();
+	for &n in &[3u64, 10, 17] {
+		let mut buckets = [0i32; 17];
+		const N: i32 = 17000;
+		for _ in 0..N {
+			let first = rng.permuted_range(n).next().unwrap();
+			buckets[first as usize] += 1;
+		}
+		let expected = N / n as i32;
+		assert!(buckets[..n as usize].iter().all(|&count| (count - expected).abs() < expected / 5), "n: {} buckets: {:?}", n, buckets);
+	}
+}
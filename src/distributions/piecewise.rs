@@ -0,0 +1,360 @@
+use core::fmt;
+use crate::{Distribution, Random, Rng};
+use crate::distributions::Float01;
+
+/// Piecewise constant distribution, a histogram with bin edges and densities.
+///
+/// A bin is sampled with probability proportional to its area `density·width`, then a value is sampled uniformly within the bin.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::PiecewiseConstant;
+///
+/// let edges = [0.0, 1.0, 2.0, 5.0];
+/// let densities = [1.0, 0.0, 2.0];
+/// let distr = PiecewiseConstant::new(&edges, &densities);
+///
+/// let mut rng = urandom::new();
+/// let value = rng.sample(&distr);
+/// assert!(value >= 0.0 && value <= 1.0 || value >= 2.0 && value <= 5.0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct PiecewiseConstant {
+	edges: Vec<f64>,
+	densities: Vec<f64>,
+	// Cumulative area up to and including each bin
+	cumulative: Vec<f64>,
+}
+
+/// Piecewise linear distribution, the density is linearly interpolated between points.
+///
+/// A segment is sampled with probability proportional to its area, then a value is sampled within the segment by inverting its quadratic CDF.
+///
+/// # Implementation notes
+///
+/// Within a segment of width `w` starting with density `d₀` and slope `m` the area up to offset `s` is `d₀·s + m·s²/2`.
+/// The offset for area `a` is computed as `2a / (d₀ + sqrt(d₀² + 2m·a))`, which is stable for zero and negative slopes.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::PiecewiseLinear;
+///
+/// // Triangular distribution peaking at 1.0
+/// let distr = PiecewiseLinear::new(&[(0.0, 0.0), (1.0, 1.0), (3.0, 0.0)]);
+///
+/// let mut rng = urandom::new();
+/// let value = rng.sample(&distr);
+/// assert!(value >= 0.0 && value <= 3.0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct PiecewiseLinear {
+	xs: Vec<f64>,
+	densities: Vec<f64>,
+	// Cumulative area up to and including each segment
+	cumulative: Vec<f64>,
+}
+
+/// Error type returned from [`PiecewiseConstant`](PiecewiseConstant) and [`PiecewiseLinear`](PiecewiseLinear) constructors.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PiecewiseError {
+	/// There are no bins or segments.
+	Empty,
+	/// The number of edges is not one more than the number of densities.
+	LengthMismatch,
+	/// The edges are not finite and strictly increasing.
+	InvalidEdges,
+	/// A density is negative or not finite.
+	InvalidDensity,
+	/// The total area is zero or not finite.
+	InvalidTotal,
+}
+
+impl fmt::Display for PiecewiseError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match self {
+			PiecewiseError::Empty => "piecewise distribution has no bins",
+			PiecewiseError::LengthMismatch => "piecewise distribution needs one more edge than densities",
+			PiecewiseError::InvalidEdges => "piecewise distribution edges are not finite and strictly increasing",
+			PiecewiseError::InvalidDensity => "piecewise distribution density is negative or not finite",
+			PiecewiseError::InvalidTotal => "piecewise distribution total area is zero or not finite",
+		})
+	}
+}
+
+impl std::error::Error for PiecewiseError {}
+
+fn check_edges(edges: &[f64]) -> Result<(), PiecewiseError> {
+	if !edges.iter().all(|x| x.is_finite()) || !edges.windows(2).all(|pair| pair[0] < pair[1]) {
+		return Err(PiecewiseError::InvalidEdges);
+	}
+	Ok(())
+}
+
+fn check_densities(densities: &[f64]) -> Result<(), PiecewiseError> {
+	if !densities.iter().all(|&density| density >= 0.0 && density.is_finite()) {
+		return Err(PiecewiseError::InvalidDensity);
+	}
+	Ok(())
+}
+
+fn cumulative<I: Iterator<Item = f64>>(areas: I) -> Result<Vec<f64>, PiecewiseError> {
+	let mut total = 0.0;
+	let cumulative: Vec<f64> = areas.map(|area| { total += area; total }).collect();
+	if !(total > 0.0 && total.is_finite()) {
+		return Err(PiecewiseError::InvalidTotal);
+	}
+	Ok(cumulative)
+}
+
+/// Samples a bin by area, returns its index and the area offset within the bin.
+#[inline]
+fn sample_bin<R: Rng + ?Sized>(cumulative: &[f64], rng: &mut Random<R>) -> (usize, f64) {
+	let total = cumulative[cumulative.len() - 1];
+	let u: f64 = Float01.sample(rng);
+	let target = u * total;
+	// Zero area bins are skipped as their cumulative area equals that of the previous bin
+	let index = usize::min(cumulative.partition_point(|&area| area <= target), cumulative.len() - 1);
+	let start = if index == 0 { 0.0 } else { cumulative[index - 1] };
+	(index, f64::max(target - start, 0.0))
+}
+
+impl PiecewiseConstant {
+	/// Constructs a new piecewise constant distribution from the bin edges and densities.
+	///
+	/// # Panics
+	///
+	/// Panics if the input is invalid, see [`try_new`](PiecewiseConstant::try_new).
+	#[inline]
+	pub fn new(edges: &[f64], densities: &[f64]) -> PiecewiseConstant {
+		match PiecewiseConstant::try_new(edges, densities) {
+			Ok(distr) => distr,
+			Err(err) => piecewise_error(err),
+		}
+	}
+	/// Constructs a new piecewise constant distribution from the bin edges and densities.
+	///
+	/// Returns an error if there are no bins, there is not exactly one more edge than densities,
+	/// the edges are not finite and strictly increasing, a density is negative or not finite, or all densities are zero.
+	pub fn try_new(edges: &[f64], densities: &[f64]) -> Result<PiecewiseConstant, PiecewiseError> {
+		if densities.is_empty() {
+			return Err(PiecewiseError::Empty);
+		}
+		if edges.len() != densities.len() + 1 {
+			return Err(PiecewiseError::LengthMismatch);
+		}
+		check_edges(edges)?;
+		check_densities(densities)?;
+		let cumulative = cumulative(densities.iter().zip(edges.windows(2)).map(|(&density, pair)| density * (pair[1] - pair[0])))?;
+		Ok(PiecewiseConstant { edges: edges.to_vec(), densities: densities.to_vec(), cumulative })
+	}
+	/// Returns the bin edges.
+	#[inline]
+	pub fn edges(&self) -> &[f64] {
+		&self.edges
+	}
+	/// Returns the bin densities.
+	#[inline]
+	pub fn densities(&self) -> &[f64] {
+		&self.densities
+	}
+	/// Returns the probability of sampling a value in the bin.
+	///
+	/// # Panics
+	///
+	/// Panics if the index is out of bounds.
+	#[inline]
+	pub fn bin_probability(&self, index: usize) -> f64 {
+		let start = if index == 0 { 0.0 } else { self.cumulative[index - 1] };
+		(self.cumulative[index] - start) / self.cumulative[self.cumulative.len() - 1]
+	}
+}
+
+impl Distribution<f64> for PiecewiseConstant {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> f64 {
+		let (index, offset) = sample_bin(&self.cumulative, rng);
+		let (x0, x1) = (self.edges[index], self.edges[index + 1]);
+		f64::min(x0 + offset / self.densities[index], x1)
+	}
+}
+
+impl PiecewiseLinear {
+	/// Constructs a new piecewise linear distribution from `(x, density)` points.
+	///
+	/// # Panics
+	///
+	/// Panics if the input is invalid, see [`try_new`](PiecewiseLinear::try_new).
+	#[inline]
+	pub fn new(points: &[(f64, f64)]) -> PiecewiseLinear {
+		match PiecewiseLinear::try_new(points) {
+			Ok(distr) => distr,
+			Err(err) => piecewise_error(err),
+		}
+	}
+	/// Constructs a new piecewise linear distribution from `(x, density)` points.
+	///
+	/// Returns an error if there are fewer than two points, the `x` coordinates are not finite and strictly increasing,
+	/// a density is negative or not finite, or all densities are zero.
+	pub fn try_new(points: &[(f64, f64)]) -> Result<PiecewiseLinear, PiecewiseError> {
+		if points.len() < 2 {
+			return Err(PiecewiseError::Empty);
+		}
+		let xs: Vec<f64> = points.iter().map(|&(x, _)| x).collect();
+		let densities: Vec<f64> = points.iter().map(|&(_, density)| density).collect();
+		check_edges(&xs)?;
+		check_densities(&densities)?;
+		let areas = xs.windows(2).zip(densities.windows(2)).map(|(x, d)| 0.5 * (d[0] + d[1]) * (x[1] - x[0]));
+		let cumulative = cumulative(areas)?;
+		Ok(PiecewiseLinear { xs, densities, cumulative })
+	}
+	/// Returns the smallest value which can be sampled.
+	#[inline]
+	pub fn low(&self) -> f64 {
+		self.xs[0]
+	}
+	/// Returns the largest value which can be sampled.
+	#[inline]
+	pub fn high(&self) -> f64 {
+		self.xs[self.xs.len() - 1]
+	}
+}
+
+impl Distribution<f64> for PiecewiseLinear {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> f64 {
+		let (index, area) = sample_bin(&self.cumulative, rng);
+		let (x0, x1) = (self.xs[index], self.xs[index + 1]);
+		let (d0, d1) = (self.densities[index], self.densities[index + 1]);
+		let slope = (d1 - d0) / (x1 - x0);
+		let denom = d0 + f64::max(d0 * d0 + 2.0 * slope * area, 0.0).sqrt();
+		if denom <= 0.0 {
+			return x0;
+		}
+		f64::min(x0 + 2.0 * area / denom, x1)
+	}
+}
+
+#[cold]
+fn piecewise_error(err: PiecewiseError) -> ! {
+	panic!("piecewise distribution constructed with invalid parameters: {}", err)
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_constant_weights() {
+	let mut rng = crate::seeded(42);
+	let edges = [-1.0, 0.0, 0.5, 2.0, 2.5, 10.0];
+	let densities = [1.0, 0.0, 2.0, 4.0, 0.1];
+	let distr = PiecewiseConstant::new(&edges, &densities);
+	let expected = [1.0, 0.0, 3.0, 2.0, 0.75].map(|area| area / 6.75);
+	for (i, &p) in expected.iter().enumerate() {
+		assert!((distr.bin_probability(i) - p).abs() < 1e-15, "bin: {}", i);
+	}
+
+	const N: usize = 200000;
+	let mut counts = [0usize; 5];
+	for _ in 0..N {
+		let x = distr.sample(&mut rng);
+		assert!((-1.0..=10.0).contains(&x), "x: {}", x);
+		let bin = edges.partition_point(|&edge| edge <= x) - 1;
+		counts[usize::min(bin, 4)] += 1;
+	}
+	assert_eq!(counts[1], 0);
+	for (i, &p) in expected.iter().enumerate() {
+		let expected = p * N as f64;
+		assert!((counts[i] as f64 - expected).abs() < 4.0 * expected.sqrt() + 1.0, "bin: {} count: {} expected: {}", i, counts[i], expected);
+	}
+
+	// Uniform within a bin
+	let mut halves = [0usize; 2];
+	for _ in 0..N {
+		let x = distr.sample(&mut rng);
+		if (0.5..2.0).contains(&x) {
+			halves[(x >= 1.25) as usize] += 1;
+		}
+	}
+	let diff = halves[0] as f64 - halves[1] as f64;
+	assert!(diff.abs() < 4.0 * ((halves[0] + halves[1]) as f64).sqrt(), "{:?}", halves);
+}
+
+#[test]
+fn test_constant_single_bin() {
+	let mut rng = crate::seeded(42);
+	let distr = PiecewiseConstant::new(&[3.0, 5.0], &[0.25]);
+	let mut sum = 0.0;
+	for _ in 0..10000 {
+		let x = distr.sample(&mut rng);
+		assert!((3.0..=5.0).contains(&x));
+		sum += x;
+	}
+	assert!((sum / 10000.0 - 4.0).abs() < 0.03);
+
+	// Zero density bins at the ends are never sampled
+	let distr = PiecewiseConstant::new(&[0.0, 1.0, 2.0, 3.0], &[0.0, 7.0, 0.0]);
+	for _ in 0..10000 {
+		let x = distr.sample(&mut rng);
+		assert!((1.0..=2.0).contains(&x), "x: {}", x);
+	}
+}
+
+#[test]
+fn test_linear_cdf() {
+	let mut rng = crate::seeded(42);
+	// Rising segment with density 2x has CDF x²
+	let distr = PiecewiseLinear::new(&[(0.0, 0.0), (1.0, 2.0)]);
+	const N: usize = 200000;
+	let samples: Vec<f64> = (0..N).map(|_| distr.sample(&mut rng)).collect();
+	assert!(samples.iter().all(|&x| (0.0..=1.0).contains(&x)));
+	for &q in &[0.1, 0.25, 0.5, 0.75, 0.9] {
+		let below = samples.iter().filter(|&&x| x <= q).count() as f64 / N as f64;
+		assert!((below - q * q).abs() < 0.005, "q: {} below: {}", q, below);
+	}
+
+	// Scaling the densities does not change the distribution, falling segments mirror rising ones
+	let distr = PiecewiseLinear::new(&[(0.0, 6.0), (1.0, 0.0)]);
+	for &q in &[0.1, 0.5, 0.9] {
+		let below = (0..N).filter(|_| distr.sample(&mut rng) <= q).count() as f64 / N as f64;
+		let expected = 1.0 - (1.0 - q) * (1.0 - q);
+		assert!((below - expected).abs() < 0.005, "q: {} below: {}", q, below);
+	}
+}
+
+#[test]
+fn test_linear_segments() {
+	let mut rng = crate::seeded(42);
+	// Flat segment, zero area segment and trapezoid
+	let distr = PiecewiseLinear::new(&[(0.0, 1.0), (1.0, 1.0), (2.0, 0.0), (3.0, 0.0), (4.0, 2.0), (5.0, 4.0)]);
+	assert_eq!((distr.low(), distr.high()), (0.0, 5.0));
+	// Areas: 1, 0.5, 0, 1, 3
+	const N: usize = 110000;
+	let mut counts = [0usize; 5];
+	for _ in 0..N {
+		let x = distr.sample(&mut rng);
+		assert!((0.0..=5.0).contains(&x));
+		counts[usize::min(x as usize, 4)] += 1;
+	}
+	assert_eq!(counts[2], 0);
+	for (i, &area) in [1.0, 0.5, 0.0, 1.0, 3.0].iter().enumerate() {
+		let expected = area / 5.5 * N as f64;
+		assert!((counts[i] as f64 - expected).abs() < 4.0 * expected.sqrt() + 1.0, "segment: {} count: {} expected: {}", i, counts[i], expected);
+	}
+}
+
+#[test]
+fn test_validation() {
+	assert_eq!(PiecewiseConstant::try_new(&[0.0], &[]).unwrap_err(), PiecewiseError::Empty);
+	assert_eq!(PiecewiseConstant::try_new(&[0.0, 1.0], &[1.0, 1.0]).unwrap_err(), PiecewiseError::LengthMismatch);
+	assert_eq!(PiecewiseConstant::try_new(&[0.0, 0.0], &[1.0]).unwrap_err(), PiecewiseError::InvalidEdges);
+	assert_eq!(PiecewiseConstant::try_new(&[0.0, f64::INFINITY], &[1.0]).unwrap_err(), PiecewiseError::InvalidEdges);
+	assert_eq!(PiecewiseConstant::try_new(&[0.0, 1.0], &[-1.0]).unwrap_err(), PiecewiseError::InvalidDensity);
+	assert_eq!(PiecewiseConstant::try_new(&[0.0, 1.0], &[f64::NAN]).unwrap_err(), PiecewiseError::InvalidDensity);
+	assert_eq!(PiecewiseConstant::try_new(&[0.0, 1.0, 2.0], &[0.0, 0.0]).unwrap_err(), PiecewiseError::InvalidTotal);
+	assert_eq!(PiecewiseConstant::try_new(&[0.0, 1e300], &[1e300]).unwrap_err(), PiecewiseError::InvalidTotal);
+	assert_eq!(PiecewiseLinear::try_new(&[(0.0, 1.0)]).unwrap_err(), PiecewiseError::Empty);
+	assert_eq!(PiecewiseLinear::try_new(&[(1.0, 1.0), (0.0, 1.0)]).unwrap_err(), PiecewiseError::InvalidEdges);
+	assert_eq!(PiecewiseLinear::try_new(&[(0.0, 1.0), (1.0, -0.5)]).unwrap_err(), PiecewiseError::InvalidDensity);
+	assert_eq!(PiecewiseLinear::try_new(&[(0.0, 0.0), (1.0, 0.0)]).unwrap_err(), PiecewiseError::InvalidTotal);
+}
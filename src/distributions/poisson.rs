@@ -0,0 +1,192 @@
+use core::fmt;
+use crate::{Distribution, Random, Rng};
+use crate::distributions::Float01;
+
+/// Sample from the [Poisson distribution](https://en.wikipedia.org/wiki/Poisson_distribution).
+///
+/// The distribution is parameterized by its rate `λ`, the expected number of events in an interval.
+/// Its mean and variance are `λ`.
+///
+/// # Implementation notes
+///
+/// For `λ < 10` the uniforms sampled with [`Float01`](Float01) are multiplied until the product drops below `e^-λ`, this takes `λ + 1` uniforms on average.
+///
+/// For `λ >= 10` samples are generated by the transformed rejection with squeeze (PTRS) of Hörmann,
+/// [_The transformed rejection method for generating Poisson random variables_](https://doi.org/10.1016/0167-6687(93)90997-4).
+/// It accepts about 9 in 10 candidates regardless of `λ`, each candidate takes two uniforms.
+///
+/// Sampling `f64` returns the same integer values as `u64`, sampling `u64` saturates for `λ` beyond the range of `u64`.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::Poisson;
+///
+/// let mut rng = urandom::new();
+/// let distr = Poisson::new(4.0);
+/// let arrivals: u64 = rng.sample(&distr);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Poisson {
+	lambda: f64,
+	method: Method,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Method {
+	Multiplication { exp_lambda: f64 },
+	Rejection { ln_lambda: f64, a: f64, b: f64, ln_inv_alpha: f64, vr: f64 },
+}
+
+/// Error type returned from [`Poisson`](Poisson) constructors.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PoissonError {
+	/// Lambda is not positive or not finite.
+	LambdaNotPositive,
+}
+
+impl fmt::Display for PoissonError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match self {
+			PoissonError::LambdaNotPositive => "lambda is not positive and finite in Poisson distribution",
+		})
+	}
+}
+
+impl std::error::Error for PoissonError {}
+
+impl Poisson {
+	/// Constructs a new `Poisson` distribution with the given rate.
+	///
+	/// # Panics
+	///
+	/// Panics if lambda is not positive and finite.
+	#[inline]
+	pub fn new(lambda: f64) -> Poisson {
+		match Poisson::try_new(lambda) {
+			Ok(distr) => distr,
+			Err(err) => poisson_error(err),
+		}
+	}
+	/// Constructs a new `Poisson` distribution with the given rate.
+	///
+	/// Returns an error if lambda is not positive and finite.
+	pub fn try_new(lambda: f64) -> Result<Poisson, PoissonError> {
+		if !(lambda > 0.0 && lambda.is_finite()) {
+			return Err(PoissonError::LambdaNotPositive);
+		}
+		let method = if lambda < 10.0 {
+			Method::Multiplication { exp_lambda: (-lambda).exp() }
+		}
+		else {
+			let b = 0.931 + 2.53 * lambda.sqrt();
+			let a = -0.059 + 0.02483 * b;
+			let inv_alpha = 1.1239 + 1.1328 / (b - 3.4);
+			let vr = 0.9277 - 3.6224 / (b - 2.0);
+			Method::Rejection { ln_lambda: lambda.ln(), a, b, ln_inv_alpha: inv_alpha.ln(), vr }
+		};
+		Ok(Poisson { lambda, method })
+	}
+	/// Returns the rate lambda.
+	#[inline]
+	pub fn lambda(&self) -> f64 {
+		self.lambda
+	}
+}
+
+#[cold]
+fn poisson_error(err: PoissonError) -> ! {
+	panic!("{}", err)
+}
+
+impl Distribution<f64> for Poisson {
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> f64 {
+		match self.method {
+			Method::Multiplication { exp_lambda } => {
+				let mut k = 0.0;
+				let mut product: f64 = Float01.sample(rng);
+				while product > exp_lambda {
+					k += 1.0;
+					let u: f64 = Float01.sample(rng);
+					product *= u;
+				}
+				k
+			},
+			Method::Rejection { ln_lambda, a, b, ln_inv_alpha, vr } => loop {
+				let u: f64 = Float01.sample(rng);
+				let v: f64 = Float01.sample(rng);
+				let u = u - 0.5;
+				let us = 0.5 - u.abs();
+				let k = ((2.0 * a / us + b) * u + self.lambda + 0.43).floor();
+				// Squeeze, accepts most candidates without evaluating the density
+				if us >= 0.07 && v <= vr {
+					return k;
+				}
+				if k < 0.0 || us < 0.013 && v > us {
+					continue;
+				}
+				if v.ln() + ln_inv_alpha - (a / (us * us) + b).ln() <= -self.lambda + k * ln_lambda - crate::stats::ln_gamma(k + 1.0) {
+					return k;
+				}
+			},
+		}
+	}
+}
+
+impl Distribution<u64> for Poisson {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> u64 {
+		Distribution::<f64>::sample(self, rng) as u64
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_moments() {
+	let mut rng = crate::seeded(42);
+	for &lambda in &[0.5, 4.0, 9.99, 10.0, 37.5, 1e4] {
+		let distr = Poisson::new(lambda);
+		const N: usize = 100000;
+		let samples: Vec<f64> = (0..N).map(|_| rng.sample(&distr)).collect();
+		assert!(samples.iter().all(|&k| k >= 0.0 && k.fract() == 0.0));
+		let mean = samples.iter().sum::<f64>() / N as f64;
+		let variance = samples.iter().map(|&k| (k - mean) * (k - mean)).sum::<f64>() / (N - 1) as f64;
+		// Five standard errors of the mean and of the variance
+		let mean_error = 5.0 * (lambda / N as f64).sqrt();
+		let variance_error = 5.0 * ((lambda + 2.0 * lambda * lambda) / N as f64).sqrt();
+		assert!((mean - lambda).abs() < mean_error, "lambda: {} mean: {}", lambda, mean);
+		assert!((variance - lambda).abs() < variance_error, "lambda: {} variance: {}", lambda, variance);
+	}
+}
+
+#[test]
+fn test_pmf() {
+	// Chi-squared test of the probabilities on both sides of the switch between the methods
+	// The tails beyond four standard deviations are lumped into the first and last bins
+	let mut rng = crate::seeded(42);
+	for &lambda in &[2.0f64, 10.0, 25.0] {
+		let distr = Poisson::new(lambda);
+		let low = f64::max(0.0, lambda - 4.0 * lambda.sqrt()) as u64;
+		let high = (lambda + 4.0 * lambda.sqrt()) as u64;
+		let mut counts = vec![0u64; (high - low + 1) as usize];
+		for _ in 0..200000 {
+			let k: u64 = rng.sample(&distr);
+			counts[(k.clamp(low, high) - low) as usize] += 1;
+		}
+		let pmf = |k: u64| (-lambda + k as f64 * lambda.ln() - crate::stats::ln_gamma(k as f64 + 1.0)).exp();
+		let mut probabilities: Vec<f64> = (low..high).map(pmf).collect();
+		probabilities[0] += (0..low).map(pmf).sum::<f64>();
+		probabilities.push(1.0 - probabilities.iter().sum::<f64>());
+		crate::stats::assert_counts(&counts, &probabilities, 1e-4);
+	}
+}
+
+#[test]
+fn test_errors() {
+	assert_eq!(Poisson::try_new(0.0), Err(PoissonError::LambdaNotPositive));
+	assert_eq!(Poisson::try_new(-1.0), Err(PoissonError::LambdaNotPositive));
+	assert_eq!(Poisson::try_new(f64::NAN), Err(PoissonError::LambdaNotPositive));
+	assert_eq!(Poisson::try_new(f64::INFINITY), Err(PoissonError::LambdaNotPositive));
+	assert_eq!(Poisson::new(3.5).lambda(), 3.5);
+}
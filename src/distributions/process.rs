@@ -0,0 +1,870 @@
+/*!
+Stateful random processes.
+
+Unlike distributions these keep state between samples, every sample depends on the previous ones.
+*/
+
+use core::fmt;
+use crate::{Distribution, Random, Rng};
+use crate::distributions::Float01;
+use super::gaussian::standard_normal_pair;
+
+/// Error type returned from the constructors of the processes in this module.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ProcessError {
+	/// The concentration parameter alpha is not positive and finite.
+	AlphaNotPositive,
+	/// A location parameter such as the starting value or the long-run mean is not finite.
+	NotFinite,
+	/// The mean reversion rate theta is not positive and finite.
+	ThetaNotPositive,
+	/// The volatility sigma is negative or not finite.
+	SigmaNegative,
+	/// The time step is not positive and finite.
+	DtNotPositive,
+	/// The transition matrix is empty or not square.
+	NotSquare,
+	/// A transition probability is negative or not finite.
+	InvalidProbability,
+	/// A row of the transition matrix does not sum to one.
+	RowSumNotOne,
+	/// The state is out of range.
+	StateOutOfRange,
+	/// The length of the heightline is not `2^k + 1` for some `k >= 0`.
+	InvalidLength,
+	/// The roughness or the initial range is negative or not finite.
+	InvalidScale,
+}
+
+impl fmt::Display for ProcessError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match self {
+			ProcessError::AlphaNotPositive => "alpha is not positive and finite in random process",
+			ProcessError::NotFinite => "location parameter is not finite in random process",
+			ProcessError::ThetaNotPositive => "theta is not positive and finite in random process",
+			ProcessError::SigmaNegative => "sigma is negative or not finite in random process",
+			ProcessError::DtNotPositive => "dt is not positive and finite in random process",
+			ProcessError::NotSquare => "transition matrix is empty or not square in random process",
+			ProcessError::InvalidProbability => "transition probability is negative or not finite in random process",
+			ProcessError::RowSumNotOne => "transition matrix row does not sum to one in random process",
+			ProcessError::StateOutOfRange => "state is out of range in random process",
+			ProcessError::InvalidLength => "length is not a power of two plus one in random process",
+			ProcessError::InvalidScale => "roughness or range is negative or not finite in random process",
+		})
+	}
+}
+
+impl std::error::Error for ProcessError {}
+
+#[inline]
+fn check_alpha(alpha: f64) -> Result<(), ProcessError> {
+	if !(alpha > 0.0 && alpha.is_finite()) {
+		return Err(ProcessError::AlphaNotPositive);
+	}
+	Ok(())
+}
+
+/// The [Chinese restaurant process](https://en.wikipedia.org/wiki/Chinese_restaurant_process).
+///
+/// Assigns items to clusters one at a time: the next item joins an existing cluster with probability proportional to its size,
+/// or starts a new cluster with probability proportional to the concentration `alpha`.
+///
+/// After `n` assignments the expected number of clusters is about `alpha·ln(1 + n/alpha)`.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::process::Crp;
+///
+/// let mut rng = urandom::new();
+/// let mut crp = Crp::new(2.0);
+/// let labels: Vec<usize> = (0..100).map(|_| crp.assign(&mut rng)).collect();
+/// assert_eq!(labels[0], 0);
+/// assert_eq!(crp.counts().iter().sum::<usize>(), 100);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Crp {
+	alpha: f64,
+	counts: Vec<usize>,
+	len: usize,
+}
+
+impl Crp {
+	/// Constructs a new Chinese restaurant process with the given concentration.
+	///
+	/// # Panics
+	///
+	/// Panics if `alpha` is not positive and finite.
+	#[inline]
+	pub fn new(alpha: f64) -> Crp {
+		match Crp::try_new(alpha) {
+			Ok(crp) => crp,
+			Err(err) => process_error(err),
+		}
+	}
+	/// Constructs a new Chinese restaurant process with the given concentration.
+	///
+	/// Returns an error if `alpha` is not positive and finite.
+	#[inline]
+	pub fn try_new(alpha: f64) -> Result<Crp, ProcessError> {
+		check_alpha(alpha)?;
+		Ok(Crp { alpha, counts: Vec::new(), len: 0 })
+	}
+	/// Assigns the next item to a cluster and returns the cluster index.
+	///
+	/// New clusters are numbered sequentially starting at zero.
+	pub fn assign<R: Rng + ?Sized>(&mut self, rand: &mut Random<R>) -> usize {
+		let u: f64 = Float01.sample(rand);
+		let mut target = u * (self.len as f64 + self.alpha);
+		let mut index = self.counts.len();
+		for (i, &count) in self.counts.iter().enumerate() {
+			target -= count as f64;
+			if target < 0.0 {
+				index = i;
+				break;
+			}
+		}
+		if index == self.counts.len() {
+			self.counts.push(0);
+		}
+		self.counts[index] += 1;
+		self.len += 1;
+		index
+	}
+	/// Returns the number of items in each cluster.
+	#[inline]
+	pub fn counts(&self) -> &[usize] {
+		&self.counts
+	}
+	/// Returns the number of clusters.
+	#[inline]
+	pub fn num_clusters(&self) -> usize {
+		self.counts.len()
+	}
+	/// Returns the number of assigned items.
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.len
+	}
+	/// Returns `true` if no items have been assigned.
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+	/// Returns the concentration parameter.
+	#[inline]
+	pub fn alpha(&self) -> f64 {
+		self.alpha
+	}
+}
+
+/// The [stick-breaking process](https://en.wikipedia.org/wiki/Dirichlet_process#The_stick-breaking_process) generating GEM weights.
+///
+/// Repeatedly breaks off a `Beta(1, alpha)` distributed fraction of the remaining stick of unit length.
+/// The weights are positive and sum to one in the limit, smaller `alpha` concentrates the mass in the first few weights.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::process::StickBreaking;
+///
+/// let mut rng = urandom::new();
+/// let mut sticks = StickBreaking::new(1.0);
+/// let total: f64 = (0..10).map(|_| sticks.next_weight(&mut rng)).sum();
+/// assert!(total <= 1.0);
+/// assert!((total + sticks.remaining() - 1.0).abs() < 1e-12);
+/// ```
+#[derive(Clone, Debug)]
+pub struct StickBreaking {
+	inv_alpha: f64,
+	remaining: f64,
+}
+
+impl StickBreaking {
+	/// Constructs a new stick-breaking process with the given concentration.
+	///
+	/// # Panics
+	///
+	/// Panics if `alpha` is not positive and finite.
+	#[inline]
+	pub fn new(alpha: f64) -> StickBreaking {
+		match StickBreaking::try_new(alpha) {
+			Ok(sticks) => sticks,
+			Err(err) => process_error(err),
+		}
+	}
+	/// Constructs a new stick-breaking process with the given concentration.
+	///
+	/// Returns an error if `alpha` is not positive and finite.
+	#[inline]
+	pub fn try_new(alpha: f64) -> Result<StickBreaking, ProcessError> {
+		check_alpha(alpha)?;
+		Ok(StickBreaking { inv_alpha: 1.0 / alpha, remaining: 1.0 })
+	}
+	/// Breaks off the next piece of the stick and returns its weight.
+	pub fn next_weight<R: Rng + ?Sized>(&mut self, rand: &mut Random<R>) -> f64 {
+		// The kept fraction 1 - V with V ~ Beta(1, alpha) is distributed as U^(1/alpha)
+		let u: f64 = Float01.sample(rand);
+		let kept = u.powf(self.inv_alpha);
+		let weight = self.remaining * (1.0 - kept);
+		self.remaining *= kept;
+		weight
+	}
+	/// Returns the length of the remaining stick, one minus the sum of the weights so far.
+	#[inline]
+	pub fn remaining(&self) -> f64 {
+		self.remaining
+	}
+}
+
+/// A random walk adding independent steps sampled from a distribution.
+///
+/// The variance of the position grows linearly with the number of steps for any step distribution with finite variance.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::{process::RandomWalk, Uniform};
+///
+/// let mut rng = urandom::new();
+/// let mut walk = RandomWalk::new(10.0, Uniform::from(-1.0..1.0));
+/// for _ in 0..100 {
+/// 	walk.step(&mut rng);
+/// }
+/// assert!((walk.current() - 10.0).abs() <= 100.0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct RandomWalk<D> {
+	current: f64,
+	step_distr: D,
+}
+
+impl<D: Distribution<f64>> RandomWalk<D> {
+	/// Constructs a new random walk at `start` taking steps sampled from `step_distr`.
+	///
+	/// # Panics
+	///
+	/// Panics if `start` is not finite.
+	#[inline]
+	pub fn new(start: f64, step_distr: D) -> RandomWalk<D> {
+		match RandomWalk::try_new(start, step_distr) {
+			Ok(walk) => walk,
+			Err(err) => process_error(err),
+		}
+	}
+	/// Constructs a new random walk at `start` taking steps sampled from `step_distr`.
+	///
+	/// Returns an error if `start` is not finite.
+	#[inline]
+	pub fn try_new(start: f64, step_distr: D) -> Result<RandomWalk<D>, ProcessError> {
+		if !start.is_finite() {
+			return Err(ProcessError::NotFinite);
+		}
+		Ok(RandomWalk { current: start, step_distr })
+	}
+	/// Takes a step and returns the new position.
+	#[inline]
+	pub fn step<R: Rng + ?Sized>(&mut self, rand: &mut Random<R>) -> f64 {
+		self.current += self.step_distr.sample(rand);
+		self.current
+	}
+	/// Returns the current position.
+	#[inline]
+	pub fn current(&self) -> f64 {
+		self.current
+	}
+	/// Returns the step distribution.
+	#[inline]
+	pub fn step_distr(&self) -> &D {
+		&self.step_distr
+	}
+}
+
+/// The [Ornstein–Uhlenbeck process](https://en.wikipedia.org/wiki/Ornstein%E2%80%93Uhlenbeck_process) sampled at fixed time steps.
+///
+/// Produces temporally correlated noise which reverts to the long-run mean `mu` at rate `theta`.
+/// The stationary distribution is normal with mean `mu` and variance `sigma²/(2·theta)`.
+///
+/// # Implementation notes
+///
+/// Uses the exact discretization, the process has the correct distribution at every step regardless of the size of `dt`:
+///
+/// `x ← x·e^(−θ·dt) + μ·(1 − e^(−θ·dt)) + σ·sqrt((1 − e^(−2θ·dt)) / (2θ))·N(0, 1)`
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::process::OrnsteinUhlenbeck;
+///
+/// let mut rng = urandom::new();
+/// let mut noise = OrnsteinUhlenbeck::new(1.0, 0.0, 0.5, 0.01, 0.0);
+/// let samples: Vec<f64> = (0..100).map(|_| noise.step(&mut rng)).collect();
+/// assert_eq!(samples[99], noise.current());
+/// ```
+#[derive(Clone, Debug)]
+pub struct OrnsteinUhlenbeck {
+	current: f64,
+	decay: f64,
+	shift: f64,
+	scale: f64,
+}
+
+impl OrnsteinUhlenbeck {
+	/// Constructs a new Ornstein–Uhlenbeck process starting at `x0`.
+	///
+	/// # Panics
+	///
+	/// Panics if `theta` or `dt` are not positive and finite, `sigma` is negative or not finite, or `mu` or `x0` are not finite.
+	#[inline]
+	pub fn new(theta: f64, mu: f64, sigma: f64, dt: f64, x0: f64) -> OrnsteinUhlenbeck {
+		match OrnsteinUhlenbeck::try_new(theta, mu, sigma, dt, x0) {
+			Ok(process) => process,
+			Err(err) => process_error(err),
+		}
+	}
+	/// Constructs a new Ornstein–Uhlenbeck process starting at `x0`.
+	///
+	/// Returns an error if `theta` or `dt` are not positive and finite, `sigma` is negative or not finite, or `mu` or `x0` are not finite.
+	pub fn try_new(theta: f64, mu: f64, sigma: f64, dt: f64, x0: f64) -> Result<OrnsteinUhlenbeck, ProcessError> {
+		if !(theta > 0.0 && theta.is_finite()) {
+			return Err(ProcessError::ThetaNotPositive);
+		}
+		if !(sigma >= 0.0 && sigma.is_finite()) {
+			return Err(ProcessError::SigmaNegative);
+		}
+		if !(dt > 0.0 && dt.is_finite()) {
+			return Err(ProcessError::DtNotPositive);
+		}
+		if !(mu.is_finite() && x0.is_finite()) {
+			return Err(ProcessError::NotFinite);
+		}
+		let decay = (-theta * dt).exp();
+		// 1 - e^(-2θdt) computed without cancellation for small steps
+		let variance = -(-2.0 * theta * dt).exp_m1() / (2.0 * theta);
+		Ok(OrnsteinUhlenbeck {
+			current: x0,
+			decay,
+			shift: -mu * (-theta * dt).exp_m1(),
+			scale: sigma * variance.sqrt(),
+		})
+	}
+	/// Advances the process by one time step and returns the new value.
+	#[inline]
+	pub fn step<R: Rng + ?Sized>(&mut self, rand: &mut Random<R>) -> f64 {
+		let (z, _) = standard_normal_pair(rand);
+		self.current = self.current * self.decay + self.shift + self.scale * z;
+		self.current
+	}
+	/// Returns the current value.
+	#[inline]
+	pub fn current(&self) -> f64 {
+		self.current
+	}
+}
+
+/// A discrete-time [Markov chain](https://en.wikipedia.org/wiki/Markov_chain) over the states `0..n`.
+///
+/// Every step moves from the current state `i` to state `j` with probability `matrix[i][j]`.
+///
+/// The rows are renormalized to sum to exactly one at construction and stored as cumulative tables,
+/// stepping is a binary search costing `O(log n)`. States with zero probability are never entered.
+///
+/// To checkpoint a trace save [`current`](MarkovChain::current) along with the generator state,
+/// and restore it with [`set_state`](MarkovChain::set_state) on a chain constructed from the same matrix.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::process::MarkovChain;
+///
+/// let mut rng = urandom::new();
+/// // Browsing, searching and checking out
+/// let mut chain = MarkovChain::new(&[
+/// 	[0.8, 0.15, 0.05],
+/// 	[0.5, 0.4, 0.1],
+/// 	[1.0, 0.0, 0.0],
+/// ]);
+/// let trace: Vec<usize> = (0..100).map(|_| chain.step(&mut rng)).collect();
+/// assert!(trace.iter().all(|&state| state < 3));
+/// ```
+#[derive(Clone, Debug)]
+pub struct MarkovChain {
+	n: usize,
+	current: usize,
+	probs: Vec<f64>,
+	cdf: Vec<f64>,
+}
+
+impl MarkovChain {
+	/// Constructs a new Markov chain from the row-stochastic transition matrix, starting in state zero.
+	///
+	/// # Panics
+	///
+	/// Panics if the matrix is not valid, see [`try_new`](MarkovChain::try_new).
+	#[inline]
+	pub fn new<M: AsRef<[f64]>>(matrix: &[M]) -> MarkovChain {
+		match MarkovChain::try_new(matrix) {
+			Ok(chain) => chain,
+			Err(err) => process_error(err),
+		}
+	}
+	/// Constructs a new Markov chain from the row-stochastic transition matrix, starting in state zero.
+	///
+	/// Returns an error if the matrix is empty or not square, has negative or non-finite entries,
+	/// or a row does not sum to one within a relative tolerance of `1e-6`.
+	pub fn try_new<M: AsRef<[f64]>>(matrix: &[M]) -> Result<MarkovChain, ProcessError> {
+		let n = matrix.len();
+		if n == 0 || matrix.iter().any(|row| row.as_ref().len() != n) {
+			return Err(ProcessError::NotSquare);
+		}
+		let mut probs = Vec::with_capacity(n * n);
+		let mut cdf = Vec::with_capacity(n * n);
+		for row in matrix {
+			let row = row.as_ref();
+			if row.iter().any(|&p| !(p >= 0.0 && p.is_finite())) {
+				return Err(ProcessError::InvalidProbability);
+			}
+			let sum: f64 = row.iter().sum();
+			if (sum - 1.0).abs() > 1e-6 {
+				return Err(ProcessError::RowSumNotOne);
+			}
+			let mut acc = 0.0;
+			for &p in row {
+				probs.push(p / sum);
+				acc += p / sum;
+				cdf.push(acc);
+			}
+			// The last reachable state ends the table at exactly one so that every uniform in [0, 1) lands on a reachable state
+			let last = row.iter().rposition(|&p| p > 0.0).unwrap();
+			let start = cdf.len() - n;
+			cdf[start + last..].fill(1.0);
+		}
+		Ok(MarkovChain { n, current: 0, probs, cdf })
+	}
+	/// Advances the chain by one step and returns the new state.
+	#[inline]
+	pub fn step<R: Rng + ?Sized>(&mut self, rand: &mut Random<R>) -> usize {
+		let u: f64 = Float01.sample(rand);
+		let row = &self.cdf[self.current * self.n..(self.current + 1) * self.n];
+		self.current = row.partition_point(|&c| c <= u);
+		self.current
+	}
+	/// Returns the current state.
+	#[inline]
+	pub fn current(&self) -> usize {
+		self.current
+	}
+	/// Sets the current state.
+	///
+	/// Returns an error if the state is out of range.
+	#[inline]
+	pub fn set_state(&mut self, state: usize) -> Result<(), ProcessError> {
+		if state >= self.n {
+			return Err(ProcessError::StateOutOfRange);
+		}
+		self.current = state;
+		Ok(())
+	}
+	/// Returns the number of states.
+	#[inline]
+	pub fn num_states(&self) -> usize {
+		self.n
+	}
+	/// Returns the renormalized transition probability from state `from` to state `to`.
+	///
+	/// # Panics
+	///
+	/// Panics if either state is out of range.
+	#[inline]
+	pub fn probability(&self, from: usize, to: usize) -> f64 {
+		assert!(from < self.n && to < self.n, "state out of range");
+		self.probs[from * self.n + to]
+	}
+	/// Computes the stationary distribution by power iteration.
+	///
+	/// Iterates until the L1 distance between successive estimates is below `tol`.
+	/// Useful to validate generated traces: the long-run state frequencies of an irreducible chain converge to this distribution.
+	///
+	/// # Implementation notes
+	///
+	/// Iterates the lazy chain `(I + P)/2` starting from the uniform distribution.
+	/// It has the same stationary distributions as `P` but also converges for periodic chains.
+	/// For reducible chains the result is one of several stationary distributions, depending on the uniform start.
+	/// The iteration stops after one million steps if the tolerance is not reached.
+	pub fn stationary(&self, tol: f64) -> Vec<f64> {
+		let n = self.n;
+		let mut pi = vec![1.0 / n as f64; n];
+		let mut next = vec![0.0; n];
+		for _ in 0..1_000_000 {
+			for (j, value) in next.iter_mut().enumerate() {
+				*value = 0.5 * pi[j];
+			}
+			for (i, &mass) in pi.iter().enumerate() {
+				let row = &self.probs[i * n..(i + 1) * n];
+				for (value, &p) in next.iter_mut().zip(row) {
+					*value += 0.5 * mass * p;
+				}
+			}
+			let diff: f64 = pi.iter().zip(&next).map(|(a, b)| (a - b).abs()).sum();
+			core::mem::swap(&mut pi, &mut next);
+			if diff < tol {
+				break;
+			}
+		}
+		pi
+	}
+}
+
+/// Fills the slice with a 1-D fractal heightline by [midpoint displacement](https://en.wikipedia.org/wiki/Diamond-square_algorithm#Midpoint_displacement).
+///
+/// Useful for procedural terrain profiles and audio synthesis.
+/// The caller sets the endpoints `out[0]` and `out[len - 1]`, they are not modified.
+///
+/// The length must be `2^k + 1` for some `k >= 0`, the heightline is computed in place without allocating.
+///
+/// # Displacement
+///
+/// The levels are processed from the coarsest to the finest, with the range `r = initial_range` at the first level and multiplied by `roughness` after every level.
+/// Within a level the midpoints are visited from left to right, every midpoint is set to the average of its two neighbors at the previous level plus the offset:
+///
+/// `r·(2·u − 1)` where `u = rng.next_f64() − 1.0`
+///
+/// The offsets are uniform in `[−r, r)` and consume exactly one `next_f64` per midpoint.
+/// This procedure is guaranteed to never change: the same generator state produces the same heightline across all versions of this crate.
+///
+/// Roughness `0.5` halves the range every level and produces the classic self-similar profile (fractal dimension 1.5),
+/// smaller values produce smoother profiles and values up to `1.0` rougher ones.
+///
+/// # Errors
+///
+/// Returns an error if the length is not `2^k + 1` or if `roughness` or `initial_range` is negative or not finite.
+/// The slice is not modified on error.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::process::midpoint_displacement;
+///
+/// let mut rng = urandom::new();
+/// let mut heights = [0.0; 17];
+/// heights[16] = 1.0;
+/// midpoint_displacement(&mut rng, &mut heights, 0.5, 0.25).unwrap();
+/// assert_eq!((heights[0], heights[16]), (0.0, 1.0));
+/// ```
+pub fn midpoint_displacement<R: Rng + ?Sized>(rand: &mut Random<R>, out: &mut [f64], roughness: f64, initial_range: f64) -> Result<(), ProcessError> {
+	let len = out.len();
+	if len < 2 || !(len - 1).is_power_of_two() {
+		return Err(ProcessError::InvalidLength);
+	}
+	if !(roughness >= 0.0 && roughness.is_finite() && initial_range >= 0.0 && initial_range.is_finite()) {
+		return Err(ProcessError::InvalidScale);
+	}
+	let mut range = initial_range;
+	let mut step = len - 1;
+	while step > 1 {
+		let half = step / 2;
+		for mid in (half..len).step_by(step) {
+			let u = rand.next_f64() - 1.0;
+			out[mid] = (out[mid - half] + out[mid + half]) * 0.5 + range * (2.0 * u - 1.0);
+		}
+		range *= roughness;
+		step = half;
+	}
+	Ok(())
+}
+
+#[cold]
+fn process_error(err: ProcessError) -> ! {
+	panic!("random process constructed with invalid parameters: {}", err)
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_crp_clusters() {
+	let mut rng = crate::seeded(42);
+	for &alpha in &[0.5, 2.0, 10.0] {
+		const N: usize = 1000;
+		const RUNS: usize = 200;
+		let mut total = 0;
+		for _ in 0..RUNS {
+			let mut crp = Crp::new(alpha);
+			for _ in 0..N {
+				let index = crp.assign(&mut rng);
+				assert!(index < crp.num_clusters());
+			}
+			assert_eq!(crp.counts().iter().sum::<usize>(), N);
+			assert_eq!(crp.len(), N);
+			total += crp.num_clusters();
+		}
+		let mean = total as f64 / RUNS as f64;
+		// The exact expectation is the sum of alpha / (alpha + i) for i in 0..N
+		let exact: f64 = (0..N).map(|i| alpha / (alpha + i as f64)).sum();
+		let approx = alpha * (1.0 + N as f64 / alpha).ln();
+		assert!((mean - exact).abs() < 0.05 * exact + 0.2, "alpha: {} mean: {} exact: {}", alpha, mean, exact);
+		assert!((mean - approx).abs() < 0.15 * approx + 0.6, "alpha: {} mean: {} approx: {}", alpha, mean, approx);
+	}
+}
+
+#[test]
+fn test_crp_deterministic() {
+	let run = |seed| {
+		let mut rng = crate::seeded(seed);
+		let mut crp = Crp::new(3.0);
+		let labels: Vec<usize> = (0..500).map(|_| crp.assign(&mut rng)).collect();
+		(labels, crp.counts().to_vec())
+	};
+	assert_eq!(run(42), run(42));
+	assert_ne!(run(42), run(43));
+}
+
+#[test]
+fn test_stick_breaking() {
+	let mut rng = crate::seeded(42);
+	// The first weight is Beta(1, alpha) distributed with mean 1 / (1 + alpha)
+	const RUNS: usize = 10000;
+	let mut sum = 0.0;
+	for _ in 0..RUNS {
+		let mut sticks = StickBreaking::new(3.0);
+		let first = sticks.next_weight(&mut rng);
+		sum += first;
+		let mut total = first;
+		for _ in 0..50 {
+			let weight = sticks.next_weight(&mut rng);
+			assert!((0.0..=1.0).contains(&weight));
+			total += weight;
+		}
+		assert!((total + sticks.remaining() - 1.0).abs() < 1e-12);
+	}
+	let mean = sum / RUNS as f64;
+	assert!((mean - 0.25).abs() < 0.01, "mean: {}", mean);
+}
+
+#[cfg(test)]
+#[derive(Debug)]
+struct StandardNormal;
+
+#[cfg(test)]
+impl Distribution<f64> for StandardNormal {
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> f64 {
+		standard_normal_pair(rng).0
+	}
+}
+
+#[test]
+fn test_random_walk_variance() {
+	let mut rng = crate::seeded(42);
+	const RUNS: usize = 4000;
+	const CHECKPOINTS: [usize; 3] = [10, 40, 160];
+	let mut sums = [0.0; 3];
+	for _ in 0..RUNS {
+		let mut walk = RandomWalk::new(5.0, StandardNormal);
+		let mut steps = 0;
+		for (i, &checkpoint) in CHECKPOINTS.iter().enumerate() {
+			while steps < checkpoint {
+				walk.step(&mut rng);
+				steps += 1;
+			}
+			let d = walk.current() - 5.0;
+			sums[i] += d * d;
+		}
+	}
+	for (&checkpoint, &sum) in CHECKPOINTS.iter().zip(&sums) {
+		let variance = sum / RUNS as f64;
+		let expected = checkpoint as f64;
+		assert!((variance - expected).abs() < 0.1 * expected, "steps: {} variance: {}", checkpoint, variance);
+	}
+}
+
+#[test]
+fn test_ornstein_uhlenbeck_stationary() {
+	let mut rng = crate::seeded(42);
+	let (theta, mu, sigma) = (2.0, 3.0, 0.8);
+	let mut process = OrnsteinUhlenbeck::new(theta, mu, sigma, 0.05, -10.0);
+	// Burn in from the distant starting value
+	for _ in 0..1000 {
+		process.step(&mut rng);
+	}
+	const N: usize = 400000;
+	let mut sum = 0.0;
+	let mut sum_sq = 0.0;
+	for _ in 0..N {
+		let x = process.step(&mut rng);
+		sum += x;
+		sum_sq += x * x;
+	}
+	let mean = sum / N as f64;
+	let variance = sum_sq / N as f64 - mean * mean;
+	let expected = sigma * sigma / (2.0 * theta);
+	assert!((mean - mu).abs() < 0.02, "mean: {}", mean);
+	assert!((variance - expected).abs() < 0.05 * expected, "variance: {} expected: {}", variance, expected);
+}
+
+#[test]
+fn test_process_deterministic() {
+	let run = |seed| {
+		let mut rng = crate::seeded(seed);
+		let mut walk = RandomWalk::new(0.0, StandardNormal);
+		let mut process = OrnsteinUhlenbeck::new(1.0, 0.0, 1.0, 0.1, 0.0);
+		(0..100).map(|_| (walk.step(&mut rng), process.step(&mut rng))).collect::<Vec<_>>()
+	};
+	assert_eq!(run(42), run(42));
+	assert_ne!(run(42), run(43));
+
+	// Without noise the process decays exactly towards the mean
+	let mut rng = crate::seeded(42);
+	let mut process = OrnsteinUhlenbeck::new(0.5, 1.0, 0.0, 2.0, 3.0);
+	let x = process.step(&mut rng);
+	assert!((x - (1.0 + 2.0 * (-1.0f64).exp())).abs() < 1e-12, "x: {}", x);
+}
+
+#[test]
+fn test_validation() {
+	assert_eq!(Crp::try_new(0.0).unwrap_err(), ProcessError::AlphaNotPositive);
+	assert_eq!(Crp::try_new(-1.0).unwrap_err(), ProcessError::AlphaNotPositive);
+	assert_eq!(Crp::try_new(f64::NAN).unwrap_err(), ProcessError::AlphaNotPositive);
+	assert_eq!(StickBreaking::try_new(0.0).unwrap_err(), ProcessError::AlphaNotPositive);
+	assert_eq!(StickBreaking::try_new(f64::INFINITY).unwrap_err(), ProcessError::AlphaNotPositive);
+	assert_eq!(RandomWalk::try_new(f64::NAN, StandardNormal).unwrap_err(), ProcessError::NotFinite);
+	assert_eq!(OrnsteinUhlenbeck::try_new(0.0, 0.0, 1.0, 0.1, 0.0).unwrap_err(), ProcessError::ThetaNotPositive);
+	assert_eq!(OrnsteinUhlenbeck::try_new(1.0, 0.0, -1.0, 0.1, 0.0).unwrap_err(), ProcessError::SigmaNegative);
+	assert_eq!(OrnsteinUhlenbeck::try_new(1.0, 0.0, 1.0, 0.0, 0.0).unwrap_err(), ProcessError::DtNotPositive);
+	assert_eq!(OrnsteinUhlenbeck::try_new(1.0, f64::INFINITY, 1.0, 0.1, 0.0).unwrap_err(), ProcessError::NotFinite);
+	assert_eq!(OrnsteinUhlenbeck::try_new(1.0, 0.0, 1.0, 0.1, f64::NAN).unwrap_err(), ProcessError::NotFinite);
+}
+
+#[test]
+fn test_markov_chain_stationary() {
+	let mut rng = crate::seeded(42);
+	let mut chain = MarkovChain::new(&[
+		[0.9, 0.075, 0.025],
+		[0.15, 0.8, 0.05],
+		[0.25, 0.25, 0.5],
+	]);
+	// The exact stationary distribution is [0.625, 0.3125, 0.0625]
+	let pi = chain.stationary(1e-12);
+	for (&p, &exact) in pi.iter().zip(&[0.625, 0.3125, 0.0625]) {
+		assert!((p - exact).abs() < 1e-9, "{:?}", pi);
+	}
+	const N: usize = 400000;
+	let mut counts = [0usize; 3];
+	for _ in 0..N {
+		counts[chain.step(&mut rng)] += 1;
+	}
+	for (&count, &p) in counts.iter().zip(&pi) {
+		let freq = count as f64 / N as f64;
+		assert!((freq - p).abs() < 0.01, "counts: {:?} stationary: {:?}", counts, pi);
+	}
+
+	// Periodic chains converge too
+	let flip = MarkovChain::new(&[[0.0, 1.0], [1.0, 0.0]]);
+	let pi = flip.stationary(1e-12);
+	assert!((pi[0] - 0.5).abs() < 1e-9 && (pi[1] - 0.5).abs() < 1e-9, "{:?}", pi);
+}
+
+#[test]
+fn test_markov_chain_absorbing() {
+	let mut rng = crate::seeded(42);
+	let matrix = vec![vec![0.5, 0.5, 0.0], vec![0.2, 0.3, 0.5], vec![0.0, 0.0, 1.0]];
+	let mut chain = MarkovChain::new(&matrix);
+	let mut steps = 0;
+	while chain.current() != 2 {
+		chain.step(&mut rng);
+		steps += 1;
+		assert!(steps < 10000);
+	}
+	for _ in 0..1000 {
+		assert_eq!(chain.step(&mut rng), 2);
+	}
+	let pi = chain.stationary(1e-12);
+	assert!((pi[2] - 1.0).abs() < 1e-9, "{:?}", pi);
+
+	// Zero probability transitions are never taken and the state can be restored
+	chain.set_state(0).unwrap();
+	for _ in 0..1000 {
+		let state = chain.step(&mut rng);
+		assert!(state != 2 || chain.probability(0, 2) > 0.0);
+		chain.set_state(0).unwrap();
+	}
+	assert_eq!(chain.set_state(3).unwrap_err(), ProcessError::StateOutOfRange);
+	assert_eq!(chain.num_states(), 3);
+}
+
+#[test]
+fn test_markov_chain_validation() {
+	let empty: &[[f64; 0]] = &[];
+	assert_eq!(MarkovChain::try_new(empty).unwrap_err(), ProcessError::NotSquare);
+	assert_eq!(MarkovChain::try_new(&[vec![1.0], vec![0.5, 0.5]]).unwrap_err(), ProcessError::NotSquare);
+	assert_eq!(MarkovChain::try_new(&[[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]).unwrap_err(), ProcessError::NotSquare);
+	assert_eq!(MarkovChain::try_new(&[[1.5, -0.5], [0.5, 0.5]]).unwrap_err(), ProcessError::InvalidProbability);
+	assert_eq!(MarkovChain::try_new(&[[f64::NAN, 1.0], [0.5, 0.5]]).unwrap_err(), ProcessError::InvalidProbability);
+	assert_eq!(MarkovChain::try_new(&[[0.5, 0.4], [0.5, 0.5]]).unwrap_err(), ProcessError::RowSumNotOne);
+	assert_eq!(MarkovChain::try_new(&[[0.0, 0.0], [0.5, 0.5]]).unwrap_err(), ProcessError::RowSumNotOne);
+
+	// Rows within tolerance are renormalized exactly
+	let chain = MarkovChain::new(&[[0.3333333, 0.6666667], [1.0, 0.0]]);
+	assert_eq!(chain.probability(0, 0) + chain.probability(0, 1), 1.0);
+}
+
+#[test]
+fn test_midpoint_displacement() {
+	// Golden values, these must never change
+	let mut rng = crate::seeded(42);
+	let mut heights = [1.0, 0.0, 0.0, 0.0, 2.0];
+	midpoint_displacement(&mut rng, &mut heights, 0.5, 1.0).unwrap();
+	assert_eq!(heights, [1.0, 0.6461669910225658, 0.6715111905909219, 0.898325376858675, 2.0]);
+
+	// Deterministic under seeding and the endpoints are unchanged
+	let mut a = [0.0; 33];
+	let mut b = [0.0; 33];
+	a[32] = 5.0;
+	b[32] = 5.0;
+	midpoint_displacement(&mut crate::seeded(7), &mut a, 0.6, 2.0).unwrap();
+	midpoint_displacement(&mut crate::seeded(7), &mut b, 0.6, 2.0).unwrap();
+	assert_eq!(a, b);
+	assert_eq!((a[0], a[32]), (0.0, 5.0));
+
+	// The displacements of every level are uniform in [-r, r) with r shrinking by the roughness
+	let mut rng = crate::seeded(42);
+	const N: usize = 20000;
+	let (roughness, range) = (0.5, 3.0);
+	let mut sum_sq = [0.0; 3];
+	for _ in 0..N {
+		let mut heights = [0.0; 9];
+		midpoint_displacement(&mut rng, &mut heights, roughness, range).unwrap();
+		let offsets = [
+			heights[4],
+			heights[2] - (heights[0] + heights[4]) * 0.5,
+			heights[1] - (heights[0] + heights[2]) * 0.5,
+		];
+		for (level, &offset) in offsets.iter().enumerate() {
+			let r = range * f64::powi(roughness, level as i32);
+			assert!(offset >= -r && offset < r, "level: {} offset: {}", level, offset);
+			sum_sq[level] += offset * offset;
+		}
+	}
+	for (level, &sum) in sum_sq.iter().enumerate() {
+		let r = range * f64::powi(roughness, level as i32);
+		let variance = sum / N as f64;
+		assert!((variance - r * r / 3.0).abs() < 0.03 * r * r, "level: {} variance: {}", level, variance);
+	}
+
+	// Lengths with no midpoints
+	let mut pair = [1.0, 2.0];
+	midpoint_displacement(&mut rng, &mut pair, 0.5, 1.0).unwrap();
+	assert_eq!(pair, [1.0, 2.0]);
+}
+
+#[test]
+fn test_midpoint_displacement_validation() {
+	let mut rng = crate::seeded(42);
+	for len in [0, 1, 4, 6, 8, 10, 16, 18] {
+		let mut heights = vec![0.0; len];
+		assert_eq!(midpoint_displacement(&mut rng, &mut heights, 0.5, 1.0), Err(ProcessError::InvalidLength), "len: {}", len);
+	}
+	let mut heights = [0.0; 5];
+	assert_eq!(midpoint_displacement(&mut rng, &mut heights, -0.5, 1.0), Err(ProcessError::InvalidScale));
+	assert_eq!(midpoint_displacement(&mut rng, &mut heights, 0.5, f64::NAN), Err(ProcessError::InvalidScale));
+	assert_eq!(midpoint_displacement(&mut rng, &mut heights, f64::INFINITY, 1.0), Err(ProcessError::InvalidScale));
+	assert_eq!(heights, [0.0; 5]);
+}
@@ -0,0 +1,71 @@
+use core::fmt;
+use crate::{Distribution, Random, Rng};
+use crate::distributions::Float01;
+
+/// Inverse transform sampling from a user-provided quantile function.
+///
+/// The quantile function (or inverse CDF) maps a probability in the open interval `(0, 1)` to a value of the distribution.
+/// Samples are generated by feeding uniform samples from [`Float01`](Float01) through the quantile function.
+///
+/// This is an escape hatch for arbitrary one-dimensional distributions without implementing [`Distribution`](Distribution) by hand.
+///
+/// # Examples
+///
+/// Sample from the exponential distribution with rate `λ = 2.0`:
+///
+/// ```
+/// use urandom::distributions::FromQuantile;
+///
+/// let mut rng = urandom::new();
+/// let distr = FromQuantile::new(|p: f64| -(1.0 - p).ln() / 2.0);
+/// let value = rng.sample(&distr);
+/// assert!(value >= 0.0);
+/// ```
+#[derive(Copy, Clone)]
+pub struct FromQuantile<F> {
+	quantile: F,
+}
+
+impl<F> FromQuantile<F> {
+	/// Constructs a new distribution from the given quantile function.
+	#[inline]
+	pub const fn new(quantile: F) -> FromQuantile<F> {
+		FromQuantile { quantile }
+	}
+}
+
+impl<F> fmt::Debug for FromQuantile<F> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("FromQuantile(impl Fn)")
+	}
+}
+
+impl<T, F: Fn(f64) -> T> Distribution<T> for FromQuantile<F> {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> T {
+		let p: f64 = Float01.sample(rng);
+		(self.quantile)(p)
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_exponential() {
+	let mut rng = crate::seeded(42);
+	let distr = FromQuantile::new(|p: f64| -(1.0 - p).ln());
+	const N: usize = 100000;
+
+	let mut sum = 0.0;
+	let mut sum2 = 0.0;
+	for _ in 0..N {
+		let value: f64 = rng.sample(&distr);
+		assert!(value >= 0.0 && value.is_finite());
+		sum += value;
+		sum2 += value * value;
+	}
+	let mean = sum / N as f64;
+	let variance = sum2 / N as f64 - mean * mean;
+	assert!((mean - 1.0).abs() < 0.02, "mean: {}", mean);
+	assert!((variance - 1.0).abs() < 0.05, "variance: {}", variance);
+}
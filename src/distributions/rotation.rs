@@ -0,0 +1,143 @@
+use core::f64::consts::TAU;
+use crate::{Distribution, Random, Rng};
+use crate::distributions::Float01;
+
+/// Uniformly distributed 3D rotations as unit quaternions.
+///
+/// The rotations are distributed according to the Haar measure on SO(3), unlike random Euler angles which cluster around the poles.
+///
+/// # Component order
+///
+/// The quaternion is returned **w-first** as `[w, x, y, z]` where `w` is the scalar part.
+/// Engines which expect `[x, y, z, w]` should rotate the array left by one: `q.rotate_left(1)`.
+///
+/// Every rotation is represented by the two quaternions `q` and `-q` (the double cover), both are sampled with equal probability.
+///
+/// # Implementation notes
+///
+/// Uses Ken Shoemake's subgroup algorithm from Graphics Gems III, consuming three uniform floats and two `sin_cos` calls.
+/// The result has unit norm up to rounding, within a few ulps of one.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::UnitQuaternion;
+///
+/// let mut rng = urandom::new();
+/// let [w, x, y, z]: [f64; 4] = rng.sample(&UnitQuaternion);
+/// assert!((w * w + x * x + y * y + z * z - 1.0).abs() < 1e-12);
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct UnitQuaternion;
+
+impl Distribution<[f64; 4]> for UnitQuaternion {
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> [f64; 4] {
+		let u1: f64 = Float01.sample(rng);
+		let u2: f64 = Float01.sample(rng);
+		let u3: f64 = Float01.sample(rng);
+		let r1 = (1.0 - u1).sqrt();
+		let r2 = u1.sqrt();
+		let (s1, c1) = (TAU * u2).sin_cos();
+		let (s2, c2) = (TAU * u3).sin_cos();
+		[r2 * c2, r1 * s1, r1 * c1, r2 * s2]
+	}
+}
+
+impl Distribution<[f32; 4]> for UnitQuaternion {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> [f32; 4] {
+		let q: [f64; 4] = self.sample(rng);
+		q.map(|x| x as f32)
+	}
+}
+
+/// Uniformly distributed 2D rotations as `(cos, sin)` pairs.
+///
+/// The angle is uniform in `[0, 2π)`, returning its cosine and sine avoids a trigonometric call downstream to build the rotation matrix.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::Rotation2;
+///
+/// let mut rng = urandom::new();
+/// let (cos, sin): (f64, f64) = rng.sample(&Rotation2);
+/// let (x, y) = (2.0, 1.0);
+/// let rotated = (cos * x - sin * y, sin * x + cos * y);
+/// assert!((rotated.0 * rotated.0 + rotated.1 * rotated.1 - 5.0).abs() < 1e-12);
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Rotation2;
+
+impl Distribution<(f64, f64)> for Rotation2 {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> (f64, f64) {
+		let u: f64 = Float01.sample(rng);
+		let (sin, cos) = (TAU * u).sin_cos();
+		(cos, sin)
+	}
+}
+
+impl Distribution<(f32, f32)> for Rotation2 {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> (f32, f32) {
+		let u: f32 = Float01.sample(rng);
+		let (sin, cos) = (core::f32::consts::TAU * u).sin_cos();
+		(cos, sin)
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_unit_quaternion() {
+	let mut rng = crate::seeded(42);
+	const N: usize = 200000;
+	const BUCKETS: usize = 20;
+	let mut counts = [0usize; BUCKETS];
+	let mut negative_w = 0;
+	for _ in 0..N {
+		let q: [f64; 4] = rng.sample(&UnitQuaternion);
+		let norm2: f64 = q.iter().map(|x| x * x).sum();
+		assert!((norm2.sqrt() - 1.0).abs() < 1e-12, "{:?}", q);
+		if q[0] < 0.0 {
+			negative_w += 1;
+		}
+		// The rotation angle of the quaternion
+		let angle = 2.0 * q[0].abs().min(1.0).acos();
+		let bucket = ((angle / core::f64::consts::PI * BUCKETS as f64) as usize).min(BUCKETS - 1);
+		counts[bucket] += 1;
+	}
+	// The angle has the density (1 - cos θ)/π with the distribution function (θ - sin θ)/π
+	let cdf = |theta: f64| (theta - theta.sin()) / core::f64::consts::PI;
+	for (i, &count) in counts.iter().enumerate() {
+		let lo = i as f64 / BUCKETS as f64 * core::f64::consts::PI;
+		let hi = (i + 1) as f64 / BUCKETS as f64 * core::f64::consts::PI;
+		let expected = (cdf(hi) - cdf(lo)) * N as f64;
+		assert!((count as f64 - expected).abs() < 5.0 * expected.sqrt() + 5.0, "bucket: {} count: {} expected: {}", i, count, expected);
+	}
+	// Both covers occur equally often
+	assert!((negative_w as f64 / N as f64 - 0.5).abs() < 0.01, "negative_w: {}", negative_w);
+
+	let q: [f32; 4] = rng.sample(&UnitQuaternion);
+	let norm2: f32 = q.iter().map(|x| x * x).sum();
+	assert!((norm2 - 1.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_rotation2() {
+	let mut rng = crate::seeded(42);
+	const N: usize = 100000;
+	let mut counts = [0usize; 8];
+	for _ in 0..N {
+		let (cos, sin): (f64, f64) = rng.sample(&Rotation2);
+		assert!((cos * cos + sin * sin - 1.0).abs() < 1e-12);
+		let angle = sin.atan2(cos).rem_euclid(TAU);
+		counts[((angle / TAU * 8.0) as usize).min(7)] += 1;
+	}
+	for &count in &counts {
+		assert!((count as f64 - N as f64 / 8.0).abs() < 500.0, "{:?}", counts);
+	}
+	let (cos, sin): (f32, f32) = rng.sample(&Rotation2);
+	assert!((cos * cos + sin * sin - 1.0).abs() < 1e-6);
+}
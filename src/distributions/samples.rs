@@ -28,3 +28,82 @@ impl<'a, R: ?Sized, D, T> Iterator for Samples<'a, R, D, T> where R: Rng, D: Dis
 	}
 }
 impl<'a, R: ?Sized, D, T> iter::FusedIterator for Samples<'a, R, D, T> where R: Rng, D: Distribution<T> {}
+
+/// An iterator that generates random values of `T` with distribution `D`, owning the source of randomness `R`.
+///
+/// This struct is created by the [`Random::into_samples`](Random::into_samples) and [`Random::into_iter_of`](Random::into_iter_of) methods.
+///
+/// # Cloning
+///
+/// Cloning this iterator clones the generator: **the clone produces exactly the same values as the original**.
+/// The two streams are perfectly correlated, which is what you want for replaying a stream and never what you want for independent streams.
+/// To split off an independent stream seed a new generator from this one instead, eg. with [`SeedRng::from_rng`](crate::rng::SeedRng::from_rng).
+pub struct IntoSamples<R, D, T> {
+	rng: Random<R>,
+	distr: D,
+	_phantom: PhantomData<fn() -> T>,
+}
+impl<R, D, T> IntoSamples<R, D, T> {
+	#[inline]
+	pub(crate) fn new(rng: Random<R>, distr: D) -> Self {
+		IntoSamples { rng, distr, _phantom: PhantomData }
+	}
+	/// Returns the generator.
+	#[inline]
+	pub fn into_inner(self) -> Random<R> {
+		self.rng
+	}
+}
+impl<R: Clone, D: Clone, T> Clone for IntoSamples<R, D, T> {
+	#[inline]
+	fn clone(&self) -> Self {
+		IntoSamples { rng: self.rng.clone(), distr: self.distr.clone(), _phantom: PhantomData }
+	}
+}
+impl<R, D, T> Iterator for IntoSamples<R, D, T> where R: Rng, D: Distribution<T> {
+	type Item = T;
+	#[inline]
+	fn next(&mut self) -> Option<T> {
+		Some(self.distr.sample(&mut self.rng))
+	}
+	#[inline]
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(usize::MAX, None)
+	}
+}
+impl<R, D, T> iter::FusedIterator for IntoSamples<R, D, T> where R: Rng, D: Distribution<T> {}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_iter_of() {
+	let mut rng = crate::seeded(42);
+	let mut reference = crate::seeded(42);
+	let values: Vec<f64> = rng.iter_of::<f64>().take(10).collect();
+	for &value in &values {
+		assert_eq!(value, reference.next::<f64>());
+	}
+	assert_eq!(rng.next_u64(), reference.next_u64());
+
+	let values: Vec<u32> = rng.into_iter_of::<u32>().take(10).collect();
+	for &value in &values {
+		assert_eq!(value, reference.next::<u32>());
+	}
+}
+
+#[test]
+fn test_into_iter_of_clone() {
+	// Cloning forks the generator, both iterators produce the same stream
+	let a = crate::new().into_iter_of::<u64>();
+	let b = a.clone();
+	for (a, b) in a.zip(b).take(100) {
+		assert_eq!(a, b);
+	}
+
+	let mut samples = crate::seeded(1).into_samples(crate::distributions::Dice::D6);
+	let value: i32 = samples.next().unwrap();
+	let mut rng = samples.into_inner();
+	let mut reference = crate::seeded(1);
+	assert_eq!(value, reference.sample(&crate::distributions::Dice::D6));
+	assert_eq!(rng.next_u64(), reference.next_u64());
+}
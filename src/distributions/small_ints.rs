@@ -0,0 +1,90 @@
+use crate::{Random, Rng};
+
+/// Samples small integers by amortizing one `u64` across multiple values.
+///
+/// This struct is created by the [`Random::small_ints`](Random::small_ints) method. See its documentation for more.
+///
+/// # Consumption order
+///
+/// Values are taken from the least significant bits of a buffered `u64` first.
+/// A `u8` consumes 8 bits, a `u16` consumes 16 bits and a `bool` consumes a single bit.
+/// When fewer bits remain than requested the remaining bits are discarded and the next `u64` is fetched with `next_u64`.
+///
+/// Any buffered bits are discarded when this struct is dropped.
+pub struct SmallInts<'a, R: ?Sized> {
+	rng: &'a mut Random<R>,
+	bits: u64,
+	len: u32,
+}
+
+impl<'a, R: Rng + ?Sized> SmallInts<'a, R> {
+	#[inline]
+	pub(crate) fn new(rng: &'a mut Random<R>) -> Self {
+		SmallInts { rng, bits: 0, len: 0 }
+	}
+
+	#[inline]
+	fn take(&mut self, n: u32) -> u64 {
+		if self.len < n {
+			self.bits = self.rng.next_u64();
+			self.len = 64;
+		}
+		let value = self.bits & ((1 << n) - 1);
+		self.bits >>= n;
+		self.len -= n;
+		value
+	}
+
+	/// Returns a uniform random `u8`, consuming 8 bits.
+	#[inline]
+	pub fn next_u8(&mut self) -> u8 {
+		self.take(8) as u8
+	}
+	/// Returns a uniform random `i8`, consuming 8 bits.
+	#[inline]
+	pub fn next_i8(&mut self) -> i8 {
+		self.take(8) as i8
+	}
+	/// Returns a uniform random `u16`, consuming 16 bits.
+	#[inline]
+	pub fn next_u16(&mut self) -> u16 {
+		self.take(16) as u16
+	}
+	/// Returns a uniform random `i16`, consuming 16 bits.
+	#[inline]
+	pub fn next_i16(&mut self) -> i16 {
+		self.take(16) as i16
+	}
+	/// Returns a uniform random `bool`, consuming a single bit.
+	#[inline]
+	pub fn next_bool(&mut self) -> bool {
+		self.take(1) != 0
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_consumption_order() {
+	let mut rng = crate::rng::MockRng::slice(&[0x0807060504030201, 0x4433221100ffeedd, 0b101]);
+	let mut ints = rng.small_ints();
+	for i in 1..=8 {
+		assert_eq!(ints.next_u8(), i);
+	}
+	assert_eq!(ints.next_u16(), 0xeedd);
+	assert_eq!(ints.next_i8(), -1);
+	assert_eq!(ints.next_u16(), 0x1100);
+	assert_eq!(ints.next_u16(), 0x3322);
+	// Only 8 bits remain, the next u16 fetches a new u64
+	assert_eq!(ints.next_i16(), 0b101);
+	assert_eq!(ints.next_bool(), false);
+}
+
+#[test]
+fn test_bools() {
+	let mut rng = crate::rng::MockRng::slice(&[0b1101]);
+	let mut ints = rng.small_ints();
+	let bools: [bool; 64] = core::array::from_fn(|_| ints.next_bool());
+	assert_eq!(bools[..5], [true, false, true, true, false]);
+	assert!(bools[5..].iter().all(|&b| !b));
+}
@@ -10,9 +10,13 @@ use crate::{Random, Rng, Distribution};
 /// * Integers (`i32`, `u32`, `isize`, `usize`, etc.): Uniformly distributed over all values of the type.
 /// * `char`: Uniformly distributed over all Unicode scalar values, i.e. all code points in the range `0..0x11_0000`,
 ///   except for the range `0xD800..0xE000` (the surrogate code points). This includes unassigned/reserved code points.
+///   The vast majority of samples are unassigned or private use code points, see [`GraphicChar`](super::GraphicChar),
+///   [`Bmp`](super::Bmp) and [`AsciiPrintable`](super::AsciiPrintable) for distributions suited to generating text.
 /// * `bool`: Generates `true` or `false`, each with equal probability.
 /// * Floating point types (`f32` and `f64`): Uniformly distributed in the half-open interval `[1.0, 2.0)`. See notes below.
+/// * `half::f16` (with the `half` feature): Uniformly distributed in the half-open interval `[1.0, 2.0)`.
 /// * Wrapping integers (`Wrapping<T>`), besides the type identical to their normal integer variants.
+/// * [`Turns`](crate::types::Turns), [`Radians`](crate::types::Radians) and [`Probability`](crate::types::Probability) (with the `std` feature): see the [`types`](crate::types) module.
 ///
 /// The `Standard` distribution also supports generation of the following compound types where all component types are supported:
 ///
@@ -54,11 +58,15 @@ use crate::{Random, Rng, Distribution};
 ///
 /// This is equivalent to calling [`Random::next_f32`](Random::next_f32) and [`Random::next_f64`](Random::next_f64) directly.
 ///
+/// The high bits of the Rng output become the mantissa: `f32` uses the high 23 bits of `next_u32` and `f64` uses the high 52 bits of `next_u64`.
+/// An all-zero output produces exactly `1.0` and an all-ones output produces the largest float less than `2.0`.
+/// As the exponent is fixed the result is never subnormal, infinite or `NaN`.
+///
 /// Subtracting `1.0` is an easy way to get a random floating point value in the half-open interval `[0.0, 1.0)` but has a small bias
 /// where it will never generate certain floating point values. This is equivalent to `rng.range(0.0..1.0)`.
 ///
 /// See also: [`Float01`](super::Float01) which samples from `(0.0, 1.0)` and does not suffer from this bias.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub struct Standard;
 
 // Implement Standard distribution for integers as simple casts.
@@ -92,6 +100,8 @@ impl_standard_dist! { isize, rng => rng.next_u64() as isize }
 impl_standard_dist! { usize, rng => rng.next_u64() as usize }
 impl_standard_dist! { f32, rng => rng.next_f32() }
 impl_standard_dist! { f64, rng => rng.next_f64() }
+#[cfg(feature = "half")]
+impl_standard_dist! { half::f16, rng => half::f16::from_bits(0b0_01111 << 10 | (rng.next_u32() >> 22) as u16) }
 
 
 impl Distribution<char> for Standard {
@@ -233,3 +243,25 @@ fn test_bool() {
 
 	assert!((results[0] - results[1]).abs() < 1000, "Unbalanced coin flips!! heads = {}, tails = {} out of {} trails ", results[1], results[0], N);
 }
+
+#[test]
+fn test_float_edges() {
+	let mut rng = crate::rng::MockRng::slice(&[0, !0, 0x8000_0000, 0, !0]);
+	assert_eq!(rng.next::<f32>(), 1.0);
+	assert_eq!(rng.next::<f32>(), 2.0 - f32::EPSILON);
+	assert_eq!(rng.next::<f32>(), 1.5);
+	assert_eq!(rng.next::<f64>(), 1.0);
+	assert_eq!(rng.next::<f64>(), 2.0 - f64::EPSILON);
+}
+
+#[cfg(feature = "half")]
+#[test]
+fn test_f16_edges() {
+	use half::f16;
+	let mut rng = crate::rng::MockRng::slice(&[0, !0]);
+	assert_eq!(rng.next::<f16>(), f16::ONE);
+	assert_eq!(rng.next::<f16>(), f16::from_f32(2.0) - f16::EPSILON);
+	for value in crate::new().samples::<f16, _>(Standard).take(1000) {
+		assert!(value >= f16::ONE && value < f16::from_f32(2.0), "value: {}", value);
+	}
+}
@@ -1,11 +1,15 @@
-use core::ops;
+use core::{hash, ops};
 use crate::{Distribution, Random, Rng};
 
 mod float;
 mod int;
+mod offset;
 
 pub use self::float::UniformFloat;
-pub use self::int::UniformInt;
+#[cfg(feature = "half")]
+pub use self::float::UniformF16;
+pub use self::int::{UniformInt, SecureSampler};
+pub use self::offset::SampleOffset;
 
 /// Helper trait specifies the concrete sampler for the sampling type.
 pub trait SampleUniform: Sized {
@@ -22,6 +26,28 @@ pub trait UniformSampler<T>: Distribution<T> {
 	/// Create a new instance which samples uniformly from the closed range `[low, high]` (inclusive).
 	/// May panic if `low > high`.
 	fn new_inclusive(low: T, high: T) -> Self;
+
+	/// Create a new instance which samples uniformly from the half-open range `[low, high)` given by reference.
+	/// May panic if `low >= high`.
+	///
+	/// The default implementation clones the bounds and calls [`new`](UniformSampler::new).
+	/// Samplers for types which are expensive to clone may override this method to construct the sampler directly from the references.
+	///
+	/// This method was added with a default implementation, existing implementations of this trait continue to work unchanged.
+	#[inline]
+	fn new_ref(low: &T, high: &T) -> Self where T: Clone, Self: Sized {
+		Self::new(low.clone(), high.clone())
+	}
+
+	/// Create a new instance which samples uniformly from the closed range `[low, high]` (inclusive) given by reference.
+	/// May panic if `low > high`.
+	///
+	/// The default implementation clones the bounds and calls [`new_inclusive`](UniformSampler::new_inclusive).
+	/// See [`new_ref`](UniformSampler::new_ref) for more information.
+	#[inline]
+	fn new_inclusive_ref(low: &T, high: &T) -> Self where T: Clone, Self: Sized {
+		Self::new_inclusive(low.clone(), high.clone())
+	}
 }
 
 /// Sample values uniformly between two bounds.
@@ -29,6 +55,9 @@ pub trait UniformSampler<T>: Distribution<T> {
 /// `Uniform` values can be constructed from a [`Range`](ops::Range) or [`RangeInclusive`](ops::RangeInclusive).
 /// See below for a demonstration.
 ///
+/// To avoid moving the bounds into the sampler, `Uniform` values can also be constructed from a reference to a range or a tuple of references `(&low, &high)` for the half-open range.
+/// These forward to [`UniformSampler::new_ref`](UniformSampler::new_ref) and [`UniformSampler::new_inclusive_ref`](UniformSampler::new_inclusive_ref).
+///
 /// Steps are taken to avoid bias which might be present in naive implementations;
 /// for example `rng.next::<u8>() % 170` samples from the interval `[0, 170)` but is twice as likely to select numbers less than 85 than other values.
 /// Further, the implementations here give more weight to the high bits generated by the Rng than the low bits,
@@ -103,6 +132,20 @@ pub trait UniformSampler<T>: Distribution<T> {
 #[derive(Copy, Clone, Debug)]
 pub struct Uniform<T: SampleUniform>(T::Sampler);
 
+impl<T: SampleUniform> PartialEq for Uniform<T> where T::Sampler: PartialEq {
+	#[inline]
+	fn eq(&self, other: &Uniform<T>) -> bool {
+		self.0 == other.0
+	}
+}
+impl<T: SampleUniform> Eq for Uniform<T> where T::Sampler: Eq {}
+impl<T: SampleUniform> hash::Hash for Uniform<T> where T::Sampler: hash::Hash {
+	#[inline]
+	fn hash<H: hash::Hasher>(&self, state: &mut H) {
+		self.0.hash(state);
+	}
+}
+
 impl<T: SampleUniform> From<ops::Range<T>> for Uniform<T> {
 	#[inline]
 	fn from(range: ops::Range<T>) -> Uniform<T> {
@@ -117,6 +160,26 @@ impl<T: SampleUniform> From<ops::RangeInclusive<T>> for Uniform<T> {
 	}
 }
 
+impl<'a, T: SampleUniform + Clone> From<&'a ops::Range<T>> for Uniform<T> {
+	#[inline]
+	fn from(range: &'a ops::Range<T>) -> Uniform<T> {
+		Uniform(T::Sampler::new_ref(&range.start, &range.end))
+	}
+}
+impl<'a, T: SampleUniform + Clone> From<&'a ops::RangeInclusive<T>> for Uniform<T> {
+	#[inline]
+	fn from(range: &'a ops::RangeInclusive<T>) -> Uniform<T> {
+		Uniform(T::Sampler::new_inclusive_ref(range.start(), range.end()))
+	}
+}
+impl<'a, T: SampleUniform + Clone> From<(&'a T, &'a T)> for Uniform<T> {
+	/// Samples from the half-open range `[low, high)`.
+	#[inline]
+	fn from((low, high): (&'a T, &'a T)) -> Uniform<T> {
+		Uniform(T::Sampler::new_ref(low, high))
+	}
+}
+
 impl<T: SampleUniform> UniformSampler<T> for Uniform<T> {
 	#[inline]
 	fn new(low: T, high: T) -> Self {
@@ -126,6 +189,20 @@ impl<T: SampleUniform> UniformSampler<T> for Uniform<T> {
 	fn new_inclusive(low: T, high: T) -> Self {
 		Uniform(T::Sampler::new_inclusive(low, high))
 	}
+	#[inline]
+	fn new_ref(low: &T, high: &T) -> Self where T: Clone {
+		Uniform(T::Sampler::new_ref(low, high))
+	}
+	#[inline]
+	fn new_inclusive_ref(low: &T, high: &T) -> Self where T: Clone {
+		Uniform(T::Sampler::new_inclusive_ref(low, high))
+	}
+}
+impl<T: SampleUniform> SecureSampler<T> for Uniform<T> where T::Sampler: SecureSampler<T> {
+	#[inline]
+	fn sample_secure<R: crate::rng::SecureRng + ?Sized>(&self, rand: &mut Random<R>) -> T {
+		self.0.sample_secure(rand)
+	}
 }
 impl<T: SampleUniform> Distribution<T> for Uniform<T> {
 	#[inline]
@@ -133,3 +210,86 @@ impl<T: SampleUniform> Distribution<T> for Uniform<T> {
 		self.0.sample(rng)
 	}
 }
+
+//----------------------------------------------------------------
+
+#[cfg(test)]
+std::thread_local! {
+	static CLONES: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+}
+
+// Fixed point type which counts its clones
+#[cfg(test)]
+#[derive(Debug, PartialEq, PartialOrd)]
+struct Fixed([u64; 4]);
+#[cfg(test)]
+impl Clone for Fixed {
+	fn clone(&self) -> Fixed {
+		CLONES.with(|clones| clones.set(clones.get() + 1));
+		Fixed(self.0)
+	}
+}
+
+#[cfg(test)]
+#[derive(Debug)]
+struct UniformFixed(UniformInt<u64>);
+#[cfg(test)]
+impl UniformSampler<Fixed> for UniformFixed {
+	fn new(low: Fixed, high: Fixed) -> UniformFixed {
+		UniformFixed::new_ref(&low, &high)
+	}
+	fn new_inclusive(low: Fixed, high: Fixed) -> UniformFixed {
+		UniformFixed::new_inclusive_ref(&low, &high)
+	}
+	fn new_ref(low: &Fixed, high: &Fixed) -> UniformFixed {
+		UniformFixed(UniformInt::new(low.0[0], high.0[0]))
+	}
+	fn new_inclusive_ref(low: &Fixed, high: &Fixed) -> UniformFixed {
+		UniformFixed(UniformInt::new_inclusive(low.0[0], high.0[0]))
+	}
+}
+#[cfg(test)]
+impl Distribution<Fixed> for UniformFixed {
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> Fixed {
+		Fixed([self.0.sample(rng), 0, 0, 0])
+	}
+}
+#[cfg(test)]
+impl SampleUniform for Fixed {
+	type Sampler = UniformFixed;
+}
+
+#[test]
+fn test_ref_no_clones() {
+	let mut rng = crate::new();
+	let low = Fixed([10, 0, 0, 0]);
+	let high = Fixed([20, 0, 0, 0]);
+	let range = low.clone()..high.clone();
+	let range_inclusive = low.clone()..=high.clone();
+	CLONES.with(|clones| clones.set(0));
+
+	let distr = Uniform::from(&range);
+	let distr_inclusive = Uniform::from(&range_inclusive);
+	let distr_tuple = Uniform::from((&low, &high));
+	for _ in 0..100 {
+		assert!(rng.sample(&distr) >= low && rng.sample(&distr) < high);
+		assert!(rng.sample(&distr_inclusive) >= low && rng.sample(&distr_inclusive) <= high);
+		assert!(rng.sample(&distr_tuple) >= low && rng.sample(&distr_tuple) < high);
+		assert!(rng.range(&range) < high);
+		assert!(rng.range((&low, &high)) < high);
+	}
+	assert_eq!(CLONES.with(|clones| clones.get()), 0);
+}
+
+#[test]
+fn test_ref_default() {
+	// The default implementation clones the bounds
+	let mut rng = crate::new();
+	let range = 1.0..2.0;
+	let value = rng.range(&range);
+	assert!(value >= 1.0 && value < 2.0);
+	let value: i32 = rng.range((&-5, &5));
+	assert!(value >= -5 && value < 5);
+	let value = rng.range(&(3u8..=4));
+	assert!(value == 3 || value == 4);
+}
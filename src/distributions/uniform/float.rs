@@ -8,55 +8,328 @@ use crate::distributions::{SampleUniform, UniformSampler};
 /// Floating point types always include the lower bound and exclude the upper bound regardless of which constructor was chosen.
 /// When the high argument is less than the low argument this is reversed and the upper bound is included and lower bound is excluded.
 ///
-/// Fast floating point values are requested directly from the `Rng` then scaled and shifted into the requested range.
+/// When the inputs are not finite the result may produce unexpected results (eg. `NaN`).
 ///
-/// When the inputs are not finite or become non-finite during setup the result may produce unexpected results (eg. `NaN`).
-#[derive(Copy, Clone, Debug)]
+/// # Sampling strategies
+///
+/// The strategy is selected once at construction from the finite bounds:
+///
+/// * Fast: floating point values in `[1, 2)` are requested directly from the `Rng` then scaled and shifted into the requested range.
+///   This is used for all ranges except the two cases below.
+///
+/// * Wide: when the width of the range `high - low` or the shift overflows to infinity, eg. for `-MAX..MAX`,
+///   the fast strategy is computed at half the scale and the result is doubled.
+///
+/// * Precise: when either bound is non-zero and smaller in magnitude than the resolution `(high - low) · EPSILON` of the fast strategy,
+///   eg. for `1e-300..1e300` or `-1e300..-1e-300`, the fast strategy would round the end of the range near zero to zero.
+///   Instead a real number is sampled uniformly from a power of two interval containing the range by sampling its exponent and mantissa separately,
+///   every floating point value in the range is reachable. Samples outside the range are rejected, at least one in four samples is accepted.
+///
+/// # Half precision
+///
+/// With the `half` feature `half::f16` is sampled by [`UniformF16`](UniformF16).
+///
+/// # Equality
+///
+/// Only `PartialEq` is implemented: the parameters are floats and `NaN` parameters are not equal to themselves.
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct UniformFloat<T> {
-	base: T,
-	scale: T,
+	strategy: Strategy<T>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Strategy<T> {
+	// Scales and shifts a float in [1, 2)
+	Fast { base: T, scale: T },
+	// The fast strategy at half the scale
+	Wide { base: T, scale: T },
+	// The bounds as given
+	Precise { low: T, high: T },
+}
+
+macro_rules! impl_uniform_float {
+	($ty:ident, $bits:ty, $next_float:ident, $next_bits:ident, $mantissa_bits:expr, $exponent_bias:expr, $precise:ident) => {
+		impl SampleUniform for $ty {
+			type Sampler = UniformFloat<$ty>;
+		}
+		impl UniformSampler<$ty> for UniformFloat<$ty> {
+			#[inline]
+			fn new(low: $ty, high: $ty) -> UniformFloat<$ty> {
+				let scale = high - low;
+				let base = low - scale;
+				let strategy = if !(low.is_finite() && high.is_finite()) {
+					Strategy::Fast { base, scale }
+				}
+				else if !(scale.is_finite() && base.is_finite()) {
+					Strategy::Wide { base: low * 0.5, scale: high * 0.5 - low * 0.5 }
+				}
+				else if low != 0.0 && low.abs() < scale.abs() * $ty::EPSILON || high != 0.0 && high.abs() < scale.abs() * $ty::EPSILON {
+					Strategy::Precise { low, high }
+				}
+				else {
+					Strategy::Fast { base, scale }
+				};
+				UniformFloat { strategy }
+			}
+			#[inline]
+			fn new_inclusive(low: $ty, high: $ty) -> UniformFloat<$ty> {
+				Self::new(low, high)
+			}
+		}
+		impl Distribution<$ty> for UniformFloat<$ty> {
+			#[inline]
+			fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> $ty {
+				match self.strategy {
+					Strategy::Fast { base, scale } => rng.$next_float() * scale + base,
+					Strategy::Wide { base, scale } => ((rng.$next_float() - 1.0) * scale + base) * 2.0,
+					Strategy::Precise { low, high } => $precise(low, high, rng),
+				}
+			}
+		}
+
+		#[inline(never)]
+		fn $precise<R: Rng + ?Sized>(low: $ty, high: $ty, rng: &mut Random<R>) -> $ty {
+			const MANTISSA_MASK: $bits = (1 << $mantissa_bits) - 1;
+			// Exponent of the smallest normal value
+			const MIN_EXPONENT: i32 = 1 - $exponent_bias;
+
+			let (min, max) = if low < high { (low, high) } else { (high, low) };
+			// Exponent of the smallest power of two greater than or equal to both bounds
+			let magnitude = $ty::max(min.abs(), max.abs()).to_bits();
+			let biased = (magnitude >> $mantissa_bits) as i32;
+			let exponent = if biased == 0 {
+				MIN_EXPONENT - $mantissa_bits + (<$bits>::BITS - (magnitude - 1).leading_zeros()) as i32
+			}
+			else {
+				biased - $exponent_bias + (magnitude & MANTISSA_MASK != 0) as i32
+			};
+
+			loop {
+				// Sample a real number uniformly from [0, 2^exponent), every leading zero bit halves the interval
+				let mut e = exponent - 1;
+				loop {
+					let bits = rng.next_u64();
+					e -= bits.leading_zeros() as i32;
+					if bits != 0 || e < MIN_EXPONENT - $mantissa_bits {
+						break;
+					}
+				}
+				let word = rng.$next_bits();
+				let mantissa = word >> (<$bits>::BITS - $mantissa_bits);
+				// The sign is taken from a random bit not used by the mantissa
+				let negative = max <= 0.0 || min < 0.0 && word & 1 != 0;
+				// Truncate the magnitude to the floating point value below, subnormal values are truncated
+				let mut bits = if e >= MIN_EXPONENT {
+					((e + $exponent_bias) as $bits) << $mantissa_bits | mantissa
+				}
+				else {
+					((1 << $mantissa_bits) | mantissa).checked_shr((MIN_EXPONENT - e) as u32).unwrap_or(0)
+				};
+				// Round towards negative infinity, the magnitude of negative values is rounded up
+				// The real number is above the truncated magnitude with probability one, the increment carries into the exponent
+				if negative {
+					bits += 1;
+				}
+				let value = if negative { -$ty::from_bits(bits) } else { $ty::from_bits(bits) };
+				if value == low || value > min && value < max {
+					break value;
+				}
+			}
+		}
+	};
 }
 
-impl SampleUniform for f32 {
-	type Sampler = UniformFloat<f32>;
+impl_uniform_float!(f32, u32, next_f32, next_u32, 23, 127, precise_f32);
+impl_uniform_float!(f64, u64, next_f64, next_u64, 52, 1023, precise_f64);
+
+/// Uniform distribution over `half::f16`.
+///
+/// # Implementation notes
+///
+/// Samples an `f32` in the requested range with [`UniformFloat`](UniformFloat) and rounds it to `f16`.
+/// Rounding may land the value on the excluded bound, such samples are rejected and resampled.
+/// This keeps the samples within the requested range even when its width is below the precision of `f16`,
+/// but the values adjacent to the bounds are less likely than the others as only half of their rounding interval lies within the range.
+///
+/// The bounds follow [`UniformFloat`](UniformFloat): the low bound is included and the high bound is excluded, also when reversed.
+#[cfg(feature = "half")]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct UniformF16 {
+	low: half::f16,
+	high: half::f16,
 }
-impl UniformSampler<f32> for UniformFloat<f32> {
+
+#[cfg(feature = "half")]
+impl SampleUniform for half::f16 {
+	type Sampler = UniformF16;
+}
+#[cfg(feature = "half")]
+impl UniformSampler<half::f16> for UniformF16 {
 	#[inline]
-	fn new(low: f32, high: f32) -> UniformFloat<f32> {
-		let scale = high - low;
-		let base = low - scale;
-		UniformFloat { base, scale }
+	fn new(low: half::f16, high: half::f16) -> UniformF16 {
+		UniformF16 { low, high }
 	}
 	#[inline]
-	fn new_inclusive(low: f32, high: f32) -> UniformFloat<f32> {
+	fn new_inclusive(low: half::f16, high: half::f16) -> UniformF16 {
 		Self::new(low, high)
 	}
 }
-impl Distribution<f32> for UniformFloat<f32> {
+#[cfg(feature = "half")]
+impl Distribution<half::f16> for UniformF16 {
 	#[inline]
-	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> f32 {
-		rng.next_f32() * self.scale + self.base
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> half::f16 {
+		let (low, high) = (self.low, self.high);
+		if low == high {
+			return low;
+		}
+		let (min, max) = if low < high { (low, high) } else { (high, low) };
+		let distr = UniformFloat::<f32>::new(low.to_f32(), high.to_f32());
+		loop {
+			let value = half::f16::from_f32(distr.sample(rng));
+			// Written to let NaN through instead of looping forever
+			if !(value < min || value > max) && value != high {
+				break value;
+			}
+		}
 	}
 }
 
-impl SampleUniform for f64 {
-	type Sampler = UniformFloat<f64>;
+//----------------------------------------------------------------
+
+#[test]
+fn test_edges() {
+	let mut rng = crate::rng::MockRng::slice(&[0, !0]);
+	let distr = UniformFloat::new(-3.0f32, 5.0);
+	assert_eq!(rng.sample(&distr), -3.0);
+	assert_eq!(rng.sample(&distr), 5.0 - 8.0 * f32::EPSILON);
 }
-impl UniformSampler<f64> for UniformFloat<f64> {
-	#[inline]
-	fn new(low: f64, high: f64) -> UniformFloat<f64> {
-		let scale = high - low;
-		let base = low - scale;
-		UniformFloat { base, scale }
+
+#[test]
+fn test_wide() {
+	let mut rng = crate::seeded(42);
+
+	let distr = UniformFloat::new(-f64::MAX, f64::MAX);
+	assert!(matches!(distr.strategy, Strategy::Wide { .. }));
+	let mut signs = [0; 2];
+	let mut decades = [0; 309];
+	for _ in 0..10000 {
+		let value = rng.sample(&distr);
+		assert!(value.is_finite() && value >= -f64::MAX && value < f64::MAX, "value: {}", value);
+		signs[(value < 0.0) as usize] += 1;
+		decades[value.abs().log10().max(0.0) as usize] += 1;
 	}
-	#[inline]
-	fn new_inclusive(low: f64, high: f64) -> UniformFloat<f64> {
-		Self::new(low, high)
+	assert!(signs.iter().all(|&count| count > 4500), "{:?}", signs);
+	assert!(decades[308] > 4000 && decades[307] > 4000 && decades[306] > 300 && decades[305] > 20, "{:?}", &decades[300..]);
+
+	// Only the shift overflows
+	let distr = UniformFloat::new(-f64::MAX, f64::MAX * 0.5);
+	assert!(matches!(distr.strategy, Strategy::Wide { .. }));
+	for _ in 0..1000 {
+		let value = rng.sample(&distr);
+		assert!(value >= -f64::MAX && value < f64::MAX * 0.5, "value: {}", value);
+	}
+
+	let distr = UniformFloat::new(f32::MAX, -f32::MAX);
+	assert!(matches!(distr.strategy, Strategy::Wide { .. }));
+	for _ in 0..1000 {
+		let value = rng.sample(&distr);
+		assert!(value > -f32::MAX && value <= f32::MAX, "value: {}", value);
 	}
 }
-impl Distribution<f64> for UniformFloat<f64> {
-	#[inline]
-	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> f64 {
-		rng.next_f64() * self.scale + self.base
+
+#[test]
+fn test_precise() {
+	let mut rng = crate::seeded(42);
+
+	let distr = UniformFloat::new(1e-300, 1e300);
+	assert!(matches!(distr.strategy, Strategy::Precise { .. }));
+	let mut below = 0;
+	for _ in 0..10000 {
+		let value = rng.sample(&distr);
+		assert!(value >= 1e-300 && value < 1e300, "value: {}", value);
+		below += (value < 1e298) as i32;
+	}
+	assert!((below - 100).abs() < 40, "below: {}", below);
+
+	// Reversed bounds include the upper bound
+	let distr = UniformFloat::new(1e-30f32, -1e30);
+	assert!(matches!(distr.strategy, Strategy::Precise { .. }));
+	for _ in 0..1000 {
+		let value = rng.sample(&distr);
+		assert!(value > -1e30 && value <= 1e-30, "value: {}", value);
+	}
+
+	// Mirrored range with the high bound near zero
+	let distr = UniformFloat::new(-1e300, -1e-300);
+	assert!(matches!(distr.strategy, Strategy::Precise { .. }));
+	let mut above = 0;
+	for _ in 0..10000 {
+		let value = rng.sample(&distr);
+		assert!(value >= -1e300 && value < -1e-300, "value: {}", value);
+		above += (value > -1e298) as i32;
+	}
+	assert!((above - 100).abs() < 40, "above: {}", above);
+
+	// The precise strategy is uniform for any range
+	let distr = UniformFloat { strategy: Strategy::Precise { low: -1.0, high: 3.0 } };
+	const N: usize = 100000;
+	let mut negative = 0;
+	let mut sum = 0.0;
+	for _ in 0..N {
+		let value = rng.sample(&distr);
+		assert!(value >= -1.0 && value < 3.0, "value: {}", value);
+		negative += (value < 0.0) as usize;
+		sum += value;
+	}
+	assert!((negative as f64 / N as f64 - 0.25).abs() < 0.01, "negative: {}", negative);
+	assert!((sum / N as f64 - 1.0).abs() < 0.02, "mean: {}", sum / N as f64);
+
+	// Every value near zero is reachable
+	let distr = UniformFloat { strategy: Strategy::Precise { low: f64::from_bits(1), high: f64::from_bits(4) } };
+	let mut seen = [false; 4];
+	for _ in 0..1000 {
+		let value = rng.sample(&distr);
+		seen[value.to_bits() as usize] = true;
+	}
+	assert_eq!(seen, [false, true, true, true]);
+	let distr = UniformFloat { strategy: Strategy::Precise { low: -f64::from_bits(4), high: -f64::from_bits(1) } };
+	let mut seen = [false; 5];
+	for _ in 0..1000 {
+		let value = rng.sample(&distr);
+		seen[(-value).to_bits() as usize] = true;
 	}
+	assert_eq!(seen, [false, false, true, true, true]);
+}
+
+#[test]
+fn test_fast() {
+	assert!(matches!(UniformFloat::new(1e-10, 2e-10).strategy, Strategy::Fast { .. }));
+	assert!(matches!(UniformFloat::new(0.0, 1.0).strategy, Strategy::Fast { .. }));
+	assert!(matches!(UniformFloat::new(-1e-300f64, 0.0).strategy, Strategy::Fast { .. }));
+	assert!(matches!(UniformFloat::new(0.0f32, f32::MAX).strategy, Strategy::Fast { .. }));
+	assert!(matches!(UniformFloat::new(1.0, f64::INFINITY).strategy, Strategy::Fast { .. }));
+}
+
+#[cfg(feature = "half")]
+#[test]
+fn test_f16_narrow() {
+	use half::f16;
+	let mut rng = crate::new();
+
+	// Range narrower than the precision of f16
+	let low = f16::from_f32(1.0);
+	let high = f16::from_bits(low.to_bits() + 1);
+	for _ in 0..1000 {
+		assert_eq!(rng.range(low..high), low);
+		assert_eq!(rng.range(high..low), high);
+	}
+
+	let low = f16::from_f32(-2.5);
+	let high = f16::from_f32(7.25);
+	for _ in 0..10000 {
+		let value = rng.range(low..high);
+		assert!(value >= low && value < high, "value: {}", value);
+	}
+
+	assert_eq!(rng.range(low..=low), low);
 }
@@ -1,5 +1,6 @@
 use core::fmt;
 use crate::{Distribution, Random, Rng};
+use crate::rng::SecureRng;
 use crate::distributions::{SampleUniform, UniformSampler};
 
 #[inline]
@@ -16,6 +17,23 @@ fn wmul64(a: u64, b: u64) -> (u64, u64) {
 	let lsw = (full & 0xffffffffffffffff) as u64;
 	(msw, lsw)
 }
+#[inline]
+fn wmul128(a: u128, b: u128) -> (u128, u128) {
+	const LOWER_MASK: u128 = !0u64 as u128;
+	let mut low = (a & LOWER_MASK).wrapping_mul(b & LOWER_MASK);
+	let mut t = low >> 64;
+	low &= LOWER_MASK;
+	t += (a >> 64).wrapping_mul(b & LOWER_MASK);
+	low += (t & LOWER_MASK) << 64;
+	let mut high = t >> 64;
+	t = low >> 64;
+	low &= LOWER_MASK;
+	t += (b >> 64).wrapping_mul(a & LOWER_MASK);
+	low += (t & LOWER_MASK) << 64;
+	high += t >> 64;
+	high += (a >> 64).wrapping_mul(b >> 64);
+	(high, low)
+}
 
 /// Uniform distribution over integral types.
 ///
@@ -32,7 +50,11 @@ fn wmul64(a: u64, b: u64) -> (u64, u64) {
 /// As a special case, we use `range = 0` to represent the full range of the result type (i.e. for the full inclusive range).
 ///
 /// For more information on this bias see the `examples/int_bias.rs` example.
-#[derive(Copy, Clone, Debug)]
+///
+/// # Equality
+///
+/// The representation is canonical: equivalent half-open and inclusive ranges such as `0..10` and `0..=9` compare and hash equal.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub struct UniformInt<T> {
 	base: T,
 	// When T is signed, it is really an unsigned integer of the same size
@@ -108,6 +130,9 @@ impl_uniform_int! { u32, u32, u64, next_u64, wmul64 }
 impl_uniform_int! { i64, u64, u64, next_u64, wmul64 }
 impl_uniform_int! { u64, u64, u64, next_u64, wmul64 }
 
+impl_uniform_int! { i128, u128, u128, next_u128, wmul128 }
+impl_uniform_int! { u128, u128, u128, next_u128, wmul128 }
+
 // Interestingly make usize/isize use the same code paths
 // This keeps the result deterministic regardless of pointer width
 #[cfg(target_pointer_width = "32")]
@@ -120,6 +145,77 @@ impl_uniform_int! { isize, u64, u64, next_u64, wmul64 }
 #[cfg(target_pointer_width = "64")]
 impl_uniform_int! { usize, u64, u64, next_u64, wmul64 }
 
+/// Uniform samplers with a constant-time sampling method for secret values.
+///
+/// The default [`Distribution`](Distribution) implementation of [`UniformInt`](UniformInt) rejects samples in a loop,
+/// the number of iterations depends on the random values and leaks timing information about where in the range a secret sample fell.
+///
+/// # Implementation notes
+///
+/// [`sample_secure`](SecureSampler::sample_secure) always consumes the same number of words from the generator and does not branch on their values:
+/// two calls to [`next_u64`](Rng::next_u64) for integer types up to 64 bits and three calls for 128-bit types.
+///
+/// The random words form a `k`-bit integer `x` (128 or 192 bits) which is reduced to the range size `r` by the multiplication `floor(x·r / 2ᵏ)`.
+/// Every output `i` is produced by the integers `x` in `[ceil(i·2ᵏ/r), ceil((i+1)·2ᵏ/r))`, either `floor(2ᵏ/r)` or `ceil(2ᵏ/r)` of them.
+/// The probability of every output thus differs from `1/r` by less than `2⁻ᵏ`, the statistical distance from the uniform distribution is less than `r·2⁻ᵏ`.
+/// As `r ≤ 2⁶⁴` for `k = 128` and `r ≤ 2¹²⁸` for `k = 192` the bias is less than `2⁻⁶⁴`.
+///
+/// The multiplication is implemented with fixed-length loops of widening multiplications, which are constant-time on common hardware.
+pub trait SecureSampler<T> {
+	/// Samples a value with a constant number of generator calls and a negligible bias.
+	fn sample_secure<R: SecureRng + ?Sized>(&self, rand: &mut Random<R>) -> T;
+}
+
+/// Returns the high 128 bits of the product of the little-endian limbs `x` and `range`, ie. `floor(x·range / 2^(64·N))`.
+#[inline]
+fn mul_high<const N: usize>(x: [u64; N], range: u128) -> u128 {
+	let r = [range as u64, (range >> 64) as u64];
+	let mut product = [0u64; 5];
+	for i in 0..N {
+		let mut carry = 0u128;
+		for j in 0..2 {
+			let t = x[i] as u128 * r[j] as u128 + product[i + j] as u128 + carry;
+			product[i + j] = t as u64;
+			carry = t >> 64;
+		}
+		product[i + 2] = carry as u64;
+	}
+	product[N] as u128 | (product[N + 1] as u128) << 64
+}
+
+macro_rules! impl_secure_sampler {
+	($ty:ty, $unsigned:ty, $words:expr) => {
+		impl SecureSampler<$ty> for UniformInt<$ty> {
+			#[inline]
+			fn sample_secure<R: SecureRng + ?Sized>(&self, rand: &mut Random<R>) -> $ty {
+				let mut words = [0u64; $words];
+				for word in &mut words {
+					*word = rand.next_u64();
+				}
+				let range = self.range as $unsigned as u128;
+				if range == 0 {
+					// The full range of the type, this only depends on the public parameters
+					return (words[0] as u128 | (words[1] as u128) << 64) as $ty;
+				}
+				self.base.wrapping_add(mul_high(words, range) as $ty)
+			}
+		}
+	};
+}
+
+impl_secure_sampler! { i8, u8, 2 }
+impl_secure_sampler! { u8, u8, 2 }
+impl_secure_sampler! { i16, u16, 2 }
+impl_secure_sampler! { u16, u16, 2 }
+impl_secure_sampler! { i32, u32, 2 }
+impl_secure_sampler! { u32, u32, 2 }
+impl_secure_sampler! { i64, u64, 2 }
+impl_secure_sampler! { u64, u64, 2 }
+impl_secure_sampler! { i128, u128, 3 }
+impl_secure_sampler! { u128, u128, 3 }
+impl_secure_sampler! { isize, usize, 2 }
+impl_secure_sampler! { usize, usize, 2 }
+
 #[cold]
 fn uniform_int_new_error<T: fmt::Debug>(low: T, high: T) -> ! {
 	panic!("UniformSampler::new called with `low >= high` where low: {:?} and high: {:?}", low, high);
@@ -135,32 +231,19 @@ fn uniform_int_new_inclusive_error<T: fmt::Debug>(low: T, high: T) -> ! {
 #[test]
 fn test_bias() {
 	let distr = UniformInt::new_inclusive(0u32, 0xC0000000);
-	println!("distr: {:#x?}", distr);
 
-	let mut rng = crate::new();
-	let mut buckets = [0u32; 3];
+	let mut rng = crate::seeded(42);
+	let mut buckets = [0u64; 3];
 
 	for _ in 0..10000 {
 		let value = rng.sample(&distr);
-
-		if value < 0x40000000 {
-			buckets[0] += 1;
-		}
-		else if value < 0x80000000 {
-			buckets[1] += 1;
-		}
-		else if value <= 0xC0000000 {
-			buckets[2] += 1;
-		}
-		else {
-			panic!("value: {:#x}", value);
-		}
+		assert!(value <= 0xC0000000, "value: {:#x}", value);
+		buckets[usize::min((value >> 30) as usize, 2)] += 1;
 	}
 
-	let mean = (buckets[0] as i64 + buckets[1] as i64 + buckets[2] as i64) / 3;
-	let pass = buckets.iter().all(|&odd| (odd as i64 - mean).abs() < 1000);
-	println!("mean:{} buckets:{:?} pass:{}", mean, buckets, pass);
-	assert!(pass);
+	// The last bucket contains one extra value
+	#[cfg(feature = "std")]
+	crate::stats::assert_counts(&buckets, &[1.0, 1.0, 1.0 + 1.0 / (1u64 << 30) as f64], 1e-4);
 }
 
 #[test]
@@ -191,6 +274,52 @@ fn test_edges_small() {
 	}
 }
 
+#[test]
+fn test_wmul128() {
+	let cases = [
+		(0u128, 0u128),
+		(1, !0),
+		(!0, !0),
+		(0x1234_5678_9abc_def0_0fed_cba9_8765_4321, 0xffff_0000_ffff_0000_0000_ffff_0000_ffff),
+		(u64::MAX as u128 + 1, u64::MAX as u128 + 1),
+	];
+	for &(a, b) in &cases {
+		let (high, low) = wmul128(a, b);
+		assert_eq!(low, a.wrapping_mul(b));
+		// Check the high half against a long multiplication of 32-bit limbs
+		let limbs = |x: u128| [x as u32 as u64, (x >> 32) as u32 as u64, (x >> 64) as u32 as u64, (x >> 96) as u32 as u64];
+		let (la, lb) = (limbs(a), limbs(b));
+		let mut r = [0u64; 9];
+		for i in 0..4 {
+			for j in 0..4 {
+				r[i + j] += la[i] * lb[j] & 0xffffffff;
+				r[i + j + 1] += la[i] * lb[j] >> 32;
+			}
+		}
+		for i in 0..8 {
+			r[i + 1] += r[i] >> 32;
+			r[i] &= 0xffffffff;
+		}
+		let expected = r[4] as u128 | (r[5] as u128) << 32 | (r[6] as u128) << 64 | (r[7] as u128) << 96;
+		assert_eq!(high, expected, "a:{:#x} b:{:#x}", a, b);
+	}
+}
+
+#[test]
+fn test_u128() {
+	let mut rng = crate::new();
+	let low = u64::MAX as u128 - 5;
+	let high = u64::MAX as u128 * 3;
+	for _ in 0..1000 {
+		let value = rng.range(low..high);
+		assert!(value >= low && value < high);
+		let value = rng.range(-5i128..=5);
+		assert!(value >= -5 && value <= 5);
+	}
+	let distr = UniformInt::new_inclusive(10u128, 10);
+	assert_eq!(rng.sample(&distr), 10);
+}
+
 #[test]
 fn test_yolo() {
 	let mut rng = crate::new();
@@ -210,3 +339,96 @@ fn test_yolo() {
 		}
 	}
 }
+
+#[test]
+fn test_eq_hash() {
+	use std::collections::HashMap;
+	use std::hash::{BuildHasher, RandomState};
+	use crate::distributions::{Dice, Uniform};
+
+	// Equivalent half-open and inclusive ranges are equal
+	assert_eq!(UniformInt::new(0i32, 10), UniformInt::new_inclusive(0, 9));
+	assert_eq!(UniformInt::new(-128i8, 127), UniformInt::new_inclusive(-128, 126));
+	assert_eq!(Uniform::from(0u64..10), Uniform::from(0u64..=9));
+	assert_ne!(UniformInt::new(0i32, 10), UniformInt::new(0, 11));
+	assert_ne!(UniformInt::new(0i32, 10), UniformInt::new(1, 11));
+	assert_eq!(Dice::new(6), Dice::D6);
+	assert_eq!(Dice::new(20), Dice::D20);
+
+	// Hashes are consistent with equality
+	let state = RandomState::new();
+	assert_eq!(state.hash_one(Uniform::from(0u64..10)), state.hash_one(Uniform::from(0u64..=9)));
+	assert_eq!(state.hash_one(Dice::new(8)), state.hash_one(Dice::D8));
+
+	let mut map = HashMap::new();
+	map.insert(Uniform::from(1..7), "d6");
+	map.insert(Uniform::from(1..=20), "d20");
+	assert_eq!(map.get(&Uniform::from(1..=6)), Some(&"d6"));
+	assert_eq!(map.get(&Uniform::from(1..21)), Some(&"d20"));
+	assert_eq!(map.get(&Uniform::from(0..6)), None);
+}
+
+#[test]
+fn test_secure_distribution() {
+	let mut rng = crate::rng::ChaCha20::from_seed(42);
+	let distr = UniformInt::new(-3i32, 4);
+	let mut counts = [0i32; 7];
+	let mut reference = [0i32; 7];
+	const N: i32 = 70000;
+	for _ in 0..N {
+		counts[(distr.sample_secure(&mut rng) + 3) as usize] += 1;
+		reference[(distr.sample(&mut rng) + 3) as usize] += 1;
+	}
+	for i in 0..7 {
+		assert!((counts[i] - N / 7).abs() < 400, "{:?}", counts);
+		assert!((counts[i] - reference[i]).abs() < 600, "{:?} {:?}", counts, reference);
+	}
+
+	// Full ranges and 128-bit ranges stay in bounds
+	let full = UniformInt::new_inclusive(i8::MIN, i8::MAX);
+	let wide = UniformInt::new(0u128, u128::MAX / 3 * 2);
+	for _ in 0..1000 {
+		let _: i8 = full.sample_secure(&mut rng);
+		assert!(wide.sample_secure(&mut rng) < u128::MAX / 3 * 2);
+	}
+}
+
+#[test]
+fn test_secure_constant_words() {
+	let mut rng = crate::rng::ChaCha20::from_seed(42).counting();
+	for &high in &[1u64, 2, 3, 1 << 63, (1 << 63) + 1, u64::MAX] {
+		let distr = UniformInt::new(0, high);
+		for _ in 0..100 {
+			rng.0.reset_stats();
+			assert!(distr.sample_secure(&mut rng) < high);
+			assert_eq!(rng.0.stats().next_u64, 2);
+			assert_eq!(rng.0.stats().bytes, 16);
+		}
+	}
+	let distr = UniformInt::new(5u128, 1 << 100);
+	rng.0.reset_stats();
+	let _ = distr.sample_secure(&mut rng);
+	assert_eq!(rng.0.stats().next_u64, 3);
+}
+
+#[test]
+fn test_secure_bias_bound() {
+	// The preimage of every output has floor(2^k/r) or ceil(2^k/r) elements, check the boundaries for k = 64
+	for &r in &[3u128, 5, 7, 1000, (1 << 63) + 12345] {
+		let mut last = 0u128;
+		for i in 1..u128::min(r, 8) {
+			// Smallest x producing output i
+			let start = ((i << 64) + r - 1) / r;
+			assert_eq!(mul_high([start as u64], r), i);
+			assert_eq!(mul_high([(start - 1) as u64], r), i - 1);
+			let count = start - last;
+			assert!(count == (1 << 64) / r || count == (1 << 64) / r + 1, "r: {} i: {} count: {}", r, i, count);
+			last = start;
+		}
+	}
+	// A tiny range with 128 bits: the largest input maps to the last output
+	assert_eq!(mul_high([u64::MAX, u64::MAX], 3), 2);
+	assert_eq!(mul_high([0, 0], 3), 0);
+	assert_eq!(mul_high([0, 1 << 63], 3), 1);
+	assert_eq!(mul_high([u64::MAX, u64::MAX, u64::MAX], u128::MAX), u128::MAX - 1);
+}
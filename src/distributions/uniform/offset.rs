@@ -0,0 +1,122 @@
+use core::{fmt, ops};
+use crate::{Random, Rng};
+use crate::distributions::SampleUniform;
+
+/// Helper trait for sampling a random offset from a value within bounds.
+///
+/// This is the implementation behind [`Random::offset`](Random::offset) and [`Random::wrapping_offset`](Random::wrapping_offset).
+/// It is implemented for the integer types up to 64 bits, the arithmetic is widened to avoid overflow near the bounds of the type.
+pub trait SampleOffset: SampleUniform {
+	/// Samples uniformly from `[value - delta, value + delta]` clamped to the bounds.
+	///
+	/// See [`Random::offset`](Random::offset) for more information.
+	fn sample_offset<R: Rng + ?Sized>(rng: &mut Random<R>, value: Self, delta: Self, bounds: ops::RangeInclusive<Self>) -> Self;
+
+	/// Samples uniformly from `[value - delta, value + delta]` wrapped around the bounds.
+	///
+	/// See [`Random::wrapping_offset`](Random::wrapping_offset) for more information.
+	fn sample_wrapping_offset<R: Rng + ?Sized>(rng: &mut Random<R>, value: Self, delta: Self, bounds: ops::RangeInclusive<Self>) -> Self;
+}
+
+macro_rules! impl_sample_offset {
+	($ty:ty) => {
+		impl SampleOffset for $ty {
+			#[inline]
+			fn sample_offset<R: Rng + ?Sized>(rng: &mut Random<R>, value: $ty, delta: $ty, bounds: ops::RangeInclusive<$ty>) -> $ty {
+				let (min, max) = bounds.into_inner();
+				if min > max {
+					offset_bounds_error(min, max);
+				}
+				let value = value as i128;
+				let delta = (delta as i128).abs();
+				let low = i128::max(value - delta, min as i128);
+				let high = i128::min(value + delta, max as i128);
+				if low > high {
+					return if value + delta < min as i128 { min } else { max };
+				}
+				rng.range(low as $ty..=high as $ty)
+			}
+			#[inline]
+			fn sample_wrapping_offset<R: Rng + ?Sized>(rng: &mut Random<R>, value: $ty, delta: $ty, bounds: ops::RangeInclusive<$ty>) -> $ty {
+				let (min, max) = bounds.into_inner();
+				if min > max {
+					offset_bounds_error(min, max);
+				}
+				let width = max as i128 - min as i128 + 1;
+				let delta = (delta as i128).abs();
+				let offset = rng.range(-delta..=delta);
+				((value as i128 + offset - min as i128).rem_euclid(width) + min as i128) as $ty
+			}
+		}
+	};
+}
+
+impl_sample_offset!(i8);
+impl_sample_offset!(u8);
+impl_sample_offset!(i16);
+impl_sample_offset!(u16);
+impl_sample_offset!(i32);
+impl_sample_offset!(u32);
+impl_sample_offset!(i64);
+impl_sample_offset!(u64);
+impl_sample_offset!(isize);
+impl_sample_offset!(usize);
+
+#[cold]
+fn offset_bounds_error<T: fmt::Debug>(min: T, max: T) -> ! {
+	panic!("offset called with empty bounds where min: {:?} and max: {:?}", min, max);
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_extremes() {
+	let mut rng = crate::new();
+	for _ in 0..1000 {
+		let _ = rng.offset(u8::MAX, u8::MAX, 0..=u8::MAX);
+		let _ = rng.offset(u8::MIN, u8::MAX, 0..=u8::MAX);
+		let _ = rng.offset(i64::MIN, i64::MAX, i64::MIN..=i64::MAX);
+		let _ = rng.offset(i64::MAX, i64::MIN, i64::MIN..=i64::MAX);
+		let _ = rng.offset(u64::MAX, u64::MAX, 0..=u64::MAX);
+		let _ = rng.wrapping_offset(u64::MAX, u64::MAX, 0..=u64::MAX);
+		let _ = rng.wrapping_offset(i64::MIN, i64::MIN, i64::MIN..=i64::MAX);
+	}
+}
+
+#[test]
+fn test_clamped() {
+	let mut rng = crate::new();
+	let mut counts = [0i32; 4];
+	for _ in 0..10000 {
+		let value = rng.offset(98u8, 5, 0..=100);
+		assert!(value >= 93 && value <= 100, "value: {}", value);
+		if value >= 97 {
+			counts[value as usize - 97] += 1;
+		}
+	}
+	// Values 93..=100 are equally likely, 97..=100 are half of them
+	let mean = (counts[0] + counts[1] + counts[2] + counts[3]) / 4;
+	assert!((mean - 1250).abs() < 150, "counts: {:?}", counts);
+	assert!(counts.iter().all(|&count| (count - mean).abs() < 150), "counts: {:?}", counts);
+
+	// Empty intersection returns the nearest bound
+	assert_eq!(rng.offset(-100i16, 10, 0..=50), 0);
+	assert_eq!(rng.offset(200i16, 10, 0..=50), 50);
+}
+
+#[test]
+fn test_wrapping() {
+	let mut rng = crate::new();
+	let mut seen = [false; 5];
+	for _ in 0..1000 {
+		let value = rng.wrapping_offset(1i32, 2, -1..=3);
+		assert!(value >= -1 && value <= 3, "value: {}", value);
+		seen[(value + 1) as usize] = true;
+	}
+	assert_eq!(seen, [true; 5]);
+
+	for _ in 0..1000 {
+		let value = rng.wrapping_offset(9u32, 1, 0..=9);
+		assert!(value == 8 || value == 9 || value == 0, "value: {}", value);
+	}
+}
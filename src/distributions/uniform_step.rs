@@ -0,0 +1,318 @@
+use core::fmt;
+use crate::{Distribution, Random, Rng};
+use crate::distributions::{UniformInt, UniformSampler};
+
+/// Sample integers uniformly from a grid of evenly spaced values.
+///
+/// Samples values `low + k·step` for a uniform integer `k` in `[0, n]` where `low + n·step = high`.
+/// Both endpoints are included, the single point `low == high` with `n = 0` always returns `low`.
+///
+/// Useful for fixed-point and decimal values such as prices in cent increments without floating point artifacts.
+/// See [`decimal`](UniformStep::decimal) for a convenient constructor which computes the scaling.
+///
+/// # Implementation notes
+///
+/// The constructor requires `high - low` to be an exact multiple of `step`, the top of the range is not silently rounded down.
+/// The step index `k` is sampled with [`UniformInt`](UniformInt) and is therefore unbiased.
+///
+/// The offset `k·step` never exceeds `high - low` which is computed in the unsigned type of the same size,
+/// the result cannot overflow even for ranges spanning the entire integer type.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::UniformStep;
+///
+/// let mut rng = urandom::new();
+/// let distr = UniformStep::new_inclusive(-100, 100, 25);
+/// let value = rng.sample(&distr);
+/// assert_eq!(value % 25, 0);
+/// assert!(value >= -100 && value <= 100);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct UniformStep<T: SampleUniformStep> {
+	low: T,
+	high: T,
+	step: T,
+	index: UniformInt<T::Unsigned>,
+}
+
+/// Error type returned from [`UniformStep`](UniformStep) constructors.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum UniformStepError {
+	/// The step is zero or negative.
+	StepNotPositive,
+	/// The low bound is greater than the high bound.
+	EmptyRange,
+	/// The step is greater than the nonzero difference between the bounds.
+	StepTooLarge,
+	/// The difference between the bounds is not a multiple of the step.
+	NotMultiple,
+	/// The decimal bounds are not finite or do not fit in the integer type after scaling.
+	InvalidDecimal,
+}
+
+impl fmt::Display for UniformStepError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match self {
+			UniformStepError::StepNotPositive => "step is not positive in UniformStep distribution",
+			UniformStepError::EmptyRange => "low is greater than high in UniformStep distribution",
+			UniformStepError::StepTooLarge => "step is greater than high - low in UniformStep distribution",
+			UniformStepError::NotMultiple => "high - low is not a multiple of step in UniformStep distribution",
+			UniformStepError::InvalidDecimal => "decimal bounds are not representable in UniformStep distribution",
+		})
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UniformStepError {}
+
+/// Types which can be sampled from the [`UniformStep`](UniformStep) distribution.
+pub trait SampleUniformStep: Copy + fmt::Debug {
+	/// The unsigned integer type of the same size used to sample the step index.
+	type Unsigned: Copy + Default + fmt::Debug;
+	/// Returns the number of steps `n` such that `low + n·step = high`.
+	fn step_count(low: Self, high: Self, step: Self) -> Result<Self::Unsigned, UniformStepError>;
+	/// Returns `low + index·step`, the caller guarantees this does not exceed the high bound.
+	fn step_offset(low: Self, step: Self, index: Self::Unsigned) -> Self;
+}
+
+impl<T: SampleUniformStep> UniformStep<T> where UniformInt<T::Unsigned>: UniformSampler<T::Unsigned> {
+	/// Constructs a new `UniformStep` distribution sampling `low`, `low + step`, ... up to and including `high`.
+	///
+	/// # Panics
+	///
+	/// Panics if `step` is not positive, `low` is greater than `high`, `step` is greater than a nonzero `high - low`
+	/// or `high - low` is not a multiple of `step`.
+	#[inline]
+	pub fn new_inclusive(low: T, high: T, step: T) -> UniformStep<T> {
+		match UniformStep::try_new_inclusive(low, high, step) {
+			Ok(distr) => distr,
+			Err(err) => uniform_step_error(err),
+		}
+	}
+	/// Constructs a new `UniformStep` distribution sampling `low`, `low + step`, ... up to and including `high`.
+	///
+	/// Returns an error if `step` is not positive, `low` is greater than `high`, `step` is greater than a nonzero `high - low`
+	/// or `high - low` is not a multiple of `step`.
+	#[inline]
+	pub fn try_new_inclusive(low: T, high: T, step: T) -> Result<UniformStep<T>, UniformStepError> {
+		let n = T::step_count(low, high, step)?;
+		let index = UniformInt::new_inclusive(Default::default(), n);
+		Ok(UniformStep { low, high, step, index })
+	}
+	/// Returns the low bound.
+	#[inline]
+	pub fn low(&self) -> T {
+		self.low
+	}
+	/// Returns the high bound.
+	#[inline]
+	pub fn high(&self) -> T {
+		self.high
+	}
+	/// Returns the step.
+	#[inline]
+	pub fn step(&self) -> T {
+		self.step
+	}
+}
+
+#[cfg(feature = "std")]
+impl UniformStep<i64> {
+	/// Constructs a new `UniformStep` distribution over decimals with `digits` fractional digits between `low` and `high`.
+	///
+	/// The samples are integers scaled by `10^digits`, eg. prices in cents for two fractional digits.
+	/// The bounds are scaled and rounded to the nearest integer.
+	///
+	/// # Panics
+	///
+	/// Panics if the bounds are not finite, do not fit in `i64` after scaling, `digits` is greater than 18 or the scaled range is empty.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use urandom::distributions::UniformStep;
+	///
+	/// let mut rng = urandom::new();
+	/// let cents = rng.sample(&UniformStep::decimal(0.01, 99.99, 2));
+	/// assert!(cents >= 1 && cents <= 9999);
+	/// let price = format!("{}.{:02}", cents / 100, cents % 100);
+	/// ```
+	#[inline]
+	pub fn decimal(low: f64, high: f64, digits: u32) -> UniformStep<i64> {
+		match UniformStep::try_decimal(low, high, digits) {
+			Ok(distr) => distr,
+			Err(err) => uniform_step_error(err),
+		}
+	}
+	/// Constructs a new `UniformStep` distribution over decimals with `digits` fractional digits between `low` and `high`.
+	///
+	/// Returns an error if the bounds are not finite, do not fit in `i64` after scaling, `digits` is greater than 18 or the scaled range is empty.
+	pub fn try_decimal(low: f64, high: f64, digits: u32) -> Result<UniformStep<i64>, UniformStepError> {
+		if digits > 18 {
+			return Err(UniformStepError::InvalidDecimal);
+		}
+		let scale = 10i64.pow(digits) as f64;
+		let scaled = |x: f64| {
+			let x = (x * scale).round();
+			// Casting to i64 saturates, check the bounds explicitly
+			if x >= i64::MIN as f64 && x < i64::MAX as f64 { Ok(x as i64) }
+			else { Err(UniformStepError::InvalidDecimal) }
+		};
+		UniformStep::try_new_inclusive(scaled(low)?, scaled(high)?, 1)
+	}
+}
+
+impl<T: SampleUniformStep> Distribution<T> for UniformStep<T> where UniformInt<T::Unsigned>: Distribution<T::Unsigned> {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> T {
+		let index = self.index.sample(rng);
+		T::step_offset(self.low, self.step, index)
+	}
+}
+
+macro_rules! impl_sample_uniform_step {
+	($ty:ty, $unsigned:ty) => {
+		impl SampleUniformStep for $ty {
+			type Unsigned = $unsigned;
+			#[inline]
+			fn step_count(low: $ty, high: $ty, step: $ty) -> Result<$unsigned, UniformStepError> {
+				if step <= 0 {
+					return Err(UniformStepError::StepNotPositive);
+				}
+				if low > high {
+					return Err(UniformStepError::EmptyRange);
+				}
+				// `high - low` may overflow for signed integers
+				let range = high.wrapping_sub(low) as $unsigned;
+				let step = step as $unsigned;
+				// A single point is zero steps of any size
+				if range != 0 && step > range {
+					return Err(UniformStepError::StepTooLarge);
+				}
+				if range % step != 0 {
+					return Err(UniformStepError::NotMultiple);
+				}
+				Ok(range / step)
+			}
+			#[inline]
+			fn step_offset(low: $ty, step: $ty, index: $unsigned) -> $ty {
+				// The offset is at most `high - low` which fits in the unsigned type
+				let offset = index.wrapping_mul(step as $unsigned);
+				low.wrapping_add(offset as $ty)
+			}
+		}
+	};
+}
+
+impl_sample_uniform_step! { i8, u8 }
+impl_sample_uniform_step! { u8, u8 }
+impl_sample_uniform_step! { i16, u16 }
+impl_sample_uniform_step! { u16, u16 }
+impl_sample_uniform_step! { i32, u32 }
+impl_sample_uniform_step! { u32, u32 }
+impl_sample_uniform_step! { i64, u64 }
+impl_sample_uniform_step! { u64, u64 }
+impl_sample_uniform_step! { i128, u128 }
+impl_sample_uniform_step! { u128, u128 }
+impl_sample_uniform_step! { isize, usize }
+impl_sample_uniform_step! { usize, usize }
+
+#[cold]
+fn uniform_step_error(err: UniformStepError) -> ! {
+	panic!("UniformStep::new_inclusive called with invalid parameters: {}", err)
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_grid() {
+	let mut rng = crate::new();
+	let distr = UniformStep::new_inclusive(-100i32, 100, 25);
+	let mut seen = [false; 9];
+	for _ in 0..1000 {
+		let value = rng.sample(&distr);
+		assert!(value >= -100 && value <= 100 && value % 25 == 0, "value: {}", value);
+		seen[((value + 100) / 25) as usize] = true;
+	}
+	assert!(seen.iter().all(|&seen| seen), "{:?}", seen);
+}
+
+#[test]
+fn test_errors() {
+	assert_eq!(UniformStep::try_new_inclusive(0, 10, 0).unwrap_err(), UniformStepError::StepNotPositive);
+	assert_eq!(UniformStep::try_new_inclusive(0, 10, -2).unwrap_err(), UniformStepError::StepNotPositive);
+	assert_eq!(UniformStep::try_new_inclusive(0u8, 10, 0).unwrap_err(), UniformStepError::StepNotPositive);
+	assert_eq!(UniformStep::try_new_inclusive(10, 0, 2).unwrap_err(), UniformStepError::EmptyRange);
+	assert_eq!(UniformStep::try_new_inclusive(0, 10, 11).unwrap_err(), UniformStepError::StepTooLarge);
+	assert_eq!(UniformStep::try_new_inclusive(0, 10, 3).unwrap_err(), UniformStepError::NotMultiple);
+}
+
+#[test]
+fn test_single_point() {
+	let mut rng = crate::new();
+	let distr = UniformStep::new_inclusive(5, 5, 1);
+	for _ in 0..100 {
+		assert_eq!(rng.sample(&distr), 5);
+	}
+	let distr = UniformStep::new_inclusive(u64::MAX, u64::MAX, u64::MAX);
+	assert_eq!(rng.sample(&distr), u64::MAX);
+	let distr = UniformStep::new_inclusive(i8::MIN, i8::MIN, 3);
+	assert_eq!(rng.sample(&distr), i8::MIN);
+}
+
+#[test]
+fn test_extremes() {
+	let mut rng = crate::new();
+
+	// Range spanning the entire type with the largest possible steps
+	let distr = UniformStep::new_inclusive(u8::MIN, u8::MAX, 255);
+	let mut seen = [false; 2];
+	for _ in 0..100 {
+		let value = rng.sample(&distr);
+		assert!(value == u8::MIN || value == u8::MAX, "value: {}", value);
+		seen[(value == u8::MAX) as usize] = true;
+	}
+	assert_eq!(seen, [true, true]);
+
+	let distr = UniformStep::new_inclusive(i8::MIN, i8::MAX, 85);
+	for _ in 0..100 {
+		let value = rng.sample(&distr);
+		assert!(matches!(value, -128 | -43 | 42 | 127), "value: {}", value);
+	}
+
+	let distr = UniformStep::new_inclusive(i64::MIN + 1, i64::MAX, i64::MAX);
+	for _ in 0..100 {
+		let value = rng.sample(&distr);
+		assert!(value == i64::MIN + 1 || value == 0 || value == i64::MAX, "value: {}", value);
+	}
+
+	let distr = UniformStep::new_inclusive(u64::MAX - 30, u64::MAX, 10);
+	for _ in 0..100 {
+		let value = rng.sample(&distr);
+		assert!(value >= u64::MAX - 30 && (u64::MAX - value).is_multiple_of(10), "value: {}", value);
+	}
+
+	// Full range with step one is the full uniform distribution
+	let distr = UniformStep::new_inclusive(u128::MIN, u128::MAX, 1);
+	let _ = rng.sample(&distr);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_decimal() {
+	let mut rng = crate::new();
+	let distr = UniformStep::decimal(0.01, 99.99, 2);
+	assert_eq!((distr.low(), distr.high(), distr.step()), (1, 9999, 1));
+	for _ in 0..1000 {
+		let cents = rng.sample(&distr);
+		assert!(cents >= 1 && cents <= 9999, "cents: {}", cents);
+	}
+
+	assert_eq!(UniformStep::try_decimal(0.2, 0.4, 0).map(|distr| (distr.low(), distr.high())), Ok((0, 0)));
+	assert_eq!(UniformStep::try_decimal(0.0, f64::INFINITY, 2).unwrap_err(), UniformStepError::InvalidDecimal);
+	assert_eq!(UniformStep::try_decimal(f64::NAN, 1.0, 2).unwrap_err(), UniformStepError::InvalidDecimal);
+	assert_eq!(UniformStep::try_decimal(0.0, 1e17, 2).unwrap_err(), UniformStepError::InvalidDecimal);
+	assert_eq!(UniformStep::try_decimal(0.0, 1.0, 19).unwrap_err(), UniformStepError::InvalidDecimal);
+}
@@ -0,0 +1,116 @@
+use crate::{Distribution, Random, Rng};
+
+/// Sample points uniformly distributed inside the unit disc.
+///
+/// The points `[x, y]` satisfy `x² + y² <= 1`.
+///
+/// # Implementation notes
+///
+/// Uses rejection sampling: points are sampled uniformly from the square `[-1, 1)²` until one falls inside the disc.
+/// The acceptance probability is `π/4`, the expected number of attempts is `4/π ≈ 1.27` consuming two `f64` samples each.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::UnitDisc;
+///
+/// let mut rng = urandom::new();
+/// let [x, y] = rng.sample(&UnitDisc);
+/// assert!(x * x + y * y <= 1.0);
+/// ```
+#[derive(Copy, Clone, Debug, Default)]
+pub struct UnitDisc;
+
+/// Sample points uniformly distributed inside the unit ball.
+///
+/// The points `[x, y, z]` satisfy `x² + y² + z² <= 1`.
+///
+/// # Implementation notes
+///
+/// Uses rejection sampling: points are sampled uniformly from the cube `[-1, 1)³` until one falls inside the ball.
+/// The acceptance probability is `π/6`, the expected number of attempts is `6/π ≈ 1.91` consuming three `f64` samples each.
+///
+/// See [`Ball`](super::Ball) for higher dimensions where rejection sampling becomes inefficient.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::UnitBall;
+///
+/// let mut rng = urandom::new();
+/// let [x, y, z] = rng.sample(&UnitBall);
+/// assert!(x * x + y * y + z * z <= 1.0);
+/// ```
+#[derive(Copy, Clone, Debug, Default)]
+pub struct UnitBall;
+
+// Uniform in the half-open interval `[-1, 1)`
+#[inline]
+fn symmetric<R: Rng + ?Sized>(rng: &mut Random<R>) -> f64 {
+	rng.next_f64() * 2.0 - 3.0
+}
+
+impl Distribution<[f64; 2]> for UnitDisc {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> [f64; 2] {
+		loop {
+			let x = symmetric(rng);
+			let y = symmetric(rng);
+			if x * x + y * y <= 1.0 {
+				return [x, y];
+			}
+		}
+	}
+}
+
+impl Distribution<[f64; 3]> for UnitBall {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> [f64; 3] {
+		loop {
+			let x = symmetric(rng);
+			let y = symmetric(rng);
+			let z = symmetric(rng);
+			if x * x + y * y + z * z <= 1.0 {
+				return [x, y, z];
+			}
+		}
+	}
+}
+
+//----------------------------------------------------------------
+
+#[cfg(test)]
+pub(crate) fn check_ball<const N: usize, D: Distribution<[f64; N]>>(distr: &D) {
+	let mut rng = crate::seeded(42);
+	const SAMPLES: usize = 100000;
+	const BUCKETS: usize = 10;
+	let mut sum = [0.0; N];
+	let mut buckets = [0usize; BUCKETS];
+	for _ in 0..SAMPLES {
+		let point = distr.sample(&mut rng);
+		let norm2: f64 = point.iter().map(|x| x * x).sum();
+		assert!(norm2 <= 1.0, "{:?}", point);
+		for (sum, x) in sum.iter_mut().zip(point) {
+			*sum += x;
+		}
+		// The radial CDF is r^N, so r^N is uniformly distributed
+		let u = norm2.sqrt().powi(N as i32);
+		buckets[usize::min((u * BUCKETS as f64) as usize, BUCKETS - 1)] += 1;
+	}
+	for sum in sum {
+		assert!((sum / SAMPLES as f64).abs() < 0.01, "mean: {}", sum / SAMPLES as f64);
+	}
+	for &count in &buckets {
+		assert!((count as f64 - (SAMPLES / BUCKETS) as f64).abs() < 400.0, "{:?}", buckets);
+	}
+}
+
+#[test]
+fn test_unit_disc() {
+	check_ball(&UnitDisc);
+}
+
+#[test]
+fn test_unit_ball() {
+	check_ball(&UnitBall);
+}
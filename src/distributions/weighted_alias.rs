@@ -0,0 +1,242 @@
+use core::fmt;
+use alloc::vec::Vec;
+use crate::{Distribution, Random, Rng};
+use crate::distributions::{SampleWeight, UniformInt, UniformSampler};
+
+/// Sample indices weighted by a table of weights in constant time.
+///
+/// The probability of index `i` is `weights[i] / total_weight`, indices with zero weight are never sampled.
+///
+/// Prefer [`WeightedIndex`](super::WeightedIndex) for small tables or few samples, it is cheaper to construct.
+///
+/// # Implementation notes
+///
+/// Implements Walker's alias method with Vose's construction in `O(n)`.
+///
+/// The table has one column per index. Every column holds a threshold and an alias.
+/// Sampling picks a column uniformly and returns its index if a random `u64` is less than its threshold, otherwise its alias, in `O(1)`.
+///
+/// The weights are converted to `f64` to build the table, the probabilities are accurate to about `2^-52` relative to the total weight.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::WeightedAliasIndex;
+///
+/// let mut rng = urandom::new();
+/// let distr = WeightedAliasIndex::new(vec![1.0, 0.5, 8.5]);
+/// let index = rng.sample(&distr);
+/// assert!(index < 3);
+/// ```
+#[derive(Clone, Debug)]
+pub struct WeightedAliasIndex<W: SampleWeight> {
+	// Threshold and alias of every column
+	table: Vec<(u64, usize)>,
+	index: UniformInt<usize>,
+	total: W,
+}
+
+/// Error type returned from [`WeightedAliasIndex`](WeightedAliasIndex) constructors.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum WeightedAliasIndexError {
+	/// There are no weights.
+	Empty,
+	/// A weight is negative or not finite.
+	InvalidWeight,
+	/// All weights are zero.
+	AllZero,
+	/// The sum of the weights overflows.
+	Overflow,
+}
+
+impl fmt::Display for WeightedAliasIndexError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match self {
+			WeightedAliasIndexError::Empty => "no weights in WeightedAliasIndex distribution",
+			WeightedAliasIndexError::InvalidWeight => "weight is negative or not finite in WeightedAliasIndex distribution",
+			WeightedAliasIndexError::AllZero => "all weights are zero in WeightedAliasIndex distribution",
+			WeightedAliasIndexError::Overflow => "sum of the weights overflows in WeightedAliasIndex distribution",
+		})
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for WeightedAliasIndexError {}
+
+impl<W: SampleWeight> WeightedAliasIndex<W> {
+	/// Constructs a new `WeightedAliasIndex` distribution from the weights.
+	///
+	/// # Panics
+	///
+	/// Panics if there are no weights, any weight is negative or not finite, all weights are zero or the sum of the weights overflows.
+	#[inline]
+	pub fn new(weights: Vec<W>) -> WeightedAliasIndex<W> {
+		match WeightedAliasIndex::try_new(weights) {
+			Ok(distr) => distr,
+			Err(err) => weighted_alias_index_error(err),
+		}
+	}
+	/// Constructs a new `WeightedAliasIndex` distribution from the weights.
+	///
+	/// Returns an error if there are no weights, any weight is negative or not finite, all weights are zero or the sum of the weights overflows.
+	pub fn try_new(weights: Vec<W>) -> Result<WeightedAliasIndex<W>, WeightedAliasIndexError> {
+		if weights.is_empty() {
+			return Err(WeightedAliasIndexError::Empty);
+		}
+		let mut total = W::ZERO;
+		for &weight in &weights {
+			if !weight.is_valid_weight() {
+				return Err(WeightedAliasIndexError::InvalidWeight);
+			}
+			total = match total.checked_add_weight(weight) {
+				Some(total) => total,
+				None => return Err(WeightedAliasIndexError::Overflow),
+			};
+		}
+		if total == W::ZERO {
+			return Err(WeightedAliasIndexError::AllZero);
+		}
+
+		// Scale the weights so that the average column is exactly full
+		// Divide by the total first, `n / total` overflows for tiny totals
+		let n = weights.len();
+		let total_f64 = total.to_f64_weight();
+		let mut probs: Vec<f64> = weights.iter().map(|&weight| weight.to_f64_weight() / total_f64 * n as f64).collect();
+		let mut small = Vec::new();
+		let mut large = Vec::new();
+		for (i, &prob) in probs.iter().enumerate() {
+			if prob < 1.0 { small.push(i) } else { large.push(i) }
+		}
+
+		// Fill every small column with the excess of a large column
+		// The columns left over due to rounding are full, their alias is themselves
+		let mut table: Vec<(u64, usize)> = (0..n).map(|i| (u64::MAX, i)).collect();
+		while let (Some(&s), Some(&l)) = (small.last(), large.last()) {
+			small.pop();
+			table[s] = (threshold(probs[s]), l);
+			probs[l] = (probs[l] + probs[s]) - 1.0;
+			if probs[l] < 1.0 {
+				large.pop();
+				small.push(l);
+			}
+		}
+
+		let index = UniformInt::new(0, n);
+		Ok(WeightedAliasIndex { table, index, total })
+	}
+
+	/// Returns the number of weights.
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.table.len()
+	}
+	/// Returns `false`, the distribution has at least one weight.
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.table.is_empty()
+	}
+	/// Returns the sum of the weights.
+	#[inline]
+	pub fn total_weight(&self) -> W {
+		self.total
+	}
+}
+
+// Maps a probability in [0, 1) to the threshold of a random u64
+#[inline]
+fn threshold(prob: f64) -> u64 {
+	(prob * 18446744073709551616.0) as u64
+}
+
+#[cold]
+fn weighted_alias_index_error(err: WeightedAliasIndexError) -> ! {
+	panic!("{}", err)
+}
+
+impl<W: SampleWeight> Distribution<usize> for WeightedAliasIndex<W> {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> usize {
+		let column = self.index.sample(rng);
+		let (threshold, alias) = self.table[column];
+		if rng.next_u64() < threshold { column } else { alias }
+	}
+}
+
+//----------------------------------------------------------------
+
+#[cfg(feature = "std")]
+#[test]
+fn test_bias() {
+	let mut rng = crate::seeded(42);
+
+	// Skewed weights where most columns are filled by the one heavy weight
+	let weights = vec![1000u32, 1, 2, 3, 0, 5, 8, 13, 21, 34];
+	let distr = WeightedAliasIndex::new(weights.clone());
+	assert_eq!(distr.total_weight(), 1087);
+	let mut counts = [0u64; 10];
+	for _ in 0..1000000 {
+		counts[rng.sample(&distr)] += 1;
+	}
+	assert_eq!(counts[4], 0);
+	let probabilities: Vec<f64> = weights.iter().map(|&weight| weight as f64).collect();
+	crate::stats::assert_counts(&counts, &probabilities, 1e-4);
+
+	let weights = vec![0.1f64, 0.0, 2.5, 0.4, 7.0];
+	let distr = WeightedAliasIndex::new(weights.clone());
+	let mut counts = [0u64; 5];
+	for _ in 0..1000000 {
+		counts[rng.sample(&distr)] += 1;
+	}
+	assert_eq!(counts[1], 0);
+	crate::stats::assert_counts(&counts, &weights, 1e-4);
+}
+
+#[test]
+fn test_table() {
+	// The columns add up to the probability of every index
+	let weights = vec![1u64, 1, 2, 4, 8];
+	let distr = WeightedAliasIndex::new(weights.clone());
+	let n = distr.len() as f64;
+	let mut mass = [0.0; 5];
+	for (column, &(threshold, alias)) in distr.table.iter().enumerate() {
+		let p = if threshold == u64::MAX { 1.0 } else { threshold as f64 / 18446744073709551616.0 };
+		mass[column] += p / n;
+		mass[alias] += (1.0 - p) / n;
+	}
+	for (&weight, &mass) in weights.iter().zip(&mass) {
+		assert!((mass - weight as f64 / 16.0).abs() < 1e-15, "{:?}", mass);
+	}
+
+	// A single weight always samples its index
+	let mut rng = crate::seeded(42);
+	let distr = WeightedAliasIndex::new(vec![0.5f32]);
+	for _ in 0..100 {
+		assert_eq!(rng.sample(&distr), 0);
+	}
+}
+
+#[test]
+fn test_subnormal() {
+	// The scale of tiny totals overflows, zero weights must still never be sampled
+	let mut rng = crate::seeded(42);
+	let distr = WeightedAliasIndex::new(vec![1e-310f64, 0.0, 1e-310]);
+	assert_eq!(distr.table[1].0, 0);
+	for _ in 0..1000 {
+		assert_ne!(rng.sample(&distr), 1);
+	}
+	let distr = WeightedAliasIndex::new(vec![f64::MIN_POSITIVE / 4.0, 0.0, 0.0, f64::MIN_POSITIVE]);
+	for _ in 0..1000 {
+		assert!(matches!(rng.sample(&distr), 0 | 3));
+	}
+}
+
+#[test]
+fn test_errors() {
+	assert_eq!(WeightedAliasIndex::<f64>::try_new(vec![]).err(), Some(WeightedAliasIndexError::Empty));
+	assert_eq!(WeightedAliasIndex::try_new(vec![0u32, 0]).err(), Some(WeightedAliasIndexError::AllZero));
+	assert_eq!(WeightedAliasIndex::try_new(vec![1.0f64, -0.5]).err(), Some(WeightedAliasIndexError::InvalidWeight));
+	assert_eq!(WeightedAliasIndex::try_new(vec![f32::NAN]).err(), Some(WeightedAliasIndexError::InvalidWeight));
+	assert_eq!(WeightedAliasIndex::try_new(vec![1.0, f64::INFINITY]).err(), Some(WeightedAliasIndexError::InvalidWeight));
+	assert_eq!(WeightedAliasIndex::try_new(vec![f64::MAX, f64::MAX]).err(), Some(WeightedAliasIndexError::Overflow));
+	assert_eq!(WeightedAliasIndex::try_new(vec![u64::MAX, 1]).err(), Some(WeightedAliasIndexError::Overflow));
+}
@@ -0,0 +1,275 @@
+use core::fmt;
+use crate::{Distribution, Random, Rng};
+use crate::distributions::Float01;
+
+/// Sample indices in `0..n` weighted by a function of the index, using `O(1)` memory.
+///
+/// Unlike a cumulative weight table the weights are never materialized, making this suitable for very large `n`.
+///
+/// * [`decay`](WeightedFn::decay) samples geometrically decaying weights `ratio^i` by inversion.
+/// * [`power`](WeightedFn::power) samples Zipf-like weights `(i + 1)^-exponent` by rejection-inversion.
+/// * [`rejection`](WeightedFn::rejection) samples arbitrary weights given an upper bound on the weights by rejection sampling.
+/// * [`new`](WeightedFn::new) samples monotonically decreasing weights by rejection sampling.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::WeightedFn;
+///
+/// let mut rng = urandom::new();
+/// let distr = WeightedFn::decay(10_000_000, 0.999).unwrap();
+/// let index = rng.sample(&distr);
+/// assert!(index < 10_000_000);
+/// ```
+#[derive(Copy, Clone)]
+pub struct WeightedFn<F = fn(usize) -> f64> {
+	n: usize,
+	method: Method<F>,
+}
+
+#[derive(Copy, Clone)]
+enum Method<F> {
+	Decay { ln_ratio: f64, mass: f64 },
+	Power { exponent: f64, t: f64, q: f64 },
+	Rejection { f: F, max_weight: f64 },
+}
+
+/// Error type returned from [`WeightedFn`](WeightedFn) constructors.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum WeightedFnError {
+	/// There are no indices to sample.
+	Empty,
+	/// The decay ratio is not in the interval `(0, 1]`.
+	InvalidRatio,
+	/// The exponent is not positive and finite.
+	InvalidExponent,
+	/// The max weight is not positive and finite.
+	InvalidMaxWeight,
+}
+
+impl fmt::Display for WeightedFnError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match self {
+			WeightedFnError::Empty => "no indices to sample in weighted distribution",
+			WeightedFnError::InvalidRatio => "decay ratio is not in (0, 1] in weighted distribution",
+			WeightedFnError::InvalidExponent => "exponent is not positive and finite in weighted distribution",
+			WeightedFnError::InvalidMaxWeight => "max weight is not positive and finite in weighted distribution",
+		})
+	}
+}
+
+impl std::error::Error for WeightedFnError {}
+
+impl WeightedFn {
+	/// Constructs a distribution with geometrically decaying weights `ratio^i` for `i` in `0..n`.
+	///
+	/// Returns an error if `n` is zero or the ratio is not in the interval `(0, 1]`.
+	pub fn decay(n: usize, ratio: f64) -> Result<WeightedFn, WeightedFnError> {
+		if n == 0 {
+			return Err(WeightedFnError::Empty);
+		}
+		if !(ratio > 0.0 && ratio <= 1.0) {
+			return Err(WeightedFnError::InvalidRatio);
+		}
+		let ln_ratio = ratio.ln();
+		// Probability mass of the truncated geometric distribution: 1 - ratio^n
+		let mass = -(n as f64 * ln_ratio).exp_m1();
+		Ok(WeightedFn { n, method: Method::Decay { ln_ratio, mass } })
+	}
+
+	/// Constructs a distribution with Zipf-like weights `(i + 1)^-exponent` for `i` in `0..n`.
+	///
+	/// Returns an error if `n` is zero or the exponent is not positive and finite.
+	pub fn power(n: usize, exponent: f64) -> Result<WeightedFn, WeightedFnError> {
+		if n == 0 {
+			return Err(WeightedFnError::Empty);
+		}
+		if !(exponent > 0.0 && exponent.is_finite()) {
+			return Err(WeightedFnError::InvalidExponent);
+		}
+		let n = n as f64;
+		let (t, q) = if exponent != 1.0 {
+			let q = 1.0 / (1.0 - exponent);
+			((n.powf(1.0 - exponent) - exponent) * q, q)
+		}
+		else {
+			(1.0 + n.ln(), 0.0)
+		};
+		Ok(WeightedFn { n: n as usize, method: Method::Power { exponent, t, q } })
+	}
+}
+
+impl<F: Fn(usize) -> f64> WeightedFn<F> {
+	/// Constructs a distribution with monotonically decreasing weights `f(i)` for `i` in `0..n`.
+	///
+	/// This is rejection sampling with the max weight `f(0)`, see [`rejection`](WeightedFn::rejection).
+	///
+	/// Returns an error if `n` is zero or `f(0)` is not positive and finite.
+	#[inline]
+	pub fn new(n: usize, f: F) -> Result<WeightedFn<F>, WeightedFnError> {
+		if n == 0 {
+			return Err(WeightedFnError::Empty);
+		}
+		let max_weight = f(0);
+		WeightedFn::rejection(n, f, max_weight)
+	}
+
+	/// Constructs a distribution with weights `f(i)` for `i` in `0..n` bounded by `max_weight`.
+	///
+	/// The expected number of evaluations of `f` per sample is `n · max_weight / Σf`, the bound should be tight.
+	///
+	/// Weights exceeding `max_weight` bias the distribution, sampling panics when this is detected in debug builds.
+	///
+	/// Returns an error if `n` is zero or `max_weight` is not positive and finite.
+	#[inline]
+	pub fn rejection(n: usize, f: F, max_weight: f64) -> Result<WeightedFn<F>, WeightedFnError> {
+		if n == 0 {
+			return Err(WeightedFnError::Empty);
+		}
+		if !(max_weight > 0.0 && max_weight.is_finite()) {
+			return Err(WeightedFnError::InvalidMaxWeight);
+		}
+		Ok(WeightedFn { n, method: Method::Rejection { f, max_weight } })
+	}
+}
+
+impl<F> WeightedFn<F> {
+	/// Returns the number of indices.
+	#[inline]
+	pub const fn n(&self) -> usize {
+		self.n
+	}
+}
+
+impl<F> fmt::Debug for WeightedFn<F> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match &self.method {
+			Method::Decay { ln_ratio, .. } => write!(f, "WeightedFn::decay({}, {})", self.n, ln_ratio.exp()),
+			Method::Power { exponent, .. } => write!(f, "WeightedFn::power({}, {})", self.n, exponent),
+			Method::Rejection { max_weight, .. } => write!(f, "WeightedFn::rejection({}, impl Fn, {})", self.n, max_weight),
+		}
+	}
+}
+
+impl<F: Fn(usize) -> f64> Distribution<usize> for WeightedFn<F> {
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> usize {
+		match self.method {
+			Method::Decay { ln_ratio, mass } => {
+				if ln_ratio == 0.0 {
+					return rng.index(self.n);
+				}
+				// Inverse CDF of the truncated geometric distribution
+				let u: f64 = Float01.sample(rng);
+				let index = (-u * mass).ln_1p() / ln_ratio;
+				usize::min(index as usize, self.n - 1)
+			},
+			Method::Power { exponent, t, q } => {
+				// Rejection-inversion, see Hörmann and Derflinger (1996)
+				loop {
+					let u: f64 = Float01.sample(rng);
+					let pt = u * t;
+					let inv_b = if pt <= 1.0 { pt }
+						else if exponent != 1.0 { (pt * (1.0 - exponent) + exponent).powf(q) }
+						else { (pt - 1.0).exp() };
+					let x = (inv_b + 1.0).floor();
+					let mut ratio = x.powf(-exponent);
+					if x > 1.0 {
+						ratio *= inv_b.powf(exponent);
+					}
+					let y: f64 = Float01.sample(rng);
+					if y < ratio {
+						return usize::min(x as usize, self.n) - 1;
+					}
+				}
+			},
+			Method::Rejection { ref f, max_weight } => {
+				loop {
+					let index = rng.index(self.n);
+					let weight = f(index);
+					if cfg!(debug_assertions) && (weight > max_weight || weight.is_nan()) {
+						max_weight_error(index, weight, max_weight);
+					}
+					let y: f64 = Float01.sample(rng);
+					if y * max_weight < weight {
+						return index;
+					}
+				}
+			},
+		}
+	}
+}
+
+#[cold]
+fn max_weight_error(index: usize, weight: f64, max_weight: f64) -> ! {
+	panic!("WeightedFn weight at index {} (is {}) exceeds max weight (is {})", index, weight, max_weight)
+}
+
+//----------------------------------------------------------------
+
+#[cfg(test)]
+fn check_frequencies<D: Distribution<usize>>(distr: &D, n: usize, weight: impl Fn(usize) -> f64) {
+	let mut rng = crate::seeded(42);
+	const N: usize = 200000;
+	let mut counts = vec![0usize; n];
+	for _ in 0..N {
+		counts[distr.sample(&mut rng)] += 1;
+	}
+	let total: f64 = (0..n).map(&weight).sum();
+	// Compare the cumulative frequencies to avoid noise in the tail
+	let mut expected = 0.0;
+	let mut observed = 0.0;
+	for i in 0..n {
+		expected += weight(i) / total;
+		observed += counts[i] as f64 / N as f64;
+		assert!((observed - expected).abs() < 0.005, "index: {} observed: {} expected: {}", i, observed, expected);
+	}
+}
+
+#[test]
+fn test_decay() {
+	let distr = WeightedFn::decay(1000, 0.99).unwrap();
+	check_frequencies(&distr, 1000, |i| 0.99f64.powi(i as i32));
+	let distr = WeightedFn::decay(10, 1.0).unwrap();
+	check_frequencies(&distr, 10, |_| 1.0);
+}
+
+#[test]
+fn test_power() {
+	for &exponent in &[0.5, 1.0, 1.5, 3.0] {
+		let distr = WeightedFn::power(1000, exponent).unwrap();
+		check_frequencies(&distr, 1000, |i| ((i + 1) as f64).powf(-exponent));
+	}
+	let distr = WeightedFn::power(1, 2.0).unwrap();
+	check_frequencies(&distr, 1, |_| 1.0);
+}
+
+#[test]
+fn test_rejection() {
+	let weight = |i: usize| [2.0, 1.5, 1.0, 0.25][i % 4];
+	let distr = WeightedFn::rejection(100, weight, 2.0).unwrap();
+	check_frequencies(&distr, 100, weight);
+	let distr = WeightedFn::new(1000, |i| 0.99f64.powi(i as i32)).unwrap();
+	check_frequencies(&distr, 1000, |i| 0.99f64.powi(i as i32));
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "exceeds max weight")]
+fn test_max_weight_violation() {
+	let distr = WeightedFn::rejection(10, |i| i as f64, 5.0).unwrap();
+	let mut rng = crate::new();
+	for _ in 0..1000 {
+		let _ = rng.sample(&distr);
+	}
+}
+
+#[test]
+fn test_validation() {
+	assert_eq!(WeightedFn::decay(0, 0.5).unwrap_err(), WeightedFnError::Empty);
+	assert_eq!(WeightedFn::decay(10, 0.0).unwrap_err(), WeightedFnError::InvalidRatio);
+	assert_eq!(WeightedFn::decay(10, 1.5).unwrap_err(), WeightedFnError::InvalidRatio);
+	assert_eq!(WeightedFn::power(10, -1.0).unwrap_err(), WeightedFnError::InvalidExponent);
+	assert_eq!(WeightedFn::rejection(10, |_| 1.0, f64::NAN).unwrap_err(), WeightedFnError::InvalidMaxWeight);
+	assert_eq!(WeightedFn::new(0, |_| 1.0).unwrap_err(), WeightedFnError::Empty);
+	assert_eq!(WeightedFn::new(10, |_| 0.0).unwrap_err(), WeightedFnError::InvalidMaxWeight);
+}
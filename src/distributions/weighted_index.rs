@@ -0,0 +1,264 @@
+use core::fmt;
+use alloc::vec::Vec;
+use crate::{Distribution, Random, Rng};
+use crate::distributions::{SampleUniform, UniformSampler};
+
+/// Sample indices weighted by a table of weights.
+///
+/// The probability of index `i` is `weights[i] / total_weight`, indices with zero weight are never sampled.
+///
+/// # Implementation notes
+///
+/// The cumulative weights are precomputed at construction.
+/// Sampling draws a value uniformly from `[0, total_weight)` and binary searches the cumulative weights in `O(log n)`.
+///
+/// Integer weights are sampled exactly, float weights are subject to rounding of the cumulative sums.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::WeightedIndex;
+///
+/// let mut rng = urandom::new();
+/// let loot = ["common", "rare", "legendary"];
+/// let distr = WeightedIndex::new([90u32, 9, 1]);
+/// let drop = loot[rng.sample(&distr)];
+/// assert_eq!(distr.total_weight(), 100);
+/// ```
+#[derive(Clone, Debug)]
+pub struct WeightedIndex<W: SampleWeight> {
+	cumulative: Vec<W>,
+	index: W::Sampler,
+	// Index of the last positive weight
+	last: usize,
+}
+
+/// Error type returned from [`WeightedIndex`](WeightedIndex) constructors.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum WeightedIndexError {
+	/// There are no weights.
+	Empty,
+	/// A weight is negative or not finite.
+	InvalidWeight,
+	/// All weights are zero.
+	AllZero,
+	/// The sum of the weights overflows.
+	Overflow,
+}
+
+impl fmt::Display for WeightedIndexError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match self {
+			WeightedIndexError::Empty => "no weights in WeightedIndex distribution",
+			WeightedIndexError::InvalidWeight => "weight is negative or not finite in WeightedIndex distribution",
+			WeightedIndexError::AllZero => "all weights are zero in WeightedIndex distribution",
+			WeightedIndexError::Overflow => "sum of the weights overflows in WeightedIndex distribution",
+		})
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for WeightedIndexError {}
+
+/// Weight types of the [`WeightedIndex`](WeightedIndex) and [`WeightedAliasIndex`](super::WeightedAliasIndex) distributions.
+pub trait SampleWeight: SampleUniform + Copy + PartialOrd {
+	/// The zero weight.
+	const ZERO: Self;
+	/// Returns `true` if the weight is non-negative and finite.
+	fn is_valid_weight(self) -> bool;
+	/// Adds two weights, returns `None` if the sum overflows or is not finite.
+	fn checked_add_weight(self, other: Self) -> Option<Self>;
+	/// Converts the weight to `f64`, rounding to the nearest value.
+	fn to_f64_weight(self) -> f64;
+}
+
+macro_rules! impl_sample_weight_int {
+	($($ty:ty),*) => {$(
+		impl SampleWeight for $ty {
+			const ZERO: $ty = 0;
+			#[inline]
+			#[allow(unused_comparisons)]
+			fn is_valid_weight(self) -> bool {
+				self >= 0
+			}
+			#[inline]
+			fn checked_add_weight(self, other: $ty) -> Option<$ty> {
+				self.checked_add(other)
+			}
+			#[inline]
+			fn to_f64_weight(self) -> f64 {
+				self as f64
+			}
+		}
+	)*};
+}
+
+macro_rules! impl_sample_weight_float {
+	($($ty:ty),*) => {$(
+		impl SampleWeight for $ty {
+			const ZERO: $ty = 0.0;
+			#[inline]
+			fn is_valid_weight(self) -> bool {
+				self >= 0.0 && self.is_finite()
+			}
+			#[inline]
+			fn checked_add_weight(self, other: $ty) -> Option<$ty> {
+				let sum = self + other;
+				if sum.is_finite() { Some(sum) } else { None }
+			}
+			#[inline]
+			fn to_f64_weight(self) -> f64 {
+				self as f64
+			}
+		}
+	)*};
+}
+
+impl_sample_weight_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+impl_sample_weight_float!(f32, f64);
+
+impl<W: SampleWeight> WeightedIndex<W> {
+	/// Constructs a new `WeightedIndex` distribution from the weights.
+	///
+	/// # Panics
+	///
+	/// Panics if there are no weights, any weight is negative or not finite, all weights are zero or the sum of the weights overflows.
+	#[inline]
+	pub fn new<I: IntoIterator<Item = W>>(weights: I) -> WeightedIndex<W> {
+		match WeightedIndex::try_new(weights) {
+			Ok(distr) => distr,
+			Err(err) => weighted_index_error(err),
+		}
+	}
+	/// Constructs a new `WeightedIndex` distribution from the weights.
+	///
+	/// Returns an error if there are no weights, any weight is negative or not finite, all weights are zero or the sum of the weights overflows.
+	pub fn try_new<I: IntoIterator<Item = W>>(weights: I) -> Result<WeightedIndex<W>, WeightedIndexError> {
+		let weights = weights.into_iter();
+		let mut cumulative = Vec::with_capacity(weights.size_hint().0);
+		let mut total = W::ZERO;
+		for weight in weights {
+			if !weight.is_valid_weight() {
+				return Err(WeightedIndexError::InvalidWeight);
+			}
+			total = match total.checked_add_weight(weight) {
+				Some(total) => total,
+				None => return Err(WeightedIndexError::Overflow),
+			};
+			cumulative.push(total);
+		}
+		if cumulative.is_empty() {
+			return Err(WeightedIndexError::Empty);
+		}
+		if total == W::ZERO {
+			return Err(WeightedIndexError::AllZero);
+		}
+		// The first cumulative weight equal to the total belongs to the last positive weight
+		let last = cumulative.partition_point(|&cumulative| cumulative < total);
+		let index = W::Sampler::new(W::ZERO, total);
+		Ok(WeightedIndex { cumulative, index, last })
+	}
+
+	/// Returns the number of weights.
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.cumulative.len()
+	}
+	/// Returns `false`, the distribution has at least one weight.
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.cumulative.is_empty()
+	}
+	/// Returns the sum of the weights.
+	#[inline]
+	pub fn total_weight(&self) -> W {
+		self.cumulative[self.cumulative.len() - 1]
+	}
+}
+
+#[cold]
+fn weighted_index_error(err: WeightedIndexError) -> ! {
+	panic!("{}", err)
+}
+
+impl<W: SampleWeight> Distribution<usize> for WeightedIndex<W> {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> usize {
+		let value = self.index.sample(rng);
+		let index = self.cumulative.partition_point(|&cumulative| cumulative <= value);
+		// Rounding of the float sampler may land on the total weight, skip the trailing zero weights
+		usize::min(index, self.last)
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_frequencies() {
+	let mut rng = crate::seeded(42);
+	let weights = [1u32, 0, 2, 7];
+	let distr = WeightedIndex::new(weights);
+	assert_eq!(distr.total_weight(), 10);
+	let mut counts = [0; 4];
+	for _ in 0..100000 {
+		counts[rng.sample(&distr)] += 1;
+	}
+	assert_eq!(counts[1], 0);
+	for (&weight, &count) in weights.iter().zip(&counts) {
+		let expected = weight as f64 * 10000.0;
+		assert!((count as f64 - expected).abs() < 600.0, "{:?}", counts);
+	}
+
+	let distr = WeightedIndex::new([0.25f64, 0.0, 0.75]);
+	assert_eq!(distr.total_weight(), 1.0);
+	let mut counts = [0; 3];
+	for _ in 0..100000 {
+		counts[rng.sample(&distr)] += 1;
+	}
+	assert_eq!(counts[1], 0);
+	assert!((counts[0] as i32 - 25000).abs() < 800, "{:?}", counts);
+
+	// Zero weights at the ends are never sampled
+	let distr = WeightedIndex::new([0u64, 5, 0]);
+	for _ in 0..1000 {
+		assert_eq!(rng.sample(&distr), 1);
+	}
+	let distr = WeightedIndex::new([0.0f32, 0.0, 1.0e-30]);
+	for _ in 0..1000 {
+		assert_eq!(rng.sample(&distr), 2);
+	}
+}
+
+#[test]
+fn test_trailing_zero() {
+	// Samples which round to the total weight return the last positive weight
+	let distr = WeightedIndex::new([1.0f64, 2.0, 0.0, 0.0]);
+	assert_eq!(distr.last, 1);
+	let distr = WeightedIndex { index: <f64 as SampleUniform>::Sampler::new(3.0, 3.0), ..distr };
+	let mut rng = crate::seeded(42);
+	for _ in 0..100 {
+		assert_eq!(rng.sample(&distr), 1);
+	}
+	assert_eq!(WeightedIndex::new([0u32, 3, 0]).last, 1);
+	assert_eq!(WeightedIndex::new([5u8]).last, 0);
+}
+
+#[test]
+fn test_errors() {
+	assert_eq!(WeightedIndex::<u32>::try_new([]).err(), Some(WeightedIndexError::Empty));
+	assert_eq!(WeightedIndex::try_new([0u32, 0]).err(), Some(WeightedIndexError::AllZero));
+	assert_eq!(WeightedIndex::try_new([0.0f64, -0.0]).err(), Some(WeightedIndexError::AllZero));
+	assert_eq!(WeightedIndex::try_new([1.0f64, -1.0]).err(), Some(WeightedIndexError::InvalidWeight));
+	assert_eq!(WeightedIndex::try_new([1.0f32, f32::NAN]).err(), Some(WeightedIndexError::InvalidWeight));
+	assert_eq!(WeightedIndex::try_new([f64::INFINITY]).err(), Some(WeightedIndexError::InvalidWeight));
+	assert_eq!(WeightedIndex::try_new([f64::MAX, f64::MAX]).err(), Some(WeightedIndexError::Overflow));
+	assert_eq!(WeightedIndex::try_new([u8::MAX, 1]).err(), Some(WeightedIndexError::Overflow));
+	assert_eq!(WeightedIndex::try_new([3i32, -1]).err(), Some(WeightedIndexError::InvalidWeight));
+	assert_eq!(WeightedIndex::new([u64::MAX]).total_weight(), u64::MAX);
+}
+
+#[test]
+#[should_panic(expected = "all weights are zero in WeightedIndex distribution")]
+fn test_all_zero_panics() {
+	let _ = WeightedIndex::new([0.0f64; 3]);
+}
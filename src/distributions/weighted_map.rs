@@ -0,0 +1,354 @@
+use core::{borrow::Borrow, fmt, hash::Hash};
+use std::collections::HashMap;
+use crate::{Distribution, Random, Rng};
+
+/// Sample keys weighted by a sparse map of weights.
+///
+/// Stores the keys together with their weights and supports changing the weights without rebuilding the distribution.
+/// Sampling returns a clone of the key, see [`sample_ref`](WeightedMap::sample_ref) to sample by reference.
+///
+/// # Implementation notes
+///
+/// The cumulative weights are maintained in a binary sum tree over the weights.
+/// Sampling descends the tree and [`set_weight`](WeightedMap::set_weight) and [`remove`](WeightedMap::remove) update a single path, all in `O(log n)`.
+///
+/// Every node is recomputed as the sum of its children instead of applying the difference in weight,
+/// the partial sums do not depend on the history of updates and keys with zero weight are never sampled.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::distributions::WeightedMap;
+///
+/// let mut rng = urandom::new();
+/// let mut distr = WeightedMap::new([("apple", 2.0), ("banana", 1.0), ("cherry", 0.5)]);
+///
+/// let fruit = rng.sample(&distr);
+/// assert!(["apple", "banana", "cherry"].contains(&fruit));
+///
+/// distr.set_weight("banana", 0.0);
+/// distr.remove(&"cherry");
+/// assert_eq!(rng.sample(&distr), "apple");
+/// ```
+#[derive(Clone, Debug)]
+pub struct WeightedMap<K> {
+	keys: Vec<K>,
+	indices: HashMap<K, usize>,
+	// Binary sum tree with the weights in the leaves starting at `tree.len() / 2`
+	tree: Vec<f64>,
+}
+
+/// Error type returned from [`WeightedMap`](WeightedMap) constructors.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum WeightedMapError {
+	/// A weight is negative or not finite.
+	InvalidWeight,
+}
+
+impl fmt::Display for WeightedMapError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match self {
+			WeightedMapError::InvalidWeight => "weight is negative or not finite in WeightedMap distribution",
+		})
+	}
+}
+
+impl std::error::Error for WeightedMapError {}
+
+impl<K: Clone + Eq + Hash> WeightedMap<K> {
+	/// Constructs a new `WeightedMap` distribution from pairs of keys and weights.
+	///
+	/// When a key is repeated the last weight is used.
+	///
+	/// # Panics
+	///
+	/// Panics if any weight is negative or not finite.
+	#[inline]
+	pub fn new<W: Into<f64>, I: IntoIterator<Item = (K, W)>>(iter: I) -> WeightedMap<K> {
+		match WeightedMap::try_new(iter) {
+			Ok(distr) => distr,
+			Err(err) => weighted_map_error(err),
+		}
+	}
+	/// Constructs a new `WeightedMap` distribution from pairs of keys and weights.
+	///
+	/// When a key is repeated the last weight is used.
+	///
+	/// Returns an error if any weight is negative or not finite.
+	pub fn try_new<W: Into<f64>, I: IntoIterator<Item = (K, W)>>(iter: I) -> Result<WeightedMap<K>, WeightedMapError> {
+		let mut keys = Vec::new();
+		let mut weights = Vec::new();
+		let mut indices = HashMap::new();
+		for (key, weight) in iter {
+			let weight = weight.into();
+			if !is_valid_weight(weight) {
+				return Err(WeightedMapError::InvalidWeight);
+			}
+			match indices.get(&key) {
+				Some(&index) => weights[index] = weight,
+				None => {
+					indices.insert(key.clone(), keys.len());
+					keys.push(key);
+					weights.push(weight);
+				},
+			}
+		}
+		let mut distr = WeightedMap { keys, indices, tree: Vec::new() };
+		distr.rebuild(&weights, weights.len());
+		Ok(distr)
+	}
+
+	/// Returns the number of keys.
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.keys.len()
+	}
+	/// Returns `true` if there are no keys.
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.keys.is_empty()
+	}
+	/// Returns the sum of the weights.
+	#[inline]
+	pub fn total_weight(&self) -> f64 {
+		self.tree[1]
+	}
+	/// Returns the weight of the key.
+	#[inline]
+	pub fn weight<Q: ?Sized + Eq + Hash>(&self, key: &Q) -> Option<f64> where K: Borrow<Q> {
+		let &index = self.indices.get(key)?;
+		Some(self.tree[self.leaves() + index])
+	}
+
+	/// Sets the weight of the key, inserting the key if it is not present.
+	///
+	/// Returns the previous weight of the key.
+	///
+	/// # Panics
+	///
+	/// Panics if the weight is negative or not finite.
+	pub fn set_weight(&mut self, key: K, weight: f64) -> Option<f64> {
+		if !is_valid_weight(weight) {
+			weighted_map_error(WeightedMapError::InvalidWeight);
+		}
+		if let Some(&index) = self.indices.get(&key) {
+			let previous = self.tree[self.leaves() + index];
+			self.update(index, weight);
+			return Some(previous);
+		}
+		let index = self.keys.len();
+		if index == self.leaves() {
+			let weights = self.tree.split_off(self.leaves());
+			self.rebuild(&weights, index + 1);
+		}
+		self.indices.insert(key.clone(), index);
+		self.keys.push(key);
+		self.update(index, weight);
+		None
+	}
+	/// Removes the key and returns its weight.
+	///
+	/// The last key takes the place of the removed key.
+	pub fn remove<Q: ?Sized + Eq + Hash>(&mut self, key: &Q) -> Option<f64> where K: Borrow<Q> {
+		let index = self.indices.remove(key)?;
+		let weight = self.tree[self.leaves() + index];
+		let last = self.keys.len() - 1;
+		if index != last {
+			self.update(index, self.tree[self.leaves() + last]);
+			*self.indices.get_mut::<K>(&self.keys[last]).unwrap() = index;
+		}
+		self.update(last, 0.0);
+		self.keys.swap_remove(index);
+		Some(weight)
+	}
+
+	/// Samples a key by reference.
+	///
+	/// # Panics
+	///
+	/// Panics if the total weight is zero.
+	#[inline]
+	pub fn sample_ref<R: Rng + ?Sized>(&self, rand: &mut Random<R>) -> &K {
+		match self.try_sample(rand) {
+			Some(key) => key,
+			None => sample_empty_error(),
+		}
+	}
+	/// Samples a key by reference.
+	///
+	/// Returns `None` if the total weight is zero, eg. after removing the last key.
+	pub fn try_sample<R: Rng + ?Sized>(&self, rand: &mut Random<R>) -> Option<&K> {
+		let total = self.total_weight();
+		if total <= 0.0 {
+			return None;
+		}
+		let leaves = self.leaves();
+		loop {
+			let mut target = (rand.next_f64() - 1.0) * total;
+			let mut node = 1;
+			while node < leaves {
+				node *= 2;
+				let left = self.tree[node];
+				if target >= left {
+					target -= left;
+					node += 1;
+				}
+			}
+			// Rounding may land on a neighbour of the target without weight, sample again
+			if self.tree[node] > 0.0 {
+				return Some(&self.keys[node - leaves]);
+			}
+		}
+	}
+
+	#[inline]
+	fn leaves(&self) -> usize {
+		self.tree.len() / 2
+	}
+	fn rebuild(&mut self, weights: &[f64], capacity: usize) {
+		let leaves = usize::max(capacity, 1).next_power_of_two();
+		self.tree.clear();
+		self.tree.resize(leaves * 2, 0.0);
+		self.tree[leaves..leaves + weights.len()].copy_from_slice(weights);
+		for node in (1..leaves).rev() {
+			self.tree[node] = self.tree[node * 2] + self.tree[node * 2 + 1];
+		}
+	}
+	fn update(&mut self, index: usize, weight: f64) {
+		let mut node = self.leaves() + index;
+		self.tree[node] = weight;
+		while node > 1 {
+			node /= 2;
+			self.tree[node] = self.tree[node * 2] + self.tree[node * 2 + 1];
+		}
+	}
+}
+
+impl<K: Clone + Eq + Hash> Distribution<K> for WeightedMap<K> {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> K {
+		self.sample_ref(rng).clone()
+	}
+}
+
+#[inline]
+fn is_valid_weight(weight: f64) -> bool {
+	weight >= 0.0 && weight.is_finite()
+}
+
+#[cold]
+fn weighted_map_error(err: WeightedMapError) -> ! {
+	panic!("WeightedMap called with invalid parameters: {}", err)
+}
+
+#[cold]
+fn sample_empty_error() -> ! {
+	panic!("WeightedMap sampled with zero total weight")
+}
+
+//----------------------------------------------------------------
+
+#[cfg(test)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+struct ItemId(u64);
+
+#[cfg(test)]
+fn check_frequencies(distr: &WeightedMap<ItemId>, weights: &[f64]) {
+	let mut rng = crate::seeded(42);
+	const N: usize = 100000;
+	let mut counts = vec![0usize; weights.len()];
+	for _ in 0..N {
+		counts[distr.sample(&mut rng).0 as usize] += 1;
+	}
+	let total: f64 = weights.iter().sum();
+	for (id, &weight) in weights.iter().enumerate() {
+		let expected = weight / total * N as f64;
+		let count = counts[id] as f64;
+		if weight == 0.0 {
+			assert_eq!(counts[id], 0, "id: {}", id);
+		}
+		assert!((count - expected).abs() < 4.0 * expected.sqrt() + 1.0, "id: {} count: {} expected: {}", id, count, expected);
+	}
+}
+
+#[test]
+fn test_frequencies() {
+	let weights = [1.0, 0.0, 3.0, 0.5, 2.5, 0.0, 1.0];
+	let distr = WeightedMap::new(weights.iter().enumerate().map(|(id, &weight)| (ItemId(id as u64), weight)));
+	assert_eq!(distr.len(), 7);
+	assert_eq!(distr.total_weight(), 8.0);
+	assert_eq!(distr.weight(&ItemId(2)), Some(3.0));
+	assert_eq!(distr.weight(&ItemId(7)), None);
+	check_frequencies(&distr, &weights);
+
+	// Repeated keys use the last weight
+	let distr = WeightedMap::new([(ItemId(0), 1u32), (ItemId(1), 5), (ItemId(0), 3)]);
+	assert_eq!(distr.len(), 2);
+	check_frequencies(&distr, &[3.0, 5.0]);
+}
+
+#[test]
+fn test_updates() {
+	let mut rng = crate::seeded(42);
+	let mut distr = WeightedMap::new([(ItemId(0), 1.0), (ItemId(1), 1.0)]);
+
+	// Inserting grows the tree
+	for id in 2..10 {
+		assert_eq!(distr.set_weight(ItemId(id), id as f64), None);
+	}
+	check_frequencies(&distr, &[1.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0]);
+
+	assert_eq!(distr.set_weight(ItemId(9), 0.1), Some(9.0));
+	assert_eq!(distr.remove(&ItemId(3)), Some(3.0));
+	assert_eq!(distr.remove(&ItemId(3)), None);
+	check_frequencies(&distr, &[1.0, 1.0, 2.0, 0.0, 4.0, 5.0, 6.0, 7.0, 8.0, 0.1]);
+
+	// Zero weights take effect exactly after arbitrary updates
+	for id in 0..10 {
+		distr.set_weight(ItemId(id), 0.1 * id as f64);
+	}
+	for id in 0..10 {
+		if id != 7 {
+			distr.set_weight(ItemId(id), 0.0);
+		}
+	}
+	assert_eq!(distr.total_weight(), 0.7000000000000001);
+	for _ in 0..1000 {
+		assert_eq!(distr.sample_ref(&mut rng), &ItemId(7));
+	}
+}
+
+#[test]
+fn test_remove_all() {
+	let mut rng = crate::seeded(42);
+	let mut distr = WeightedMap::new([(ItemId(0), 1.0), (ItemId(1), 2.0), (ItemId(2), 3.0)]);
+	assert_eq!(distr.remove(&ItemId(0)), Some(1.0));
+	assert_eq!(distr.weight(&ItemId(2)), Some(3.0));
+	assert_eq!(distr.remove(&ItemId(2)), Some(3.0));
+	for _ in 0..100 {
+		assert_eq!(distr.try_sample(&mut rng), Some(&ItemId(1)));
+	}
+	assert_eq!(distr.remove(&ItemId(1)), Some(2.0));
+	assert!(distr.is_empty());
+	assert_eq!(distr.total_weight(), 0.0);
+	assert_eq!(distr.try_sample(&mut rng), None);
+
+	// Reinserting after removing everything
+	distr.set_weight(ItemId(5), 1.0);
+	assert_eq!(distr.try_sample(&mut rng), Some(&ItemId(5)));
+}
+
+#[test]
+#[should_panic(expected = "WeightedMap sampled with zero total weight")]
+fn test_sample_empty() {
+	let distr = WeightedMap::new([(ItemId(0), 0.0)]);
+	distr.sample(&mut crate::new());
+}
+
+#[test]
+fn test_validation() {
+	assert_eq!(WeightedMap::try_new([(0, -1.0)]).unwrap_err(), WeightedMapError::InvalidWeight);
+	assert_eq!(WeightedMap::try_new([(0, f64::NAN)]).unwrap_err(), WeightedMapError::InvalidWeight);
+	assert_eq!(WeightedMap::try_new([(0, f64::INFINITY)]).unwrap_err(), WeightedMapError::InvalidWeight);
+	assert!(WeightedMap::<u64>::try_new([(0, 0.0f64); 0]).unwrap().is_empty());
+}
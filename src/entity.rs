@@ -0,0 +1,181 @@
+/*!
+Repeatable per-entity randomness.
+
+Games which persist their worlds need randomness which belongs to an entity rather than to the order in which things happen:
+a tree generated from entity 1234 must look the same after a save and load, no matter which entities were spawned before it.
+
+An [`EntityRng`] is a factory keyed by a world seed. Every entity id gets its own independent stream derived from the world seed and the id alone.
+*/
+
+use crate::{distributions, Distribution, Random};
+use crate::hash::mix64;
+use crate::rng::SplitMix64;
+
+const GOLDEN_GAMMA: u64 = 0x9e3779b97f4a7c15;
+
+/// Factory of reproducible per-entity random number generators.
+///
+/// The factory holds only the world seed, it is `Copy` and can be shared between threads freely.
+/// Every method is a pure function of the world seed and the entity id.
+///
+/// # Stability
+///
+/// The child seed of an entity is derived with two rounds of [`mix64`]:
+///
+/// ```text
+/// child_seed = mix64(mix64(seed + 0x9e3779b97f4a7c15) ^ id)
+/// ```
+///
+/// The stream of the entity is a [`SplitMix64`] seeded with the child seed.
+/// The derivation and the stream are guaranteed to never change: the same world seed and id produce the same stream across all versions of this crate and on all platforms.
+///
+/// Entities never share their first output: for a fixed world seed the first output is a bijection of the id.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::entity::EntityRng;
+///
+/// let world = EntityRng::new(0x5eed);
+/// let tree_id = 1234;
+///
+/// // One-shot values without a generator
+/// let height = world.uniform(tree_id, 4.0..12.0);
+/// assert_eq!(height, world.uniform(tree_id, 4.0..12.0));
+///
+/// // A stream for many values
+/// let mut rng = world.stream(tree_id);
+/// let branches: u32 = rng.range(3..8);
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EntityRng {
+	seed: u64,
+}
+
+impl EntityRng {
+	/// Creates a new factory from the world seed.
+	#[inline]
+	pub const fn new(seed: u64) -> EntityRng {
+		EntityRng { seed }
+	}
+
+	/// Returns the world seed.
+	#[inline]
+	pub const fn seed(&self) -> u64 {
+		self.seed
+	}
+
+	/// Returns the child seed of the entity.
+	///
+	/// See the [stability](EntityRng#stability) section for the derivation.
+	#[inline]
+	pub const fn child_seed(&self, id: u64) -> u64 {
+		mix64(mix64(self.seed.wrapping_add(GOLDEN_GAMMA)) ^ id)
+	}
+
+	/// Creates the stream of the entity.
+	///
+	/// The same id always produces the same stream, independent of the order of the calls and the thread.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let world = urandom::entity::EntityRng::new(42);
+	/// let mut a = world.stream(7);
+	/// let mut b = world.stream(7);
+	/// assert_eq!(a.next_u64(), b.next_u64());
+	/// ```
+	#[inline]
+	pub fn stream(&self, id: u64) -> Random<SplitMix64> {
+		SplitMix64::from_seed(self.child_seed(id))
+	}
+
+	/// Returns a value of the entity sampled from the [`Standard`](distributions::Standard) distribution.
+	///
+	/// This is the first value of the [`stream`](EntityRng::stream) of the entity.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let world = urandom::entity::EntityRng::new(42);
+	/// let color: u32 = world.value(7);
+	/// assert_eq!(color, world.value(7));
+	/// ```
+	#[inline]
+	pub fn value<T>(&self, id: u64) -> T where distributions::Standard: Distribution<T> {
+		self.stream(id).next()
+	}
+
+	/// Returns a value of the entity sampled uniformly from the interval.
+	///
+	/// This is the first value of the [`stream`](EntityRng::stream) of the entity, see [`Random::range`](Random::range).
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let world = urandom::entity::EntityRng::new(42);
+	/// let level = world.uniform(7, 1..=10);
+	/// assert!(level >= 1 && level <= 10);
+	/// ```
+	#[inline]
+	pub fn uniform<T, I>(&self, id: u64, interval: I) -> T where T: distributions::SampleUniform, distributions::Uniform<T>: From<I> {
+		self.stream(id).range(interval)
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_stable() {
+	// Golden values, these must never change
+	let world = EntityRng::new(42);
+	assert_eq!(world.child_seed(0), 0xb29ed950786f5ae3);
+	assert_eq!(world.child_seed(1234), 0x053ec509785af17d);
+	assert_eq!(world.stream(1234).next_u64(), 0x4653ca5a22eb3677);
+	assert_eq!(world.value::<u32>(1234), 0x22eb3677);
+	assert_eq!(world.value::<f64>(1234), 1.2747160406169484);
+	assert_eq!(world.uniform(1234, 0..100), 27);
+	assert_eq!(world.uniform(1234, -1.0f32..1.0), -0.72719693);
+	assert_eq!(EntityRng::new(0).child_seed(0), 0x48218226ff3cd4bf);
+}
+
+#[test]
+fn test_independent_ids() {
+	let world = EntityRng::new(0x5eed);
+	let mut seen = std::collections::HashSet::new();
+	for id in 0..100000 {
+		assert!(seen.insert(world.stream(id).next_u64()), "id: {}", id);
+	}
+	// Neighboring world seeds do not share streams
+	let other = EntityRng::new(0x5eed + 1);
+	for id in 0..100000 {
+		assert!(!seen.contains(&other.stream(id).next_u64()), "id: {}", id);
+	}
+}
+
+#[test]
+fn test_order_and_thread() {
+	let world = EntityRng::new(7);
+	let ids = [5u64, 1, 1 << 40, 3, u64::MAX];
+	let expected: Vec<[u64; 4]> = ids.iter().map(|&id| {
+		let mut values = [0; 4];
+		world.stream(id).fill_u64(&mut values);
+		values
+	}).collect();
+
+	// Reverse order on another thread
+	let handle = std::thread::spawn(move || {
+		ids.iter().rev().map(|&id| {
+			let mut values = [0; 4];
+			world.stream(id).fill_u64(&mut values);
+			values
+		}).collect::<Vec<_>>()
+	});
+	let mut actual = handle.join().unwrap();
+	actual.reverse();
+	assert_eq!(actual, expected);
+
+	for (&id, values) in ids.iter().zip(&expected) {
+		assert_eq!(world.value::<u64>(id), values[0]);
+	}
+}
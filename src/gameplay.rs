@@ -0,0 +1,233 @@
+/*!
+Randomness helpers for game mechanics.
+*/
+
+use core::fmt;
+use crate::{Random, Rng};
+
+/// Bad luck protection for random drops, also known as a pity timer.
+///
+/// Every failed roll increases the success probability of the next roll by a fixed increment,
+/// and success is guaranteed at the hard pity attempt. The probability resets after every success.
+///
+/// The `k`-th attempt since the last success (starting at 1) succeeds with probability `min(1, base_p + (k - 1)·increment)`,
+/// or with probability 1 if `k >= hard_pity`.
+///
+/// The pity counter is the only mutable state, it can be persisted with [`attempts_since_success`](PityTimer::attempts_since_success)
+/// and restored with [`set_attempts_since_success`](PityTimer::set_attempts_since_success).
+///
+/// # Examples
+///
+/// ```
+/// use urandom::gameplay::PityTimer;
+///
+/// let mut rng = urandom::new();
+/// let mut timer = PityTimer::new(0.006, 0.06, 90);
+/// let attempts = (1..).find(|_| timer.roll(&mut rng)).unwrap();
+/// assert!(attempts <= 90);
+/// assert_eq!(timer.attempts_since_success(), 0);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct PityTimer {
+	base_p: f64,
+	increment: f64,
+	hard_pity: u32,
+	attempts: u32,
+}
+
+/// Error type returned from [`PityTimer`](PityTimer) constructors.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PityTimerError {
+	/// The base probability is not in the closed interval `[0, 1]`.
+	InvalidProbability,
+	/// The increment is negative or not finite.
+	InvalidIncrement,
+	/// The hard pity is zero.
+	ZeroHardPity,
+}
+
+impl fmt::Display for PityTimerError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match self {
+			PityTimerError::InvalidProbability => "base probability is not in [0, 1] in pity timer",
+			PityTimerError::InvalidIncrement => "increment is negative or not finite in pity timer",
+			PityTimerError::ZeroHardPity => "hard pity is zero in pity timer",
+		})
+	}
+}
+
+impl std::error::Error for PityTimerError {}
+
+impl PityTimer {
+	/// Constructs a new pity timer.
+	///
+	/// # Panics
+	///
+	/// Panics if `base_p` is not in `[0, 1]`, `increment` is negative or not finite, or `hard_pity` is zero.
+	#[inline]
+	pub fn new(base_p: f64, increment: f64, hard_pity: u32) -> PityTimer {
+		match PityTimer::try_new(base_p, increment, hard_pity) {
+			Ok(timer) => timer,
+			Err(err) => pity_timer_error(err),
+		}
+	}
+	/// Constructs a new pity timer.
+	///
+	/// Returns an error if `base_p` is not in `[0, 1]`, `increment` is negative or not finite, or `hard_pity` is zero.
+	#[inline]
+	pub fn try_new(base_p: f64, increment: f64, hard_pity: u32) -> Result<PityTimer, PityTimerError> {
+		if !(0.0..=1.0).contains(&base_p) {
+			return Err(PityTimerError::InvalidProbability);
+		}
+		if !(increment.is_finite() && increment >= 0.0) {
+			return Err(PityTimerError::InvalidIncrement);
+		}
+		if hard_pity == 0 {
+			return Err(PityTimerError::ZeroHardPity);
+		}
+		Ok(PityTimer { base_p, increment, hard_pity, attempts: 0 })
+	}
+
+	/// Rolls the next attempt, returns `true` on success.
+	///
+	/// The pity counter is reset on success and incremented on failure.
+	#[inline]
+	pub fn roll<R: Rng + ?Sized>(&mut self, rng: &mut Random<R>) -> bool {
+		let success = rng.chance(self.probability());
+		self.attempts = if success { 0 } else { self.attempts + 1 };
+		success
+	}
+
+	/// Returns the success probability of the next roll.
+	#[inline]
+	pub fn probability(&self) -> f64 {
+		self.attempt_probability(self.attempts)
+	}
+
+	#[inline]
+	fn attempt_probability(&self, failures: u32) -> f64 {
+		if failures + 1 >= self.hard_pity {
+			return 1.0;
+		}
+		f64::min(1.0, self.base_p + failures as f64 * self.increment)
+	}
+
+	/// Returns the number of failed rolls since the last success.
+	#[inline]
+	pub fn attempts_since_success(&self) -> u32 {
+		self.attempts
+	}
+
+	/// Sets the number of failed rolls since the last success, eg. to restore a persisted pity counter.
+	///
+	/// The counter is clamped to `hard_pity - 1`, the next roll succeeds with certainty at most.
+	#[inline]
+	pub fn set_attempts_since_success(&mut self, attempts: u32) {
+		self.attempts = u32::min(attempts, self.hard_pity - 1);
+	}
+
+	/// Resets the pity counter as if the last roll was a success.
+	#[inline]
+	pub fn reset(&mut self) {
+		self.attempts = 0;
+	}
+
+	/// Returns the long-run frequency of successes.
+	///
+	/// This is the reciprocal of the expected number of attempts per success, computed exactly from the schedule of probabilities.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use urandom::gameplay::PityTimer;
+	///
+	/// // Without increment the soft pity has no effect and the hard pity is a guarantee
+	/// let timer = PityTimer::new(0.0, 0.0, 10);
+	/// assert_eq!(timer.effective_rate(), 0.1);
+	///
+	/// let timer = PityTimer::new(0.5, 0.0, 100);
+	/// assert!((timer.effective_rate() - 0.5).abs() < 1e-12);
+	/// ```
+	pub fn effective_rate(&self) -> f64 {
+		// Expected attempts E[T] = Σ P(T >= k) for k in 1..=hard_pity
+		let mut expected = 0.0;
+		let mut survival = 1.0;
+		for failures in 0..self.hard_pity {
+			expected += survival;
+			survival *= 1.0 - self.attempt_probability(failures);
+			if survival <= 0.0 {
+				break;
+			}
+		}
+		1.0 / expected
+	}
+}
+
+#[cold]
+fn pity_timer_error(err: PityTimerError) -> ! {
+	panic!("PityTimer::new called with invalid parameters: {}", err)
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_hard_pity() {
+	let mut rng = crate::new();
+	let mut timer = PityTimer::new(0.0, 0.0, 7);
+	for _ in 0..10 {
+		for i in 1..7 {
+			assert!(!timer.roll(&mut rng));
+			assert_eq!(timer.attempts_since_success(), i);
+		}
+		assert_eq!(timer.probability(), 1.0);
+		assert!(timer.roll(&mut rng));
+		assert_eq!(timer.attempts_since_success(), 0);
+	}
+
+	// Hard pity of one always succeeds
+	let mut timer = PityTimer::new(0.0, 0.0, 1);
+	assert!((0..100).all(|_| timer.roll(&mut rng)));
+}
+
+#[test]
+fn test_effective_rate() {
+	let mut rng = crate::seeded(42);
+	for &(base_p, increment, hard_pity) in &[(0.006, 0.06, 90), (0.05, 0.01, 50), (0.2, 0.0, 5), (0.01, 0.3, 1000)] {
+		let mut timer = PityTimer::new(base_p, increment, hard_pity);
+		const ROLLS: u32 = 200000;
+		let successes = (0..ROLLS).filter(|_| timer.roll(&mut rng)).count();
+		let rate = successes as f64 / ROLLS as f64;
+		let expected = timer.effective_rate();
+		// Five binomial standard errors, the pity counter only makes the successes more regular
+		let tolerance = 5.0 * (expected * (1.0 - expected) / ROLLS as f64).sqrt();
+		assert!((rate - expected).abs() < tolerance, "rate: {} expected: {}", rate, expected);
+	}
+}
+
+#[test]
+fn test_reset() {
+	let mut rng = crate::new();
+	let mut timer = PityTimer::new(0.1, 0.2, 10);
+	assert_eq!(timer.probability(), 0.1);
+	timer.set_attempts_since_success(2);
+	assert!((timer.probability() - 0.5).abs() < 1e-12);
+	timer.set_attempts_since_success(100);
+	assert_eq!(timer.attempts_since_success(), 9);
+	assert_eq!(timer.probability(), 1.0);
+	assert!(timer.roll(&mut rng));
+	assert_eq!(timer.attempts_since_success(), 0);
+	assert_eq!(timer.probability(), 0.1);
+	timer.set_attempts_since_success(3);
+	timer.reset();
+	assert_eq!(timer.probability(), 0.1);
+}
+
+#[test]
+fn test_validation() {
+	assert_eq!(PityTimer::try_new(-0.1, 0.0, 10).unwrap_err(), PityTimerError::InvalidProbability);
+	assert_eq!(PityTimer::try_new(1.1, 0.0, 10).unwrap_err(), PityTimerError::InvalidProbability);
+	assert_eq!(PityTimer::try_new(f64::NAN, 0.0, 10).unwrap_err(), PityTimerError::InvalidProbability);
+	assert_eq!(PityTimer::try_new(0.1, -0.1, 10).unwrap_err(), PityTimerError::InvalidIncrement);
+	assert_eq!(PityTimer::try_new(0.1, f64::INFINITY, 10).unwrap_err(), PityTimerError::InvalidIncrement);
+	assert_eq!(PityTimer::try_new(0.1, 0.1, 0).unwrap_err(), PityTimerError::ZeroHardPity);
+}
@@ -0,0 +1,119 @@
+/*!
+Stateless hash-based randomness.
+
+These functions are fast, bijective bit mixers with good avalanche behavior: every input bit affects every output bit.
+They are suitable for turning identifiers into random-looking values, eg. for per-entity jitter, without keeping any generator state.
+
+They are **not** cryptographic hash functions. The outputs are guaranteed to be stable across all versions of this crate.
+
+See also [`hashed`](crate::hashed) to create a generator whose stream is a pure function of a key.
+*/
+
+/// Mixes the bits of a 64-bit value.
+///
+/// This is the finalizer of the [`SplitMix64`](crate::rng::SplitMix64) generator (David Stafford's Mix13 variant).
+/// See [Better Bit Mixing](https://zimbry.blogspot.com/2011/09/better-bit-mixing-improving-on.html).
+///
+/// Note that zero maps to zero.
+///
+/// # Examples
+///
+/// ```
+/// let value = urandom::hash::mix64(42);
+/// assert_eq!(value, 0xa759ea27d4727622);
+/// ```
+#[inline]
+pub const fn mix64(mut z: u64) -> u64 {
+	z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+	z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+	return z ^ (z >> 31);
+}
+
+/// Mixes the bits of a 32-bit value.
+///
+/// This is Chris Wellons' `lowbias32` integer hash.
+/// See [Prospecting for Hash Functions](https://nullprogram.com/blog/2018/07/31/).
+///
+/// Note that zero maps to zero.
+///
+/// # Examples
+///
+/// ```
+/// let value = urandom::hash::mix32(42);
+/// assert_eq!(value, 0x172733c2);
+/// ```
+#[inline]
+pub const fn mix32(mut z: u32) -> u32 {
+	z ^= z >> 16;
+	z = z.wrapping_mul(0x7feb352d);
+	z ^= z >> 15;
+	z = z.wrapping_mul(0x846ca68b);
+	return z ^ (z >> 16);
+}
+
+/// Maps a key to a random-looking `f64` in the half-open interval `[0.0, 1.0)`.
+///
+/// The key is mixed with [`mix64`] and the upper 53 bits are used as the fraction.
+/// The key is offset before mixing so that zero does not map to zero.
+///
+/// # Examples
+///
+/// ```
+/// let value = urandom::hash::unit_f64_from(1234);
+/// assert!(value >= 0.0 && value < 1.0);
+/// ```
+#[inline]
+pub fn unit_f64_from(key: u64) -> f64 {
+	const GOLDEN_GAMMA: u64 = 0x9e3779b97f4a7c15;
+	let bits = mix64(key.wrapping_add(GOLDEN_GAMMA)) >> 11;
+	bits as f64 * (1.0 / (1u64 << 53) as f64)
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_stable() {
+	assert_eq!(mix64(0), 0);
+	assert_eq!(mix64(1), 0x5692161d100b05e5);
+	assert_eq!(mix64(42), 0xa759ea27d4727622);
+	assert_eq!(mix32(0), 0);
+	assert_eq!(mix32(1), 0x688990c0);
+	assert_eq!(mix32(42), 0x172733c2);
+	assert_eq!(unit_f64_from(0), 0.8833108082136426);
+}
+
+#[test]
+fn test_avalanche() {
+	let mut rng = crate::new();
+	const N: u32 = 1000;
+	for bit in 0..64 {
+		let mut flips = 0;
+		for _ in 0..N {
+			let x: u64 = rng.next();
+			flips += (mix64(x) ^ mix64(x ^ 1 << bit)).count_ones();
+		}
+		let mean = flips as f64 / N as f64;
+		assert!((mean - 32.0).abs() < 1.0, "bit: {} mean: {}", bit, mean);
+	}
+	for bit in 0..32 {
+		let mut flips = 0;
+		for _ in 0..N {
+			let x: u32 = rng.next();
+			flips += (mix32(x) ^ mix32(x ^ 1 << bit)).count_ones();
+		}
+		let mean = flips as f64 / N as f64;
+		assert!((mean - 16.0).abs() < 0.75, "bit: {} mean: {}", bit, mean);
+	}
+}
+
+#[test]
+fn test_unit_f64() {
+	let mut sum = 0.0;
+	for key in 0..100000u64 {
+		let value = unit_f64_from(key);
+		assert!(value >= 0.0 && value < 1.0, "key: {} value: {}", key, value);
+		sum += value;
+	}
+	let mean = sum / 100000.0;
+	assert!((mean - 0.5).abs() < 0.01, "mean: {}", mean);
+}
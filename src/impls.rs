@@ -26,7 +26,8 @@ pub fn fill_u32<R: crate::Rng>(mut rng: R, mut buffer: &mut [u32]) -> R {
 		buffer = &mut buffer[2..];
 	}
 	if buffer.len() > 0 {
-		buffer[0] = rng.next_u32();
+		// Low half of the next word, identical to filling the bytes
+		buffer[0] = rng.next_u64() as u32;
 	}
 	rng
 }
@@ -37,6 +38,34 @@ pub fn fill_u64<R: crate::Rng>(mut rng: R, buffer: &mut [u64]) -> R {
 	}
 	rng
 }
+/// Fills the buffer four values at the time, the output is identical to filling sequentially with `next_u64`.
+#[inline]
+pub fn fill_u64_x4<R: crate::Rng + Clone>(rng: &mut R, buffer: &mut [u64], next4: impl Fn(&mut R) -> [u64; 4]) {
+	let mut chunks = buffer.chunks_exact_mut(4);
+	for chunk in &mut chunks {
+		chunk.copy_from_slice(&next4(rng));
+	}
+	for elem in chunks.into_remainder() {
+		*elem = rng.next_u64();
+	}
+}
+/// Fills the buffer 32 bytes at the time, the output is identical to [`fill_bytes`].
+#[inline]
+pub fn fill_bytes_x4<R: crate::Rng + Clone>(rng: &mut R, buffer: &mut [u8], next4: impl Fn(&mut R) -> [u64; 4]) {
+	let mut chunks = buffer.chunks_exact_mut(32);
+	for chunk in &mut chunks {
+		let values = next4(rng);
+		let mut bytes = [0u8; 32];
+		for (dest, value) in bytes.chunks_exact_mut(8).zip(values) {
+			dest.copy_from_slice(&value.to_le_bytes());
+		}
+		chunk.copy_from_slice(&bytes);
+	}
+	let rem = chunks.into_remainder();
+	if rem.len() > 0 {
+		*rng = fill_bytes(rng.clone(), rem);
+	}
+}
 #[inline]
 pub fn fill_bytes<R: crate::Rng>(mut rng: R, mut buffer: &mut [u8]) -> R {
 	// Loop unrolled for eight bytes at the time
@@ -31,20 +31,136 @@ This library was inspired by the semi-official [`rand`](https://crates.io/crates
 // Unsafe code is restricted to certain specific Rng implementations
 #![deny(unsafe_code)]
 
+// Style lints which conflict with the code style of this crate
+#![allow(
+	clippy::tabs_in_doc_comments,
+	clippy::needless_return,
+	clippy::identity_op,
+	clippy::len_zero,
+	clippy::precedence,
+	clippy::unused_unit,
+	clippy::needless_range_loop,
+	clippy::should_implement_trait,
+	clippy::manual_div_ceil,
+	clippy::manual_memcpy,
+	clippy::unnecessary_cast,
+	clippy::redundant_pattern_matching,
+	clippy::manual_swap,
+	clippy::bool_assert_comparison,
+	clippy::manual_range_contains,
+)]
+
 #![cfg_attr(not(any(test, feature = "std")), no_std)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 mod random;
 mod impls;
 
 pub mod rng;
 pub mod distributions;
+pub mod hash;
+pub mod entity;
+
+#[cfg(feature = "std")]
+pub mod qmc;
+
+#[cfg(feature = "std")]
+pub mod types;
+
+#[cfg(feature = "std")]
+pub mod select;
+
+#[cfg(feature = "std")]
+pub mod lorem;
+
+#[cfg(feature = "std")]
+pub mod gameplay;
+
+#[cfg(feature = "std")]
+pub mod cards;
+
+#[cfg(feature = "std")]
+pub mod stats;
+
+#[cfg(feature = "std")]
+pub mod datetime;
 
-pub use self::rng::Rng;
+#[cfg(feature = "self-test")]
+pub mod self_test;
+
+pub use self::rng::{Rng, SeedSeq};
 pub use self::distributions::Distribution;
 pub use self::random::Random;
 
+#[cfg(feature = "self-test")]
+pub use self::self_test::{self_test, new_checked};
+
 //----------------------------------------------------------------
 
+/// The default pseudorandom number generator returned by [`new`] and [`seeded`].
+///
+/// Name this alias to store the default generator, eg. in a struct field, without hard-coding the algorithm.
+///
+/// The algorithm is subject to change across major versions of this crate, together with the streams produced by [`seeded`].
+/// Currently this is [`Xoshiro256`](rng::Xoshiro256).
+///
+/// # Choosing another generator
+///
+/// There are no features to switch the default generator.
+/// Features are unified across the dependency graph, a feature enabled by one binary would silently change the [`seeded`] streams of every library it depends on.
+///
+/// Instead libraries which want their users to choose the generator are generic over `R: Rng`, with `DefaultRng` as the default type argument.
+/// Binaries pick the generator where they construct the value:
+///
+/// ```
+/// use urandom::{DefaultRng, Random, Rng};
+///
+/// // Library code names only the alias
+/// struct Simulation<R: Rng = DefaultRng> {
+/// 	rng: Random<R>,
+/// 	steps: u32,
+/// }
+/// impl<R: Rng> Simulation<R> {
+/// 	fn new(rng: Random<R>) -> Simulation<R> {
+/// 		Simulation { rng, steps: 0 }
+/// 	}
+/// 	fn step(&mut self) -> f64 {
+/// 		self.steps += 1;
+/// 		self.rng.next_f64()
+/// 	}
+/// }
+///
+/// // The default generator
+/// let mut sim: Simulation = Simulation::new(urandom::new());
+/// sim.step();
+///
+/// // A binary opting into another generator
+/// let mut sim = Simulation::new(urandom::rng::ChaCha20::from_seed(42));
+/// sim.step();
+/// ```
+pub type DefaultRng = rng::Xoshiro256;
+
+/// The default cryptographically secure pseudorandom number generator returned by [`csprng`].
+///
+/// The algorithm is subject to change across major versions of this crate.
+/// Currently this is [`ChaCha20`](rng::ChaCha20).
+///
+/// See [`DefaultRng`] for choosing another generator.
+///
+/// # Examples
+///
+/// ```
+/// struct Session {
+/// 	rng: urandom::Random<urandom::DefaultSecureRng>,
+/// }
+///
+/// let mut session = Session { rng: urandom::csprng() };
+/// let token: [u8; 16] = session.rng.next();
+/// ```
+pub type DefaultSecureRng = rng::ChaCha20;
+
 /// Creates a new instance of the default pseudorandom number generator.
 ///
 /// The generator is seeded securely from the system entropy source.
@@ -55,9 +171,41 @@ pub use self::random::Random;
 /// let mut rng = urandom::new();
 /// let value: i32 = rng.next();
 /// ```
+///
+/// The generator can be stored by naming [`DefaultRng`]:
+///
+/// ```
+/// struct Player {
+/// 	rng: urandom::Random<urandom::DefaultRng>,
+/// }
+///
+/// let player = Player { rng: urandom::new() };
+/// ```
+#[inline]
+pub fn new() -> Random<DefaultRng> {
+	DefaultRng::new()
+}
+
+/// Creates a new instance of the default pseudorandom number generator.
+///
+/// Returns an error instead of panicking if the system entropy source is unable to provide entropy,
+/// eg. early during boot or in a sandbox which denies access to the entropy source.
+///
+/// # Examples
+///
+/// ```
+/// let mut rng = match urandom::try_new() {
+/// 	Ok(rng) => rng,
+/// 	Err(err) => {
+/// 		eprintln!("{}, falling back to a fixed seed", err);
+/// 		urandom::seeded(42)
+/// 	}
+/// };
+/// let value: i32 = rng.next();
+/// ```
 #[inline]
-pub fn new() -> Random<impl Rng + Clone> {
-	crate::rng::Xoshiro256::new()
+pub fn try_new() -> Result<Random<DefaultRng>, rng::EntropyError> {
+	DefaultRng::try_new()
 }
 
 /// Creates a new instance of the default pseudorandom number generator with the given seed.
@@ -74,14 +222,17 @@ pub fn new() -> Random<impl Rng + Clone> {
 /// assert_eq!(value, 368317477);
 /// ```
 #[inline]
-pub fn seeded(seed: u64) -> Random<impl Rng + Clone> {
-	crate::rng::Xoshiro256::from_seed(seed)
+pub fn seeded(seed: u64) -> Random<DefaultRng> {
+	DefaultRng::from_seed(seed)
 }
 
 /// Creates a new cryptographically secure pseudorandom number generator.
 ///
 /// The generator is seeded securely from the system entropy source.
 ///
+/// After a fork the child process continues with an exact copy of the generator.
+/// Services which fork worker processes should use `rng::ForkGuard` (std, unix only) to reseed in the children.
+///
 /// # Examples
 ///
 /// ```
@@ -89,6 +240,239 @@ pub fn seeded(seed: u64) -> Random<impl Rng + Clone> {
 /// let value: i32 = rng.next();
 /// ```
 #[inline]
-pub fn csprng() -> Random<impl Rng + Clone> {
-	crate::rng::ChaCha20::new()
+pub fn csprng() -> Random<DefaultSecureRng> {
+	DefaultSecureRng::new()
+}
+
+/// Creates a new cryptographically secure pseudorandom number generator.
+///
+/// Returns an error instead of panicking if the system entropy source is unable to provide entropy.
+///
+/// # Examples
+///
+/// ```
+/// let mut rng = urandom::try_csprng().expect("no secure entropy available");
+/// let value: i32 = rng.next();
+/// ```
+#[inline]
+pub fn try_csprng() -> Result<Random<DefaultSecureRng>, rng::EntropyError> {
+	DefaultSecureRng::try_new()
+}
+
+/// Creates a new pseudorandom number generator whose stream is a pure function of the key.
+///
+/// Intended for stateless randomness derived from an identifier, such as per-entity jitter,
+/// where the same key must always produce the same values without storing a generator.
+///
+/// The generator is a [`SplitMix64`](rng::SplitMix64) seeded with the key.
+/// Unlike [`seeded`] the algorithm is guaranteed to never change: the same key produces the same stream across all versions of this crate.
+///
+/// For a single value see the functions in the [`hash`] module, for streams keyed by a world seed and an entity id see [`entity::EntityRng`].
+///
+/// # Examples
+///
+/// ```
+/// let entity_id = 1234;
+/// let jitter: f32 = urandom::hashed(entity_id).range(-0.5..0.5);
+/// assert_eq!(jitter, urandom::hashed(entity_id).range(-0.5..0.5));
+/// ```
+#[inline]
+pub fn hashed(key: u64) -> Random<impl Rng + Clone> {
+	crate::rng::SplitMix64::from_seed(key)
+}
+
+/// Shuffles the slice with a permutation that depends only on the seed and the length of the slice.
+///
+/// Useful for content-addressable shuffles, eg. in save files, without constructing and possibly reusing a generator.
+///
+/// The permutation for a given seed and length is guaranteed to never change across all versions of this crate and is identical on all platforms.
+///
+/// # Examples
+///
+/// ```
+/// let mut a = [1, 2, 3, 4, 5];
+/// let mut b = [1, 2, 3, 4, 5];
+/// urandom::shuffle_seeded(42, &mut a);
+/// urandom::shuffle_seeded(42, &mut b);
+/// assert_eq!(a, b);
+/// ```
+pub fn shuffle_seeded<T>(seed: u64, slice: &mut [T]) {
+	// Fisher–Yates shuffle sampling u64 indices to be independent of the pointer width
+	let mut rng = crate::hashed(seed);
+	let mut len = slice.len();
+	while len > 1 {
+		let k = rng.range(0..len as u64) as usize;
+		slice.swap(k, len - 1);
+		len -= 1;
+	}
+}
+
+/// Returns a permutation of `0..n` that depends only on the seed and `n`.
+///
+/// This is the permutation applied by [`shuffle_seeded`] and shares its stability guarantee.
+///
+/// # Examples
+///
+/// ```
+/// let perm = urandom::permutation_seeded(42, 5);
+/// let mut values = ['a', 'b', 'c', 'd', 'e'];
+/// urandom::shuffle_seeded(42, &mut values);
+/// for (i, &p) in perm.iter().enumerate() {
+/// 	assert_eq!(values[i], ['a', 'b', 'c', 'd', 'e'][p]);
+/// }
+/// ```
+#[cfg(feature = "alloc")]
+pub fn permutation_seeded(seed: u64, n: usize) -> alloc::vec::Vec<usize> {
+	let mut perm: alloc::vec::Vec<usize> = (0..n).collect();
+	shuffle_seeded(seed, &mut perm);
+	perm
+}
+
+//----------------------------------------------------------------
+// One-shot conveniences
+
+// With std the one-shot functions share a lazily seeded generator instead of fetching entropy on every call
+#[cfg(feature = "std")]
+fn with_global<T>(f: impl FnOnce(&mut Random<DefaultRng>) -> T) -> T {
+	static GLOBAL: std::sync::Mutex<Option<Random<DefaultRng>>> = std::sync::Mutex::new(None);
+	// The generator state is always valid, ignore poisoning by a panic in another thread
+	let mut guard = GLOBAL.lock().unwrap_or_else(|err| err.into_inner());
+	f(guard.get_or_insert_with(DefaultRng::new))
+}
+#[cfg(not(feature = "std"))]
+fn with_global<T>(f: impl FnOnce(&mut Random<DefaultRng>) -> T) -> T {
+	f(&mut DefaultRng::new())
+}
+
+/// Shuffles the slice with a process-wide generator.
+///
+/// The one-shot functions [`shuffle`], [`choose`], [`index`] and [`chance`] are convenient where holding a generator is awkward, eg. in macro-generated code.
+/// With the `std` feature they share a process-wide generator seeded from system entropy on first use and guarded by a mutex.
+/// Without the `std` feature every call seeds a new generator from system entropy.
+///
+/// Either way they are slower than reusing a generator, hot code should create one with [`new`] and call its methods instead.
+/// The shared generator is not reseeded after the process forks, see `rng::ForkGuard` (std, unix only).
+///
+/// # Examples
+///
+/// ```
+/// let mut values = [1, 2, 3, 4, 5];
+/// urandom::shuffle(&mut values);
+/// values.sort();
+/// assert_eq!(values, [1, 2, 3, 4, 5]);
+/// ```
+#[inline]
+pub fn shuffle<T>(slice: &mut [T]) {
+	with_global(|rng| rng.shuffle(slice))
+}
+
+/// Returns a random element of the slice with a process-wide generator, or `None` if the slice is empty.
+///
+/// See [`shuffle`] for more information about the one-shot functions.
+///
+/// # Examples
+///
+/// ```
+/// let greeting = urandom::choose(&["hello", "hi", "hey"]).unwrap();
+/// ```
+#[inline]
+pub fn choose<T>(slice: &[T]) -> Option<&T> {
+	with_global(|rng| rng.choose(slice))
+}
+
+/// Returns a random index in the `[0, len)` interval with a process-wide generator.
+///
+/// Consecutive calls draw consecutive values from the same generator, they are independent.
+/// See [`shuffle`] for more information about the one-shot functions.
+///
+/// # Examples
+///
+/// ```
+/// let index = urandom::index(10);
+/// assert!(index < 10);
+/// ```
+#[inline]
+pub fn index(len: usize) -> usize {
+	with_global(|rng| rng.index(len))
+}
+
+/// Returns `true` with the given probability with a process-wide generator.
+///
+/// See [`shuffle`] for more information about the one-shot functions.
+///
+/// # Examples
+///
+/// ```
+/// if urandom::chance(0.25) {
+/// 	println!("critical hit!");
+/// }
+/// ```
+#[inline]
+pub fn chance(p: f64) -> bool {
+	with_global(|rng| rng.chance(p))
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_default_rng() {
+	// The functions return the named generators
+	let mut rng: Random<DefaultRng> = new();
+	let _ = rng.next_u64();
+	let mut a: Random<DefaultRng> = seeded(42);
+	let mut b = DefaultRng::from_seed(42);
+	assert_eq!(a.next_u64(), b.next_u64());
+	let _: Random<DefaultSecureRng> = csprng();
+	fn secure<R: rng::SecureRng>(_: &Random<R>) {}
+	secure(&csprng());
+	let _: Random<DefaultRng> = try_new().unwrap();
+	let _: Random<DefaultSecureRng> = try_csprng().unwrap();
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_permutation_seeded() {
+	// Golden values, these must never change
+	assert_eq!(permutation_seeded(0, 0), []);
+	assert_eq!(permutation_seeded(0, 1), [0]);
+	assert_eq!(permutation_seeded(0, 5), [2, 3, 0, 1, 4]);
+	assert_eq!(permutation_seeded(42, 10), [8, 3, 6, 5, 4, 0, 9, 2, 1, 7]);
+	assert_eq!(permutation_seeded(0xdeadbeef, 16), [9, 8, 11, 10, 15, 12, 1, 14, 6, 3, 7, 2, 5, 0, 13, 4]);
+}
+
+#[test]
+fn test_shuffle_seeded() {
+	let mut values = *b"abcdefghij";
+	shuffle_seeded(42, &mut values);
+	assert_eq!(&values, b"idgfeajcbh");
+}
+
+#[test]
+fn test_one_shot() {
+	let mut values = [1, 2, 3, 4, 5, 6, 7, 8];
+	shuffle(&mut values);
+	values.sort();
+	assert_eq!(values, [1, 2, 3, 4, 5, 6, 7, 8]);
+
+	assert!(choose(&values).is_some_and(|value| values.contains(value)));
+	assert_eq!(choose::<i32>(&[]), None);
+	assert!((0..100).all(|_| index(10) < 10));
+	assert!(chance(1.0) && !chance(0.0));
+
+	// Consecutive calls are not trivially correlated
+	const N: usize = 10000;
+	let same = (0..N).filter(|_| index(10) == index(10)).count();
+	assert!((same as f64 / N as f64 - 0.1).abs() < 0.02, "same: {}", same);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_one_shot_threads() {
+	let threads: Vec<_> = (0..8).map(|_| std::thread::spawn(|| {
+		(0..1000).map(|_| index(100)).sum::<usize>()
+	})).collect();
+	for thread in threads {
+		let sum = thread.join().unwrap();
+		assert!(sum > 30000 && sum < 70000, "sum: {}", sum);
+	}
 }
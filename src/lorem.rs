@@ -0,0 +1,198 @@
+/*!
+Placeholder text.
+
+Generates plausible-looking filler text from a small embedded list of common English words, useful for test fixtures and mockups.
+
+All output is ASCII and reproducible: the same generator state always produces the same text on every platform.
+_Changing_ the output of these functions for a given generator state is considered a breaking change.
+
+# Examples
+
+```
+let mut rng = urandom::seeded(42);
+let title = urandom::lorem::title(&mut rng, 3);
+let text = urandom::lorem::paragraph(&mut rng, 4);
+assert!(text.ends_with('.'));
+```
+*/
+
+use crate::{Random, Rng};
+
+static WORDS: [&str; 200] = [
+	"a", "about", "above", "across", "after", "again", "against", "air", "all", "almost",
+	"along", "also", "always", "among", "an", "and", "animal", "another", "answer", "any",
+	"are", "around", "as", "ask", "at", "away", "back", "be", "because", "been",
+	"before", "began", "being", "below", "best", "better", "between", "big", "black", "body",
+	"book", "both", "bring", "build", "but", "by", "call", "came", "can", "carry",
+	"change", "children", "city", "close", "cold", "come", "could", "country", "cut", "day",
+	"did", "different", "do", "does", "door", "down", "draw", "during", "each", "early",
+	"earth", "eat", "end", "enough", "even", "every", "eye", "face", "fact", "far",
+	"father", "feet", "few", "field", "find", "fire", "first", "follow", "food", "for",
+	"form", "found", "four", "from", "full", "game", "gave", "get", "give", "go",
+	"good", "great", "green", "ground", "group", "grow", "hand", "hard", "has", "have",
+	"head", "hear", "heavy", "help", "here", "high", "hold", "home", "house", "idea",
+	"important", "in", "into", "is", "it", "just", "keep", "kind", "know", "land",
+	"large", "last", "later", "learn", "leave", "left", "less", "letter", "life", "light",
+	"line", "list", "little", "live", "long", "look", "made", "make", "many", "map",
+	"mark", "may", "mean", "measure", "might", "mile", "mind", "more", "most", "mountain",
+	"move", "much", "must", "name", "near", "need", "never", "new", "next", "night",
+	"no", "not", "now", "number", "of", "off", "often", "old", "on", "once",
+	"one", "only", "open", "or", "order", "other", "our", "out", "over", "own",
+	"page", "paper", "part", "people", "picture", "place", "plant", "play", "point", "river",
+];
+
+/// Returns `n` random lowercase words separated by single spaces.
+///
+/// Each word is chosen uniformly from the embedded word list.
+///
+/// # Examples
+///
+/// ```
+/// let mut rng = urandom::new();
+/// let words = urandom::lorem::words(&mut rng, 5);
+/// assert_eq!(words.split(' ').count(), 5);
+/// ```
+pub fn words<R: Rng + ?Sized>(rng: &mut Random<R>, n: usize) -> String {
+	let mut text = String::new();
+	push_words(rng, &mut text, n, false);
+	text
+}
+
+/// Returns a random sentence.
+///
+/// The sentence starts with a capital letter and ends with a period.
+/// The number of words follows a triangular distribution from 4 to 18 words with a mode of 11,
+/// computed as `4 + a + b` where `a` and `b` are uniform integers in `0..8`.
+///
+/// # Examples
+///
+/// ```
+/// let mut rng = urandom::new();
+/// let sentence = urandom::lorem::sentence(&mut rng);
+/// assert!(sentence.ends_with('.'));
+/// ```
+pub fn sentence<R: Rng + ?Sized>(rng: &mut Random<R>) -> String {
+	let mut text = String::new();
+	push_sentence(rng, &mut text);
+	text
+}
+
+/// Returns a paragraph of random sentences separated by single spaces.
+///
+/// Each sentence is generated as if by [`sentence`].
+///
+/// # Examples
+///
+/// ```
+/// let mut rng = urandom::new();
+/// let paragraph = urandom::lorem::paragraph(&mut rng, 3);
+/// assert_eq!(paragraph.matches('.').count(), 3);
+/// ```
+pub fn paragraph<R: Rng + ?Sized>(rng: &mut Random<R>, sentences: usize) -> String {
+	let mut text = String::new();
+	for i in 0..sentences {
+		if i > 0 {
+			text.push(' ');
+		}
+		push_sentence(rng, &mut text);
+	}
+	text
+}
+
+/// Returns `words` random words with every word capitalized.
+///
+/// # Examples
+///
+/// ```
+/// let mut rng = urandom::new();
+/// let title = urandom::lorem::title(&mut rng, 3);
+/// assert!(title.split(' ').all(|word| word.starts_with(|c: char| c.is_ascii_uppercase())));
+/// ```
+pub fn title<R: Rng + ?Sized>(rng: &mut Random<R>, words: usize) -> String {
+	let mut text = String::new();
+	push_words(rng, &mut text, words, true);
+	text
+}
+
+fn push_sentence<R: Rng + ?Sized>(rng: &mut Random<R>, text: &mut String) {
+	let n = 4 + rng.range(0..8u32) + rng.range(0..8u32);
+	let start = text.len();
+	push_words(rng, text, n as usize, false);
+	text[start..start + 1].make_ascii_uppercase();
+	text.push('.');
+}
+
+fn push_words<R: Rng + ?Sized>(rng: &mut Random<R>, text: &mut String, n: usize, capitalize: bool) {
+	for i in 0..n {
+		if i > 0 {
+			text.push(' ');
+		}
+		// Sample a u32 index to be independent of the pointer width
+		let word = WORDS[rng.range(0..WORDS.len() as u32) as usize];
+		let start = text.len();
+		text.push_str(word);
+		if capitalize {
+			text[start..start + 1].make_ascii_uppercase();
+		}
+	}
+}
+
+//----------------------------------------------------------------
+
+#[cfg(test)]
+fn check_text(text: &str) {
+	assert!(text.is_ascii(), "{:?}", text);
+	assert!(!text.contains("  "), "{:?}", text);
+	assert_eq!(text.trim(), text, "{:?}", text);
+}
+
+#[test]
+fn test_golden() {
+	let mut rng = crate::rng::Xoshiro256::from_seed(42);
+	assert_eq!(words(&mut rng, 4), "must does plant line");
+	assert_eq!(sentence(&mut rng), "Away in both our hear no less among father have bring along high from.");
+	assert_eq!(title(&mut rng, 3), "Being Now Large");
+	assert_eq!(paragraph(&mut rng, 2), "Because fire own group of animal kind live come plant live more feet hard. Below from house almost few know high may can many get form much just better make.");
+}
+
+#[test]
+fn test_words() {
+	let mut rng = crate::new();
+	assert_eq!(words(&mut rng, 0), "");
+	assert_eq!(title(&mut rng, 0), "");
+	assert_eq!(paragraph(&mut rng, 0), "");
+	for n in 1..50 {
+		let text = words(&mut rng, n);
+		check_text(&text);
+		assert_eq!(text.split(' ').count(), n);
+		assert!(text.split(' ').all(|word| WORDS.contains(&word)));
+
+		let text = title(&mut rng, n);
+		check_text(&text);
+		assert_eq!(text.split(' ').count(), n);
+		assert!(text.split(' ').all(|word| word.as_bytes()[0].is_ascii_uppercase()));
+	}
+}
+
+#[test]
+fn test_sentences() {
+	let mut rng = crate::new();
+	let mut lengths = [0; 19];
+	for _ in 0..10000 {
+		let text = sentence(&mut rng);
+		check_text(&text);
+		assert!(text.as_bytes()[0].is_ascii_uppercase());
+		assert!(text.ends_with('.') && text.matches('.').count() == 1);
+		lengths[text.split(' ').count()] += 1;
+	}
+	assert!(lengths[..4].iter().all(|&count| count == 0));
+	assert!(lengths[4..].iter().all(|&count| count > 0));
+	assert!(lengths[11] > lengths[10] && lengths[11] > lengths[12]);
+
+	for n in 1..10 {
+		let text = paragraph(&mut rng, n);
+		check_text(&text);
+		assert_eq!(text.matches(". ").count() + 1, n);
+		assert!(text.ends_with('.'));
+	}
+}
@@ -0,0 +1,13 @@
+/*!
+Quasi-Monte Carlo sequences.
+
+Low-discrepancy sequences cover the sample space more evenly than random samples, which makes Monte Carlo integration converge faster.
+They are deterministic and dimension-aware and are therefore not random number generators.
+Randomized variants are seeded from a [`Random`](crate::Random) generator.
+
+See also [`Random::latin_hypercube`](crate::Random::latin_hypercube) for stratified random sampling.
+*/
+
+mod sobol;
+
+pub use self::sobol::{Sobol, SobolError};
@@ -0,0 +1,347 @@
+use core::fmt;
+use crate::{Random, Rng};
+
+mod joe_kuo;
+
+/// The [Sobol sequence](https://en.wikipedia.org/wiki/Sobol_sequence) low-discrepancy sequence.
+///
+/// Uses the Joe–Kuo direction numbers `new-joe-kuo-6.21201` for up to [`MAX_DIMS`](Sobol::MAX_DIMS) dimensions.
+/// Points are generated in Gray code order with 32 bits of resolution, the sequence is exhausted after `2³²` points.
+///
+/// The first point is the origin. Taking the first `2^k` points gives the best balanced point sets.
+///
+/// # Scrambling
+///
+/// The [`scrambled`](Sobol::scrambled) sequence applies a nested uniform (Owen-style) scramble to every coordinate,
+/// using the hash-based permutation from Burley, [_Practical Hash-based Owen Scrambling_](https://jcgt.org/published/0009/04/01/).
+/// Scrambling removes the bias of the unscrambled sequence towards the origin while preserving its stratification.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::qmc::Sobol;
+///
+/// let mut sobol = Sobol::new(2);
+/// let mut point = [0.0; 2];
+/// sobol.next_point(&mut point);
+/// assert_eq!(point, [0.0, 0.0]);
+/// sobol.next_point(&mut point);
+/// assert_eq!(point, [0.5, 0.5]);
+/// sobol.next_point(&mut point);
+/// assert_eq!(point, [0.75, 0.25]);
+/// ```
+#[derive(Clone)]
+pub struct Sobol {
+	directions: Vec<[u32; 32]>,
+	state: Vec<u32>,
+	seeds: Option<Vec<u32>>,
+	index: u64,
+}
+
+/// Error type returned from [`Sobol`](Sobol) constructors.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SobolError {
+	/// The number of dimensions is zero.
+	ZeroDims,
+	/// The number of dimensions exceeds [`Sobol::MAX_DIMS`](Sobol::MAX_DIMS).
+	TooManyDims,
+}
+
+impl fmt::Display for SobolError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match self {
+			SobolError::ZeroDims => "number of dimensions is zero in Sobol sequence",
+			SobolError::TooManyDims => "number of dimensions is too large in Sobol sequence",
+		})
+	}
+}
+
+impl std::error::Error for SobolError {}
+
+impl Sobol {
+	/// The maximum number of dimensions supported.
+	pub const MAX_DIMS: usize = joe_kuo::JOE_KUO.len() + 1;
+
+	/// Constructs a new Sobol sequence with the given number of dimensions.
+	///
+	/// # Panics
+	///
+	/// Panics if `dims` is zero or greater than [`MAX_DIMS`](Sobol::MAX_DIMS).
+	#[inline]
+	pub fn new(dims: usize) -> Sobol {
+		match Sobol::try_new(dims) {
+			Ok(sobol) => sobol,
+			Err(err) => sobol_error(err),
+		}
+	}
+	/// Constructs a new Sobol sequence with the given number of dimensions.
+	///
+	/// Returns an error if `dims` is zero or greater than [`MAX_DIMS`](Sobol::MAX_DIMS).
+	pub fn try_new(dims: usize) -> Result<Sobol, SobolError> {
+		if dims == 0 {
+			return Err(SobolError::ZeroDims);
+		}
+		if dims > Sobol::MAX_DIMS {
+			return Err(SobolError::TooManyDims);
+		}
+		let directions = (0..dims).map(directions).collect();
+		let state = vec![0; dims];
+		Ok(Sobol { directions, state, seeds: None, index: 0 })
+	}
+	/// Constructs a new scrambled Sobol sequence with the given number of dimensions.
+	///
+	/// The scramble of each dimension is seeded from the given generator.
+	///
+	/// # Panics
+	///
+	/// Panics if `dims` is zero or greater than [`MAX_DIMS`](Sobol::MAX_DIMS).
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use urandom::qmc::Sobol;
+	///
+	/// let mut rng = urandom::new();
+	/// let sobol = Sobol::scrambled(3, &mut rng);
+	/// for point in sobol.take(16) {
+	/// 	assert!(point.iter().all(|&x| x >= 0.0 && x < 1.0));
+	/// }
+	/// ```
+	#[inline]
+	pub fn scrambled<R: Rng + ?Sized>(dims: usize, rand: &mut Random<R>) -> Sobol {
+		match Sobol::try_scrambled(dims, rand) {
+			Ok(sobol) => sobol,
+			Err(err) => sobol_error(err),
+		}
+	}
+	/// Constructs a new scrambled Sobol sequence with the given number of dimensions.
+	///
+	/// Returns an error if `dims` is zero or greater than [`MAX_DIMS`](Sobol::MAX_DIMS).
+	pub fn try_scrambled<R: Rng + ?Sized>(dims: usize, rand: &mut Random<R>) -> Result<Sobol, SobolError> {
+		let mut sobol = Sobol::try_new(dims)?;
+		let mut seeds = vec![0; dims];
+		rand.fill_u32(&mut seeds);
+		sobol.seeds = Some(seeds);
+		Ok(sobol)
+	}
+
+	/// Returns the number of dimensions.
+	#[inline]
+	pub fn dims(&self) -> usize {
+		self.state.len()
+	}
+	/// Returns the index of the next point.
+	#[inline]
+	pub const fn index(&self) -> u64 {
+		self.index
+	}
+
+	/// Writes the next point to `out`, each coordinate in the half-open interval `[0, 1)`.
+	///
+	/// # Panics
+	///
+	/// Panics if the length of `out` is not equal to the number of dimensions or if the sequence is exhausted.
+	pub fn next_point(&mut self, out: &mut [f64]) {
+		if out.len() != self.state.len() {
+			next_point_len_error(out.len(), self.state.len());
+		}
+		if self.index >= 1 << 32 {
+			exhausted_error();
+		}
+		// The next point differs in the direction numbers indexed by the lowest set bit of the index
+		if self.index > 0 {
+			let bit = self.index.trailing_zeros() as usize;
+			for (x, v) in self.state.iter_mut().zip(&self.directions) {
+				*x ^= v[bit];
+			}
+		}
+		self.index += 1;
+		match &self.seeds {
+			None => {
+				for (slot, &x) in out.iter_mut().zip(&self.state) {
+					*slot = to_f64(x);
+				}
+			},
+			Some(seeds) => {
+				for ((slot, &x), &seed) in out.iter_mut().zip(&self.state).zip(seeds) {
+					*slot = to_f64(nested_uniform_scramble(x, seed));
+				}
+			},
+		}
+	}
+}
+
+impl Iterator for Sobol {
+	type Item = Vec<f64>;
+
+	#[inline]
+	fn next(&mut self) -> Option<Vec<f64>> {
+		if self.index >= 1 << 32 {
+			return None;
+		}
+		let mut point = vec![0.0; self.state.len()];
+		self.next_point(&mut point);
+		Some(point)
+	}
+	#[inline]
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		let remaining = (1u64 << 32) - self.index;
+		(usize::try_from(remaining).unwrap_or(usize::MAX), usize::try_from(remaining).ok())
+	}
+}
+
+impl fmt::Debug for Sobol {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Sobol")
+			.field("dims", &self.state.len())
+			.field("index", &self.index)
+			.field("scrambled", &self.seeds.is_some())
+			.finish()
+	}
+}
+
+/// Computes the direction numbers of the given dimension as 32-bit fractions.
+fn directions(dim: usize) -> [u32; 32] {
+	let mut v = [0u32; 32];
+	if dim == 0 {
+		for (k, v) in v.iter_mut().enumerate() {
+			*v = 1 << (31 - k);
+		}
+		return v;
+	}
+	let (s, a, m) = joe_kuo::JOE_KUO[dim - 1];
+	let s = s as usize;
+	for k in 0..s {
+		v[k] = m[k] << (31 - k);
+	}
+	for k in s..32 {
+		let mut x = v[k - s] ^ (v[k - s] >> s);
+		for j in 1..s {
+			if (a >> (s - 1 - j)) & 1 != 0 {
+				x ^= v[k - j];
+			}
+		}
+		v[k] = x;
+	}
+	v
+}
+
+#[inline]
+fn to_f64(x: u32) -> f64 {
+	x as f64 * (1.0 / 4294967296.0)
+}
+
+// Each output bit depends only on the same and more significant input bits, which makes it a nested permutation of the dyadic intervals
+#[inline]
+fn nested_uniform_scramble(x: u32, seed: u32) -> u32 {
+	laine_karras_permutation(x.reverse_bits(), seed).reverse_bits()
+}
+
+#[inline]
+fn laine_karras_permutation(mut x: u32, seed: u32) -> u32 {
+	x = x.wrapping_add(seed);
+	x ^= x.wrapping_mul(0x6c50b47c);
+	x ^= x.wrapping_mul(0xb82f1e52);
+	x ^= x.wrapping_mul(0xc7afe638);
+	x ^= x.wrapping_mul(0x8d22f6e6);
+	x
+}
+
+#[cold]
+fn sobol_error(err: SobolError) -> ! {
+	panic!("Sobol::new called with invalid parameters: {}", err)
+}
+
+#[cold]
+fn next_point_len_error(len: usize, dims: usize) -> ! {
+	panic!("Sobol::next_point out len (is {}) should be equal to dims (is {})", len, dims)
+}
+
+#[cold]
+fn exhausted_error() -> ! {
+	panic!("Sobol sequence is exhausted")
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_first_points() {
+	let expected = [
+		[0.0, 0.0, 0.0, 0.0],
+		[0.5, 0.5, 0.5, 0.5],
+		[0.75, 0.25, 0.25, 0.25],
+		[0.25, 0.75, 0.75, 0.75],
+		[0.375, 0.375, 0.625, 0.875],
+		[0.875, 0.875, 0.125, 0.375],
+		[0.625, 0.125, 0.875, 0.625],
+		[0.125, 0.625, 0.375, 0.125],
+		[0.1875, 0.3125, 0.9375, 0.4375],
+	];
+	for dims in 1..=4 {
+		let mut sobol = Sobol::new(dims);
+		for point in &expected {
+			assert_eq!(sobol.next().unwrap(), &point[..dims]);
+		}
+	}
+}
+
+#[cfg(test)]
+fn check_stratification(sobol: Sobol, k: u32) {
+	let n = 1usize << k;
+	let dims = sobol.dims();
+	let mut counts = vec![vec![0; n]; dims];
+	for point in sobol.take(n) {
+		for (d, &x) in point.iter().enumerate() {
+			assert!(x >= 0.0 && x < 1.0, "x: {}", x);
+			counts[d][(x * n as f64) as usize] += 1;
+		}
+	}
+	for (d, counts) in counts.iter().enumerate() {
+		assert!(counts.iter().all(|&count| count == 1), "dim: {} k: {}", d, k);
+	}
+}
+
+#[test]
+fn test_stratification() {
+	for k in 0..12 {
+		check_stratification(Sobol::new(Sobol::MAX_DIMS), k);
+	}
+}
+
+#[test]
+fn test_scrambled() {
+	let mut rng = crate::seeded(42);
+	for k in 0..12 {
+		check_stratification(Sobol::scrambled(Sobol::MAX_DIMS, &mut rng), k);
+	}
+	// Scrambling moves the origin
+	let mut sobol = Sobol::scrambled(4, &mut rng);
+	let first = sobol.next().unwrap();
+	assert!(first.iter().all(|&x| x != 0.0), "{:?}", first);
+}
+
+#[test]
+fn test_net() {
+	// The first two dimensions form a (0, m, 2)-net: each square box of area 2^-8 contains exactly one of the first 2^8 points
+	let mut sobol = Sobol::new(2);
+	let mut counts = [[0; 16]; 16];
+	for point in sobol.by_ref().take(256) {
+		counts[(point[0] * 16.0) as usize][(point[1] * 16.0) as usize] += 1;
+	}
+	assert!(counts.iter().flatten().all(|&count| count == 1));
+	assert_eq!(sobol.index(), 256);
+}
+
+#[test]
+fn test_validation() {
+	assert_eq!(Sobol::try_new(0).unwrap_err(), SobolError::ZeroDims);
+	assert_eq!(Sobol::try_new(Sobol::MAX_DIMS + 1).unwrap_err(), SobolError::TooManyDims);
+	assert_eq!(Sobol::try_scrambled(0, &mut crate::new()).unwrap_err(), SobolError::ZeroDims);
+	assert!(Sobol::try_new(Sobol::MAX_DIMS).is_ok());
+}
+
+#[test]
+#[should_panic]
+fn test_next_point_len() {
+	Sobol::new(3).next_point(&mut [0.0; 2]);
+}
@@ -1,6 +1,10 @@
-use core::fmt;
+use core::{fmt, ops};
+use core::hash::{Hash, Hasher};
 use crate::*;
 
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
+
 /// Rich interface for consuming random number generators.
 #[derive(Clone)]
 pub struct Random<R: ?Sized>(pub R);
@@ -30,6 +34,14 @@ impl<R: Rng + ?Sized> Random<R> {
 		self.0.next_u64()
 	}
 
+	/// Returns the next `u128` in the sequence, constructed from two `u64` values.
+	#[inline]
+	pub(crate) fn next_u128(&mut self) -> u128 {
+		let low = self.0.next_u64() as u128;
+		let high = self.0.next_u64() as u128;
+		low | high << 64
+	}
+
 	/// Returns a uniform random `f32` in the half-open interval `[1.0, 2.0)`.
 	///
 	/// As only 23 bits are necessary to construct a random float in this range,
@@ -45,7 +57,9 @@ impl<R: Rng + ?Sized> Random<R> {
 	/// ```
 	#[inline]
 	pub fn next_f32(&mut self) -> f32 {
-		self.0.next_f32()
+		let value = self.0.next_f32();
+		debug_assert!(value >= 1.0 && value < 2.0, "Rng::next_f32 returned {:?} outside [1.0, 2.0)", value);
+		value
 	}
 
 	/// Returns a uniform random `f64` in the half-open interval `[1.0, 2.0)`.
@@ -63,12 +77,15 @@ impl<R: Rng + ?Sized> Random<R> {
 	/// ```
 	#[inline]
 	pub fn next_f64(&mut self) -> f64 {
-		self.0.next_f64()
+		let value = self.0.next_f64();
+		debug_assert!(value >= 1.0 && value < 2.0, "Rng::next_f64 returned {:?} outside [1.0, 2.0)", value);
+		value
 	}
 
 	/// Fills the destination buffer with random values from the Rng.
 	///
 	/// The underlying Rng may implement this as efficiently as possible and may not be the same as simply filling with `next_u32`.
+	/// The values are identical to [`fill_bytes`](Random::fill_bytes) filling the same number of bytes read as little-endian words.
 	///
 	/// # Examples
 	///
@@ -86,6 +103,7 @@ impl<R: Rng + ?Sized> Random<R> {
 	/// Fills the destination buffer with uniform random values from the Rng.
 	///
 	/// The underlying Rng may implement this as efficiently as possible and may not be the same as simply filling with `next_u64`.
+	/// The values are identical to [`fill_bytes`](Random::fill_bytes) filling the same number of bytes read as little-endian words.
 	///
 	/// # Examples
 	///
@@ -117,6 +135,46 @@ impl<R: Rng + ?Sized> Random<R> {
 		self.0.fill_bytes(buffer)
 	}
 
+	/// Fills the destination buffer with uniform random `u16` values from the Rng.
+	///
+	/// Implemented with [`fill_bytes`](Random::fill_bytes) with the values in little-endian byte order.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let mut buffer = [0u16; 32];
+	/// rng.fill_u16(&mut buffer);
+	/// assert_ne!(buffer, [0; 32]);
+	/// ```
+	#[inline]
+	pub fn fill_u16(&mut self, buffer: &mut [u16]) {
+		self.fill_bytes(dataview::bytes_mut(buffer));
+		for elem in buffer {
+			*elem = u16::from_le(*elem);
+		}
+	}
+
+	/// Returns a helper to sample small integers amortizing one `u64` across multiple values.
+	///
+	/// Sampling a `u8` with [`next`](Random::next) consumes a full `next_u32`, this helper instead consumes 8 bits.
+	/// This is beneficial with generators where entropy is comparatively expensive such as [`ChaCha20`](rng::ChaCha20).
+	/// See [`SmallInts`](distributions::SmallInts) for the documented consumption order.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::csprng();
+	/// let mut ints = rng.small_ints();
+	/// let a = ints.next_u8();
+	/// let b = ints.next_u16();
+	/// let c = ints.next_bool();
+	/// ```
+	#[inline]
+	pub fn small_ints(&mut self) -> distributions::SmallInts<'_, R> {
+		distributions::SmallInts::new(self)
+	}
+
 	/// Advances the internal state significantly.
 	///
 	/// Useful to produce deterministic independent random number generators for parallel computation.
@@ -128,6 +186,12 @@ impl<R: Rng + ?Sized> Random<R> {
 	/// Clones the current instance and advances the internal state significantly.
 	///
 	/// Useful to produce deterministic independent random number generators for parallel computation.
+	/// How independent the generators are depends on [`Rng::jump_support`](Rng::jump_support).
+	///
+	/// # Panics
+	///
+	/// In debug builds panics if the generator cannot jump, the clone would produce the same values.
+	/// Use [`try_split`](Random::try_split) to handle such generators.
 	///
 	/// # Examples
 	///
@@ -140,11 +204,100 @@ impl<R: Rng + ?Sized> Random<R> {
 	/// ```
 	#[inline]
 	pub fn split(&mut self) -> Self where Self: Clone {
+		debug_assert!(self.0.jump_support() != rng::JumpStrength::None, "split a generator which cannot jump");
 		let cur = self.clone();
 		self.0.jump();
 		return cur;
 	}
 
+	/// Clones the current instance and advances the internal state significantly.
+	///
+	/// Returns an error without changing the generator if it cannot jump, see [`split`](Random::split).
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::rng::ReadRng::new(&[0u8; 64][..]);
+	/// assert!(rng.try_split().is_err());
+	/// ```
+	#[inline]
+	pub fn try_split(&mut self) -> Result<Self, rng::JumpError> where Self: Clone {
+		if self.0.jump_support() == rng::JumpStrength::None {
+			return Err(rng::JumpError);
+		}
+		let cur = self.clone();
+		self.0.jump();
+		Ok(cur)
+	}
+
+	/// Returns a debug view of the inner generator.
+	///
+	/// The `Debug` implementation of `Random` does not print the state of the generator.
+	/// This method forwards to the `Debug` implementation of the generator instead, useful for diagnosing desyncs in simulation state dumps.
+	///
+	/// Cryptographically secure generators such as [`ChaCha20`](rng::ChaCha20) redact their key material in their `Debug` implementation.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let rng = urandom::rng::Xoshiro256::from_seed(42);
+	/// println!("{:?}", rng.dump_state());
+	/// ```
+	#[inline]
+	pub fn dump_state(&self) -> impl fmt::Debug + '_ where R: fmt::Debug {
+		&self.0
+	}
+
+	/// Derives a child generator keyed by the hash of the given value.
+	///
+	/// The child depends only on the seed material of this generator and the key, not on its current position in the sequence.
+	/// See the [`DeriveRng`](rng::DeriveRng) trait for which generators support this.
+	///
+	/// The key is hashed to 64 bits with a portable (but not cryptographic) hasher, distinct keys may collide.
+	///
+	/// # Examples
+	///
+	/// Pick a random but stable backend for a key:
+	///
+	/// ```
+	/// let rng = urandom::rng::ChaCha20::from_seed(42);
+	/// let backends = ["alpha", "beta", "gamma", "delta"];
+	///
+	/// let first = rng.derive("user:1234").choose(&backends);
+	/// let second = rng.derive("user:1234").choose(&backends);
+	/// assert_eq!(first, second);
+	/// ```
+	#[inline]
+	pub fn derive<K: Hash>(&self, key: K) -> Random<R> where R: rng::DeriveRng {
+		let mut hasher = rng::KeyHasher::new();
+		key.hash(&mut hasher);
+		Random(self.0.derive(hasher.finish()))
+	}
+
+	/// Mixes additional entropy into the running state of the generator.
+	///
+	/// All future outputs change without resetting the generator, identical generators which mix the same bytes remain identical.
+	/// Mixing an empty slice does nothing.
+	///
+	/// This provides prediction resistance going forward only to the extent that the bytes are unknown to an observer.
+	/// There is no backtracking resistance for the non-cryptographic generators.
+	/// See the [`MixRng`](rng::MixRng) trait for which generators support this.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::rng::Xoshiro256::from_seed(42);
+	/// let mut copy = rng.clone();
+	///
+	/// rng.mix_entropy(b"tick 1000");
+	/// copy.mix_entropy(b"tick 1000");
+	/// assert_eq!(rng.next_u64(), copy.next_u64());
+	/// ```
+	#[inline]
+	pub fn mix_entropy(&mut self, extra: &[u8]) where R: rng::MixRng {
+		self.0.mix(extra)
+	}
+
 	/// Returns a sample from the [`Standard`](distributions::Standard) distribution.
 	///
 	/// # Examples
@@ -160,6 +313,8 @@ impl<R: Rng + ?Sized> Random<R> {
 	/// Fills the given slice with samples from the [`Standard`](distributions::Standard) distribution.
 	///
 	/// Because of its generic nature no optimizations are applied and all values are sampled individually from the distribution.
+	/// Small integers consume a full `next_u32` per value, see [`fill_bytes`](Random::fill_bytes) and [`fill_u16`](Random::fill_u16)
+	/// which produce the same distribution for `u8` and `u16` with fewer calls to the Rng.
 	///
 	/// # Examples
 	///
@@ -178,6 +333,9 @@ impl<R: Rng + ?Sized> Random<R> {
 
 	/// Returns a sample from the [`Uniform`](distributions::Uniform) distribution within the given interval.
 	///
+	/// The interval may be a range, a reference to a range or a tuple of references `(&low, &high)` for the half-open range.
+	/// The latter avoid moving the bounds into the sampler.
+	///
 	/// # Examples
 	///
 	/// ```
@@ -204,6 +362,69 @@ impl<R: Rng + ?Sized> Random<R> {
 		distributions::Uniform::<T>::from(interval).sample(self)
 	}
 
+	/// Returns a value offset by a random amount in `[-delta, delta]` clamped to the bounds.
+	///
+	/// The feasible interval `[value - delta, value + delta]` is intersected with the bounds without overflow and sampled uniformly.
+	/// If the intersection is empty the bound nearest to the interval is returned.
+	/// For signed types a negative `delta` is treated as its absolute value.
+	///
+	/// # Panics
+	///
+	/// Panics if the bounds are empty.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let health: u8 = 250;
+	/// let health = rng.offset(health, 10, 0..=255);
+	/// assert!(health >= 240);
+	/// ```
+	#[inline]
+	pub fn offset<T: distributions::SampleOffset>(&mut self, value: T, delta: T, bounds: ops::RangeInclusive<T>) -> T {
+		T::sample_offset(self, value, delta, bounds)
+	}
+
+	/// Returns a value offset by a random amount in `[-delta, delta]` wrapped around the bounds.
+	///
+	/// Useful for toroidal coordinates, the result wraps modulo the width of the bounds instead of being clamped.
+	/// When `2 * delta + 1` exceeds the width of the bounds the wrapped result is no longer uniform.
+	/// For signed types a negative `delta` is treated as its absolute value.
+	///
+	/// # Panics
+	///
+	/// Panics if the bounds are empty.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let x = rng.wrapping_offset(0i32, 1, 0..=99);
+	/// assert!(x == 99 || x == 0 || x == 1);
+	/// ```
+	#[inline]
+	pub fn wrapping_offset<T: distributions::SampleOffset>(&mut self, value: T, delta: T, bounds: ops::RangeInclusive<T>) -> T {
+		T::sample_wrapping_offset(self, value, delta, bounds)
+	}
+
+	/// Returns a random value in the given interval without leaking timing information about the value.
+	///
+	/// Use this instead of [`range`](Random::range) to generate secret values, eg. scalars for cryptography, with a cryptographically secure generator.
+	/// The sample consumes a constant number of words from the generator and has a bias of less than `2⁻⁶⁴`,
+	/// see [`SecureSampler`](distributions::SecureSampler) for the details. [`range`](Random::range) remains the default and is exactly unbiased.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::csprng();
+	/// let secret = rng.uniform_secure(1..1_000_000_007u64);
+	/// assert!(secret >= 1 && secret < 1_000_000_007);
+	/// ```
+	#[inline]
+	pub fn uniform_secure<T, I>(&mut self, interval: I) -> T where R: rng::SecureRng, T: distributions::SampleUniform, distributions::Uniform<T>: From<I> + distributions::SecureSampler<T> {
+		distributions::SecureSampler::sample_secure(&distributions::Uniform::from(interval), self)
+	}
+
 	/// Returns a sample from the given distribution.
 	///
 	/// See the [`distributions`](distributions) documentation for a list of available distributions.
@@ -212,6 +433,115 @@ impl<R: Rng + ?Sized> Random<R> {
 		distr.sample(self)
 	}
 
+	/// Returns an array of samples from the given distribution.
+	///
+	/// The elements are sampled in order, the result is the same as sampling `N` times with [`sample`](Random::sample).
+	/// The array is constructed directly without zero-initializing it first.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use urandom::distributions::Uniform;
+	///
+	/// let mut rng = urandom::new();
+	/// let values: [f64; 1024] = rng.collect_array(&Uniform::from(-1.0..1.0));
+	/// assert!(values.iter().all(|&x| x >= -1.0 && x < 1.0));
+	/// ```
+	#[inline]
+	pub fn collect_array<T, D, const N: usize>(&mut self, distr: &D) -> [T; N] where D: Distribution<T> {
+		core::array::from_fn(|_| distr.sample(self))
+	}
+
+	/// Returns a vector of `n` samples from the given distribution.
+	///
+	/// The elements are sampled in order, the result is the same as sampling `n` times with [`sample`](Random::sample).
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use urandom::distributions::Uniform;
+	///
+	/// let mut rng = urandom::new();
+	/// let dice: Vec<i32> = rng.collect_vec(&Uniform::from(1..=6), 100);
+	/// assert_eq!(dice.len(), 100);
+	/// ```
+	#[cfg(feature = "alloc")]
+	#[inline]
+	pub fn collect_vec<T, D>(&mut self, distr: &D, n: usize) -> Vec<T> where D: Distribution<T> {
+		let mut vec = Vec::with_capacity(n);
+		for _ in 0..n {
+			vec.push(distr.sample(self));
+		}
+		vec
+	}
+
+	/// Returns a random valid UTF-8 string whose encoding is exactly `len` bytes long.
+	///
+	/// Useful to fuzz text parsers with multi-byte characters at every position, including at the end of the input.
+	///
+	/// # Implementation notes
+	///
+	/// The string is built one char at a time. The encoded width of the next char is chosen uniformly among the widths `1..=4` which fit the remaining byte budget,
+	/// then the char is sampled uniformly among the Unicode scalar values of that width.
+	/// With 1 byte remaining the char is ASCII, with 2 bytes remaining the next char is ASCII or a 2-byte char with equal probability, and so on.
+	///
+	/// Every width is equally likely, multi-byte chars are common and make up most of the bytes of long strings.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let s = rng.utf8_bytes(13);
+	/// assert_eq!(s.len(), 13);
+	/// ```
+	#[cfg(feature = "alloc")]
+	pub fn utf8_bytes(&mut self, len: usize) -> String {
+		// Scalar values by encoded width, the 3-byte range excludes the surrogates
+		const GAP_SIZE: u32 = 0xE000 - 0xD800;
+		let mut s = String::with_capacity(len);
+		while s.len() < len {
+			let remaining = len - s.len();
+			let width = self.index(usize::min(remaining, 4)) + 1;
+			let c = match width {
+				1 => distributions::UniformInt::constant(0u32, 0x80).sample(self),
+				2 => distributions::UniformInt::constant(0x80u32, 0x800 - 0x80).sample(self),
+				3 => {
+					let n = distributions::UniformInt::constant(0x800u32, 0x1_0000 - 0x800 - GAP_SIZE).sample(self);
+					if n >= 0xD800 { n + GAP_SIZE } else { n }
+				},
+				_ => distributions::UniformInt::constant(0x1_0000u32, 0x11_0000 - 0x1_0000).sample(self),
+			};
+			s.push(char::from_u32(c).unwrap());
+		}
+		s
+	}
+
+	/// Returns a random string of chars sampled from the given distribution whose UTF-8 encoding is exactly `len` bytes long.
+	///
+	/// Chars which do not fit the remaining byte budget are rejected and sampled again.
+	/// The distribution must be able to produce ASCII chars, otherwise this may never return.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use urandom::distributions::GraphicChar;
+	///
+	/// let mut rng = urandom::new();
+	/// let s = rng.utf8_bytes_from(20, GraphicChar);
+	/// assert_eq!(s.len(), 20);
+	/// ```
+	#[cfg(feature = "alloc")]
+	pub fn utf8_bytes_from<D: Distribution<char>>(&mut self, len: usize, distr: D) -> String {
+		let mut s = String::with_capacity(len);
+		while s.len() < len {
+			let c = distr.sample(self);
+			if c.len_utf8() <= len - s.len() {
+				s.push(c);
+			}
+		}
+		s
+	}
+
 	/// Returns an iterator of samples from the given distribution.
 	///
 	/// See the [`distributions`](distributions) documentation for a list of available distributions.
@@ -220,6 +550,48 @@ impl<R: Rng + ?Sized> Random<R> {
 		distributions::Samples::new(self, distr)
 	}
 
+	/// Returns an iterator of samples from the [`Standard`](distributions::Standard) distribution.
+	///
+	/// The type parameter comes first to make turbofishing convenient.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let names = ["a", "b", "c"];
+	/// for (name, x) in names.iter().zip(rng.iter_of::<f64>()) {
+	/// 	println!("{}: {}", name, x);
+	/// }
+	/// ```
+	#[inline]
+	pub fn iter_of<T>(&mut self) -> distributions::Samples<'_, R, distributions::Standard, T> where distributions::Standard: Distribution<T> {
+		distributions::Samples::new(self, distributions::Standard)
+	}
+
+	/// Returns an iterator over all values in `0..n` exactly once in random order.
+	///
+	/// Unlike shuffling a list of indices this does not allocate, making it suitable for exhaustive but randomized testing of large ranges.
+	/// See [`PermutedRange`](distributions::PermutedRange) for the implementation details.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let mut values: Vec<u64> = rng.permuted_range(10).collect();
+	/// values.sort();
+	/// assert_eq!(values, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+	///
+	/// // Visit a billion values in random order without materializing them
+	/// let iter = rng.permuted_range(1_000_000_000);
+	/// assert_eq!(iter.len(), 1_000_000_000);
+	/// ```
+	#[inline]
+	pub fn permuted_range(&mut self, n: u64) -> distributions::PermutedRange {
+		let mut keys = [0u64; 4];
+		self.fill_u64(&mut keys);
+		distributions::PermutedRange::new(keys, n)
+	}
+
 	/// Returns `true` with the given probability.
 	///
 	/// This is known as the [`Bernoulli`](distributions::Bernoulli) distribution.
@@ -233,6 +605,100 @@ impl<R: Rng + ?Sized> Random<R> {
 		distributions::Bernoulli::new(p).sample(self)
 	}
 
+	/// Rounds the value to one of the neighbouring integers at random, unbiased in expectation.
+	///
+	/// This is known as stochastic rounding: the value is rounded up with probability equal to its fractional part, otherwise down.
+	/// The expected value of the result is the value itself, eg. `1.25` rounds to `2` with 25% probability and to `1` otherwise.
+	///
+	/// Integral values are returned as is without consuming randomness, as are values outside the range of `i64` which saturate to `i64::MIN` or `i64::MAX`.
+	///
+	/// # Panics
+	///
+	/// Panics if the value is NaN.
+	///
+	/// # Precision
+	///
+	/// The fractional part is compared against a random float with 52 bits of precision.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let value = rng.round(-2.75);
+	/// assert!(value == -3 || value == -2);
+	/// ```
+	#[inline]
+	pub fn round(&mut self, x: f64) -> i64 {
+		let (floor, frac) = split_f64(x);
+		if frac == 0.0 {
+			return floor;
+		}
+		floor + (self.next_f64() - 1.0 < frac) as i64
+	}
+
+	/// Rounds the value to one of the neighbouring integers at random, unbiased in expectation.
+	///
+	/// See [`round`](Self::round) for more information, the fractional part is compared against a random float with 23 bits of precision.
+	///
+	/// # Panics
+	///
+	/// Panics if the value is NaN.
+	#[inline]
+	pub fn round_f32(&mut self, x: f32) -> i32 {
+		let (floor, frac) = split_f32(x);
+		if frac == 0.0 {
+			return floor;
+		}
+		floor + (self.next_f32() - 1.0 < frac) as i32
+	}
+
+	/// Rounds the values to one of the neighbouring integers at random, unbiased in expectation.
+	///
+	/// See [`round`](Self::round) for more information.
+	///
+	/// Amortizes the random number generation: every `u64` draw provides the randomness to round two values.
+	/// Integral values do not consume randomness.
+	///
+	/// # Panics
+	///
+	/// Panics if `src` and `out` have different lengths or if any value is NaN.
+	///
+	/// # Precision
+	///
+	/// The fractional part is compared against 32 random bits, the probability of rounding up is exact up to an error less than `2⁻³²`.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let src = [0.5, 1.0, -1.5, 2.25];
+	/// let mut out = [0; 4];
+	/// rng.round_slice(&src, &mut out);
+	/// assert_eq!(out[1], 1);
+	/// ```
+	pub fn round_slice(&mut self, src: &[f64], out: &mut [i64]) {
+		if src.len() != out.len() {
+			round_slice_error(src.len(), out.len());
+		}
+		let mut bits = 0u64;
+		let mut available = 0;
+		for (&x, out) in src.iter().zip(out.iter_mut()) {
+			let (floor, frac) = split_f64(x);
+			if frac == 0.0 {
+				*out = floor;
+				continue;
+			}
+			if available == 0 {
+				bits = self.next_u64();
+				available = 2;
+			}
+			let u = bits as u32;
+			bits >>= 32;
+			available -= 1;
+			*out = floor + ((u as f64) < frac * 4294967296.0) as i64;
+		}
+	}
+
 	/// Flips a coin.
 	///
 	/// Returns `true` when heads and `false` when tails with 50% probability for either result.
@@ -243,6 +709,43 @@ impl<R: Rng + ?Sized> Random<R> {
 		self.next()
 	}
 
+	/// Returns a random date in the range of years.
+	///
+	/// Every day in the range is equally likely, eg. February 29 is sampled only in leap years and the months are weighted by their number of days.
+	///
+	/// # Panics
+	///
+	/// Panics if the range of years is empty.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let date = rng.date(2020..=2024);
+	/// assert!(date.is_valid() && date.year >= 2020 && date.year <= 2024);
+	/// ```
+	#[cfg(feature = "std")]
+	pub fn date(&mut self, years: ops::RangeInclusive<i32>) -> crate::datetime::Date {
+		use crate::datetime::Date;
+		let (&start, &end) = (years.start(), years.end());
+		if start > end {
+			date_error(start, end);
+		}
+		let first = Date { year: start, month: 1, day: 1 }.to_days();
+		let last = Date { year: end, month: 12, day: 31 }.to_days();
+		Date::from_days(self.range(first..=last))
+	}
+
+	/// Returns a random time of day as `(hour, minute, second)`.
+	///
+	/// Every second of the day is equally likely, leap seconds are not produced.
+	#[cfg(feature = "std")]
+	#[inline]
+	pub fn time_of_day(&mut self) -> (u8, u8, u8) {
+		let seconds = self.index(86400);
+		((seconds / 3600) as u8, (seconds / 60 % 60) as u8, (seconds % 60) as u8)
+	}
+
 	/// Returns a random sample from the collection.
 	///
 	/// Returns `None` if and only if the collection is empty.
@@ -326,24 +829,280 @@ impl<R: Rng + ?Sized> Random<R> {
 		len
 	}
 
-	/// Returns a random usize in the `[0, len)` interval, mostly.
+	/// Collect weighted random samples from the collection into the buffer until it is filled.
 	///
-	/// If the `len` is zero an arbitrary value is returned directly from the Rng.
-	/// When used with indexing the bounds check should fail. Do not assume this value is inbounds.
+	/// Each element is selected with probability proportional to its weight, without replacement.
+	/// This is equivalent to repeatedly picking an element proportional to its weight from the remaining elements.
+	///
+	/// Elements with a weight which is not positive are skipped. In debug builds this panics as it likely indicates a bug.
+	///
+	/// Although the elements are selected randomly, the order of elements in the buffer is neither stable nor fully random.
+	/// If random ordering is desired, shuffle the result.
+	///
+	/// Returns the number of elements added to the buffer.
+	/// This equals the length of the buffer unless the iterator contains insufficient elements,
+	/// in which case this equals the number of elements available.
+	///
+	/// # Implementation notes
+	///
+	/// Implements the A-ExpJ algorithm by Efraimidis and Spirakis, [_Weighted random sampling with a reservoir_](https://doi.org/10.1016/j.ipl.2005.11.003).
+	/// Every element is assigned the key `u^(1/w)` and the elements with the largest keys are kept.
+	/// Instead of drawing a key for every element an exponential jump skips over elements which would not enter the reservoir.
+	///
+	/// Complexity is `O(n)` weight evaluations where `n` is the size of the collection, but only `O(k log(n/k))` random numbers are drawn where `k` is the size of the buffer.
 	///
 	/// # Examples
 	///
 	/// ```
 	/// let mut rng = urandom::new();
-	/// for len in 1..12345 {
-	/// 	let index = rng.index(len);
-	/// 	assert!(index < len, "len:{} index:{} was not inbounds", len, index);
-	/// }
+	/// let mut buffer = [0; 3];
+	/// let n = rng.multiple_weighted(1..=100, |&x| x as f64, &mut buffer);
+	/// assert_eq!(n, 3);
 	/// ```
-	pub fn index(&mut self, len: usize) -> usize {
+	#[cfg(feature = "std")]
+	pub fn multiple_weighted<I: IntoIterator, W: Fn(&I::Item) -> f64>(&mut self, collection: I, weight: W, buffer: &mut [I::Item]) -> usize {
+		let amount = buffer.len();
+		if amount == 0 {
+			return 0;
+		}
+
+		// Min-heap of log keys `ln(u)/w` and their slots in the buffer
+		let mut heap: Vec<(f64, usize)> = Vec::with_capacity(amount);
+		let mut jump = 0.0;
+
+		for elem in collection {
+			let w = weight(&elem);
+			debug_assert!(w > 0.0, "multiple_weighted weight (is {}) should be positive", w);
+			if w.is_nan() || w <= 0.0 {
+				continue;
+			}
+
+			if heap.len() < amount {
+				let u: f64 = distributions::Float01.sample(self);
+				let slot = heap.len();
+				buffer[slot] = elem;
+				heap.push((u.ln() / w, slot));
+				if heap.len() == amount {
+					for i in (0..amount / 2).rev() {
+						sift_down(&mut heap, i);
+					}
+					jump = self.exp_jump(heap[0].0);
+				}
+				continue;
+			}
+
+			// Skip elements until the accumulated weight exceeds the jump
+			jump -= w;
+			if jump > 0.0 {
+				continue;
+			}
+
+			// The new key is uniformly distributed above the current minimum key
+			let threshold = (heap[0].0 * w).exp();
+			let u: f64 = distributions::Float01.sample(self);
+			let r = threshold + (1.0 - threshold) * u;
+			let slot = heap[0].1;
+			buffer[slot] = elem;
+			heap[0] = (r.ln() / w, slot);
+			sift_down(&mut heap, 0);
+			jump = self.exp_jump(heap[0].0);
+		}
+
+		heap.len()
+	}
+	#[cfg(feature = "std")]
+	#[inline]
+	fn exp_jump(&mut self, min_key: f64) -> f64 {
+		let u: f64 = distributions::Float01.sample(self);
+		u.ln() / min_key
+	}
+
+	/// Returns a random usize in the `[0, len)` interval, mostly.
+	///
+	/// If the `len` is zero an arbitrary value is returned directly from the Rng.
+	/// When used with indexing the bounds check should fail. Do not assume this value is inbounds.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// for len in 1..12345 {
+	/// 	let index = rng.index(len);
+	/// 	assert!(index < len, "len:{} index:{} was not inbounds", len, index);
+	/// }
+	/// ```
+	pub fn index(&mut self, len: usize) -> usize {
 		distributions::UniformInt::constant(0, len).sample(self)
 	}
 
+	/// Returns a random index in the given range.
+	///
+	/// Unlike [`index`](Random::index) the range does not need to start at zero, useful to pick a pivot in a subslice.
+	///
+	/// # Panics
+	///
+	/// Panics if the range is empty.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let pivot = rng.index_in(10..20);
+	/// assert!(pivot >= 10 && pivot < 20);
+	/// ```
+	#[inline]
+	pub fn index_in(&mut self, range: ops::Range<usize>) -> usize {
+		if range.start >= range.end {
+			index_in_error(range.start, range.end);
+		}
+		range.start + self.index(range.end - range.start)
+	}
+
+	/// Fills `out` with a random priority for each key, stable for equal keys.
+	///
+	/// The priorities are useful as treap priorities or skip list heights, equal keys always receive equal priorities.
+	/// Each priority depends only on the hash of its key and the current state of the generator, not on the other keys or their order.
+	///
+	/// This method does not advance the generator, subsequent output is unchanged and repeated calls return the same priorities.
+	/// Advance the generator to draw a fresh set of priorities.
+	///
+	/// # Panics
+	///
+	/// Panics if `keys` and `out` have different lengths.
+	///
+	/// # Implementation notes
+	///
+	/// A salt is read from a clone of the generator. Each key is hashed to 64 bits with a portable (but not cryptographic) hasher
+	/// and the priority is [`mix64`](hash::mix64) of the hash xor the salt. Distinct keys may collide.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let rng = urandom::seeded(42);
+	/// let mut priorities = [0; 3];
+	/// rng.priorities(&["b", "a", "b"], &mut priorities);
+	/// assert_eq!(priorities[0], priorities[2]);
+	/// ```
+	pub fn priorities<T: Hash>(&self, keys: &[T], out: &mut [u64]) where R: Clone {
+		if keys.len() != out.len() {
+			priorities_error(keys.len(), out.len());
+		}
+		let salt = self.0.clone().next_u64();
+		for (key, priority) in keys.iter().zip(out) {
+			let mut hasher = rng::KeyHasher::new();
+			key.hash(&mut hasher);
+			*priority = hash::mix64(hasher.finish() ^ salt);
+		}
+	}
+
+	/// Returns a random index in the `[0, len)` interval excluding the given indices, or `None` if all indices are excluded.
+	///
+	/// The result is exactly uniform over the allowed indices without allocating.
+	/// The excluded indices must be sorted in ascending order, duplicates and indices out of range are ignored.
+	///
+	/// When few indices are excluded (at most 1/8th of `len`) this samples an index and retries if it is excluded.
+	/// Otherwise an index is sampled among the allowed indices and mapped over the gaps in `O(k)` for `k` excluded indices.
+	///
+	/// # Panics
+	///
+	/// Panics if the excluded indices are not sorted.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let occupied = [1, 2, 4];
+	/// let spawn = rng.index_excluding(5, &occupied);
+	/// assert!(matches!(spawn, Some(0) | Some(3)));
+	///
+	/// assert_eq!(rng.index_excluding(3, &[0, 1, 2]), None);
+	/// ```
+	pub fn index_excluding(&mut self, len: usize, excluded: &[usize]) -> Option<usize> {
+		// Count the distinct excluded indices in range
+		let mut count = 0;
+		let mut prev = None;
+		for &index in excluded {
+			if let Some(prev) = prev {
+				if index < prev {
+					index_excluding_error();
+				}
+				if index == prev {
+					continue;
+				}
+			}
+			prev = Some(index);
+			if index < len {
+				count += 1;
+			}
+		}
+		if count >= len {
+			return None;
+		}
+		if count <= len / 8 {
+			loop {
+				let index = self.index(len);
+				if excluded.binary_search(&index).is_err() {
+					return Some(index);
+				}
+			}
+		}
+		let mut index = self.index(len - count);
+		let mut prev = None;
+		for &excl in excluded {
+			if prev == Some(excl) {
+				continue;
+			}
+			prev = Some(excl);
+			if excl > index {
+				break;
+			}
+			index += 1;
+		}
+		Some(index)
+	}
+
+	/// Fills `out` with a uniformly random big-endian integer below the big-endian `modulus`.
+	///
+	/// Performs unbiased rejection sampling: candidates are masked to the bit length of the modulus and rejected while they are not less than the modulus.
+	/// The expected number of iterations is less than two for any modulus.
+	///
+	/// This is the primitive to sample eg. scalar field elements in cryptography without a bignum dependency.
+	///
+	/// # Panics
+	///
+	/// Panics if `out` and `modulus` have different lengths, or if the modulus is zero.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let modulus = [0x01, 0x00, 0x00];
+	/// let mut out = [0u8; 3];
+	/// rng.below_bytes(&modulus, &mut out);
+	/// assert!(out < modulus);
+	/// ```
+	pub fn below_bytes(&mut self, modulus: &[u8], out: &mut [u8]) {
+		if out.len() != modulus.len() {
+			below_bytes_len_error(out.len(), modulus.len());
+		}
+		// Skip the leading zero bytes of the modulus
+		let start = match modulus.iter().position(|&byte| byte != 0) {
+			Some(start) => start,
+			None => below_bytes_zero_error(),
+		};
+		out[..start].fill(0);
+		let out = &mut out[start..];
+		let modulus = &modulus[start..];
+		let mask = u8::MAX >> modulus[0].leading_zeros();
+		loop {
+			self.fill_bytes(out);
+			out[0] &= mask;
+			if *out < *modulus {
+				break;
+			}
+		}
+	}
+
 	/// Returns a shared reference to one random element of the slice, or `None` if the slice is empty.
 	#[inline]
 	pub fn choose<'a, T>(&mut self, slice: &'a [T]) -> Option<&'a T> {
@@ -356,6 +1115,26 @@ impl<R: Rng + ?Sized> Random<R> {
 		let index = self.index(slice.len());
 		slice.get_mut(index)
 	}
+	/// Returns a random index into the weights, the probability of each index is proportional to its weight.
+	///
+	/// For repeated sampling from the same weights construct a [`WeightedIndex`](distributions::WeightedIndex) once.
+	///
+	/// # Panics
+	///
+	/// Panics if there are no weights, any weight is negative or not finite, all weights are zero or the sum of the weights overflows.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let loot = ["common", "rare", "legendary"];
+	/// let drop = loot[rng.weighted(&[90, 9, 1])];
+	/// ```
+	#[cfg(feature = "alloc")]
+	#[inline]
+	pub fn weighted<W: distributions::SampleWeight>(&mut self, weights: &[W]) -> usize {
+		distributions::WeightedIndex::new(weights.iter().copied()).sample(self)
+	}
 
 	/// Returns an iterator over random chosen elements of the slice with repetition.
 	///
@@ -365,6 +1144,66 @@ impl<R: Rng + ?Sized> Random<R> {
 		core::iter::from_fn(move || self.choose(slice))
 	}
 
+	/// Returns a random window of `len` contiguous elements of the slice, or `None` if the window does not fit.
+	///
+	/// The start of the window is uniform over the `slice.len() - len + 1` positions where the window fits.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let text = b"the quick brown fox jumps over the lazy dog";
+	/// let window = rng.window(text, 5).unwrap();
+	/// assert_eq!(window.len(), 5);
+	/// assert!(rng.window(text, 100).is_none());
+	/// ```
+	#[inline]
+	pub fn window<'a, T>(&mut self, slice: &'a [T], len: usize) -> Option<&'a [T]> {
+		let start = self.window_start(slice.len(), len)?;
+		Some(&slice[start..start + len])
+	}
+	/// Returns a random window of `len` contiguous elements of the slice, or `None` if the window does not fit.
+	///
+	/// See [`window`](Random::window) for more information.
+	#[inline]
+	pub fn window_mut<'a, T>(&mut self, slice: &'a mut [T], len: usize) -> Option<&'a mut [T]> {
+		let start = self.window_start(slice.len(), len)?;
+		Some(&mut slice[start..start + len])
+	}
+	#[inline]
+	fn window_start(&mut self, slice_len: usize, len: usize) -> Option<usize> {
+		let positions = slice_len.checked_sub(len)?;
+		Some(if positions == 0 { 0 } else { self.range(0..=positions) })
+	}
+
+	/// Returns a random contiguous subrange of `0..len`.
+	///
+	/// The result is uniform over all `(len + 1)·(len + 2) / 2` ranges `start..end` with `start <= end <= len`.
+	/// Empty ranges are included and are distinct by their position: `3..3` and `5..5` are different outcomes.
+	/// An empty range is returned with probability `2 / (len + 2)`, ignore those results to sample only non-empty ranges.
+	///
+	/// Note that sampling the endpoints independently and sorting them is not uniform: it favors the empty ranges.
+	///
+	/// # Implementation notes
+	///
+	/// A single uniform index `end·(end + 1)/2 + start` into all the ranges is decoded with the inverse triangular numbers.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let data = [1, 2, 3, 4, 5, 6, 7, 8];
+	/// let range = rng.subrange(data.len());
+	/// assert!(range.start <= range.end && range.end <= data.len());
+	/// let _ = &data[range];
+	/// ```
+	#[inline]
+	pub fn subrange(&mut self, len: usize) -> ops::Range<usize> {
+		let n = len as u128 + 1;
+		let (start, end) = decode_triangular(self.range(0..triangular(n)));
+		start..end
+	}
+
 	/// Standard [Fisher–Yates](https://en.wikipedia.org/wiki/Fisher%E2%80%93Yates_shuffle) shuffle.
 	///
 	/// # Examples
@@ -386,6 +1225,56 @@ impl<R: Rng + ?Sized> Random<R> {
 		}
 	}
 
+	/// Returns a shuffled copy of the slice.
+	///
+	/// Equivalent to `to_vec` followed by [`shuffle`](Self::shuffle) but builds the shuffled vector in a single pass.
+	///
+	/// # Implementation notes
+	///
+	/// Uses the inside-out variant of the Fisher–Yates shuffle.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let original = ["a", "b", "c", "d", "e"];
+	/// let shuffled = rng.shuffled(&original);
+	/// assert!(original.iter().all(|s| shuffled.contains(s)));
+	/// ```
+	#[cfg(feature = "alloc")]
+	pub fn shuffled<T: Clone>(&mut self, src: &[T]) -> Vec<T> {
+		let mut out = Vec::with_capacity(src.len());
+		for (i, value) in src.iter().enumerate() {
+			let j = self.index(i + 1);
+			out.push(value.clone());
+			out.swap(i, j);
+		}
+		out
+	}
+
+	/// Returns a shuffled copy of the array.
+	///
+	/// Same as [`shuffled`](Self::shuffled) without allocating.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let mut shuffled = rng.shuffle_copy(&[1, 2, 3, 4, 5]);
+	/// shuffled.sort();
+	/// assert_eq!(shuffled, [1, 2, 3, 4, 5]);
+	/// ```
+	#[inline]
+	pub fn shuffle_copy<T: Copy, const N: usize>(&mut self, src: &[T; N]) -> [T; N] {
+		let mut out = *src;
+		for i in 1..N {
+			let j = self.index(i + 1);
+			out[i] = out[j];
+			out[j] = src[i];
+		}
+		out
+	}
+
 	/// Shuffle only the first _n_ elements.
 	///
 	/// This is an efficient method to select _n_ elements at random from the slice without repetition, provided the slice may be mutated.
@@ -399,47 +1288,1715 @@ impl<R: Rng + ?Sized> Random<R> {
 			}
 		}
 	}
-}
 
-impl<R: Rng + ?Sized> fmt::Debug for Random<R> {
-	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		f.write_str("Random(impl Rng)")
+	/// Deals `HANDS` hands of `SIZE` cards each from the deck.
+	///
+	/// The deck is partially shuffled until the dealt cards are in place, then the cards are dealt round-robin from the top.
+	/// Afterwards the first `HANDS * SIZE` elements of the deck are the dealt cards, the remaining elements are the rest of the deck in unspecified order.
+	///
+	/// See the [`cards`](crate::cards) module for a standard deck of playing cards.
+	///
+	/// # Panics
+	///
+	/// Panics if `HANDS * SIZE` is greater than the length of the deck.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let mut deck: Vec<u8> = (0..32).collect();
+	/// let hands = rng.deal::<_, 4, 8>(&mut deck);
+	/// assert!(hands.iter().flatten().all(|card| *card < 32));
+	/// ```
+	#[inline]
+	pub fn deal<T: Copy, const HANDS: usize, const SIZE: usize>(&mut self, deck: &mut [T]) -> [[T; SIZE]; HANDS] {
+		let n = HANDS.checked_mul(SIZE).filter(|&n| n <= deck.len()).unwrap_or_else(|| deal_error(HANDS, SIZE, deck.len()));
+		self.partial_shuffle(deck, n);
+		core::array::from_fn(|hand| core::array::from_fn(|i| deck[i * HANDS + hand]))
 	}
-}
 
-#[cfg(feature = "std")]
-impl<R: Rng> std::io::Read for Random<R> {
-	fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-		self.fill_bytes(buf);
-		Ok(buf.len())
-	}
-	fn read_to_end(&mut self, _buf: &mut Vec<u8>) -> std::io::Result<usize> {
-		panic!("cannot read_to_end from Rng")
-	}
-	fn read_to_string(&mut self, _buf: &mut String) -> std::io::Result<usize> {
-		panic!("cannot read_to_string from Rng")
-	}
-	fn read_exact(&mut self, buf: &mut [u8]) -> std::io::Result<()> {
-		self.fill_bytes(buf);
-		Ok(())
+	/// Cuts the deck at a random position.
+	///
+	/// Rotates the deck to the left by a uniformly chosen amount in `[0, len)`.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let mut deck = [1, 2, 3, 4, 5];
+	/// rng.cut(&mut deck);
+	/// let top = deck[0];
+	/// assert_eq!(deck[4], if top == 1 { 5 } else { top - 1 });
+	/// ```
+	#[inline]
+	pub fn cut<T>(&mut self, deck: &mut [T]) {
+		if deck.len() > 1 {
+			let mid = self.index(deck.len());
+			deck.rotate_left(mid);
+		}
 	}
-}
-
-//----------------------------------------------------------------
 
-#[test]
-fn test_choose() {
-	let mut rng = crate::new();
+	/// Clears the mask and sets exactly `k` uniformly chosen positions to `true`.
+	///
+	/// Every one of the `C(n, k)` masks of length `n = out.len()` is equally likely.
+	///
+	/// # Implementation notes
+	///
+	/// Uses [selection sampling](https://en.wikipedia.org/wiki/Reservoir_sampling#Simple:_Algorithm_S) (Knuth's Algorithm S):
+	/// walks the slice once and sets each position with probability `needed / remaining`.
+	/// Runs in `O(n)` without allocating.
+	///
+	/// # Panics
+	///
+	/// Panics if `k` is greater than the length of the mask.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let mut dropout = [false; 10];
+	/// rng.mask(&mut dropout, 3);
+	/// assert_eq!(dropout.iter().filter(|&&dropped| dropped).count(), 3);
+	/// ```
+	#[inline]
+	pub fn mask(&mut self, out: &mut [bool], k: usize) {
+		let n = out.len();
+		if k > n {
+			mask_error(k, n);
+		}
+		let mut needed = k;
+		for (i, elem) in out.iter_mut().enumerate() {
+			*elem = needed > 0 && self.index(n - i) < needed;
+			needed -= *elem as usize;
+		}
+	}
 
-	let mut array = [0, 1, 2, 3, 4];
-	let mut result = [0i32; 5];
+	/// Clears the bitset and sets exactly `k` uniformly chosen bits among the first `n` bits.
+	///
+	/// Bit `i` is stored in `out[i / 64]` at bit position `i % 64`. All words of `out` are cleared, including bits past `n`.
+	/// See [`mask`](Random::mask) for the algorithm.
+	///
+	/// # Panics
+	///
+	/// Panics if `k` is greater than `n` or `out` has fewer than `n` bits.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let mut features = [0u64; 2];
+	/// rng.mask_bits(&mut features, 100, 10);
+	/// assert_eq!(features[0].count_ones() + features[1].count_ones(), 10);
+	/// assert_eq!(features[1] >> 36, 0);
+	/// ```
+	#[inline]
+	pub fn mask_bits(&mut self, out: &mut [u64], n: usize, k: usize) {
+		if k > n || out.len() < n.div_ceil(64) {
+			mask_bits_error(n, k, out.len());
+		}
+		let mut needed = k;
+		for (w, word) in out.iter_mut().enumerate() {
+			*word = 0;
+			for b in 0..usize::min(64, n.saturating_sub(w * 64)) {
+				let i = w * 64 + b;
+				if needed > 0 && self.index(n - i) < needed {
+					*word |= 1 << b;
+					needed -= 1;
+				}
+			}
+		}
+	}
 
-	for _ in 0..10000 {
-		result[*rng.choose(&array).unwrap()] += 1;
-		result[*rng.choose_mut(&mut array).unwrap()] += 1;
+	/// Fills the packed bitset with fair coin flips, every bit is independently 1 with probability 1/2.
+	///
+	/// Consumes exactly one `u64` of randomness per word.
+	/// See [`bitplane`](Random::bitplane) for other probabilities.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let mut cells = [0u64; 16];
+	/// rng.coin_flips(&mut cells);
+	/// ```
+	#[inline]
+	pub fn coin_flips(&mut self, out: &mut [u64]) {
+		self.fill_u64(out);
 	}
 
-	let mean = (result[0] + result[1] + result[2] + result[3] + result[4]) / 5;
-	let success = result.iter().all(|&x| (x - mean).abs() < 500);
-	assert!(success, "mean: {}, result: {:?}", mean, result);
+	/// Fills the packed bitset with biased bits, every bit is independently 1 with probability `p_numerator / 2^p_denominator_log2`.
+	///
+	/// The probability is exact, each bit is a boolean function of independent fair random bits.
+	/// This is much cheaper than a Bernoulli trial per bit, useful for cellular automata, dithering and procedural masks.
+	///
+	/// # Implementation notes
+	///
+	/// The bits are composed from random bit-planes following the binary expansion of the probability,
+	/// from the lowest set bit of the numerator upwards: a set bit ORs the next plane into the result, a clear bit ANDs it.
+	/// For example `3/8 = 0.011₂` computes `(a | b) & c` for the planes `a`, `b` and `c`.
+	///
+	/// This uses the minimal number of planes `p_denominator_log2 - p_numerator.trailing_zeros()`, consuming one `u64` per plane per word.
+	/// The probabilities zero and one consume no randomness.
+	///
+	/// # Panics
+	///
+	/// Panics if `p_denominator_log2` is greater than 32 or the probability is greater than one.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// // Every cell is alive with probability 3/8
+	/// let mut cells = [0u64; 16];
+	/// rng.bitplane(&mut cells, 3, 3);
+	/// ```
+	pub fn bitplane(&mut self, out: &mut [u64], p_numerator: u32, p_denominator_log2: u32) {
+		if p_denominator_log2 > 32 || p_numerator as u64 > 1u64 << p_denominator_log2 {
+			bitplane_error(p_numerator, p_denominator_log2);
+		}
+		if p_numerator == 0 {
+			out.fill(0);
+			return;
+		}
+		let lowest = p_numerator.trailing_zeros();
+		if lowest >= p_denominator_log2 {
+			out.fill(!0);
+			return;
+		}
+		for word in out {
+			let mut bits = self.next_u64();
+			for i in lowest + 1..p_denominator_log2 {
+				let plane = self.next_u64();
+				bits = if p_numerator >> i & 1 != 0 { bits | plane } else { bits & plane };
+			}
+			*word = bits;
+		}
+	}
+
+	/// Splits the items in two groups, every item independently goes to the first group with probability `p`.
+	///
+	/// The relative order of the items is preserved within each group.
+	/// See [`split_exact`](Random::split_exact) to choose the exact size of the first group.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let samples: Vec<i32> = (0..100).collect();
+	/// let (train, test) = rng.partition(samples, 0.8);
+	/// assert_eq!(train.len() + test.len(), 100);
+	/// ```
+	#[cfg(feature = "alloc")]
+	pub fn partition<T>(&mut self, items: Vec<T>, p: f64) -> (Vec<T>, Vec<T>) {
+		let distr = distributions::Bernoulli::new(p);
+		let mut left = Vec::new();
+		let mut right = Vec::new();
+		for item in items {
+			if distr.sample(self) { left.push(item) } else { right.push(item) }
+		}
+		(left, right)
+	}
+
+	/// Splits the items in two groups with exactly `k` uniformly chosen items in the first group.
+	///
+	/// The relative order of the items is preserved within each group.
+	/// The items are chosen with selection sampling, see [`mask`](Random::mask).
+	///
+	/// # Panics
+	///
+	/// Panics if `k` is greater than the number of items.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let samples: Vec<i32> = (0..100).collect();
+	/// let (train, test) = rng.split_exact(samples, 80);
+	/// assert_eq!((train.len(), test.len()), (80, 20));
+	/// ```
+	#[cfg(feature = "alloc")]
+	pub fn split_exact<T>(&mut self, items: Vec<T>, k: usize) -> (Vec<T>, Vec<T>) {
+		let n = items.len();
+		if k > n {
+			split_exact_error(k, n);
+		}
+		let mut left = Vec::with_capacity(k);
+		let mut right = Vec::with_capacity(n - k);
+		for (i, item) in items.into_iter().enumerate() {
+			if left.len() < k && self.index(n - i) < k - left.len() { left.push(item) } else { right.push(item) }
+		}
+		(left, right)
+	}
+
+	/// Swaps the element at the given index with a uniformly chosen element at or before it.
+	///
+	/// This is one step of the [Fisher–Yates](https://en.wikipedia.org/wiki/Fisher%E2%80%93Yates_shuffle) shuffle.
+	/// If `slice[..index]` is uniformly shuffled then `slice[..=index]` is uniformly shuffled afterwards.
+	///
+	/// # Panics
+	///
+	/// Panics if the index is out of bounds.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let mut queue = vec![1, 2, 3];
+	/// rng.shuffle(&mut queue);
+	///
+	/// // Shuffle a new element into the queue
+	/// queue.push(4);
+	/// rng.swap_random(&mut queue, 3);
+	/// ```
+	#[inline]
+	pub fn swap_random<T>(&mut self, slice: &mut [T], index: usize) {
+		if index >= slice.len() {
+			swap_random_error(index, slice.len());
+		}
+		let k = self.index(index + 1);
+		slice.swap(k, index);
+	}
+
+	/// Shuffles the elements of `slice[start..]` into the slice.
+	///
+	/// This is the incremental dual of [`partial_shuffle`](Random::partial_shuffle):
+	/// every element of the tail is swapped with a uniformly chosen element at or before it (see [`swap_random`](Random::swap_random)).
+	/// Elements of the tail may end up anywhere in the slice, not only in the tail.
+	///
+	/// If `slice[..start]` is uniformly shuffled then the whole slice is uniformly shuffled afterwards.
+	/// It follows that calling this method after every append to an initially empty queue always yields a uniformly random permutation.
+	/// With a `start` of zero this is a full shuffle.
+	///
+	/// # Panics
+	///
+	/// Panics if `start` is greater than the length of the slice.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let mut queue = Vec::new();
+	/// for track in 0..10 {
+	/// 	let start = queue.len();
+	/// 	queue.push(track);
+	/// 	rng.shuffle_tail(&mut queue, start);
+	/// }
+	/// ```
+	#[inline]
+	pub fn shuffle_tail<T>(&mut self, slice: &mut [T], start: usize) {
+		if start > slice.len() {
+			shuffle_tail_error(start, slice.len());
+		}
+		for i in usize::max(start, 1)..slice.len() {
+			let k = self.index(i + 1);
+			slice.swap(k, i);
+		}
+	}
+
+	/// Shuffles the slice and returns the permutation used.
+	///
+	/// After shuffling the element at index `i` is the element originally at index `permutation[i]`.
+	/// The permutation can be used to map the shuffled elements back to their original positions.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let original = ['a', 'b', 'c', 'd', 'e'];
+	/// let mut shuffled = original;
+	/// let permutation = rng.apply_permutation(&mut shuffled);
+	/// for i in 0..original.len() {
+	/// 	assert_eq!(shuffled[i], original[permutation[i]]);
+	/// }
+	/// ```
+	#[cfg(feature = "alloc")]
+	pub fn apply_permutation<T>(&mut self, slice: &mut [T]) -> Vec<usize> {
+		let mut permutation: Vec<usize> = (0..slice.len()).collect();
+		let mut len = slice.len();
+		while len > 1 {
+			let k = self.index(len);
+			slice.swap(k, len - 1);
+			permutation.swap(k, len - 1);
+			len -= 1;
+		}
+		permutation
+	}
+
+	/// Returns a random [derangement](https://en.wikipedia.org/wiki/Derangement) of `0..n`, a permutation where no element remains in its original position.
+	///
+	/// Returns `None` if `n` is `1` as there are no derangements of a single element.
+	/// Returns an empty derangement if `n` is `0`.
+	///
+	/// All derangements are equally likely.
+	///
+	/// # Implementation notes
+	///
+	/// Uses the rejection method: a shuffle is restarted as soon as a fixed point is placed.
+	/// Restarting early is equivalent to rejecting the completed shuffle as the placed elements are final.
+	/// The expected number of restarts is about `e`.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let derangement = rng.derangement(10).unwrap();
+	/// for (i, &j) in derangement.iter().enumerate() {
+	/// 	assert_ne!(i, j);
+	/// }
+	/// ```
+	#[cfg(feature = "alloc")]
+	pub fn derangement(&mut self, n: usize) -> Option<Vec<usize>> {
+		if n == 1 {
+			return None;
+		}
+		let mut derangement: Vec<usize> = (0..n).collect();
+		'restart: loop {
+			for (i, slot) in derangement.iter_mut().enumerate() {
+				*slot = i;
+			}
+			let mut len = n;
+			while len > 1 {
+				let k = self.index(len);
+				derangement.swap(k, len - 1);
+				if derangement[len - 1] == len - 1 {
+					continue 'restart;
+				}
+				len -= 1;
+			}
+			if n == 0 || derangement[0] != 0 {
+				return Some(derangement);
+			}
+		}
+	}
+
+	/// Fills the buffer with stratified samples in the half-open interval `[0, 1)`.
+	///
+	/// The interval is divided in `n = out.len()` strata of equal width, each stratum contains exactly one sample.
+	/// Every sample is uniformly distributed within its stratum and the strata are visited in random order.
+	///
+	/// This is stratified random sampling, not a low-discrepancy sequence.
+	/// Stratified samples reduce the variance of Monte Carlo estimates compared to independent uniform samples.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let mut samples = [0.0; 4];
+	/// rng.stratified_1d(&mut samples);
+	/// samples.sort_by(|a, b| a.partial_cmp(b).unwrap());
+	/// for (i, &x) in samples.iter().enumerate() {
+	/// 	assert!(x >= i as f64 / 4.0 && x < (i + 1) as f64 / 4.0);
+	/// }
+	/// ```
+	pub fn stratified_1d(&mut self, out: &mut [f64]) {
+		let n = out.len() as f64;
+		for (i, slot) in out.iter_mut().enumerate() {
+			let u = self.next_f64() - 1.0;
+			let x = (i as f64 + u) / n;
+			// Rounding may push the sample into the next stratum
+			let end = (i + 1) as f64 / n;
+			*slot = if x < end { x } else { f64::from_bits(end.to_bits() - 1) };
+		}
+		self.shuffle(out);
+	}
+
+	/// Returns `n` points in the unit hypercube `[0, 1)^dims` using [Latin hypercube sampling](https://en.wikipedia.org/wiki/Latin_hypercube_sampling).
+	///
+	/// Along every dimension the coordinates of the points occupy each of the `n` strata of width `1/n` exactly once,
+	/// see [`stratified_1d`](Random::stratified_1d). The strata are permuted independently per dimension.
+	///
+	/// This is stratified random sampling, not a low-discrepancy sequence.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let points = rng.latin_hypercube(3, 100);
+	/// assert_eq!(points.len(), 100);
+	/// assert!(points.iter().all(|point| point.len() == 3));
+	/// ```
+	#[cfg(feature = "alloc")]
+	pub fn latin_hypercube(&mut self, dims: usize, n: usize) -> Vec<Vec<f64>> {
+		let mut points = alloc::vec![alloc::vec![0.0; dims]; n];
+		let mut column = alloc::vec![0.0; n];
+		for d in 0..dims {
+			self.stratified_1d(&mut column);
+			for (point, &x) in points.iter_mut().zip(&column) {
+				point[d] = x;
+			}
+		}
+		points
+	}
+
+	/// Returns `k` distinct unordered pairs `(i, j)` with `i < j < n`, sampled uniformly.
+	///
+	/// The pairs are the edges of a uniformly random graph with `n` vertices and `k` edges.
+	///
+	/// Although the pairs are selected randomly, their order is not fully random.
+	/// If random ordering is desired, shuffle the result.
+	///
+	/// # Implementation notes
+	///
+	/// Distinct indices into the `n·(n-1)/2` pairs are sampled with Floyd's algorithm and decoded with the inverse triangular numbers.
+	/// The indices are sampled in 128 bits, every pair is reachable for any `n`.
+	/// The pairs are never enumerated, memory is `O(k)`.
+	///
+	/// # Panics
+	///
+	/// Panics if `k` is greater than the number of pairs `n·(n-1)/2`.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let edges = rng.pairs(1000, 5);
+	/// assert_eq!(edges.len(), 5);
+	/// assert!(edges.iter().all(|&(i, j)| i < j && j < 1000));
+	/// ```
+	#[cfg(feature = "std")]
+	pub fn pairs(&mut self, n: usize, k: usize) -> Vec<(usize, usize)> {
+		let n = n as u128;
+		let total = n * n.saturating_sub(1) / 2;
+		if k as u128 > total {
+			pairs_error(k, total);
+		}
+		let mut chosen = std::collections::HashSet::with_capacity(k);
+		let mut pairs = Vec::with_capacity(k);
+		// The pair indices of more than 2³² elements do not fit in 64 bits
+		for j in total - k as u128..total {
+			let t = self.range(0..=j);
+			let index = if chosen.insert(t) { t } else { chosen.insert(j); j };
+			pairs.push(decode_pair(index));
+		}
+		pairs
+	}
+
+	/// Returns a uniformly random perfect matching of `0..n`.
+	///
+	/// Every element appears in exactly one pair `(i, j)` with `i < j`.
+	///
+	/// # Panics
+	///
+	/// Panics if `n` is odd.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::new();
+	/// let matching = rng.matching(6);
+	/// assert_eq!(matching.len(), 3);
+	/// ```
+	#[cfg(feature = "alloc")]
+	pub fn matching(&mut self, n: usize) -> Vec<(usize, usize)> {
+		if !n.is_multiple_of(2) {
+			matching_error(n);
+		}
+		let mut elements: Vec<usize> = (0..n).collect();
+		self.shuffle(&mut elements);
+		elements.chunks_exact(2).map(|pair| (usize::min(pair[0], pair[1]), usize::max(pair[0], pair[1]))).collect()
+	}
+}
+
+// Decodes the index `j·(j-1)/2 + i` to the pair `(i, j)` with `i < j`
+#[cfg(feature = "std")]
+fn decode_pair(index: u128) -> (usize, usize) {
+	let (i, j) = decode_triangular(index);
+	(i, j + 1)
+}
+
+// Computes j·(j+1)/2 without overflow for j up to 2⁶⁴
+#[inline]
+fn triangular(j: u128) -> u128 {
+	if j.is_multiple_of(2) { j / 2 * (j + 1) } else { (j + 1) / 2 * j }
+}
+
+// Decodes the index `j·(j+1)/2 + i` to the pair `(i, j)` with `i <= j`
+fn decode_triangular(index: u128) -> (usize, usize) {
+	// The integer square root of 2·index is within one of the result
+	let mut j = index.saturating_mul(2).isqrt();
+	if triangular(j) > index {
+		j -= 1;
+	}
+	else if triangular(j + 1) <= index {
+		j += 1;
+	}
+	let i = index - triangular(j);
+	(i as usize, j as usize)
+}
+
+#[cfg(feature = "std")]
+fn sift_down(heap: &mut [(f64, usize)], mut i: usize) {
+	loop {
+		let left = 2 * i + 1;
+		let right = left + 1;
+		let mut min = i;
+		if left < heap.len() && heap[left].0 < heap[min].0 {
+			min = left;
+		}
+		if right < heap.len() && heap[right].0 < heap[min].0 {
+			min = right;
+		}
+		if min == i {
+			break;
+		}
+		heap.swap(i, min);
+		i = min;
+	}
+}
+
+// Splits the value into its floor and fractional part without requiring std
+#[inline]
+fn split_f64(x: f64) -> (i64, f64) {
+	if x.is_nan() {
+		round_nan_error();
+	}
+	// Values this large are integral
+	if x.abs() >= 4503599627370496.0 {
+		return (x as i64, 0.0);
+	}
+	let mut floor = x as i64;
+	if floor as f64 > x {
+		floor -= 1;
+	}
+	(floor, x - floor as f64)
+}
+#[inline]
+fn split_f32(x: f32) -> (i32, f32) {
+	if x.is_nan() {
+		round_nan_error();
+	}
+	if x.abs() >= 8388608.0 {
+		return (x as i32, 0.0);
+	}
+	let mut floor = x as i32;
+	if floor as f32 > x {
+		floor -= 1;
+	}
+	(floor, x - floor as f32)
+}
+
+#[cold]
+fn round_nan_error() -> ! {
+	panic!("round called with NaN");
+}
+
+#[cold]
+fn round_slice_error(src: usize, out: usize) -> ! {
+	panic!("round_slice src len (is {}) should be equal to out len (is {})", src, out);
+}
+
+#[cold]
+fn index_in_error(start: usize, end: usize) -> ! {
+	panic!("index_in range {}..{} should not be empty", start, end);
+}
+
+#[cold]
+fn priorities_error(keys: usize, out: usize) -> ! {
+	panic!("priorities keys len (is {}) should be equal to out len (is {})", keys, out);
+}
+
+#[cold]
+fn index_excluding_error() -> ! {
+	panic!("index_excluding excluded indices should be sorted");
+}
+
+#[cold]
+fn below_bytes_len_error(len: usize, modulus_len: usize) -> ! {
+	panic!("below_bytes out len (is {}) should be equal to modulus len (is {})", len, modulus_len);
+}
+
+#[cold]
+fn below_bytes_zero_error() -> ! {
+	panic!("below_bytes modulus should be nonzero");
+}
+
+#[cfg(feature = "std")]
+#[cold]
+fn date_error(start: i32, end: i32) -> ! {
+	panic!("date range of years {}..={} should not be empty", start, end);
+}
+
+#[cfg(feature = "std")]
+#[cold]
+fn pairs_error(k: usize, total: u128) -> ! {
+	panic!("pairs k (is {}) should be <= the number of pairs (is {})", k, total);
+}
+
+#[cfg(feature = "alloc")]
+#[cold]
+fn matching_error(n: usize) -> ! {
+	panic!("matching n (is {}) should be even", n);
+}
+
+#[cold]
+fn deal_error(hands: usize, size: usize, len: usize) -> ! {
+	panic!("deal {} hands of {} cards (is {}) should be <= len (is {})", hands, size, hands as u128 * size as u128, len);
+}
+
+#[cold]
+fn mask_error(k: usize, n: usize) -> ! {
+	panic!("mask k (is {}) should be <= len (is {})", k, n);
+}
+
+#[cold]
+fn mask_bits_error(n: usize, k: usize, words: usize) -> ! {
+	panic!("mask_bits k (is {}) should be <= n (is {}) and n should fit in {} words", k, n, words);
+}
+
+#[cold]
+fn bitplane_error(numerator: u32, denominator_log2: u32) -> ! {
+	panic!("bitplane probability {}/2^{} should be <= 1 with a denominator of at most 2^32", numerator, denominator_log2);
+}
+
+#[cfg(feature = "alloc")]
+#[cold]
+fn split_exact_error(k: usize, n: usize) -> ! {
+	panic!("split_exact k (is {}) should be <= len (is {})", k, n);
+}
+
+#[cold]
+fn swap_random_error(index: usize, len: usize) -> ! {
+	panic!("swap_random index (is {}) should be < len (is {})", index, len);
+}
+
+#[cold]
+fn shuffle_tail_error(start: usize, len: usize) -> ! {
+	panic!("shuffle_tail start (is {}) should be <= len (is {})", start, len);
+}
+
+impl<R> Random<R> {
+	/// Returns an iterator of samples from the given distribution which owns the generator.
+	///
+	/// See [`IntoSamples`](distributions::IntoSamples) for the hazards of cloning the iterator.
+	#[inline]
+	pub fn into_samples<T, D>(self, distr: D) -> distributions::IntoSamples<R, D, T> where D: Distribution<T> {
+		distributions::IntoSamples::new(self, distr)
+	}
+
+	/// Returns an iterator of samples from the [`Standard`](distributions::Standard) distribution which owns the generator.
+	///
+	/// The type parameter comes first to make turbofishing convenient.
+	/// See [`IntoSamples`](distributions::IntoSamples) for the hazards of cloning the iterator.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let jitter: Vec<f32> = urandom::seeded(42).into_iter_of::<f32>().take(4).collect();
+	/// assert_eq!(jitter.len(), 4);
+	/// ```
+	#[inline]
+	pub fn into_iter_of<T>(self) -> distributions::IntoSamples<R, distributions::Standard, T> where distributions::Standard: Distribution<T> {
+		distributions::IntoSamples::new(self, distributions::Standard)
+	}
+
+	/// Wraps the generator to count the calls made to it.
+	///
+	/// See [`Counting`](rng::Counting) for more information.
+	#[inline]
+	pub fn counting(self) -> Random<rng::Counting<R>> {
+		Random(rng::Counting::new(self.0))
+	}
+
+	/// Wraps the generator to byte-swap its words.
+	///
+	/// See [`ByteSwapped`](rng::ByteSwapped) for more information.
+	#[inline]
+	pub fn byte_swapped(self) -> Random<rng::ByteSwapped<R>> {
+		Random(rng::ByteSwapped::new(self.0))
+	}
+
+	/// Wraps the raw entropy source to condition its output through the ChaCha20 block function.
+	///
+	/// See [`Conditioned`](rng::Conditioned) for more information.
+	#[inline]
+	pub fn conditioned(self) -> Random<rng::Conditioned<R>> {
+		Random(rng::Conditioned::new(self.0))
+	}
+
+	/// Wraps the generator to split its `u64` words into pairs of `u32` words in the given order.
+	///
+	/// See [`Lagged`](rng::Lagged) for more information.
+	#[inline]
+	pub fn lagged(self, order: rng::WordOrder) -> Random<rng::Lagged<R>> {
+		Random(rng::Lagged::new(self.0, order))
+	}
+
+	/// Wraps the generator to record every value produced by it.
+	///
+	/// See [`Recorder`](rng::Recorder) for more information.
+	#[cfg(feature = "alloc")]
+	#[inline]
+	pub fn recording(self) -> Random<rng::Recorder<R>> {
+		Random(rng::Recorder::new(self.0))
+	}
+}
+
+impl<R: Rng + ?Sized> fmt::Debug for Random<R> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("Random(impl Rng)")
+	}
+}
+
+#[cfg(feature = "zeroize")]
+impl<R: zeroize::Zeroize + ?Sized> zeroize::Zeroize for Random<R> {
+	#[inline]
+	fn zeroize(&mut self) {
+		self.0.zeroize();
+	}
+}
+#[cfg(feature = "zeroize")]
+impl<R: zeroize::ZeroizeOnDrop + ?Sized> zeroize::ZeroizeOnDrop for Random<R> {}
+
+#[cfg(feature = "std")]
+impl<R: Rng> std::io::Read for Random<R> {
+	fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+		self.fill_bytes(buf);
+		Ok(buf.len())
+	}
+	fn read_to_end(&mut self, _buf: &mut Vec<u8>) -> std::io::Result<usize> {
+		panic!("cannot read_to_end from Rng")
+	}
+	fn read_to_string(&mut self, _buf: &mut String) -> std::io::Result<usize> {
+		panic!("cannot read_to_string from Rng")
+	}
+	fn read_exact(&mut self, buf: &mut [u8]) -> std::io::Result<()> {
+		self.fill_bytes(buf);
+		Ok(())
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_fill_u16() {
+	let mut rng = crate::rng::SplitMix64::from_seed(42);
+	let mut reference = rng.clone();
+	let mut buffer = [0u16; 5];
+	rng.fill_u16(&mut buffer);
+	let mut bytes = [0u8; 10];
+	reference.fill_bytes(&mut bytes);
+	for (elem, bytes) in buffer.iter().zip(bytes.chunks(2)) {
+		assert_eq!(*elem, u16::from_le_bytes([bytes[0], bytes[1]]));
+	}
+	assert_eq!(rng.next_u64(), reference.next_u64());
+}
+
+#[test]
+fn test_collect_array() {
+	let distr = distributions::Uniform::from(0.0..10.0);
+	let mut a = crate::seeded(42);
+	let mut b = crate::seeded(42);
+	let values: [f64; 100] = a.collect_array(&distr);
+	for &value in &values {
+		assert_eq!(value, b.sample(&distr));
+	}
+	let empty: [f64; 0] = a.collect_array(&distr);
+	assert_eq!(empty, []);
+	assert_eq!(a.next_u64(), b.next_u64());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_collect_vec() {
+	let distr = distributions::Uniform::from(1..=6);
+	let mut a = crate::seeded(42);
+	let mut b = crate::seeded(42);
+	let values: Vec<i32> = a.collect_vec(&distr, 1000);
+	let expected: Vec<i32> = (0..1000).map(|_| b.sample(&distr)).collect();
+	assert_eq!(values, expected);
+	let array: [i32; 50] = a.collect_array(&distr);
+	assert_eq!(a.collect_vec(&distr, 0), Vec::<i32>::new());
+	assert_eq!(&array[..], &b.collect_vec(&distr, 50)[..]);
+
+	// Non-Copy types are supported
+	let strings: [String; 3] = a.collect_array(&distributions::Standard.map(|x: u8| x.to_string()));
+	assert!(strings.iter().all(|s| s.parse::<u8>().is_ok()));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_utf8_bytes() {
+	let mut rng = crate::seeded(42);
+	let mut widths = [0usize; 4];
+	for len in 0..=64 {
+		for _ in 0..100 {
+			let s = rng.utf8_bytes(len);
+			assert_eq!(s.len(), len);
+			assert_eq!(core::str::from_utf8(s.as_bytes()), Ok(s.as_str()));
+			for c in s.chars() {
+				widths[c.len_utf8() - 1] += 1;
+			}
+		}
+	}
+	// Every width is chosen with probability 1/4 when it fits
+	let total: usize = widths.iter().sum();
+	for &count in &widths[1..] {
+		assert!(count * 5 > total, "{:?}", widths);
+	}
+
+	// Multi-byte chars appear for every length of at least 4
+	for len in 4..=64 {
+		let multi = (0..100).filter(|_| !rng.utf8_bytes(len).is_ascii()).count();
+		assert!(multi > 50, "len: {} multi: {}", len, multi);
+	}
+
+	assert_eq!(rng.utf8_bytes(1).len(), 1);
+	assert!(rng.utf8_bytes(1).is_ascii());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_utf8_bytes_from() {
+	let mut rng = crate::seeded(42);
+	for len in 0..=64 {
+		for _ in 0..20 {
+			let s = rng.utf8_bytes_from(len, distributions::GraphicChar);
+			assert_eq!(s.len(), len);
+			assert!(s.chars().all(|c| !c.is_control()));
+		}
+	}
+	let s = rng.utf8_bytes_from(7, distributions::Alphanumeric);
+	assert_eq!(s.len(), 7);
+	assert!(s.chars().all(|c| c.is_ascii_alphanumeric()));
+}
+
+#[test]
+fn test_index_in() {
+	let mut rng = crate::seeded(42);
+	let mut counts = [0i32; 7];
+	const N: i32 = 70000;
+	for _ in 0..N {
+		let index = rng.index_in(13..20);
+		assert!((13..20).contains(&index), "index: {}", index);
+		counts[index - 13] += 1;
+	}
+	assert!(counts.iter().all(|&count| (count - N / 7).abs() < 400), "{:?}", counts);
+	assert_eq!(rng.index_in(5..6), 5);
+}
+
+#[test]
+#[should_panic]
+fn test_index_in_empty() {
+	crate::new().index_in(3..3);
+}
+
+#[test]
+fn test_priorities() {
+	let rng = crate::seeded(42);
+	let keys = ["treap", "skip", "list", "treap"];
+	let mut a = [0; 4];
+	rng.priorities(&keys, &mut a);
+	assert_eq!(a[0], a[3]);
+	assert_ne!(a[0], a[1]);
+
+	// Independent of the order of the keys
+	let reversed = ["treap", "list", "skip", "treap"];
+	let mut b = [0; 4];
+	rng.priorities(&reversed, &mut b);
+	assert_eq!((a[0], a[1], a[2]), (b[0], b[2], b[1]));
+
+	// Does not advance the generator
+	let mut mock = crate::rng::MockRng::slice(&[1, 2, 3]);
+	let mut out = [0; 2];
+	mock.priorities(&[10, 20], &mut out);
+	mock.priorities(&[10, 20], &mut out);
+	assert_eq!(mock.next_u64(), 1);
+	assert_eq!(mock.next_u64(), 2);
+	let mut again = [0; 2];
+	crate::rng::MockRng::slice(&[1]).priorities(&[20, 10], &mut again);
+	assert_eq!(out, [again[1], again[0]]);
+
+	// Advancing the generator draws fresh priorities
+	let mut rng = crate::seeded(42);
+	let _ = rng.next_u64();
+	let mut c = [0; 4];
+	rng.priorities(&keys, &mut c);
+	assert_ne!(a, c);
+}
+
+#[test]
+fn test_index_excluding() {
+	let mut rng = crate::new();
+	let shapes: [(usize, &[usize]); 6] = [
+		(10, &[]),
+		(10, &[0, 9]),
+		(10, &[1, 1, 2, 5, 5, 5, 8]),
+		(6, &[0, 1, 2, 3, 4, 7, 100]),
+		(40, &[3, 17, 17]),
+		(8, &[0, 2, 4, 6]),
+	];
+	for &(len, excluded) in &shapes {
+		let allowed = (0..len).filter(|index| !excluded.contains(index)).count();
+		let mut counts = [0i32; 40];
+		const N: i32 = 1000;
+		for _ in 0..N * allowed as i32 {
+			let index = rng.index_excluding(len, excluded).unwrap();
+			counts[index] += 1;
+		}
+		for index in 0..len {
+			if excluded.contains(&index) {
+				assert_eq!(counts[index], 0, "len: {} excluded: {:?}", len, excluded);
+			}
+			else {
+				assert!((counts[index] - N).abs() < 150, "len: {} excluded: {:?} counts: {:?}", len, excluded, &counts[..len]);
+			}
+		}
+	}
+
+	assert_eq!(rng.index_excluding(0, &[]), None);
+	assert_eq!(rng.index_excluding(3, &[0, 0, 1, 2, 3]), None);
+}
+
+#[test]
+#[should_panic]
+fn test_index_excluding_unsorted() {
+	crate::new().index_excluding(10, &[5, 2, 3, 4]);
+}
+
+#[test]
+fn test_below_bytes() {
+	let mut rng = crate::new();
+
+	// Small modulus with leading zero bytes covers all values
+	let modulus = [0, 0, 0, 5];
+	let mut seen = [false; 5];
+	for _ in 0..1000 {
+		let mut out = [0xff; 4];
+		rng.below_bytes(&modulus, &mut out);
+		assert_eq!(out[..3], [0, 0, 0]);
+		seen[out[3] as usize] = true;
+	}
+	assert_eq!(seen, [true; 5]);
+
+	// A modulus of one always returns zero
+	for _ in 0..100 {
+		let mut out = [0xff; 32];
+		rng.below_bytes(&[0, 1], &mut out[..2]);
+		assert_eq!(out[..2], [0, 0]);
+		rng.below_bytes(&[1; 1], &mut out[..1]);
+		assert_eq!(out[0], 0);
+	}
+
+	// Large modulus with the top bit set
+	let mut modulus = [0u8; 32];
+	modulus[0] = 0x80;
+	modulus[31] = 0x01;
+	let mut out = [0u8; 32];
+	for _ in 0..1000 {
+		rng.below_bytes(&modulus, &mut out);
+		assert!(out < modulus);
+	}
+}
+
+#[test]
+fn test_below_bytes_iterations() {
+	// Count the number of candidates drawn for the worst case modulus
+	struct Counting<R>(R, u32);
+	impl<R: Rng> Rng for Counting<R> {
+		fn next_u32(&mut self) -> u32 { self.0.next_u32() }
+		fn next_u64(&mut self) -> u64 { self.0.next_u64() }
+		fn fill_u32(&mut self, buffer: &mut [u32]) { self.0.fill_u32(buffer) }
+		fn fill_u64(&mut self, buffer: &mut [u64]) { self.0.fill_u64(buffer) }
+		fn fill_bytes(&mut self, buffer: &mut [u8]) { self.1 += 1; self.0.fill_bytes(buffer) }
+		fn jump(&mut self) { self.0.jump() }
+	}
+	let mut rng = Random(Counting(crate::rng::Xoshiro256::from_seed(7).0, 0));
+	let mut modulus = [0u8; 32];
+	modulus[0] = 0x80;
+	modulus[31] = 0x01;
+	let mut out = [0u8; 32];
+	const N: u32 = 10000;
+	for _ in 0..N {
+		rng.below_bytes(&modulus, &mut out);
+	}
+	let mean = rng.0.1 as f64 / N as f64;
+	assert!(mean < 2.1, "mean: {}", mean);
+}
+
+#[test]
+#[should_panic]
+fn test_below_bytes_zero() {
+	crate::new().below_bytes(&[0, 0], &mut [0, 0]);
+}
+
+#[test]
+fn test_dump_state() {
+	let rng = crate::rng::Xoshiro256::from_seed(42);
+	let dump = format!("{:?}", rng.dump_state());
+	assert_eq!(format!("{:?}", rng), "Random(impl Rng)");
+	assert!(dump.starts_with("Xoshiro256 { state: ["), "{}", dump);
+	assert_eq!(dump.matches(", ").count(), 3, "{}", dump);
+}
+
+#[test]
+fn test_derive() {
+	let mut rng = crate::rng::ChaCha20::new();
+	let expected = rng.derive(("job", 42)).next_u64();
+	for _ in 0..100 {
+		let _ = rng.next_u64();
+		assert_eq!(rng.derive(("job", 42)).next_u64(), expected);
+	}
+	assert_ne!(rng.derive(("job", 43)).next_u64(), expected);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_derangement() {
+	let mut rng = crate::new();
+	assert_eq!(rng.derangement(0), Some(Vec::new()));
+	assert_eq!(rng.derangement(1), None);
+	assert_eq!(rng.derangement(2), Some(vec![1, 0]));
+
+	// There are 9 derangements of 4 elements, encode them as base 4 numbers
+	let mut counts = std::collections::HashMap::new();
+	const N: i32 = 9000;
+	for _ in 0..N {
+		let derangement = rng.derangement(4).unwrap();
+		assert!(derangement.iter().enumerate().all(|(i, &j)| i != j), "{:?}", derangement);
+		let key = derangement.iter().fold(0, |acc, &j| acc * 4 + j);
+		*counts.entry(key).or_insert(0) += 1;
+	}
+	assert_eq!(counts.len(), 9);
+	assert!(counts.values().all(|&count| (count - N / 9).abs() < 150), "{:?}", counts);
+
+	for n in 2..50 {
+		let derangement = rng.derangement(n).unwrap();
+		assert!(derangement.iter().enumerate().all(|(i, &j)| i != j), "{:?}", derangement);
+	}
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_apply_permutation() {
+	let mut rng = crate::new();
+	let original: Vec<i32> = (100..200).collect();
+	let mut shuffled = original.clone();
+	let permutation = rng.apply_permutation(&mut shuffled);
+	for i in 0..original.len() {
+		assert_eq!(shuffled[i], original[permutation[i]]);
+	}
+	// Invert the permutation to restore the original order
+	let mut restored = vec![0; original.len()];
+	for i in 0..original.len() {
+		restored[permutation[i]] = shuffled[i];
+	}
+	assert_eq!(restored, original);
+}
+
+#[test]
+fn test_shuffle_tail() {
+	let mut rng = crate::new();
+	let mut counts = [0i32; 256];
+	const N: i32 = 24000;
+	for _ in 0..N {
+		let mut queue = [0u8; 4];
+		for i in 0..4 {
+			queue[i] = i as u8;
+			rng.shuffle_tail(&mut queue[..i + 1], i);
+		}
+		let key = queue.iter().fold(0, |acc, &x| acc * 4 + x as usize);
+		counts[key] += 1;
+	}
+	// All 24 permutations are equally likely
+	let permutations = counts.iter().filter(|&&count| count > 0).count();
+	assert_eq!(permutations, 24);
+	assert!(counts.iter().all(|&count| count == 0 || (count - N / 24).abs() < 150), "{:?}", counts);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_shuffled() {
+	let mut rng = crate::seeded(42);
+	let original = [0u8, 1, 2, 3];
+	let mut counts = [0i32; 256];
+	let mut counts_copy = [0i32; 256];
+	const N: i32 = 48000;
+	for _ in 0..N {
+		let shuffled = rng.shuffled(&original);
+		let mut sorted = shuffled.clone();
+		sorted.sort();
+		assert_eq!(sorted, original);
+		counts[shuffled.iter().fold(0, |acc, &x| acc * 4 + x as usize)] += 1;
+
+		let shuffled = rng.shuffle_copy(&original);
+		counts_copy[shuffled.iter().fold(0, |acc, &x| acc * 4 + x as usize)] += 1;
+	}
+	for counts in [&counts, &counts_copy] {
+		// All 24 permutations are equally likely, in particular the identity permutation is not over-represented
+		let permutations = counts.iter().filter(|&&count| count > 0).count();
+		assert_eq!(permutations, 24);
+		assert!(counts.iter().all(|&count| count == 0 || (count - N / 24).abs() < 200), "{:?}", counts);
+		let identity = counts[0b00_01_10_11];
+		assert!((identity - N / 24).abs() < 200, "identity: {}", identity);
+	}
+
+	assert_eq!(rng.shuffled::<i32>(&[]), Vec::<i32>::new());
+	assert_eq!(rng.shuffled(&[String::from("a")]), [String::from("a")]);
+}
+
+#[test]
+fn test_shuffle_copy_small() {
+	let mut rng = crate::new();
+	assert_eq!(rng.shuffle_copy::<i32, 0>(&[]), []);
+	assert_eq!(rng.shuffle_copy(&[7]), [7]);
+}
+
+#[test]
+fn test_round() {
+	let mut rng = crate::seeded(42);
+	const N: usize = 200000;
+	for &x in &[0.25, 0.5, 0.75, 0.1, 0.999, 3.125, -0.25, -0.5, -0.75, -7.9, 1e6 + 0.5] {
+		let mut sum = 0i64;
+		let mut sum_f32 = 0i64;
+		let src = [x; 100];
+		let mut out = [0; 100];
+		let mut sum_slice = 0i64;
+		for _ in 0..N {
+			let value = rng.round(x);
+			assert!(value as f64 == x.floor() || value as f64 == x.ceil(), "x: {} value: {}", x, value);
+			sum += value;
+			sum_f32 += rng.round_f32(x as f32) as i64;
+		}
+		for _ in 0..N / 100 {
+			rng.round_slice(&src, &mut out);
+			sum_slice += out.iter().sum::<i64>();
+		}
+		// The standard deviation of the mean is at most 0.5 / sqrt(N)
+		for sum in [sum, sum_f32, sum_slice] {
+			let mean = sum as f64 / N as f64;
+			assert!((mean - x).abs() < 0.005, "x: {} mean: {}", x, mean);
+		}
+	}
+
+	// Negative values are symmetric
+	let positive: i64 = (0..N).map(|_| rng.round(2.3)).sum();
+	let negative: i64 = (0..N).map(|_| rng.round(-2.3)).sum();
+	assert!(((positive + negative) as f64 / N as f64).abs() < 0.005, "positive: {} negative: {}", positive, negative);
+}
+
+#[test]
+fn test_round_edges() {
+	// Integral values do not consume randomness
+	let mut rng = crate::rng::MockRng::slice(&[]);
+	assert_eq!(rng.round(-3.0), -3);
+	assert_eq!(rng.round(-0.0), 0);
+	assert_eq!(rng.round_f32(16777216.0), 16777216);
+	assert_eq!(rng.round(1e300), i64::MAX);
+	assert_eq!(rng.round(f64::NEG_INFINITY), i64::MIN);
+	assert_eq!(rng.round(9007199254740993.0), 9007199254740992);
+	let mut out = [0; 3];
+	rng.round_slice(&[1.0, -2.0, 1e20], &mut out);
+	assert_eq!(out, [1, -2, i64::MAX]);
+
+	// Two values per draw
+	let mut rng = crate::rng::MockRng::slice(&[0x7fffffff_80000000]);
+	let mut out = [0; 5];
+	rng.round_slice(&[0.5, 4.0, 0.5, 2.0], &mut out[..4]);
+	assert_eq!(out[..4], [0, 4, 1, 2]);
+}
+
+#[test]
+#[should_panic(expected = "round called with NaN")]
+fn test_round_nan() {
+	crate::new().round(f64::NAN);
+}
+
+#[test]
+#[should_panic(expected = "swap_random index (is 3) should be < len (is 3)")]
+fn test_swap_random_oob() {
+	let mut rng = crate::new();
+	rng.swap_random(&mut [1, 2, 3], 3);
+}
+
+#[test]
+#[should_panic(expected = "shuffle_tail start (is 4) should be <= len (is 3)")]
+fn test_shuffle_tail_oob() {
+	let mut rng = crate::new();
+	rng.shuffle_tail(&mut [1, 2, 3], 4);
+}
+
+#[test]
+fn test_window() {
+	let mut rng = crate::seeded(42);
+	let data: Vec<u32> = (0..10).collect();
+	let mut counts = [0u32; 8];
+	for _ in 0..80000 {
+		let window = rng.window(&data, 3).unwrap();
+		assert_eq!(window.len(), 3);
+		assert_eq!(window[2], window[0] + 2);
+		counts[window[0] as usize] += 1;
+	}
+	for &count in &counts {
+		assert!(count > 9500 && count < 10500, "{:?}", counts);
+	}
+
+	// Edge cases
+	assert_eq!(rng.window(&data, 0), Some(&[][..]));
+	assert_eq!(rng.window(&data, 10), Some(&data[..]));
+	assert_eq!(rng.window(&data, 11), None);
+	assert_eq!(rng.window::<u32>(&[], 0), Some(&[][..]));
+	let mut data = data;
+	rng.window_mut(&mut data, 10).unwrap().fill(0);
+	assert!(data.iter().all(|&x| x == 0));
+	assert!(rng.window_mut(&mut data, 11).is_none());
+}
+
+#[test]
+fn test_subrange() {
+	let mut rng = crate::seeded(42);
+	for len in 0..6 {
+		// Exhaustively count every subrange
+		let total = (len + 1) * (len + 2) / 2;
+		let mut counts = vec![0u32; (len + 1) * (len + 1)];
+		let n = 2000 * total as u32;
+		for _ in 0..n {
+			let range = rng.subrange(len);
+			assert!(range.start <= range.end && range.end <= len);
+			counts[range.start * (len + 1) + range.end] += 1;
+		}
+		for start in 0..=len {
+			for end in 0..=len {
+				let count = counts[start * (len + 1) + end];
+				if start > end {
+					assert_eq!(count, 0);
+				}
+				else {
+					assert!(count > 1800 && count < 2200, "len: {} range: {}..{} count: {}", len, start, end, count);
+				}
+			}
+		}
+	}
+	assert_eq!(rng.subrange(0), 0..0);
+	for _ in 0..100 {
+		let range = rng.subrange(usize::MAX);
+		assert!(range.start <= range.end);
+	}
+}
+
+#[test]
+fn test_choose() {
+	let mut rng = crate::new();
+
+	let mut array = [0, 1, 2, 3, 4];
+	let mut result = [0i32; 5];
+
+	for _ in 0..10000 {
+		result[*rng.choose(&array).unwrap()] += 1;
+		result[*rng.choose_mut(&mut array).unwrap()] += 1;
+	}
+
+	let mean = (result[0] + result[1] + result[2] + result[3] + result[4]) / 5;
+	let success = result.iter().all(|&x| (x - mean).abs() < 500);
+	assert!(success, "mean: {}, result: {:?}", mean, result);
+}
+
+#[test]
+fn test_stratified_1d() {
+	let mut rng = crate::new();
+	for n in [0, 1, 2, 3, 10, 1000] {
+		let mut samples = vec![0.0; n];
+		rng.stratified_1d(&mut samples);
+		samples.sort_by(|a, b| a.partial_cmp(b).unwrap());
+		for (i, &x) in samples.iter().enumerate() {
+			assert_eq!((x * n as f64) as usize, i, "x: {} n: {}", x, n);
+		}
+	}
+	// Samples are uniform within their stratum
+	let mut sum = 0.0;
+	for _ in 0..10000 {
+		let mut sample = [0.0];
+		rng.stratified_1d(&mut sample);
+		sum += sample[0];
+	}
+	assert!((sum / 10000.0 - 0.5).abs() < 0.02, "mean: {}", sum / 10000.0);
+	// Largest jitter stays inside the last stratum
+	let mut rng = crate::rng::MockRng::repeat(!0);
+	let mut samples = [0.0; 3];
+	rng.stratified_1d(&mut samples);
+	assert!(samples.iter().all(|&x| x < 1.0), "{:?}", samples);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_latin_hypercube() {
+	let mut rng = crate::new();
+	assert!(rng.latin_hypercube(3, 0).is_empty());
+	assert_eq!(rng.latin_hypercube(0, 5), vec![Vec::<f64>::new(); 5]);
+
+	let n = 100;
+	let points = rng.latin_hypercube(4, n);
+	assert_eq!(points.len(), n);
+	for d in 0..4 {
+		let mut column: Vec<f64> = points.iter().map(|point| point[d]).collect();
+		column.sort_by(|a, b| a.partial_cmp(b).unwrap());
+		for (i, &x) in column.iter().enumerate() {
+			assert!(x >= i as f64 / n as f64 && x < (i + 1) as f64 / n as f64, "dim: {} x: {}", d, x);
+		}
+	}
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_multiple_weighted() {
+	let mut rng = crate::seeded(42);
+	const K: usize = 10;
+	const TRIALS: usize = 20000;
+	let weight = |&x: &usize| (x + 1) as f64;
+
+	// Reference: repeatedly pick an element proportional to its weight from the remaining elements
+	let mut expected = [0.0; 100];
+	for _ in 0..TRIALS {
+		let mut remaining: Vec<usize> = (0..100).collect();
+		for _ in 0..K {
+			let total: f64 = remaining.iter().map(weight).sum();
+			let mut target = rng.range(0.0..total);
+			let mut pos = remaining.len() - 1;
+			for (i, x) in remaining.iter().enumerate() {
+				target -= weight(x);
+				if target < 0.0 {
+					pos = i;
+					break;
+				}
+			}
+			expected[remaining.swap_remove(pos)] += 1.0;
+		}
+	}
+
+	let mut observed = [0.0; 100];
+	for _ in 0..TRIALS {
+		let mut buffer = [0; K];
+		assert_eq!(rng.multiple_weighted(0..100, weight, &mut buffer), K);
+		let mut sorted = buffer;
+		sorted.sort();
+		assert!(sorted.windows(2).all(|w| w[0] != w[1]), "{:?}", buffer);
+		for &x in &buffer {
+			observed[x] += 1.0;
+		}
+	}
+
+	for i in 0..100 {
+		let (e, o) = (expected[i] / TRIALS as f64, observed[i] / TRIALS as f64);
+		assert!((e - o).abs() < 0.02, "item: {} expected: {} observed: {}", i, e, o);
+	}
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_multiple_weighted_edges() {
+	let mut rng = crate::new();
+	let mut buffer = [0; 5];
+	assert_eq!(rng.multiple_weighted(0..3, |_| 1.0, &mut buffer), 3);
+	let mut sorted = buffer[..3].to_vec();
+	sorted.sort();
+	assert_eq!(sorted, [0, 1, 2]);
+	assert_eq!(rng.multiple_weighted(0..3, |_| 1.0, &mut []), 0);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_multiple_weighted_jumps() {
+	let mut source = crate::seeded(42);
+	let calls = core::cell::Cell::new(0);
+	let mut rng = Random(crate::rng::MockRng(core::iter::from_fn(|| {
+		calls.set(calls.get() + 1);
+		Some(source.next_u64())
+	})));
+	let mut buffer = [0; 10];
+	rng.multiple_weighted(0..100000, |_| 1.0, &mut buffer);
+	let calls = calls.get();
+	// Every key and jump takes two calls, there are about k ln(n/k) replacements
+	assert!(calls < 1000, "calls: {}", calls);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_decode_pair() {
+	let encode = |(i, j): (usize, usize)| (j * (j - 1) / 2 + i) as u128;
+	for index in 0..1000 {
+		let (i, j) = decode_pair(index);
+		assert!(i < j);
+		assert_eq!(encode((i, j)), index);
+	}
+	// Indices near the triangular numbers of large n
+	for j in [1u128 << 16, 1 << 24, (1 << 32) - 1, 1 << 32, 6_000_000_000] {
+		let start = j * (j - 1) / 2;
+		for index in [start - 1, start, start + 1, start + j - 1, start + j] {
+			let (a, b) = decode_pair(index);
+			assert!(a < b);
+			assert_eq!(b as u128 * (b as u128 - 1) / 2 + a as u128, index);
+		}
+	}
+}
+
+#[test]
+fn test_decode_triangular() {
+	for index in 0..1000 {
+		let (i, j) = decode_triangular(index);
+		assert!(i <= j);
+		assert_eq!(triangular(j as u128) + i as u128, index);
+	}
+	// The last ranges of usize::MAX
+	let last = u64::MAX as u128;
+	for index in [triangular(last) - 1, triangular(last), triangular(last) + last] {
+		let (i, j) = decode_triangular(index);
+		assert!(i <= j);
+		assert_eq!(triangular(j as u128) + i as u128, index);
+	}
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_pairs() {
+	let mut rng = crate::seeded(42);
+	assert!(rng.pairs(0, 0).is_empty());
+	assert!(rng.pairs(1, 0).is_empty());
+	let mut all = rng.pairs(5, 10);
+	all.sort();
+	assert_eq!(all, [(0, 1), (0, 2), (0, 3), (0, 4), (1, 2), (1, 3), (1, 4), (2, 3), (2, 4), (3, 4)]);
+
+	let pairs = rng.pairs(100_000, 1000);
+	let distinct: std::collections::HashSet<_> = pairs.iter().collect();
+	assert_eq!(distinct.len(), 1000);
+	assert!(pairs.iter().all(|&(i, j)| i < j && j < 100_000));
+
+	// Nearly all pairs of usize::MAX elements have an index past 2⁶⁴
+	#[cfg(target_pointer_width = "64")]
+	{
+		let pairs = rng.pairs(usize::MAX, 1000);
+		assert!(pairs.iter().all(|&(i, j)| i < j && j < usize::MAX));
+		assert!(pairs.iter().filter(|&&(_, j)| j > 1 << 33).count() > 990);
+	}
+
+	// All 15 pairs of 6 elements are equally likely
+	let mut counts = [[0i32; 6]; 6];
+	const N: i32 = 15000;
+	for _ in 0..N / 3 {
+		for (i, j) in rng.pairs(6, 3) {
+			counts[i][j] += 1;
+		}
+	}
+	for j in 0..6 {
+		for i in 0..j {
+			assert!((counts[i][j] - N / 15).abs() < 100, "{:?}", counts);
+		}
+	}
+}
+
+#[cfg(feature = "std")]
+#[test]
+#[should_panic]
+fn test_pairs_too_many() {
+	crate::new().pairs(4, 7);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_matching() {
+	let mut rng = crate::seeded(42);
+	assert!(rng.matching(0).is_empty());
+	assert_eq!(rng.matching(2), [(0, 1)]);
+	// There are 3 perfect matchings of 4 elements, identified by the partner of 0
+	let mut counts = [0i32; 4];
+	const N: i32 = 3000;
+	for _ in 0..N {
+		let matching = rng.matching(4);
+		let mut seen = [false; 4];
+		for &(i, j) in &matching {
+			assert!(i < j && !seen[i] && !seen[j]);
+			seen[i] = true;
+			seen[j] = true;
+		}
+		let partner = matching.iter().find(|&&(i, _)| i == 0).unwrap().1;
+		counts[partner] += 1;
+	}
+	assert_eq!(counts[0], 0);
+	assert!(counts[1..].iter().all(|&count| (count - N / 3).abs() < 100), "{:?}", counts);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+#[should_panic]
+fn test_matching_odd() {
+	crate::new().matching(5);
+}
+
+#[test]
+fn test_mask() {
+	let mut rng = crate::new();
+	let mut mask = [false; 20];
+	let mut counts = [0i32; 20];
+	const N: i32 = 20000;
+	for _ in 0..N {
+		rng.mask(&mut mask, 5);
+		assert_eq!(mask.iter().filter(|&&set| set).count(), 5);
+		for (count, &set) in counts.iter_mut().zip(&mask) {
+			*count += set as i32;
+		}
+	}
+	assert!(counts.iter().all(|&count| (count - N / 4).abs() < 300), "{:?}", counts);
+
+	rng.mask(&mut mask, 0);
+	assert_eq!(mask, [false; 20]);
+	rng.mask(&mut mask, 20);
+	assert_eq!(mask, [true; 20]);
+	rng.mask(&mut [], 0);
+}
+
+#[test]
+fn test_mask_bits() {
+	let mut rng = crate::new();
+	let mut bits = [!0u64; 3];
+	let mut counts = [0i32; 130];
+	const N: i32 = 10000;
+	for _ in 0..N {
+		rng.mask_bits(&mut bits, 130, 13);
+		assert_eq!(bits.iter().map(|word| word.count_ones()).sum::<u32>(), 13);
+		assert_eq!(bits[2] >> 2, 0);
+		for (i, count) in counts.iter_mut().enumerate() {
+			*count += (bits[i / 64] >> (i % 64) & 1) as i32;
+		}
+	}
+	assert!(counts.iter().all(|&count| (count - N / 10).abs() < 150), "{:?}", counts);
+
+	rng.mask_bits(&mut bits, 130, 130);
+	assert_eq!(bits, [!0, !0, 3]);
+	rng.mask_bits(&mut bits, 64, 0);
+	assert_eq!(bits, [0; 3]);
+}
+
+#[test]
+#[should_panic]
+fn test_mask_too_many() {
+	crate::new().mask(&mut [false; 4], 5);
+}
+
+#[test]
+#[should_panic]
+fn test_mask_bits_too_short() {
+	crate::new().mask_bits(&mut [0; 2], 129, 1);
+}
+
+#[test]
+fn test_bitplane() {
+	let mut rng = crate::seeded(42);
+	let mut bits = [0u64; 4096];
+	for log2 in [3, 4] {
+		for numerator in 1..1u32 << log2 {
+			rng.bitplane(&mut bits, numerator, log2);
+			let ones: u32 = bits.iter().map(|word| word.count_ones()).sum();
+			// 262144 bits, the standard deviation is at most 256
+			let expected = (bits.len() * 64) as f64 * numerator as f64 / (1u32 << log2) as f64;
+			assert!((ones as f64 - expected).abs() < 1500.0, "{}/2^{}: {} ones, expected {}", numerator, log2, ones, expected);
+		}
+	}
+
+	// Exact probabilities consume no randomness
+	let mut rng = crate::seeded(42).counting();
+	rng.bitplane(&mut bits, 0, 4);
+	assert!(bits.iter().all(|&word| word == 0));
+	rng.bitplane(&mut bits, 16, 4);
+	assert!(bits.iter().all(|&word| word == !0));
+	rng.bitplane(&mut bits, 1, 0);
+	assert!(bits.iter().all(|&word| word == !0));
+	assert_eq!(rng.0.stats().bytes, 0);
+
+	// The minimal number of planes is consumed
+	for (numerator, log2, planes) in [(1, 1, 1), (3, 3, 3), (4, 3, 1), (6, 4, 3), (5, 4, 4), (1, 32, 32)] {
+		rng.0.reset_stats();
+		rng.bitplane(&mut bits[..10], numerator, log2);
+		assert_eq!(rng.0.stats().next_u64, 10 * planes, "{}/2^{}", numerator, log2);
+		assert_eq!(rng.0.stats().bytes, 80 * planes);
+	}
+	rng.0.reset_stats();
+	rng.coin_flips(&mut bits[..10]);
+	assert_eq!(rng.0.stats().bytes, 80);
+
+	// The half probability matches the coin flips
+	let mut a = crate::seeded(7);
+	let mut b = a.clone();
+	let mut x = [0u64; 8];
+	let mut y = [0u64; 8];
+	a.bitplane(&mut x, 1, 1);
+	b.coin_flips(&mut y);
+	assert_eq!(x, y);
+}
+
+#[test]
+#[should_panic]
+fn test_bitplane_invalid() {
+	crate::new().bitplane(&mut [0; 2], 9, 3);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_partition() {
+	let mut rng = crate::new();
+	const N: usize = 100;
+	const TRIALS: usize = 10000;
+	let p = 0.3;
+	let mut sum = 0.0;
+	let mut sum_sq = 0.0;
+	for _ in 0..TRIALS {
+		let (left, right) = rng.partition((0..N).collect(), p);
+		assert_eq!(left.len() + right.len(), N);
+		assert!(left.windows(2).all(|pair| pair[0] < pair[1]));
+		assert!(right.windows(2).all(|pair| pair[0] < pair[1]));
+		let len = left.len() as f64;
+		sum += len;
+		sum_sq += len * len;
+	}
+	// Binomial(n, p) has mean np and variance np(1 - p)
+	let mean = sum / TRIALS as f64;
+	let variance = sum_sq / TRIALS as f64 - mean * mean;
+	assert!((mean - 30.0).abs() < 0.2, "mean: {}", mean);
+	assert!((variance - 21.0).abs() < 1.0, "variance: {}", variance);
+
+	assert_eq!(rng.partition(vec![1, 2, 3], 1.0), (vec![1, 2, 3], vec![]));
+	assert_eq!(rng.partition(vec![1, 2, 3], 0.0), (vec![], vec![1, 2, 3]));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_split_exact() {
+	let mut rng = crate::new();
+	let mut counts = [0i32; 10];
+	const TRIALS: i32 = 10000;
+	for _ in 0..TRIALS {
+		let (left, right) = rng.split_exact((0..10).collect::<Vec<usize>>(), 3);
+		assert_eq!((left.len(), right.len()), (3, 7));
+		assert!(left.windows(2).all(|pair| pair[0] < pair[1]));
+		assert!(right.windows(2).all(|pair| pair[0] < pair[1]));
+		for &i in &left {
+			counts[i] += 1;
+		}
+	}
+	assert!(counts.iter().all(|&count| (count - TRIALS * 3 / 10).abs() < 200), "{:?}", counts);
+
+	assert_eq!(rng.split_exact(vec![1, 2, 3], 3), (vec![1, 2, 3], vec![]));
+	assert_eq!(rng.split_exact(vec![1, 2, 3], 0), (vec![], vec![1, 2, 3]));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+#[should_panic]
+fn test_split_exact_too_many() {
+	crate::new().split_exact(vec![1, 2, 3], 4);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "Rng::next_f64 returned 0.5 outside [1.0, 2.0)")]
+fn test_float_contract() {
+	// A float which violates the interval of the Rng contract
+	struct BadFloat;
+	impl Rng for BadFloat {
+		fn next_u32(&mut self) -> u32 { 0 }
+		fn next_u64(&mut self) -> u64 { 0 }
+		fn next_f64(&mut self) -> f64 { 0.5 }
+		fn fill_u32(&mut self, _: &mut [u32]) {}
+		fn fill_u64(&mut self, _: &mut [u64]) {}
+		fn fill_bytes(&mut self, _: &mut [u8]) {}
+		fn jump(&mut self) {}
+	}
+	Random(BadFloat).next_f64();
 }
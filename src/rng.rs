@@ -14,6 +14,14 @@ These generators implement fast PRNG which are suitable for normal use in non-cr
 
   Kindly taken from [Sebastiano Vigna](http://vigna.di.unimi.it/)'s excellent [PRNG shootout](http://prng.di.unimi.it/) article.
 
+* [`Xoroshiro128pp`](Xoroshiro128pp) and [`Xoroshiro128p`](Xoroshiro128p) Rngs:
+
+  Small-state variants of Xoshiro256 from the same authors, for applications which embed many generators.
+
+* [`Philox`](Philox) Rng:
+
+  Counter-based Philox4x32-10 generator from the [Random123](https://www.thesalmons.org/john/random123/) library, bit-exact with its GPU implementations.
+
 Cryptographically secure generators
 -----------------------------------
 
@@ -23,6 +31,54 @@ These generators implement suitable CSPRNG implementations without calling out t
 
   Daniel J. Bernstein's ChaCha20 adapted as a deterministic random number generator.
 
+Wrappers
+--------
+
+* `ForkGuard` Rng:
+
+  Reseeds the inner generator from system entropy after the process forks (std, unix only).
+
+* [`ByteSwapped`](ByteSwapped) and [`Lagged`](Lagged) Rngs:
+
+  Reorder the bytes and words of the inner generator to reproduce streams recorded by other-endian tools.
+
+* [`Conditioned`](Conditioned) Rng:
+
+  Compresses a raw entropy source of unknown quality through the ChaCha20 block function at a 2:1 ratio.
+
+* [`Recorder`](Recorder) and [`Replayer`](Replayer) Rngs:
+
+  Record every value produced by a generator and replay them later, detecting where the calls diverge (alloc only).
+
+Implementing Rng
+----------------
+
+The [`Rng`](Rng) trait documents the contract every implementation must uphold, eg. the interval of the floats and the agreement of the fill methods.
+[`verify`](verify) checks a generator against the contract and reports the first violation (std only).
+
+Jumping
+-------
+
+[`Random::split`](Random::split) relies on [`Rng::jump`](Rng::jump) to hand out independent generators.
+Generators report how well they support this with [`Rng::jump_support`](Rng::jump_support):
+
+* Strong: [`Xoshiro256`](Xoshiro256), [`Xoroshiro128pp`](Xoroshiro128pp) and [`Xoroshiro128p`](Xoroshiro128p) jump ahead with their jump polynomials by 2¹²⁸ and 2⁶⁴ outputs,
+  [`ChaCha20`](ChaCha20) derives a new key, [`Philox`](Philox) advances the high word of its counter by 2⁹⁶ blocks.
+  The system entropy source produces independent output without jumping.
+
+* Weak: [`SplitMix64`](SplitMix64) and the [`SeedSeq`](SeedSeq) streams advance their counter, the streams are offset copies of the same sequence.
+
+* None: [`ReadRng`](ReadRng), [`MockRng`](MockRng) and [`Replayer`](Replayer) ignore jumps, use [`Random::try_split`](Random::try_split) to detect them.
+
+The wrappers report the strength of their inner generator.
+
+Entropy
+-------
+
+* [`getentropy`](getentropy) provides cryptographically secure entropy from the system, [`try_getentropy`](try_getentropy) reports failure instead of panicking.
+
+* [`Sealed`](Sealed) derives all randomness from a single entropy pull at startup and detects later use of the system entropy source.
+
 */
 
 #![allow(unsafe_code)]
@@ -41,6 +97,7 @@ pub trait Rng {
 	///
 	/// As only 23 bits are necessary to construct a random float in this range,
 	/// implementations may override this method to provide a more efficient implementation.
+	/// Implementations are required to return values in exactly this interval, the float distributions rely on it.
 	///
 	/// The default implementation simply gets its random bits from `next_u32`.
 	fn next_f32(&mut self) -> f32 {
@@ -51,6 +108,7 @@ pub trait Rng {
 	///
 	/// As only 52 bits are necessary to construct a random double in this range,
 	/// implementations may override this method to provide a more efficient implementation.
+	/// Implementations are required to return values in exactly this interval, the float distributions rely on it.
 	///
 	/// The default implementation simply gets its random bits from `next_u64`.
 	fn next_f64(&mut self) -> f64 {
@@ -62,10 +120,15 @@ pub trait Rng {
 	/// Implementations are not required to implement this method with `next_u32`.
 	/// This may produce distinct values compared to filling naively with `next_u32`.
 	///
-	/// Implementations are required to produce the same result regardless of endianness.
+	/// Implementations are required to produce the same words as [`fill_bytes`](Rng::fill_bytes) filling the same number of bytes,
+	/// read as little-endian words, and to leave the generator in the same state.
+	/// This is a fast path for callers which want native words without converting bytes.
 	fn fill_u32(&mut self, buffer: &mut [u32]);
 
 	/// Fills the next `u64` elements in the sequence.
+	///
+	/// Implementations are required to produce the same words as [`fill_bytes`](Rng::fill_bytes) filling the same number of bytes,
+	/// read as little-endian words, and to leave the generator in the same state.
 	fn fill_u64(&mut self, buffer: &mut [u64]);
 
 	/// Fills the byte slice with uniform random bytes.
@@ -76,9 +139,45 @@ pub trait Rng {
 	/// Advances the internal state significantly.
 	///
 	/// Useful to produce deterministic independent random number generators for parallel computation.
+	/// See [`jump_support`](Rng::jump_support) for how independent the resulting generators are.
 	fn jump(&mut self);
+
+	/// Returns how well [`jump`](Rng::jump) separates a generator from its clone.
+	///
+	/// The default implementation returns [`JumpStrength::Weak`](JumpStrength::Weak).
+	/// Implementations are required to report the strength accurately, wrappers forward the strength of the inner generator.
+	#[inline]
+	fn jump_support(&self) -> JumpStrength {
+		JumpStrength::Weak
+	}
+}
+
+/// How well [`Rng::jump`](Rng::jump) separates a generator from its clone.
+///
+/// See the [module documentation](self#jumping) for the strength of every generator.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum JumpStrength {
+	/// The jumped streams never overlap in practice, eg. a jump polynomial or a fresh key.
+	Strong,
+	/// The jumped stream is the same sequence at an offset, the streams overlap after a modest number of outputs.
+	Weak,
+	/// Jumping does nothing, a clone and the jumped generator produce identical output.
+	None,
+}
+
+/// Error type returned from [`Random::try_split`](Random::try_split) for generators which cannot jump.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct JumpError;
+
+impl core::fmt::Display for JumpError {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		f.write_str("the generator does not support jumping")
+	}
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for JumpError {}
+
 /// Constructors for deterministic random number generators.
 pub trait SeedRng: Sized {
 	/// Creates a new instance seeded securely from system entropy.
@@ -88,7 +187,25 @@ pub trait SeedRng: Sized {
 	/// # Panics
 	///
 	/// If [`getentropy`](getentropy) is unable to provide secure entropy this method will panic.
-	fn new() -> Random<Self>;
+	#[inline]
+	fn new() -> Random<Self> {
+		match Self::try_new() {
+			Ok(rng) => rng,
+			Err(err) => entropy::getentropy_error(err),
+		}
+	}
+
+	/// Creates a new instance seeded securely from system entropy.
+	///
+	/// Returns an error if [`try_getentropy`](try_getentropy) is unable to provide secure entropy.
+	///
+	/// The default implementation seeds the generator [`from_rng`](SeedRng::from_rng) a [`ChaCha20`](ChaCha20) keyed with 32 bytes of system entropy.
+	#[inline]
+	fn try_new() -> Result<Random<Self>, EntropyError> {
+		let mut seed = [0u8; 32];
+		try_getentropy(&mut seed)?;
+		Ok(Self::from_rng(&mut ChaCha20::from_seed_bytes(seed)))
+	}
 
 	/// Creates a new PRNG seeded from another `Rng`.
 	///
@@ -109,6 +226,101 @@ pub trait SeedRng: Sized {
 	fn from_seed(seed: u64) -> Random<Self>;
 }
 
+/// Marker trait for cryptographically secure random number generators.
+///
+/// Implemented by [`ChaCha20`](ChaCha20) and by wrappers which do not alter the output of a secure generator.
+/// Algorithms which must not leak timing information about their secret outputs, such as [`Random::uniform_secure`](Random::uniform_secure), require this trait.
+pub trait SecureRng: Rng {}
+
+/// Error type returned from the `from_state` constructors of the generators.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum StateError {
+	/// The all-zero state is invalid for this generator.
+	AllZero,
+	/// The position within the buffered block is out of range.
+	IndexOutOfRange,
+}
+
+impl core::fmt::Display for StateError {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		f.write_str(match self {
+			StateError::AllZero => "the all-zero state is invalid for this generator",
+			StateError::IndexOutOfRange => "the block index is out of range",
+		})
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for StateError {}
+
+/// Random number generators which can derive child generators keyed by a value.
+///
+/// The child generator depends only on the key and the seed material of the parent,
+/// not on how many values the parent has generated. This enables rendezvous-style choices
+/// where the same key must pick the same random outcome no matter where in the stream the parent is.
+///
+/// Only generators whose seed material is not consumed while generating values can implement this trait.
+/// PRNGs such as [`Xoshiro256`](Xoshiro256) and [`SplitMix64`](SplitMix64) advance their entire state with every value
+/// and retain no record of their seed, they do not implement this trait.
+///
+/// [`ChaCha20`](ChaCha20) keeps its key separate from its block counter and implements this trait.
+pub trait DeriveRng: Rng + Sized {
+	/// Derives a child generator from the parent's seed material and the given hashed key.
+	///
+	/// Implementations are required to be reproducible given the same seed material and key.
+	fn derive(&self, key: u64) -> Self;
+}
+
+/// Random number generators which can mix additional entropy into their running state.
+///
+/// Mixing changes all future outputs without resetting the generator, eg. a long-lived server can fold in fresh entropy
+/// to stop observers from predicting future values after observing many outputs.
+/// Mixing is deterministic: identical generators which mix the same bytes remain identical, preserving reproducibility.
+///
+/// This provides prediction resistance going forward only to the extent that the mixed bytes are unknown to the observer.
+/// The non-cryptographic generators provide no backtracking resistance: their state update is invertible,
+/// an observer who learns the state after mixing can recover earlier outputs.
+///
+/// See [`Random::mix_entropy`](Random::mix_entropy).
+pub trait MixRng: Rng {
+	/// Mixes the bytes into the state of the generator.
+	///
+	/// Mixing an empty slice does nothing. Implementations are required to be reproducible given the same state and bytes.
+	fn mix(&mut self, bytes: &[u8]);
+}
+
+// Compresses the bytes into N well mixed words to be XORed into the state of a generator
+#[inline]
+pub(crate) fn mix_lanes<const N: usize>(bytes: &[u8]) -> [u64; N] {
+	const GOLDEN_GAMMA: u64 = 0x9e3779b97f4a7c15;
+	let mut lanes: [u64; N] = core::array::from_fn(|k| (k as u64 + 1).wrapping_mul(GOLDEN_GAMMA));
+	for (i, chunk) in bytes.chunks(8).enumerate() {
+		let mut word = [0u8; 8];
+		word[..chunk.len()].copy_from_slice(chunk);
+		let lane = &mut lanes[i % N];
+		*lane = crate::hash::mix64(*lane ^ u64::from_le_bytes(word)).wrapping_add(GOLDEN_GAMMA);
+	}
+	// The length distinguishes inputs which differ only by trailing zero bytes
+	let len = (bytes.len() as u64).wrapping_mul(GOLDEN_GAMMA);
+	for lane in &mut lanes {
+		*lane = crate::hash::mix64(*lane ^ len);
+	}
+	lanes
+}
+
+/// Random number generators which can be seeded from a [`SeedSeq`](SeedSeq).
+///
+/// The default implementation seeds the generator with [`from_rng`](SeedRng::from_rng) from the seed material of the seed sequence.
+pub trait FromSeedSeq: SeedRng {
+	/// Creates a new PRNG seeded from the seed sequence.
+	///
+	/// Implementations are required to be reproducible given the same seed sequence.
+	#[inline]
+	fn from_seed_seq(seq: &SeedSeq) -> Random<Self> {
+		Self::from_rng(&mut seq.stream())
+	}
+}
+
 macro_rules! forward_seed_rng_impl {
 	($ty:ty) => {
 		impl $ty {
@@ -119,6 +331,13 @@ macro_rules! forward_seed_rng_impl {
 			pub fn new() -> Random<$ty> {
 				SeedRng::new()
 			}
+			/// Creates a new instance seeded securely from system entropy.
+			///
+			/// See the [`SeedRng`](SeedRng::try_new) trait for more information.
+			#[inline]
+			pub fn try_new() -> Result<Random<$ty>, EntropyError> {
+				SeedRng::try_new()
+			}
 			/// Creates a new PRNG seeded from another `Rng`.
 			///
 			/// See the [`SeedRng`](SeedRng::from_rng) trait for more information.
@@ -142,25 +361,75 @@ macro_rules! forward_seed_rng_impl {
 
 mod splitmix64;
 pub use self::splitmix64::SplitMix64;
+pub(crate) use self::splitmix64::KeyHasher;
 
 mod xoshiro256;
 pub use self::xoshiro256::Xoshiro256;
 
+mod xoroshiro128;
+pub use self::xoroshiro128::{Xoroshiro128pp, Xoroshiro128p};
+
+mod philox;
+pub use self::philox::Philox;
+
+mod seed_seq;
+pub use self::seed_seq::SeedSeq;
+
 mod mock;
 pub use self::mock::MockRng;
 
+mod counting;
+pub use self::counting::{Counting, RngStats};
+
+mod endian;
+pub use self::endian::{ByteSwapped, Lagged, WordOrder};
+
+mod conditioned;
+pub use self::conditioned::Conditioned;
+
+cfg_if::cfg_if! {
+	if #[cfg(feature = "alloc")] {
+		mod replay;
+		pub use self::replay::{Recorder, Replayer, RngRecord};
+	}
+}
+
 cfg_if::cfg_if! {
 	if #[cfg(feature = "std")] {
 		mod read;
 		pub use self::read::ReadRng;
+
+		mod verify;
+		pub use self::verify::{verify, verify_next_u64, ContractViolation};
+	}
+}
+
+cfg_if::cfg_if! {
+	if #[cfg(all(feature = "std", unix))] {
+		mod fork;
+		pub use self::fork::ForkGuard;
 	}
 }
 
 mod chacha20;
-pub use self::chacha20::ChaCha20;
+pub use self::chacha20::{ChaCha20, ChaCha20State};
 
 mod entropy;
-pub use self::entropy::getentropy;
+pub use self::entropy::{getentropy, try_getentropy, EntropyError};
+
+cfg_if::cfg_if! {
+	if #[cfg(target_has_atomic = "8")] {
+		mod sealed;
+		pub use self::sealed::Sealed;
+	}
+	else {
+		// Sealing requires atomic read-modify-write, the entropy source is never sealed
+		mod sealed {
+			#[inline]
+			pub(crate) fn check() {}
+		}
+	}
+}
 
 //----------------------------------------------------------------
 
@@ -173,3 +442,118 @@ fn test_trait_object() {
 	}
 	test(&mut rng);
 }
+
+#[cfg(test)]
+fn check_fill_words<R: Rng + Clone>(rng: R) {
+	for len in (0..70).chain([1024, 1031]) {
+		let mut bytes = vec![0u8; len * 8];
+		let mut words32 = vec![0u32; len];
+		let mut words64 = vec![0u64; len];
+
+		let mut a = rng.clone();
+		a.fill_bytes(&mut bytes[..len * 4]);
+		let mut b = rng.clone();
+		b.fill_u32(&mut words32);
+		for (chunk, &word) in bytes.chunks_exact(4).zip(&words32) {
+			assert_eq!(u32::from_le_bytes(chunk.try_into().unwrap()), word, "len: {}", len);
+		}
+		assert_eq!(a.next_u64(), b.next_u64(), "len: {}", len);
+
+		let mut a = rng.clone();
+		a.fill_bytes(&mut bytes);
+		let mut b = rng.clone();
+		b.fill_u64(&mut words64);
+		for (chunk, &word) in bytes.chunks_exact(8).zip(&words64) {
+			assert_eq!(u64::from_le_bytes(chunk.try_into().unwrap()), word, "len: {}", len);
+		}
+		assert_eq!(a.next_u64(), b.next_u64(), "len: {}", len);
+	}
+}
+
+#[test]
+fn test_jump_support() {
+	use JumpStrength::*;
+	assert_eq!(SplitMix64::from_seed(1).0.jump_support(), Weak);
+	assert_eq!(Xoshiro256::from_seed(1).0.jump_support(), Strong);
+	assert_eq!(Xoroshiro128pp::from_seed(1).0.jump_support(), Strong);
+	assert_eq!(Xoroshiro128p::from_seed(1).0.jump_support(), Strong);
+	assert_eq!(Philox::from_seed(1).0.jump_support(), Strong);
+	assert_eq!(ChaCha20::from_seed(1).0.jump_support(), Strong);
+	assert_eq!(SeedSeq::new(1).stream().0.jump_support(), Weak);
+	assert_eq!((getentropy as fn(&mut [u8])).jump_support(), Strong);
+	assert_eq!(MockRng::repeat(1).0.jump_support(), None);
+	// Wrappers forward the strength
+	assert_eq!(SplitMix64::from_seed(1).counting().0.jump_support(), Weak);
+	assert_eq!(Xoshiro256::from_seed(1).byte_swapped().lagged(WordOrder::LowFirst).0.jump_support(), Strong);
+	assert_eq!(MockRng::repeat(1).conditioned().0.jump_support(), None);
+	#[cfg(feature = "std")]
+	{
+		assert_eq!(ReadRng::new(&[0u8; 8][..]).0.jump_support(), None);
+		assert_eq!(Replayer::new(Vec::new()).0.jump_support(), None);
+		assert_eq!(ReadRng::new(&[0u8; 8][..]).recording().0.jump_support(), None);
+		let mut rng = ReadRng::new(&[0u8; 64][..]);
+		assert_eq!(rng.try_split().unwrap_err(), JumpError);
+	}
+
+	// Splitting generators which cannot jump fails
+	let mut rng = MockRng::repeat(1);
+	assert_eq!(rng.try_split().unwrap_err(), JumpError);
+
+	// Splitting strong generators is unchanged
+	let mut rng = Xoshiro256::from_seed(42);
+	let mut original = rng.clone();
+	let mut jumped = rng.clone();
+	jumped.jump();
+	let mut split = rng.try_split().unwrap();
+	assert_eq!(split.next_u64(), original.next_u64());
+	assert_eq!(rng.clone().next_u64(), jumped.clone().next_u64());
+	let mut split = rng.split();
+	assert_eq!(split.next_u64(), jumped.next_u64());
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "cannot jump")]
+fn test_split_no_jump() {
+	let _ = MockRng::repeat(1).split();
+}
+
+#[test]
+fn test_fill_words() {
+	check_fill_words(SplitMix64::from_seed(42).0);
+	check_fill_words(Xoshiro256::from_seed(42).0);
+	check_fill_words(Xoroshiro128pp::from_seed(42).0);
+	check_fill_words(Xoroshiro128p::from_seed(42).0);
+	check_fill_words(Philox::from_seed(42).0);
+	check_fill_words(ChaCha20::from_seed(42).0);
+	check_fill_words(crate::new().counting().0);
+	check_fill_words(crate::new().byte_swapped().0);
+	check_fill_words(crate::new().lagged(WordOrder::HighFirst).0);
+	check_fill_words(crate::new().conditioned().0);
+}
+
+#[test]
+fn test_seed_rng_default() {
+	// Generators outside the crate only implement the seeding constructors
+	#[derive(Clone)]
+	struct Wrapper(SplitMix64);
+	impl Rng for Wrapper {
+		fn next_u32(&mut self) -> u32 { self.0.next_u32() }
+		fn next_u64(&mut self) -> u64 { self.0.next_u64() }
+		fn fill_u32(&mut self, buffer: &mut [u32]) { self.0.fill_u32(buffer) }
+		fn fill_u64(&mut self, buffer: &mut [u64]) { self.0.fill_u64(buffer) }
+		fn fill_bytes(&mut self, buffer: &mut [u8]) { self.0.fill_bytes(buffer) }
+		fn jump(&mut self) { self.0.jump() }
+	}
+	impl SeedRng for Wrapper {
+		fn from_rng<R: Rng + ?Sized>(rng: &mut Random<R>) -> Random<Wrapper> {
+			Random(Wrapper(SplitMix64::from_rng(rng).0))
+		}
+		fn from_seed(seed: u64) -> Random<Wrapper> {
+			Random(Wrapper(SplitMix64::from_seed(seed).0))
+		}
+	}
+	let mut a = Wrapper::try_new().unwrap();
+	let mut b = Wrapper::new();
+	assert_ne!(a.next_u64(), b.next_u64());
+}
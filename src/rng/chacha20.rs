@@ -1,15 +1,28 @@
+use core::fmt;
 use crate::{Random, Rng};
-use super::SeedRng;
+use super::{DeriveRng, EntropyError, FromSeedSeq, JumpStrength, MixRng, SecureRng, SeedRng, StateError};
 
 /// Daniel J. Bernstein's ChaCha20 adapted as a deterministic random number generator.
 ///
+/// # Zeroize
+///
+/// With the `zeroize` feature enabled the generator implements [`Zeroize`](zeroize::Zeroize) and scrubs its memory on drop.
+/// Both the key state and any buffered but not yet consumed output words are cleared.
+///
+/// Note that this only covers the generator itself, copies made by cloning or moving the generator are not tracked.
+///
+/// # Debug
+///
+/// The `Debug` implementation redacts the key and buffered output words.
+/// Only a short fingerprint of the key is printed to tell generators apart, along with the block counter and stream.
+///
 /// # Examples
 ///
 /// ```
 /// let mut rng = urandom::rng::ChaCha20::new();
 /// let value: i32 = rng.next();
 /// ```
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct ChaCha20 {
 	// The current state of the ChaCha20 cipher
 	state: [u32; BLOCK_WORDS],
@@ -19,15 +32,53 @@ pub struct ChaCha20 {
 	index: u32,
 }
 
+impl fmt::Debug for ChaCha20 {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let mut hasher = super::KeyHasher::new();
+		for &word in &self.state[4..12] {
+			core::hash::Hasher::write_u32(&mut hasher, word);
+		}
+		let fingerprint = core::hash::Hasher::finish(&hasher) >> 32;
+		f.debug_struct("ChaCha20")
+			.field("key", &format_args!("<redacted {:08x}>", fingerprint))
+			.field("counter", &(self.state[12] as u64 | (self.state[13] as u64) << 32))
+			.field("stream", &(self.state[14] as u64 | (self.state[15] as u64) << 32))
+			.field("index", &self.index)
+			.finish()
+	}
+}
+
+/// The complete state of a [`ChaCha20`](ChaCha20) generator, including its position within the buffered block.
+///
+/// The ChaCha20 block function is applied to the state words `constant, key, counter, stream` as in the ChaCha20 specification,
+/// where the 64-bit `counter` occupies words 12 and 13 and the 64-bit `stream` occupies the nonce words 14 and 15, all little-endian.
+/// Together they form a 128-bit block counter: the counter carries into the stream when it wraps around.
+///
+/// Output words are consumed in order from the block at `counter - 1`, `index` is the number of words already consumed from it.
+/// An `index` of 16 means no words are buffered and the next output starts at the block at `counter`.
+///
+/// The state contains the key, `Debug` is intentionally not implemented.
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub struct ChaCha20State {
+	/// The 256-bit key as eight words.
+	pub key: [u32; 8],
+	/// The block counter of the next block to generate.
+	pub counter: u64,
+	/// The stream id in the nonce words.
+	pub stream: u64,
+	/// The number of words consumed from the buffered block, between 0 and 16.
+	pub index: u32,
+}
+
 impl SeedRng for ChaCha20 {
 	#[inline]
-	fn new() -> Random<ChaCha20> {
+	fn try_new() -> Result<Random<ChaCha20>, EntropyError> {
 		let mut state = [
 			CONSTANT[0], CONSTANT[1], CONSTANT[2], CONSTANT[3],
 			0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
 		];
-		super::getentropy(dataview::bytes_mut(&mut state[4..]));
-		Random(ChaCha20 { state, random: [0; BLOCK_WORDS], index: !0 })
+		super::try_getentropy(dataview::bytes_mut(&mut state[4..]))?;
+		Ok(Random(ChaCha20 { state, random: [0; BLOCK_WORDS], index: !0 }))
 	}
 	#[inline]
 	fn from_rng<R: Rng + ?Sized>(rng: &mut Random<R>) -> Random<ChaCha20> {
@@ -57,6 +108,200 @@ impl SeedRng for ChaCha20 {
 
 forward_seed_rng_impl!(ChaCha20);
 
+impl ChaCha20 {
+	/// Creates a new generator from the 256-bit key, the block counter and stream start at zero.
+	///
+	/// The key words are read in little-endian byte order as in the ChaCha20 specification.
+	/// _Changing_ the implementation of this function is considered a breaking change.
+	///
+	/// Note that [`from_seed`](ChaCha20::from_seed) repeats the seed bytes four times as the key but starts with a block counter of one.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::rng::ChaCha20::from_seed_bytes([0x01; 32]);
+	/// assert_eq!(rng.next_u64(), 0xc476243a20373f02);
+	/// ```
+	#[inline]
+	pub fn from_seed_bytes(seed: [u8; 32]) -> Random<ChaCha20> {
+		let mut state = [
+			CONSTANT[0], CONSTANT[1], CONSTANT[2], CONSTANT[3],
+			0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+		];
+		for (word, bytes) in state[4..12].iter_mut().zip(seed.chunks_exact(4)) {
+			*word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+		}
+		Random(ChaCha20 { state, random: [0; BLOCK_WORDS], index: !0 })
+	}
+	/// Creates a new generator from a seed and a label, eg. the name of a test.
+	///
+	/// Every label selects an independent stream, the same seed and label always produce the same stream.
+	/// Unlike hashing the label to a 64-bit seed, different labels cannot collide on the key, only on the 64-bit stream id.
+	///
+	/// The construction is stable, _changing_ the output of this function is considered a breaking change:
+	///
+	/// 1. The key is the first four outputs of [`SplitMix64`](super::SplitMix64) seeded with `seed`, as eight little-endian 32-bit words.
+	/// 2. The message is the 8 bytes `label\0\0\0`, the length of the label as a little-endian `u64`, the label,
+	///    and zero bytes up to a multiple of 16 bytes. The length prefix ensures distinct labels produce distinct messages.
+	/// 3. The chaining value `h` starts as the key. For every 16-byte chunk of the message the ChaCha20 block function is applied
+	///    to the state with the key words replaced by `h` and the counter and nonce words replaced by the chunk as little-endian words.
+	///    The new chaining value is the XOR of the first and second halves of the output block.
+	/// 4. The stream id is the first two words of the final chaining value as a little-endian `u64`.
+	/// 5. The generator is keyed with the key from step 1, with the block counter at zero and the stream id in the nonce words.
+	///
+	/// The stream id is available from [`stream`](ChaCha20::stream) for logging.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use urandom::rng::ChaCha20;
+	///
+	/// let mut a = ChaCha20::from_seed_and_label(42, b"parser::test_utf8");
+	/// let mut b = ChaCha20::from_seed_and_label(42, b"parser::test_utf8");
+	/// assert_eq!(a.next_u64(), b.next_u64());
+	/// println!("stream: {:#x}", a.0.stream());
+	/// ```
+	pub fn from_seed_and_label(seed: u64, label: &[u8]) -> Random<ChaCha20> {
+		let mut key = [0u32; 8];
+		let mut splitmix = super::SplitMix64(seed);
+		for pair in key.chunks_exact_mut(2) {
+			let value = splitmix.next_u64();
+			pair[0] = value as u32;
+			pair[1] = (value >> 32) as u32;
+		}
+
+		let mut h = key;
+		let mut header = [0u8; 16];
+		header[..8].copy_from_slice(b"label\0\0\0");
+		header[8..].copy_from_slice(&(label.len() as u64).to_le_bytes());
+		compress(&mut h, &header);
+		for chunk in label.chunks(16) {
+			compress(&mut h, chunk);
+		}
+
+		let state = [
+			CONSTANT[0], CONSTANT[1], CONSTANT[2], CONSTANT[3],
+			key[0], key[1], key[2], key[3],
+			key[4], key[5], key[6], key[7],
+			0, 0, h[0], h[1],
+		];
+		Random(ChaCha20 { state, random: [0; BLOCK_WORDS], index: !0 })
+	}
+	/// Creates a new generator from its complete state.
+	///
+	/// Returns an error if the index is greater than 16.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use urandom::rng::ChaCha20;
+	///
+	/// let mut rng = ChaCha20::from_seed(42);
+	/// let _ = rng.next_u32();
+	///
+	/// let mut copy = ChaCha20::from_state(rng.0.state()).unwrap();
+	/// assert_eq!(rng.next_u64(), copy.next_u64());
+	/// ```
+	pub fn from_state(state: ChaCha20State) -> Result<Random<ChaCha20>, StateError> {
+		if state.index > BLOCK_WORDS as u32 {
+			return Err(StateError::IndexOutOfRange);
+		}
+		let mut rng = ChaCha20 {
+			state: [
+				CONSTANT[0], CONSTANT[1], CONSTANT[2], CONSTANT[3],
+				state.key[0], state.key[1], state.key[2], state.key[3],
+				state.key[4], state.key[5], state.key[6], state.key[7],
+				state.counter as u32, (state.counter >> 32) as u32, state.stream as u32, (state.stream >> 32) as u32,
+			],
+			random: [0; BLOCK_WORDS],
+			index: !0,
+		};
+		if state.index < BLOCK_WORDS as u32 {
+			// Regenerate the buffered block, this advances the counter back to its original value
+			let position = (state.counter as u128 | (state.stream as u128) << 64).wrapping_sub(1);
+			rng.state[12] = position as u32;
+			rng.state[13] = (position >> 32) as u32;
+			rng.state[14] = (position >> 64) as u32;
+			rng.state[15] = (position >> 96) as u32;
+			chacha20_block(&mut rng.state, &mut rng.random);
+			rng.index = state.index;
+		}
+		Ok(Random(rng))
+	}
+	/// Returns the complete state of the generator.
+	///
+	/// Passing the result to [`from_state`](ChaCha20::from_state) recreates the generator at its current position.
+	#[inline]
+	pub fn state(&self) -> ChaCha20State {
+		let mut key = [0u32; 8];
+		key.copy_from_slice(&self.state[4..12]);
+		ChaCha20State {
+			key,
+			counter: self.state[12] as u64 | (self.state[13] as u64) << 32,
+			stream: self.stream(),
+			index: u32::min(self.index, BLOCK_WORDS as u32),
+		}
+	}
+	/// Returns the stream id, the nonce words of the state as a little-endian `u64`.
+	#[inline]
+	pub fn stream(&self) -> u64 {
+		self.state[14] as u64 | (self.state[15] as u64) << 32
+	}
+	/// Returns the key in little-endian byte order.
+	///
+	/// The block counter and stream are not included.
+	#[inline]
+	pub fn to_seed_bytes(&self) -> [u8; 32] {
+		let mut seed = [0u8; 32];
+		for (bytes, word) in seed.chunks_exact_mut(4).zip(&self.state[4..12]) {
+			bytes.copy_from_slice(&word.to_le_bytes());
+		}
+		seed
+	}
+	/// Fills the buffer with the keystream starting at the given byte offset, without mutating the generator.
+	///
+	/// Offset zero is the start of the next block the generator would produce, for a freshly seeded generator this is the start of its stream.
+	/// Any buffered words from the current block are not accounted for.
+	///
+	/// This provides random access into the stream, eg. to generate a window of a large deterministic dataset without generating everything before it.
+	/// Windows past the end of `u64` byte offsets continue with the next blocks, the 128-bit block counter wraps around like [`next_u32`](Rng::next_u32) does.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let rng = urandom::rng::ChaCha20::from_seed(42);
+	///
+	/// let mut window = [0u8; 16];
+	/// rng.0.stream_at(1_000_000_000, &mut window);
+	///
+	/// let mut stream = vec![0u8; 100];
+	/// rng.clone().fill_bytes(&mut stream);
+	/// let mut window = [0u8; 16];
+	/// rng.0.stream_at(50, &mut window);
+	/// assert_eq!(window, stream[50..66]);
+	/// ```
+	pub fn stream_at(&self, byte_offset: u64, mut buf: &mut [u8]) {
+		let mut state = self.state;
+		let counter = state[12] as u128 | (state[13] as u128) << 32 | (state[14] as u128) << 64 | (state[15] as u128) << 96;
+		let counter = counter.wrapping_add((byte_offset / BLOCK_SIZE as u64) as u128);
+		state[12] = counter as u32;
+		state[13] = (counter >> 32) as u32;
+		state[14] = (counter >> 64) as u32;
+		state[15] = (counter >> 96) as u32;
+		let mut skip = (byte_offset % BLOCK_SIZE as u64) as usize;
+		let mut tmp = [0; BLOCK_WORDS];
+		while buf.len() > 0 {
+			chacha20_block(&mut state, &mut tmp);
+			let len = usize::min(BLOCK_SIZE - skip, buf.len());
+			buf[..len].copy_from_slice(&dataview::bytes(&tmp)[skip..skip + len]);
+			buf = &mut buf[len..];
+			skip = 0;
+		}
+	}
+}
+
+impl FromSeedSeq for ChaCha20 {}
+
 impl Rng for ChaCha20 {
 	#[inline]
 	fn next_u32(&mut self) -> u32 {
@@ -103,10 +348,9 @@ impl Rng for ChaCha20 {
 			index = 0;
 		}
 		// Fill the remaining words from the random block
-		while buffer.len() > 0 {
-			buffer[0] = self.random[index];
-			index += 1;
-		}
+		let len = buffer.len();
+		buffer.copy_from_slice(&self.random[index..index + len]);
+		index += len;
 		self.index = index as u32;
 	}
 	#[inline]
@@ -126,7 +370,7 @@ impl Rng for ChaCha20 {
 			buffer = &mut buffer[BLOCK_SIZE..];
 		}
 		// Generate a new block if there are not enough words remaining
-		let max_index = (BLOCK_SIZE + 3 - buffer.len()) / 4;
+		let max_index = (BLOCK_SIZE - buffer.len()) / 4;
 		let mut index = self.index as usize;
 		if index > max_index {
 			chacha20_block(&mut self.state, &mut self.random);
@@ -150,6 +394,74 @@ impl Rng for ChaCha20 {
 		}
 		self.index = !0;
 	}
+	#[inline]
+	fn jump_support(&self) -> JumpStrength {
+		JumpStrength::Strong
+	}
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for ChaCha20 {
+	/// Clears the key state and the buffered output.
+	///
+	/// The next output of the generator is the keystream of the all-zero state.
+	#[inline]
+	fn zeroize(&mut self) {
+		self.state.zeroize();
+		self.random.zeroize();
+		self.index = !0;
+	}
+}
+#[cfg(feature = "zeroize")]
+impl Drop for ChaCha20 {
+	#[inline]
+	fn drop(&mut self) {
+		zeroize::Zeroize::zeroize(self);
+	}
+}
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for ChaCha20 {}
+
+impl SecureRng for ChaCha20 {}
+
+impl MixRng for ChaCha20 {
+	/// The mixed bytes are XORed into the stream id in the nonce words, the key and block counter are unchanged.
+	///
+	/// Any buffered output words are discarded so that the very next output depends on the mixed bytes.
+	#[inline]
+	fn mix(&mut self, bytes: &[u8]) {
+		if !bytes.is_empty() {
+			let [lane] = super::mix_lanes::<1>(bytes);
+			self.state[14] ^= lane as u32;
+			self.state[15] ^= (lane >> 32) as u32;
+			self.index = !0;
+		}
+	}
+}
+
+impl DeriveRng for ChaCha20 {
+	/// The child key is the first half of a keystream block of the parent's key,
+	/// with the parent's [stream id](ChaCha20::stream) as the block counter and the derivation key as the nonce.
+	///
+	/// The derived generators depend on the parent's key and stream id: parents created with different labels by
+	/// [`from_seed_and_label`](ChaCha20::from_seed_and_label) derive different children, and so do calls to `jump` and
+	/// [`mix_entropy`](crate::Random::mix_entropy) on the parent. The block counter of the parent is ignored.
+	#[inline]
+	fn derive(&self, key: u64) -> ChaCha20 {
+		let mut state = self.state;
+		state[12] = state[14];
+		state[13] = state[15];
+		state[14] = (key & 0xffffffff) as u32;
+		state[15] = (key >> 32) as u32;
+		let mut block = [0; BLOCK_WORDS];
+		chacha20_block(&mut state, &mut block);
+		state[4..12].copy_from_slice(&block[..8]);
+		state[12] = 0;
+		state[13] = 0;
+		state[14] = 0;
+		state[15] = 0;
+		ChaCha20 { state, random: [0; BLOCK_WORDS], index: !0 }
+	}
 }
 
 //----------------------------------------------------------------
@@ -161,6 +473,24 @@ const CONSTANT: [u32; 4] = [0x61707865, 0x3320646e, 0x79622d32, 0x6b206574];
 const BLOCK_WORDS: usize = 16;
 const BLOCK_SIZE: usize = 16 * 4;
 
+// Absorbs up to 16 bytes into the chaining value with the ChaCha20 block function
+// The chunk is zero padded into the four counter and nonce words, the output halves are folded together
+pub(super) fn compress(h: &mut [u32; 8], chunk: &[u8]) {
+	let mut state = [0u32; BLOCK_WORDS];
+	state[..4].copy_from_slice(&CONSTANT);
+	state[4..12].copy_from_slice(h);
+	for (word, bytes) in state[12..].iter_mut().zip(chunk.chunks(4)) {
+		let mut buf = [0u8; 4];
+		buf[..bytes.len()].copy_from_slice(bytes);
+		*word = u32::from_le_bytes(buf);
+	}
+	let mut block = [0; BLOCK_WORDS];
+	chacha20_block(&mut state, &mut block);
+	for i in 0..8 {
+		h[i] = block[i] ^ block[i + 8];
+	}
+}
+
 #[inline]
 fn increment_counter(state: &mut [u32; 16]) {
 	// Yeah this can technically be written in pure safe code but that would be a chore...
@@ -204,3 +534,178 @@ fn chacha20_selftest() {
 	chacha20_block(&mut state, &mut result);
 	assert_eq!(expected, result);
 }
+
+#[test]
+fn test_seed_bytes() {
+	for seed in [0, 1, 42, u64::MAX] {
+		let a = ChaCha20::from_seed(seed);
+		let expected: Vec<u8> = [seed.to_le_bytes(); 4].concat();
+		assert_eq!(a.0.to_seed_bytes()[..], expected[..]);
+		// The same key with the block counter set to one
+		let b = ChaCha20::from_seed_bytes(a.0.to_seed_bytes());
+		let mut first = [0u8; 64];
+		a.0.stream_at(0, &mut first);
+		let mut second = [0u8; 64];
+		b.0.stream_at(64, &mut second);
+		assert_eq!(first, second);
+	}
+}
+
+#[test]
+fn test_stream_at() {
+	let rng = ChaCha20::from_seed(0x1234);
+	let mut stream = [0u8; 1000];
+	for len in [0, 1, 63, 64, 65, 130, 1000] {
+		rng.clone().fill_bytes(&mut stream[..len]);
+		let mut window = [0u8; 1000];
+		rng.0.stream_at(0, &mut window[..len]);
+		assert_eq!(stream[..len], window[..len]);
+	}
+
+	// Overlapping windows agree on their intersection
+	for offset in [1, 17, 63, 64, 500] {
+		let mut window = [0u8; 300];
+		rng.0.stream_at(offset as u64, &mut window);
+		assert_eq!(window[..], stream[offset..offset + 300]);
+	}
+
+	// Windows crossing the end of u64 byte offsets
+	let mut a = [0u8; 200];
+	let mut b = [0u8; 100];
+	rng.0.stream_at(u64::MAX - 100, &mut a);
+	rng.0.stream_at(u64::MAX - 50, &mut b);
+	assert_eq!(a[50..150], b[..]);
+}
+
+#[test]
+fn test_debug_redacted() {
+	let rng = ChaCha20::from_seed(0x0123456789abcdef);
+	let dump = format!("{:?}", rng.dump_state());
+	assert!(dump.starts_with("ChaCha20 { key: <redacted "), "{}", dump);
+	for word in ["89abcdef", "1234567", "2309737967", "19088743"] {
+		assert!(!dump.contains(word), "{}", dump);
+	}
+}
+
+#[test]
+fn test_derive_position_independent() {
+	let mut rng = ChaCha20::from_seed(42);
+	let mut first = rng.0.derive(13);
+	for _ in 0..100 {
+		rng.next_u32();
+	}
+	let mut second = rng.0.derive(13);
+	for _ in 0..100 {
+		assert_eq!(first.next_u64(), second.next_u64());
+	}
+
+	let mut other = rng.0.derive(14);
+	let mut third = rng.0.derive(13);
+	assert_ne!(other.next_u64(), third.next_u64());
+}
+
+#[test]
+fn test_derive_stream() {
+	// Parents which differ only in their stream derive different children
+	let a = ChaCha20::from_seed_and_label(42, b"a");
+	let b = ChaCha20::from_seed_and_label(42, b"b");
+	assert_eq!(a.0.to_seed_bytes(), b.0.to_seed_bytes());
+	let mut first = Random(a.0.derive(13));
+	let mut second = Random(b.0.derive(13));
+	assert_ne!(first.next_u64(), second.next_u64());
+
+	let mut parent = ChaCha20::from_seed(42);
+	let mut before = Random(parent.0.derive(13));
+	parent.mix_entropy(b"entropy");
+	let mut after = Random(parent.0.derive(13));
+	assert_ne!(before.next_u64(), after.next_u64());
+}
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn test_zeroize() {
+	use zeroize::Zeroize;
+
+	let mut rng = ChaCha20::from_seed(42);
+	// Leave some buffered output words unconsumed
+	let _ = rng.next_u32();
+	rng.zeroize();
+	assert_eq!(rng.0.state, [0; BLOCK_WORDS]);
+	assert_eq!(rng.0.random, [0; BLOCK_WORDS]);
+
+	let mut zero = Random(ChaCha20 { state: [0; BLOCK_WORDS], random: [0; BLOCK_WORDS], index: !0 });
+	for _ in 0..40 {
+		assert_eq!(rng.next_u32(), zero.next_u32());
+	}
+}
+
+#[test]
+fn test_seed_and_label() {
+	let mut rng = ChaCha20::from_seed_and_label(42, b"parser::test_utf8");
+	assert_eq!(rng.0.stream(), 0xcf2a9d4f3b037637);
+	assert_eq!([rng.next_u64(), rng.next_u64()], [0x496ee7d7db08f99f, 0xbebf390b3052dc74]);
+
+	// The key is the SplitMix64 expansion of the seed
+	let mut key = [0u8; 32];
+	super::SplitMix64::from_seed(42).fill_bytes(&mut key);
+	assert_eq!(rng.0.to_seed_bytes(), key);
+
+	// Labels select distinct streams
+	let mut firsts = Vec::new();
+	for i in 0..1000 {
+		let label = format!("test_{}", i);
+		let mut rng = ChaCha20::from_seed_and_label(42, label.as_bytes());
+		firsts.push([rng.next_u64(), rng.next_u64(), rng.next_u64(), rng.next_u64()]);
+	}
+	firsts.sort();
+	assert!(firsts.windows(2).all(|pair| pair[0] != pair[1]));
+
+	// Labels are length prefixed, padding with zeros does not collide
+	let streams = [&b""[..], b"\0", b"a", b"a\0", b"ab", &[0; 16], &[0; 17]].map(|label| ChaCha20::from_seed_and_label(42, label).0.stream());
+	for (i, a) in streams.iter().enumerate() {
+		assert!(streams[i + 1..].iter().all(|b| a != b), "{:?}", streams);
+	}
+	assert_ne!(ChaCha20::from_seed_and_label(1, b"a").0.stream(), ChaCha20::from_seed_and_label(2, b"a").0.stream());
+}
+
+#[test]
+fn test_state() {
+	// RFC 8439 section 2.3.2 test vector: the block at counter 1 starts with 0xe4e7f110
+	let key = [0x03020100, 0x07060504, 0x0b0a0908, 0x0f0e0d0c, 0x13121110, 0x17161514, 0x1b1a1918, 0x1f1e1d1c];
+	// The counter word is followed by the nonce words 0x09000000, 0x4a000000 and 0x00000000
+	let counter = 1 | 0x09000000 << 32;
+	let mut rng = ChaCha20::from_state(ChaCha20State { key, counter, stream: 0x4a000000, index: 16 }).unwrap();
+	assert_eq!(rng.next_u32(), 0xe4e7f110);
+	assert_eq!(rng.0.state().counter, counter + 1);
+	assert_eq!(rng.0.state().index, 1);
+
+	// Round-trips at every position within a block and across the counter carry
+	for &(counter, stream) in &[(0, 0), (5, 7), (0, 1), (u64::MAX, 3)] {
+		let mut rng = ChaCha20::from_state(ChaCha20State { key, counter, stream, index: 16 }).unwrap();
+		for _ in 0..40 {
+			let mut copy = ChaCha20::from_state(rng.0.state()).unwrap();
+			assert_eq!(copy.next_u64(), rng.clone().next_u64());
+			assert!(ChaCha20::from_state(rng.0.state()).unwrap().0.state() == rng.0.state());
+			let _ = rng.next_u32();
+		}
+	}
+	let state = ChaCha20State { key, counter: 0, stream: 0, index: 17 };
+	assert_eq!(ChaCha20::from_state(state).err(), Some(StateError::IndexOutOfRange));
+}
+
+#[test]
+fn test_mix() {
+	let mut a = ChaCha20::from_seed(42);
+	let _ = a.next_u32();
+	let mut b = a.clone();
+	b.0.mix(&[]);
+	assert!(a.0.state() == b.0.state());
+	let mut c = a.clone();
+	b.mix_entropy(b"fresh entropy");
+	c.mix_entropy(b"fresh entropy");
+	let value = b.next_u64();
+	assert_ne!(a.next_u64(), value);
+	assert_eq!(c.next_u64(), value);
+	assert_eq!(a.0.to_seed_bytes(), b.0.to_seed_bytes());
+	assert_ne!(a.0.stream(), b.0.stream());
+}
@@ -0,0 +1,198 @@
+use core::fmt;
+use crate::Rng;
+use super::JumpStrength;
+
+/// Conditions a raw entropy source through the ChaCha20 block function.
+///
+/// Hardware sources of unknown quality produce biased and correlated bytes.
+/// The conditioner compresses every 64 bytes of raw input into 32 bytes of output, halving the rate.
+/// The output has full entropy if every 64 bytes of input carry at least 256 bits of min-entropy, 4 bits per byte.
+/// Use [`stats::min_entropy`](crate::stats::min_entropy) to estimate the min-entropy of a source (std only).
+///
+/// # Construction
+///
+/// A chaining value of eight words starts at zero.
+/// For every eight words drawn with `fill_u64` from the inner source, the 64 bytes of the words in little-endian order are absorbed as four chunks of 16 bytes:
+///
+/// 1. The ChaCha20 state is set to the four constants, the chaining value in the key words and the chunk in the counter and nonce words as little-endian words.
+/// 2. The block function computes 16 words, including the feed-forward addition of the input state.
+/// 3. The new chaining value is the XOR of the first eight words with the last eight words.
+///
+/// The chaining value after absorbing the 64 bytes is the next 32 bytes of output, the chain continues with the next 64 bytes of input.
+///
+/// The words of the output are returned in order: [`next_u64`](Rng::next_u64) combines two words low first,
+/// [`fill_bytes`](Rng::fill_bytes) copies the words as little-endian bytes and discards the unused bytes of a partial last word.
+/// [`jump`](Rng::jump) passes through and discards the buffered output.
+///
+/// # Debug
+///
+/// The `Debug` implementation redacts the chaining value, it is the buffered output.
+///
+/// # Examples
+///
+/// ```
+/// let raw = vec![0x55; 4096];
+/// let mut rng = urandom::rng::ReadRng::new(&raw[..]).conditioned();
+/// let value: u64 = rng.next();
+/// ```
+#[derive(Clone)]
+pub struct Conditioned<R: ?Sized> {
+	chain: [u32; 8],
+	index: usize,
+	rng: R,
+}
+
+impl<R: fmt::Debug + ?Sized> fmt::Debug for Conditioned<R> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Conditioned")
+			.field("chain", &format_args!("<redacted>"))
+			.field("index", &self.index)
+			.field("rng", &&self.rng)
+			.finish()
+	}
+}
+
+impl<R> Conditioned<R> {
+	/// Wraps the raw source with a zero chaining value.
+	#[inline]
+	pub const fn new(rng: R) -> Conditioned<R> {
+		Conditioned { chain: [0; 8], index: 8, rng }
+	}
+	/// Returns the inner source, the buffered output is discarded.
+	#[inline]
+	pub fn into_inner(self) -> R {
+		self.rng
+	}
+}
+
+impl<R: ?Sized> Conditioned<R> {
+	/// Returns a reference to the inner source.
+	#[inline]
+	pub fn inner(&self) -> &R {
+		&self.rng
+	}
+}
+
+impl<R: Rng + ?Sized> Conditioned<R> {
+	#[inline(never)]
+	fn absorb(&mut self) {
+		let mut words = [0u64; 8];
+		self.rng.fill_u64(&mut words);
+		for pair in words.chunks_exact(2) {
+			let mut chunk = [0u8; 16];
+			chunk[..8].copy_from_slice(&pair[0].to_le_bytes());
+			chunk[8..].copy_from_slice(&pair[1].to_le_bytes());
+			super::chacha20::compress(&mut self.chain, &chunk);
+		}
+		self.index = 0;
+	}
+}
+
+impl<R: super::SecureRng + ?Sized> super::SecureRng for Conditioned<R> {}
+
+impl<R: Rng + ?Sized> Rng for Conditioned<R> {
+	#[inline]
+	fn next_u32(&mut self) -> u32 {
+		if self.index >= self.chain.len() {
+			self.absorb();
+		}
+		let value = self.chain[self.index];
+		self.index += 1;
+		value
+	}
+	#[inline]
+	fn next_u64(&mut self) -> u64 {
+		let low = self.next_u32() as u64;
+		let high = self.next_u32() as u64;
+		high << 32 | low
+	}
+	#[inline]
+	fn fill_u32(&mut self, buffer: &mut [u32]) {
+		for word in buffer {
+			*word = self.next_u32();
+		}
+	}
+	#[inline]
+	fn fill_u64(&mut self, buffer: &mut [u64]) {
+		for word in buffer {
+			*word = self.next_u64();
+		}
+	}
+	#[inline]
+	fn fill_bytes(&mut self, buffer: &mut [u8]) {
+		for chunk in buffer.chunks_mut(4) {
+			let value = self.next_u32().to_le_bytes();
+			chunk.copy_from_slice(&value[..chunk.len()]);
+		}
+	}
+	#[inline]
+	fn jump(&mut self) {
+		self.index = self.chain.len();
+		self.rng.jump()
+	}
+	#[inline]
+	fn jump_support(&self) -> JumpStrength {
+		self.rng.jump_support()
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_deterministic() {
+	use super::MockRng;
+
+	let values: Vec<u64> = (0..64).collect();
+	let mut a = MockRng::slice(&values).conditioned();
+	let mut b = MockRng::slice(&values).conditioned();
+	for _ in 0..32 {
+		assert_eq!(a.next_u64(), b.next_u64());
+	}
+	// Consumes 64 bytes of input for every 32 bytes of output
+	let mut rng = crate::new().counting().conditioned();
+	let mut buffer = [0u8; 33];
+	rng.fill_bytes(&mut buffer);
+	let stats = rng.0.inner().stats();
+	assert_eq!((stats.fill_u64, stats.bytes), (2, 128));
+
+	// The output is a pure function of the input bytes
+	let raw: Vec<u8> = values.iter().flat_map(|value| value.to_le_bytes()).collect();
+	let mut chain = [0u32; 8];
+	for chunk in raw[..64].chunks(16) {
+		super::chacha20::compress(&mut chain, chunk);
+	}
+	let mut rng = MockRng::slice(&values).conditioned();
+	let mut words = [0u32; 8];
+	rng.fill_u32(&mut words);
+	assert_eq!(words, chain);
+}
+
+#[test]
+fn test_debug_redacted() {
+	let values: Vec<u64> = (0..64).collect();
+	let mut rng = super::MockRng::slice(&values).conditioned();
+	let mut words = [0u32; 4];
+	rng.fill_u32(&mut words);
+	let dump = format!("{:?}", rng.0);
+	assert!(dump.starts_with("Conditioned { chain: <redacted>, index: 4, "), "{}", dump);
+	for word in rng.0.chain {
+		assert!(!dump.contains(&word.to_string()), "{}", dump);
+	}
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_biased_input() {
+	// Bits of the raw input are set with probability 0.9
+	let mut source = crate::seeded(42);
+	let raw: Vec<u8> = (0..6000).map(|_| (0..8).fold(0u8, |byte, bit| byte | (source.chance(0.9) as u8) << bit)).collect();
+	let ones: u32 = raw[..2500].iter().map(|byte| byte.count_ones()).sum();
+	assert!(ones > 17000);
+
+	// The monobit test of FIPS 140-2 on 20000 bits of output
+	let mut rng = super::ReadRng::new(&raw[..]).conditioned();
+	let mut output = [0u8; 2500];
+	rng.fill_bytes(&mut output);
+	let ones: u32 = output.iter().map(|byte| byte.count_ones()).sum();
+	assert!(ones > 9725 && ones < 10275, "ones: {}", ones);
+}
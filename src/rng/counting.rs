@@ -0,0 +1,196 @@
+use core::mem;
+use crate::Rng;
+use super::JumpStrength;
+
+/// Counts the calls to the inner generator.
+///
+/// The counters are plain `u64` values, counting adds no allocation and no change to the generated values.
+/// Useful to measure how much randomness each subsystem of a reproducible simulation consumes.
+///
+/// Cloning copies the counters along with the inner generator.
+///
+/// # Examples
+///
+/// ```
+/// let mut rng = urandom::rng::Xoshiro256::from_seed(42).counting();
+/// let _: u64 = rng.next();
+/// let _: f64 = rng.next();
+/// let mut buffer = [0u8; 100];
+/// rng.fill_bytes(&mut buffer);
+///
+/// let stats = rng.0.stats();
+/// assert_eq!(stats.next_u64, 1);
+/// assert_eq!(stats.next_f64, 1);
+/// assert_eq!(stats.fill_bytes, 1);
+/// assert_eq!(stats.bytes, 8 + 8 + 100);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Counting<R: ?Sized> {
+	stats: RngStats,
+	rng: R,
+}
+
+/// Statistics collected by [`Counting`](Counting).
+///
+/// The method counters count the number of calls, `bytes` counts the total number of random bytes requested from all methods.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct RngStats {
+	/// Number of calls to [`Rng::next_u32`](Rng::next_u32).
+	pub next_u32: u64,
+	/// Number of calls to [`Rng::next_u64`](Rng::next_u64).
+	pub next_u64: u64,
+	/// Number of calls to [`Rng::next_f32`](Rng::next_f32).
+	pub next_f32: u64,
+	/// Number of calls to [`Rng::next_f64`](Rng::next_f64).
+	pub next_f64: u64,
+	/// Number of calls to [`Rng::fill_u32`](Rng::fill_u32).
+	pub fill_u32: u64,
+	/// Number of calls to [`Rng::fill_u64`](Rng::fill_u64).
+	pub fill_u64: u64,
+	/// Number of calls to [`Rng::fill_bytes`](Rng::fill_bytes).
+	pub fill_bytes: u64,
+	/// Number of calls to [`Rng::jump`](Rng::jump).
+	pub jump: u64,
+	/// Total number of random bytes requested.
+	pub bytes: u64,
+}
+
+impl<R> Counting<R> {
+	/// Wraps the generator with zeroed counters.
+	#[inline]
+	pub const fn new(rng: R) -> Counting<R> {
+		Counting { stats: RngStats { next_u32: 0, next_u64: 0, next_f32: 0, next_f64: 0, fill_u32: 0, fill_u64: 0, fill_bytes: 0, jump: 0, bytes: 0 }, rng }
+	}
+	/// Returns the inner generator.
+	#[inline]
+	pub fn into_inner(self) -> R {
+		self.rng
+	}
+}
+
+impl<R: ?Sized> Counting<R> {
+	/// Returns the statistics collected so far.
+	#[inline]
+	pub fn stats(&self) -> RngStats {
+		self.stats
+	}
+	/// Resets the counters to zero.
+	#[inline]
+	pub fn reset_stats(&mut self) {
+		self.stats = RngStats::default();
+	}
+	/// Returns a reference to the inner generator.
+	#[inline]
+	pub fn inner(&self) -> &R {
+		&self.rng
+	}
+}
+
+impl<R: super::SecureRng + ?Sized> super::SecureRng for Counting<R> {}
+
+impl<R: Rng + ?Sized> Rng for Counting<R> {
+	#[inline]
+	fn next_u32(&mut self) -> u32 {
+		self.stats.next_u32 += 1;
+		self.stats.bytes += 4;
+		self.rng.next_u32()
+	}
+	#[inline]
+	fn next_u64(&mut self) -> u64 {
+		self.stats.next_u64 += 1;
+		self.stats.bytes += 8;
+		self.rng.next_u64()
+	}
+	#[inline]
+	fn next_f32(&mut self) -> f32 {
+		self.stats.next_f32 += 1;
+		self.stats.bytes += 4;
+		self.rng.next_f32()
+	}
+	#[inline]
+	fn next_f64(&mut self) -> f64 {
+		self.stats.next_f64 += 1;
+		self.stats.bytes += 8;
+		self.rng.next_f64()
+	}
+	#[inline]
+	fn fill_u32(&mut self, buffer: &mut [u32]) {
+		self.stats.fill_u32 += 1;
+		self.stats.bytes += mem::size_of_val(buffer) as u64;
+		self.rng.fill_u32(buffer)
+	}
+	#[inline]
+	fn fill_u64(&mut self, buffer: &mut [u64]) {
+		self.stats.fill_u64 += 1;
+		self.stats.bytes += mem::size_of_val(buffer) as u64;
+		self.rng.fill_u64(buffer)
+	}
+	#[inline]
+	fn fill_bytes(&mut self, buffer: &mut [u8]) {
+		self.stats.fill_bytes += 1;
+		self.stats.bytes += buffer.len() as u64;
+		self.rng.fill_bytes(buffer)
+	}
+	#[inline]
+	fn jump(&mut self) {
+		self.stats.jump += 1;
+		self.rng.jump()
+	}
+	#[inline]
+	fn jump_support(&self) -> JumpStrength {
+		self.rng.jump_support()
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_scripted() {
+	let mut rng = super::ChaCha20::from_seed(42).counting();
+	let _: u32 = rng.next();
+	let _: u64 = rng.next();
+	let _: f32 = rng.next();
+	rng.fill_u32(&mut [0; 3]);
+	rng.fill_u64(&mut [0; 5]);
+	// Byte fills crossing the 64 byte block boundaries
+	for len in [1, 63, 64, 65, 200] {
+		rng.fill_bytes(&mut vec![0u8; len]);
+	}
+	rng.jump();
+	assert_eq!(rng.0.stats(), RngStats {
+		next_u32: 1,
+		next_u64: 1,
+		next_f32: 1,
+		next_f64: 0,
+		fill_u32: 1,
+		fill_u64: 1,
+		fill_bytes: 5,
+		jump: 1,
+		bytes: 4 + 8 + 4 + 12 + 40 + (1 + 63 + 64 + 65 + 200),
+	});
+
+	rng.0.reset_stats();
+	assert_eq!(rng.0.stats(), RngStats::default());
+
+	// Clones copy the counters
+	let _: u64 = rng.next();
+	let clone = rng.clone();
+	assert_eq!(clone.0.stats().next_u64, 1);
+}
+
+#[test]
+fn test_transparent() {
+	let mut bare = super::ChaCha20::from_seed(42);
+	let mut counted = super::ChaCha20::from_seed(42).counting();
+	assert_eq!(bare.next_u64(), counted.next_u64());
+	assert_eq!(bare.next_u32(), counted.next_u32());
+	assert_eq!(bare.next_f64(), counted.next_f64());
+	let (mut a, mut b) = ([0u8; 100], [0u8; 100]);
+	bare.fill_bytes(&mut a);
+	counted.fill_bytes(&mut b);
+	assert_eq!(a, b);
+	bare.jump();
+	counted.jump();
+	assert_eq!(bare.next::<[u64; 4]>(), counted.next::<[u64; 4]>());
+	assert_eq!(counted.0.into_inner().next_u64(), bare.next_u64());
+}
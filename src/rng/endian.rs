@@ -0,0 +1,272 @@
+use crate::Rng;
+use super::JumpStrength;
+
+/// Byte-swaps the words produced by the inner generator.
+///
+/// Reproduces the consumption of a stream recorded on a machine of the other endianness, where the words were read from memory in the opposite byte order.
+///
+/// Every method maps to exactly one call of the same method of the inner generator:
+///
+/// * [`next_u32`](Rng::next_u32) and [`next_u64`](Rng::next_u64) return the inner word with its bytes reversed.
+/// * [`next_f32`](Rng::next_f32) and [`next_f64`](Rng::next_f64) construct the float from the byte-swapped `next_u32` and `next_u64`.
+/// * [`fill_u32`](Rng::fill_u32), [`fill_u64`](Rng::fill_u64) and [`fill_bytes`](Rng::fill_bytes) pass through unchanged.
+///   Byte buffers have no endianness and the fill methods are required to produce the same words as `fill_bytes`.
+/// * [`jump`](Rng::jump) passes through.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::rng::MockRng;
+///
+/// let mut rng = MockRng::slice(&[0x0102030405060708]).byte_swapped();
+/// assert_eq!(rng.next_u64(), 0x0807060504030201);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ByteSwapped<R: ?Sized> {
+	rng: R,
+}
+
+impl<R> ByteSwapped<R> {
+	/// Wraps the generator.
+	#[inline]
+	pub const fn new(rng: R) -> ByteSwapped<R> {
+		ByteSwapped { rng }
+	}
+	/// Returns the inner generator.
+	#[inline]
+	pub fn into_inner(self) -> R {
+		self.rng
+	}
+}
+
+impl<R: ?Sized> ByteSwapped<R> {
+	/// Returns a reference to the inner generator.
+	#[inline]
+	pub fn inner(&self) -> &R {
+		&self.rng
+	}
+}
+
+impl<R: super::SecureRng + ?Sized> super::SecureRng for ByteSwapped<R> {}
+
+impl<R: Rng + ?Sized> Rng for ByteSwapped<R> {
+	#[inline]
+	fn next_u32(&mut self) -> u32 {
+		self.rng.next_u32().swap_bytes()
+	}
+	#[inline]
+	fn next_u64(&mut self) -> u64 {
+		self.rng.next_u64().swap_bytes()
+	}
+	#[inline]
+	fn fill_u32(&mut self, buffer: &mut [u32]) {
+		self.rng.fill_u32(buffer)
+	}
+	#[inline]
+	fn fill_u64(&mut self, buffer: &mut [u64]) {
+		self.rng.fill_u64(buffer)
+	}
+	#[inline]
+	fn fill_bytes(&mut self, buffer: &mut [u8]) {
+		self.rng.fill_bytes(buffer)
+	}
+	#[inline]
+	fn jump(&mut self) {
+		self.rng.jump()
+	}
+	#[inline]
+	fn jump_support(&self) -> JumpStrength {
+		self.rng.jump_support()
+	}
+}
+
+/// The order in which [`Lagged`](Lagged) returns the halves of a `u64`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum WordOrder {
+	/// The low half first, then the high half.
+	LowFirst,
+	/// The high half first, then the low half.
+	HighFirst,
+}
+
+/// Splits the `u64` words of the inner generator into pairs of `u32` words.
+///
+/// Reproduces tools which drew a `u64` and consumed its halves as two `u32` values, in the configured [`WordOrder`](WordOrder).
+///
+/// The methods map to the inner generator as follows:
+///
+/// * [`next_u32`](Rng::next_u32) returns the buffered half if there is one.
+///   Otherwise it calls `next_u64` once, returns the first half and buffers the second half.
+/// * [`next_u64`](Rng::next_u64) calls `next_u64` once and returns it unchanged, the buffered half is left for the next `next_u32`.
+/// * [`next_f32`](Rng::next_f32) and [`next_f64`](Rng::next_f64) construct the float from `next_u32` and `next_u64` above.
+/// * [`fill_u32`](Rng::fill_u32), [`fill_u64`](Rng::fill_u64) and [`fill_bytes`](Rng::fill_bytes) pass through unchanged and leave the buffered half alone.
+///   The fill methods are required to produce the same words as `fill_bytes`, which has no word order.
+/// * [`jump`](Rng::jump) passes through and discards the buffered half.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::rng::{MockRng, WordOrder};
+///
+/// let mut rng = MockRng::slice(&[0x11111111_22222222]).lagged(WordOrder::HighFirst);
+/// assert_eq!(rng.next_u32(), 0x11111111);
+/// assert_eq!(rng.next_u32(), 0x22222222);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Lagged<R: ?Sized> {
+	order: WordOrder,
+	buffered: Option<u32>,
+	rng: R,
+}
+
+impl<R> Lagged<R> {
+	/// Wraps the generator with no buffered half.
+	#[inline]
+	pub const fn new(rng: R, order: WordOrder) -> Lagged<R> {
+		Lagged { order, buffered: None, rng }
+	}
+	/// Returns the inner generator, the buffered half is discarded.
+	#[inline]
+	pub fn into_inner(self) -> R {
+		self.rng
+	}
+}
+
+impl<R: ?Sized> Lagged<R> {
+	/// Returns the configured word order.
+	#[inline]
+	pub fn order(&self) -> WordOrder {
+		self.order
+	}
+	/// Returns the buffered half which the next `next_u32` returns, if any.
+	#[inline]
+	pub fn buffered(&self) -> Option<u32> {
+		self.buffered
+	}
+	/// Returns a reference to the inner generator.
+	#[inline]
+	pub fn inner(&self) -> &R {
+		&self.rng
+	}
+}
+
+impl<R: super::SecureRng + ?Sized> super::SecureRng for Lagged<R> {}
+
+impl<R: Rng + ?Sized> Rng for Lagged<R> {
+	#[inline]
+	fn next_u32(&mut self) -> u32 {
+		if let Some(value) = self.buffered.take() {
+			return value;
+		}
+		let value = self.rng.next_u64();
+		let (low, high) = (value as u32, (value >> 32) as u32);
+		let (first, second) = match self.order {
+			WordOrder::LowFirst => (low, high),
+			WordOrder::HighFirst => (high, low),
+		};
+		self.buffered = Some(second);
+		first
+	}
+	#[inline]
+	fn next_u64(&mut self) -> u64 {
+		self.rng.next_u64()
+	}
+	#[inline]
+	fn fill_u32(&mut self, buffer: &mut [u32]) {
+		self.rng.fill_u32(buffer)
+	}
+	#[inline]
+	fn fill_u64(&mut self, buffer: &mut [u64]) {
+		self.rng.fill_u64(buffer)
+	}
+	#[inline]
+	fn fill_bytes(&mut self, buffer: &mut [u8]) {
+		self.rng.fill_bytes(buffer)
+	}
+	#[inline]
+	fn jump(&mut self) {
+		self.buffered = None;
+		self.rng.jump()
+	}
+	#[inline]
+	fn jump_support(&self) -> JumpStrength {
+		self.rng.jump_support()
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_byte_swapped() {
+	use super::MockRng;
+
+	let mut rng = MockRng::slice(&[0x0102030405060708, 0x1112131415161718, 0x2122232425262728, 0x3132333435363738]).byte_swapped();
+	assert_eq!(rng.next_u64(), 0x0807060504030201);
+	assert_eq!(rng.next_u32(), 0x18171615);
+	let mut words = [0u32; 1];
+	rng.fill_u32(&mut words);
+	assert_eq!(words, [0x25262728]);
+	let mut words = [0u64; 1];
+	rng.fill_u64(&mut words);
+	assert_eq!(words, [0x3132333435363738]);
+
+	// The floats are built from the swapped words
+	let mut rng = MockRng::slice(&[0x00000000_0000f03f]).byte_swapped();
+	assert_eq!(rng.next_f64(), crate::impls::rng_f64(0x3ff0000000000000));
+
+	// The byte fills pass through for any length
+	let mut bare = crate::seeded(42);
+	let mut swapped = crate::seeded(42).byte_swapped();
+	for len in [0, 1, 3, 7, 9, 33, 65] {
+		let (mut a, mut b) = ([0u8; 65], [0u8; 65]);
+		bare.fill_bytes(&mut a[..len]);
+		swapped.fill_bytes(&mut b[..len]);
+		assert_eq!(a, b);
+	}
+	bare.jump();
+	swapped.jump();
+	assert_eq!(bare.next_u64().swap_bytes(), swapped.next_u64());
+	assert_eq!(bare.next_u32().swap_bytes(), swapped.next_u32());
+}
+
+#[test]
+fn test_lagged() {
+	use super::MockRng;
+
+	let values = [0x11111111_22222222, 0x33333333_44444444, 0x55555555_66666666, 0x77777777_88888888, 0x99999999_aaaaaaaa];
+	let mut rng = MockRng::slice(&values).lagged(WordOrder::HighFirst);
+	assert_eq!(rng.next_u32(), 0x11111111);
+	// The buffered half survives next_u64 and the fills
+	assert_eq!(rng.next_u64(), 0x33333333_44444444);
+	let mut words = [0u32; 2];
+	rng.fill_u32(&mut words);
+	assert_eq!(words, [0x66666666, 0x88888888]);
+	assert_eq!(rng.0.buffered(), Some(0x22222222));
+	assert_eq!(rng.next_u32(), 0x22222222);
+	assert_eq!(rng.next_u32(), 0x99999999);
+
+	let mut rng = MockRng::slice(&values).lagged(WordOrder::LowFirst);
+	assert_eq!(rng.next_u32(), 0x22222222);
+	assert_eq!(rng.next_u32(), 0x11111111);
+	assert_eq!(rng.0.buffered(), None);
+	assert_eq!(rng.next_u32(), 0x44444444);
+	let mut words = [0u64; 1];
+	rng.fill_u64(&mut words);
+	assert_eq!(words, [0x55555555_66666666]);
+	// Jumping discards the buffered half
+	rng.jump();
+	assert_eq!(rng.next_u32(), 0x88888888);
+
+	// The byte fills pass through for any length
+	let mut bare = crate::seeded(42);
+	let mut lagged = crate::seeded(42).lagged(WordOrder::HighFirst);
+	for len in [0, 1, 3, 7, 9, 33, 65] {
+		let (mut a, mut b) = ([0u8; 65], [0u8; 65]);
+		bare.fill_bytes(&mut a[..len]);
+		lagged.fill_bytes(&mut b[..len]);
+		assert_eq!(a, b);
+	}
+	let value = bare.next_u64();
+	assert_eq!(lagged.next_u32(), (value >> 32) as u32);
+	assert_eq!(lagged.next_u32(), value as u32);
+}
@@ -1,4 +1,6 @@
+use core::fmt;
 use crate::Rng;
+use super::JumpStrength;
 
 impl Rng for fn(&mut [u8]) {
 	#[inline]
@@ -29,56 +31,224 @@ impl Rng for fn(&mut [u8]) {
 	fn jump(&mut self) {
 		// This method is intentionally left blank.
 	}
+	#[inline]
+	fn jump_support(&self) -> JumpStrength {
+		JumpStrength::Strong
+	}
+}
+
+/// Error type returned from [`try_getentropy`](try_getentropy).
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum EntropyError {
+	/// The entropy source is not ready or failed temporarily, eg. early during boot.
+	NotReady,
+	/// The entropy source is not available on this platform or access to it was denied, eg. by a seccomp filter.
+	Unsupported,
+}
+
+impl fmt::Display for EntropyError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match self {
+			EntropyError::NotReady => "getentropy not ready",
+			EntropyError::Unsupported => "getentropy unsupported",
+		})
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EntropyError {}
+
+/// Provides cryptographically secure entropy.
+///
+/// # Panics
+///
+/// If unable to provide secure entropy this method will panic, see [`try_getentropy`] for a fallible version.
+///
+/// Panics after [`Sealed::enforce`](super::Sealed::enforce) has been called.
+///
+/// # Implementation notes
+///
+/// With the `getrandom` feature the implementation is provided by the [`getrandom`](https://crates.io/crates/getrandom) crate.
+///
+/// Without the `getrandom` feature the implementation is provided by linking against an extern function:
+///
+/// ```
+/// extern "C" {
+/// 	fn getentropy_raw(buffer_ptr: *mut u8, buffer_len: usize) -> u8;
+/// }
+/// ```
+///
+/// The function returns `1` on success, `0` if the entropy source is not ready and any other value if the entropy source is unsupported.
+/// Existing implementations returning `bool` follow this convention.
+#[inline]
+pub fn getentropy(buffer: &mut [u8]) {
+	if let Err(err) = try_getentropy(buffer) {
+		getentropy_error(err)
+	}
+}
+
+/// Provides cryptographically secure entropy.
+///
+/// Returns an error if unable to provide secure entropy, the buffer contents are unspecified.
+///
+/// # Panics
+///
+/// Panics after [`Sealed::enforce`](super::Sealed::enforce) has been called.
+///
+/// # Examples
+///
+/// ```
+/// let mut seed = [0u8; 32];
+/// if let Err(err) = urandom::rng::try_getentropy(&mut seed) {
+/// 	eprintln!("{}, continuing without randomness", err);
+/// }
+/// ```
+#[inline]
+pub fn try_getentropy(buffer: &mut [u8]) -> Result<(), EntropyError> {
+	super::sealed::check();
+	try_getentropy_unchecked(buffer)
+}
+
+#[cfg(target_has_atomic = "8")]
+#[inline]
+pub(crate) fn getentropy_unchecked(buffer: &mut [u8]) {
+	if let Err(err) = try_getentropy_unchecked(buffer) {
+		getentropy_error(err)
+	}
+}
+
+#[inline]
+pub(crate) fn try_getentropy_unchecked(buffer: &mut [u8]) -> Result<(), EntropyError> {
+	#[cfg(test)]
+	if let Some(err) = test_entropy::FAILURE.with(|failure| failure.get()) {
+		return Err(err);
+	}
+	backend(buffer)
 }
 
 cfg_if::cfg_if! {
 	if #[cfg(feature = "getrandom")] {
-		/// Provides cryptographically secure entropy.
-		///
-		/// # Panics
-		///
-		/// If unable to provide secure entropy this method will panic.
-		///
-		/// # Implementation notes
-		///
-		/// The implementation is provided by the [`getrandom`](https://crates.io/crates/getrandom) crate.
 		#[inline]
-		pub fn getentropy(buffer: &mut [u8]) {
-			if let Err(_) = ::getrandom::getrandom(buffer) {
-				getentropy_not_ready()
-			}
+		fn backend(buffer: &mut [u8]) -> Result<(), EntropyError> {
+			::getrandom::getrandom(buffer).map_err(|err| {
+				if err == ::getrandom::Error::UNSUPPORTED || err == ::getrandom::Error::NO_RDRAND {
+					return EntropyError::Unsupported;
+				}
+				// The system call is not implemented, eg. denied by a seccomp filter with ENOSYS
+				#[cfg(feature = "std")]
+				if let Some(code) = err.raw_os_error() {
+					if std::io::Error::from_raw_os_error(code).kind() == std::io::ErrorKind::Unsupported {
+						return EntropyError::Unsupported;
+					}
+				}
+				EntropyError::NotReady
+			})
 		}
 	}
 	else {
-		/// Provides cryptographically secure entropy.
-		///
-		/// # Panics
-		///
-		/// If unable to provide secure entropy this method will panic.
-		///
-		/// # Implementation notes
-		///
-		/// The implementation is provided by linking against an extern function.
-		/// If `false` is returned then this function panics.
-		///
-		/// ```
-		/// extern "C" {
-		/// 	fn getentropy_raw(buffer_ptr: *mut u8, buffer_len: usize) -> bool;
-		/// }
-		/// ```
 		#[inline]
-		pub fn getentropy(buffer: &mut [u8]) {
-			if !unsafe { getentropy_raw(buffer.as_mut_ptr(), buffer.len()) } {
-				getentropy_not_ready()
+		fn backend(buffer: &mut [u8]) -> Result<(), EntropyError> {
+			match unsafe { getentropy_raw(buffer.as_mut_ptr(), buffer.len()) } {
+				1 => Ok(()),
+				0 => Err(EntropyError::NotReady),
+				_ => Err(EntropyError::Unsupported),
 			}
 		}
 		extern "C" {
-			fn getentropy_raw(buffer_ptr: *mut u8, buffer_len: usize) -> bool;
+			fn getentropy_raw(buffer_ptr: *mut u8, buffer_len: usize) -> u8;
 		}
 	}
 }
 
 #[cold]
-fn getentropy_not_ready() -> ! {
-	panic!("getentropy not ready")
+pub(super) fn getentropy_error(err: EntropyError) -> ! {
+	panic!("{}", err)
+}
+
+// Simulates a failing entropy source on the current thread in the unit tests
+#[cfg(test)]
+pub(crate) mod test_entropy {
+	use std::cell::Cell;
+	use super::EntropyError;
+
+	std::thread_local! {
+		pub static FAILURE: Cell<Option<EntropyError>> = const { Cell::new(None) };
+	}
+
+	pub fn with_failure<T>(err: EntropyError, f: impl FnOnce() -> T) -> T {
+		struct Reset;
+		impl Drop for Reset {
+			fn drop(&mut self) {
+				FAILURE.with(|failure| failure.set(None));
+			}
+		}
+		FAILURE.with(|failure| failure.set(Some(err)));
+		let _reset = Reset;
+		f()
+	}
+}
+
+// Provide the entropy source to the unit tests when building without getrandom
+// The seeds are hashed from std's randomly keyed hasher
+#[cfg(all(test, not(feature = "getrandom")))]
+#[allow(unsafe_code)]
+mod test_getentropy_raw {
+	use std::hash::{BuildHasher, Hasher};
+
+	#[no_mangle]
+	extern "C" fn getentropy_raw(buffer_ptr: *mut u8, buffer_len: usize) -> u8 {
+		let buffer = unsafe { core::slice::from_raw_parts_mut(buffer_ptr, buffer_len) };
+		let state = std::collections::hash_map::RandomState::new();
+		for (i, chunk) in buffer.chunks_mut(8).enumerate() {
+			let mut hasher = state.build_hasher();
+			hasher.write_usize(i);
+			let value = hasher.finish().to_le_bytes();
+			chunk.copy_from_slice(&value[..chunk.len()]);
+		}
+		1
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_try_getentropy() {
+	use super::*;
+
+	// The fallible and panicking paths agree when entropy works
+	let mut buffer = [0u8; 64];
+	assert_eq!(try_getentropy(&mut buffer), Ok(()));
+	getentropy(&mut buffer);
+	assert!(Xoshiro256::try_new().is_ok());
+	assert!(ChaCha20::try_new().is_ok());
+	assert!(crate::try_new().is_ok());
+	assert!(crate::try_csprng().is_ok());
+
+	for &err in &[EntropyError::NotReady, EntropyError::Unsupported] {
+		test_entropy::with_failure(err, || {
+			assert_eq!(try_getentropy(&mut buffer), Err(err));
+			assert_eq!(Xoshiro256::try_new().err(), Some(err));
+			assert_eq!(ChaCha20::try_new().err(), Some(err));
+			assert_eq!(Philox::try_new().err(), Some(err));
+			assert_eq!(crate::try_new().err(), Some(err));
+			assert_eq!(crate::try_csprng().err(), Some(err));
+		});
+	}
+	assert_eq!(try_getentropy(&mut buffer), Ok(()));
+}
+
+#[test]
+#[should_panic(expected = "getentropy not ready")]
+fn test_new_not_ready() {
+	test_entropy::with_failure(EntropyError::NotReady, || {
+		let _ = crate::new();
+	});
+}
+
+#[test]
+#[should_panic(expected = "getentropy unsupported")]
+fn test_csprng_unsupported() {
+	test_entropy::with_failure(EntropyError::Unsupported, || {
+		let _ = crate::csprng();
+	});
 }
@@ -0,0 +1,171 @@
+use core::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Once;
+use crate::{Random, Rng};
+use super::{JumpStrength, SeedRng};
+
+/// Reseeds the inner generator from system entropy after the process forks.
+///
+/// When a process forks, the child inherits an exact copy of the generator state from its parent.
+/// Without intervention the parent and its children produce identical random values, eg. identical session tokens in pre-fork worker models.
+///
+/// The fork is detected with a process-wide generation counter bumped in the child by a `pthread_atfork` handler.
+/// The handler is registered the first time a `ForkGuard` is created.
+/// Every call checks the generation and transparently reseeds the inner generator with [`SeedRng::new`] in a forked child.
+///
+/// Only available with the `std` feature on unix targets.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::rng::{ChaCha20, ForkGuard};
+///
+/// let mut rng = ForkGuard::<ChaCha20>::new();
+/// let token: [u8; 16] = rng.next();
+/// ```
+#[derive(Clone, Debug)]
+pub struct ForkGuard<R> {
+	rng: R,
+	generation: usize,
+}
+
+impl<R: SeedRng> ForkGuard<R> {
+	/// Creates a new instance seeded securely from system entropy.
+	///
+	/// # Panics
+	///
+	/// If [`getentropy`](super::getentropy) is unable to provide secure entropy this method will panic.
+	#[inline]
+	pub fn new() -> Random<ForkGuard<R>> {
+		let generation = register();
+		Random(ForkGuard { rng: R::new().0, generation })
+	}
+
+	/// Wraps the given generator.
+	///
+	/// After a fork the generator is reseeded from system entropy, the original seed is not used.
+	#[inline]
+	pub fn wrap(rng: Random<R>) -> Random<ForkGuard<R>> {
+		let generation = register();
+		Random(ForkGuard { rng: rng.0, generation })
+	}
+
+	/// Returns a reference to the inner generator.
+	#[inline]
+	pub fn inner(&self) -> &R {
+		&self.rng
+	}
+
+	#[inline]
+	fn check(&mut self) {
+		let generation = GENERATION.load(Ordering::Acquire);
+		if generation != self.generation {
+			self.reseed(generation);
+		}
+	}
+
+	#[cold]
+	fn reseed(&mut self, generation: usize) {
+		self.rng = R::new().0;
+		self.generation = generation;
+	}
+}
+
+impl<R: SeedRng + super::SecureRng> super::SecureRng for ForkGuard<R> {}
+
+impl<R: SeedRng + Rng> Rng for ForkGuard<R> {
+	#[inline]
+	fn next_u32(&mut self) -> u32 {
+		self.check();
+		self.rng.next_u32()
+	}
+	#[inline]
+	fn next_u64(&mut self) -> u64 {
+		self.check();
+		self.rng.next_u64()
+	}
+	#[inline]
+	fn next_f32(&mut self) -> f32 {
+		self.check();
+		self.rng.next_f32()
+	}
+	#[inline]
+	fn next_f64(&mut self) -> f64 {
+		self.check();
+		self.rng.next_f64()
+	}
+	#[inline]
+	fn fill_u32(&mut self, buffer: &mut [u32]) {
+		self.check();
+		self.rng.fill_u32(buffer)
+	}
+	#[inline]
+	fn fill_u64(&mut self, buffer: &mut [u64]) {
+		self.check();
+		self.rng.fill_u64(buffer)
+	}
+	#[inline]
+	fn fill_bytes(&mut self, buffer: &mut [u8]) {
+		self.check();
+		self.rng.fill_bytes(buffer)
+	}
+	#[inline]
+	fn jump(&mut self) {
+		self.check();
+		self.rng.jump()
+	}
+	#[inline]
+	fn jump_support(&self) -> JumpStrength {
+		self.rng.jump_support()
+	}
+}
+
+//----------------------------------------------------------------
+// Fork detection implementation details
+
+static GENERATION: AtomicUsize = AtomicUsize::new(0);
+static REGISTER: Once = Once::new();
+
+extern "C" {
+	fn pthread_atfork(prepare: Option<extern "C" fn()>, parent: Option<extern "C" fn()>, child: Option<extern "C" fn()>) -> i32;
+}
+
+extern "C" fn on_fork_child() {
+	GENERATION.fetch_add(1, Ordering::AcqRel);
+}
+
+// Registers the fork handler and returns the current generation
+#[inline]
+fn register() -> usize {
+	REGISTER.call_once(|| {
+		let result = unsafe { pthread_atfork(None, None, Some(on_fork_child)) };
+		if result != 0 {
+			register_failed(result);
+		}
+	});
+	GENERATION.load(Ordering::Acquire)
+}
+
+#[cold]
+fn register_failed(result: i32) -> ! {
+	panic!("pthread_atfork failed with error code {}", result)
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_reseed_on_generation_bump() {
+	use super::ChaCha20;
+
+	let mut rng = ForkGuard::wrap(ChaCha20::from_seed(42));
+	let mut reference = ChaCha20::from_seed(42);
+	assert_eq!(rng.next_u64(), reference.next_u64());
+
+	// Simulate a fork by running the child handler
+	on_fork_child();
+	assert_ne!(rng.next_u64(), reference.next_u64());
+	assert_ne!(rng.0.inner().to_seed_bytes(), reference.0.to_seed_bytes());
+
+	// No further reseeding until the next fork
+	let mut copy = rng.0.inner().clone();
+	assert_eq!(rng.next_u64(), copy.next_u64());
+}
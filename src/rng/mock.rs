@@ -1,5 +1,6 @@
 use core::{iter, slice};
 use crate::{Random, Rng};
+use super::JumpStrength;
 
 /// Random number generator mock.
 ///
@@ -66,4 +67,8 @@ impl<I> Rng for MockRng<I> where I: Iterator<Item = u64> {
 	fn jump(&mut self) {
 		// This method is intentionally left blank.
 	}
+	#[inline]
+	fn jump_support(&self) -> JumpStrength {
+		JumpStrength::None
+	}
 }
@@ -0,0 +1,254 @@
+use crate::{Random, Rng};
+use super::{EntropyError, FromSeedSeq, JumpStrength, SeedRng};
+
+/// John K. Salmon et al.'s Philox4x32-10 counter-based random number generator.
+///
+/// Philox is the generator of choice for GPU computing (eg. cuRAND and the [Random123](https://www.thesalmons.org/john/random123/) library).
+/// The same `(key, counter)` pairs produce the exact same 4×32-bit blocks as the reference implementation,
+/// which allows sharing bit-exact random streams between this crate and GPU kernels.
+///
+/// The output is the sequence of 32-bit words of the blocks for consecutive counters, the counter is incremented as a 128-bit little-endian integer.
+/// `next_u64` combines two consecutive words with the first word in the low half.
+///
+/// # Examples
+///
+/// ```
+/// let mut rng = urandom::rng::Philox::from_key_counter([1, 2], [0, 0, 0, 0]);
+/// let value: u32 = rng.next();
+/// ```
+#[derive(Clone, Debug)]
+pub struct Philox {
+	key: [u32; 2],
+	// The counter of the next block to generate
+	counter: [u32; 4],
+	// The Rng produces 4 words per block
+	random: [u32; 4],
+	// Consume the random words before producing more
+	index: u32,
+}
+
+impl SeedRng for Philox {
+	#[inline]
+	fn try_new() -> Result<Random<Philox>, EntropyError> {
+		let mut key = [0u32; 2];
+		super::try_getentropy(dataview::bytes_mut(&mut key))?;
+		Ok(Philox::from_key_counter(key, [0; 4]))
+	}
+	#[inline]
+	fn from_rng<R: Rng + ?Sized>(rng: &mut Random<R>) -> Random<Philox> {
+		let mut key = [0u32; 2];
+		rng.fill_u32(&mut key);
+		Philox::from_key_counter(key, [0; 4])
+	}
+	/// The seed is used as the key with the low word first, the counter starts at zero.
+	#[inline]
+	fn from_seed(seed: u64) -> Random<Philox> {
+		Philox::from_key_counter([seed as u32, (seed >> 32) as u32], [0; 4])
+	}
+}
+
+forward_seed_rng_impl!(Philox);
+
+impl FromSeedSeq for Philox {}
+
+impl Philox {
+	/// Creates a new generator from the given key and counter.
+	#[inline]
+	pub fn from_key_counter(key: [u32; 2], counter: [u32; 4]) -> Random<Philox> {
+		Random(Philox { key, counter, random: [0; 4], index: !0 })
+	}
+	/// Creates a new generator from the key bytes as two little-endian `u32` words, the counter starts at zero.
+	///
+	/// This is equivalent to [`from_seed`](Philox::from_seed) with the seed in little-endian byte order.
+	/// _Changing_ the implementation of this function is considered a breaking change.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::rng::Philox::from_seed_bytes([0x01; 8]);
+	/// assert_eq!(rng.next_u64(), 0x72b4006322d5a63e);
+	/// ```
+	#[inline]
+	pub fn from_seed_bytes(seed: [u8; 8]) -> Random<Philox> {
+		Philox::from_seed(u64::from_le_bytes(seed))
+	}
+	/// Returns the key in little-endian byte order.
+	#[inline]
+	pub fn to_seed_bytes(&self) -> [u8; 8] {
+		(self.key[0] as u64 | (self.key[1] as u64) << 32).to_le_bytes()
+	}
+	/// Returns the key.
+	#[inline]
+	pub fn key(&self) -> [u32; 2] {
+		self.key
+	}
+	/// Returns the counter of the next block to generate.
+	///
+	/// Any buffered words from the current block are not accounted for.
+	#[inline]
+	pub fn get_counter(&self) -> [u32; 4] {
+		self.counter
+	}
+	/// Sets the counter of the next block to generate.
+	///
+	/// Any buffered words from the current block are discarded.
+	#[inline]
+	pub fn set_counter(&mut self, counter: [u32; 4]) {
+		self.counter = counter;
+		self.index = !0;
+	}
+}
+
+impl Rng for Philox {
+	#[inline]
+	fn next_u32(&mut self) -> u32 {
+		let mut index = self.index as usize;
+		// Generate a new block if there are no more random words
+		if index >= BLOCK_WORDS {
+			self.random = philox4x32(self.counter, self.key);
+			increment_counter(&mut self.counter);
+			index = 0;
+		}
+		let value = self.random[index];
+		self.index = (index + 1) as u32;
+		value
+	}
+	#[inline]
+	fn next_u64(&mut self) -> u64 {
+		let low = self.next_u32() as u64;
+		let high = self.next_u32() as u64;
+		high << 32 | low
+	}
+	#[inline]
+	fn fill_u32(&mut self, buffer: &mut [u32]) {
+		for elem in buffer {
+			*elem = self.next_u32();
+		}
+	}
+	#[inline]
+	fn fill_u64(&mut self, buffer: &mut [u64]) {
+		for elem in buffer {
+			*elem = self.next_u64();
+		}
+	}
+	#[inline(never)]
+	fn fill_bytes(&mut self, buffer: &mut [u8]) {
+		let mut chunks = buffer.chunks_exact_mut(4);
+		for chunk in &mut chunks {
+			chunk.copy_from_slice(&self.next_u32().to_le_bytes());
+		}
+		let rem = chunks.into_remainder();
+		if rem.len() > 0 {
+			let len = rem.len();
+			rem.copy_from_slice(&self.next_u32().to_le_bytes()[..len]);
+		}
+	}
+	/// Advances the high word of the counter, discarding any buffered words.
+	#[inline]
+	fn jump(&mut self) {
+		self.counter[3] = self.counter[3].wrapping_add(1);
+		self.index = !0;
+	}
+	#[inline]
+	fn jump_support(&self) -> JumpStrength {
+		JumpStrength::Strong
+	}
+}
+
+//----------------------------------------------------------------
+// Philox implementation details
+// http://www.thesalmons.org/john/random123/papers/random123sc11.pdf
+
+const BLOCK_WORDS: usize = 4;
+const PHILOX_M0: u32 = 0xD2511F53;
+const PHILOX_M1: u32 = 0xCD9E8D57;
+const PHILOX_W0: u32 = 0x9E3779B9;
+const PHILOX_W1: u32 = 0xBB67AE85;
+
+#[inline]
+fn mulhilo(a: u32, b: u32) -> (u32, u32) {
+	let full = a as u64 * b as u64;
+	((full >> 32) as u32, full as u32)
+}
+
+#[inline]
+fn philox4x32(mut ctr: [u32; 4], mut key: [u32; 2]) -> [u32; 4] {
+	for round in 0..10 {
+		if round > 0 {
+			key[0] = key[0].wrapping_add(PHILOX_W0);
+			key[1] = key[1].wrapping_add(PHILOX_W1);
+		}
+		let (hi0, lo0) = mulhilo(PHILOX_M0, ctr[0]);
+		let (hi1, lo1) = mulhilo(PHILOX_M1, ctr[2]);
+		ctr = [hi1 ^ ctr[1] ^ key[0], lo1, hi0 ^ ctr[3] ^ key[1], lo0];
+	}
+	ctr
+}
+
+#[inline]
+fn increment_counter(ctr: &mut [u32; 4]) {
+	for word in ctr {
+		*word = word.wrapping_add(1);
+		if *word != 0 {
+			break;
+		}
+	}
+}
+
+#[test]
+fn test_known_answers() {
+	// Known answer test vectors from Random123's kat_vectors
+	let vectors: [([u32; 4], [u32; 2], [u32; 4]); 3] = [
+		([0, 0, 0, 0], [0, 0], [0x6627e8d5, 0xe169c58d, 0xbc57ac4c, 0x9b00dbd8]),
+		([!0, !0, !0, !0], [!0, !0], [0x408f276d, 0x41c83b0e, 0xa20bc7c6, 0x6d5451fd]),
+		([0x243f6a88, 0x85a308d3, 0x13198a2e, 0x03707344], [0xa4093822, 0x299f31d0], [0xd16cfe09, 0x94fdcceb, 0x5001e420, 0x24126ea1]),
+	];
+	for &(counter, key, expected) in &vectors {
+		assert_eq!(philox4x32(counter, key), expected);
+		let mut rng = Philox::from_key_counter(key, counter);
+		let mut result = [0u32; 4];
+		rng.fill_u32(&mut result);
+		assert_eq!(result, expected);
+	}
+}
+
+#[test]
+fn test_counter() {
+	let mut rng = Philox::from_key_counter([1, 2], [!0, !0, 0, 0]);
+	let _ = rng.next_u32();
+	assert_eq!(rng.0.get_counter(), [0, 0, 1, 0]);
+	rng.jump();
+	assert_eq!(rng.0.get_counter(), [0, 0, 1, 1]);
+
+	// Setting the counter reproduces the block
+	let mut rng = Philox::from_seed(42);
+	let first = rng.next_u64();
+	rng.0.set_counter([0; 4]);
+	assert_eq!(rng.next_u64(), first);
+}
+
+#[test]
+fn test_seed_bytes() {
+	for seed in [0, 1, 42, u64::MAX] {
+		let mut a = Philox::from_seed(seed);
+		let mut b = Philox::from_seed_bytes(seed.to_le_bytes());
+		assert_eq!(a.0.to_seed_bytes(), seed.to_le_bytes());
+		assert_eq!(a.next_u64(), b.next_u64());
+	}
+}
+
+#[test]
+fn test_stream() {
+	// Words are produced in order regardless of the method used to request them
+	let mut a = Philox::from_seed(13);
+	let mut b = Philox::from_seed(13);
+	let _ = a.next_u32();
+	let _ = b.next_u32();
+	let mut bytes = [0u8; 22];
+	a.fill_bytes(&mut bytes);
+	for chunk in bytes.chunks(4) {
+		let word = b.next_u32().to_le_bytes();
+		assert_eq!(chunk, &word[..chunk.len()]);
+	}
+	assert_eq!(a.next_u64(), b.next_u64());
+}
@@ -1,5 +1,6 @@
 use std::io;
 use crate::{Random, Rng};
+use super::JumpStrength;
 
 /// An RNG that reads random bytes straight from any type supporting [`std::io::Read`], for example files.
 ///
@@ -10,6 +11,10 @@ use crate::{Random, Rng};
 /// `ReadRng` uses [`std::io::Read::read_exact`], which retries on interrupts.
 /// All other errors from the underlying reader, including when it does not have enough data, will panic in case of an error.
 ///
+/// # Safety
+///
+/// The reader is only ever handed initialized buffers, as required by the contract of [`std::io::Read`].
+///
 /// # Examples
 ///
 /// ```
@@ -50,12 +55,18 @@ impl<R: io::Read> Rng for ReadRng<R> {
 		if let Err(err) = self.0.read_exact(dataview::bytes_mut(buffer)) {
 			read_failed(err);
 		}
+		for word in buffer {
+			*word = u32::from_le(*word);
+		}
 	}
 	#[inline]
 	fn fill_u64(&mut self, buffer: &mut [u64]) {
 		if let Err(err) = self.0.read_exact(dataview::bytes_mut(buffer)) {
 			read_failed(err);
 		}
+		for word in buffer {
+			*word = u64::from_le(*word);
+		}
 	}
 	#[inline]
 	fn fill_bytes(&mut self, buffer: &mut [u8]) {
@@ -65,6 +76,10 @@ impl<R: io::Read> Rng for ReadRng<R> {
 	}
 	#[inline]
 	fn jump(&mut self) {}
+	#[inline]
+	fn jump_support(&self) -> JumpStrength {
+		JumpStrength::None
+	}
 }
 
 #[inline(never)]
@@ -97,6 +112,22 @@ fn test_next_u32() {
 	assert_eq!(rng.next_u32(), 3);
 }
 
+#[test]
+fn test_fill_words() {
+	// The words are read in little-endian order like next_u32 and next_u64
+	let v = [0u8, 0, 0, 1, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 3];
+	let mut rng = ReadRng::new(&v[..]);
+	let mut words = [0u32; 2];
+	rng.fill_u32(&mut words);
+	assert_eq!(words, [1 << 24, 2 << 16]);
+	let mut words = [0u64; 1];
+	rng.fill_u64(&mut words);
+	assert_eq!(words, [3 << 56]);
+
+	let data: Vec<u8> = (0..10000).map(|i| (i * 7 + i / 256) as u8).collect();
+	super::check_fill_words(ReadRng(&data[..]));
+}
+
 #[test]
 fn test_fill_bytes() {
 	let v = [1u8, 2, 3, 4, 5, 6, 7, 8];
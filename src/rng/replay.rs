@@ -0,0 +1,399 @@
+use core::fmt;
+use alloc::vec::Vec;
+use crate::{Random, Rng};
+use super::JumpStrength;
+
+/// A single call recorded by [`Recorder`](Recorder) along with the values it produced.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RngRecord {
+	/// A call to [`Rng::next_u32`](Rng::next_u32).
+	U32(u32),
+	/// A call to [`Rng::next_u64`](Rng::next_u64).
+	U64(u64),
+	/// A call to [`Rng::next_f32`](Rng::next_f32).
+	F32(f32),
+	/// A call to [`Rng::next_f64`](Rng::next_f64).
+	F64(f64),
+	/// A call to [`Rng::fill_u32`](Rng::fill_u32).
+	FillU32(Vec<u32>),
+	/// A call to [`Rng::fill_u64`](Rng::fill_u64).
+	FillU64(Vec<u64>),
+	/// A call to [`Rng::fill_bytes`](Rng::fill_bytes).
+	FillBytes(Vec<u8>),
+	/// A call to [`Rng::jump`](Rng::jump).
+	Jump,
+}
+
+impl RngRecord {
+	#[inline]
+	fn call(&self) -> Call {
+		match self {
+			RngRecord::U32(_) => Call::NextU32,
+			RngRecord::U64(_) => Call::NextU64,
+			RngRecord::F32(_) => Call::NextF32,
+			RngRecord::F64(_) => Call::NextF64,
+			RngRecord::FillU32(words) => Call::FillU32(words.len()),
+			RngRecord::FillU64(words) => Call::FillU64(words.len()),
+			RngRecord::FillBytes(bytes) => Call::FillBytes(bytes.len()),
+			RngRecord::Jump => Call::Jump,
+		}
+	}
+}
+
+// Describes a call for the divergence messages
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Call {
+	NextU32,
+	NextU64,
+	NextF32,
+	NextF64,
+	FillU32(usize),
+	FillU64(usize),
+	FillBytes(usize),
+	Jump,
+}
+
+impl fmt::Display for Call {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			Call::NextU32 => f.write_str("next_u32"),
+			Call::NextU64 => f.write_str("next_u64"),
+			Call::NextF32 => f.write_str("next_f32"),
+			Call::NextF64 => f.write_str("next_f64"),
+			Call::FillU32(len) => write!(f, "fill_u32 of {} words", len),
+			Call::FillU64(len) => write!(f, "fill_u64 of {} words", len),
+			Call::FillBytes(len) => write!(f, "fill_bytes of {} bytes", len),
+			Call::Jump => f.write_str("jump"),
+		}
+	}
+}
+
+/// Records every value produced by the inner generator.
+///
+/// All calls are forwarded unchanged and appended to an in-memory log, retrieve it with [`into_log`](Recorder::into_log).
+/// Pass the log to [`Replayer`](Replayer) to replay the exact values later, eg. to bisect where a simulation diverges after code changes.
+///
+/// The log grows with every call, only record bounded workloads.
+///
+/// # Debug
+///
+/// The `Debug` implementation prints the length of the log, the recorded values are the secret output of the inner generator.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::rng::{Recorder, Replayer};
+///
+/// let mut rng = urandom::seeded(42).recording();
+/// let a: f64 = rng.next();
+/// let b = rng.range(0..100);
+/// let log = rng.0.into_log();
+///
+/// let mut replay = Replayer::new(log);
+/// assert_eq!(replay.next::<f64>(), a);
+/// assert_eq!(replay.range(0..100), b);
+/// ```
+#[derive(Clone)]
+pub struct Recorder<R: ?Sized> {
+	log: Vec<RngRecord>,
+	rng: R,
+}
+
+impl<R: fmt::Debug + ?Sized> fmt::Debug for Recorder<R> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Recorder")
+			.field("log_len", &self.log.len())
+			.field("rng", &&self.rng)
+			.finish()
+	}
+}
+
+impl<R> Recorder<R> {
+	/// Wraps the generator with an empty log.
+	#[inline]
+	pub const fn new(rng: R) -> Recorder<R> {
+		Recorder { log: Vec::new(), rng }
+	}
+	/// Returns the recorded log.
+	#[inline]
+	pub fn into_log(self) -> Vec<RngRecord> {
+		self.log
+	}
+	/// Returns the recorded log and the inner generator.
+	#[inline]
+	pub fn into_parts(self) -> (Vec<RngRecord>, R) {
+		(self.log, self.rng)
+	}
+}
+
+impl<R: ?Sized> Recorder<R> {
+	/// Returns the log recorded so far.
+	#[inline]
+	pub fn log(&self) -> &[RngRecord] {
+		&self.log
+	}
+	/// Returns a reference to the inner generator.
+	#[inline]
+	pub fn inner(&self) -> &R {
+		&self.rng
+	}
+}
+
+impl<R: super::SecureRng + ?Sized> super::SecureRng for Recorder<R> {}
+
+impl<R: Rng + ?Sized> Rng for Recorder<R> {
+	#[inline]
+	fn next_u32(&mut self) -> u32 {
+		let value = self.rng.next_u32();
+		self.log.push(RngRecord::U32(value));
+		value
+	}
+	#[inline]
+	fn next_u64(&mut self) -> u64 {
+		let value = self.rng.next_u64();
+		self.log.push(RngRecord::U64(value));
+		value
+	}
+	#[inline]
+	fn next_f32(&mut self) -> f32 {
+		let value = self.rng.next_f32();
+		self.log.push(RngRecord::F32(value));
+		value
+	}
+	#[inline]
+	fn next_f64(&mut self) -> f64 {
+		let value = self.rng.next_f64();
+		self.log.push(RngRecord::F64(value));
+		value
+	}
+	#[inline]
+	fn fill_u32(&mut self, buffer: &mut [u32]) {
+		self.rng.fill_u32(buffer);
+		self.log.push(RngRecord::FillU32(buffer.to_vec()));
+	}
+	#[inline]
+	fn fill_u64(&mut self, buffer: &mut [u64]) {
+		self.rng.fill_u64(buffer);
+		self.log.push(RngRecord::FillU64(buffer.to_vec()));
+	}
+	#[inline]
+	fn fill_bytes(&mut self, buffer: &mut [u8]) {
+		self.rng.fill_bytes(buffer);
+		self.log.push(RngRecord::FillBytes(buffer.to_vec()));
+	}
+	#[inline]
+	fn jump(&mut self) {
+		self.rng.jump();
+		self.log.push(RngRecord::Jump);
+	}
+	#[inline]
+	fn jump_support(&self) -> JumpStrength {
+		self.rng.jump_support()
+	}
+}
+
+/// Replays the values recorded by a [`Recorder`](Recorder).
+///
+/// Every call must match the recorded call in order: the same method and, for the fill methods, the same length.
+///
+/// # Panics
+///
+/// Panics when the replay diverges from the log, the message contains the index of the call and the expected and actual call.
+/// Also panics when the log runs out.
+///
+/// # Examples
+///
+/// ```should_panic
+/// use urandom::rng::{Replayer, RngRecord};
+///
+/// let mut rng = Replayer::new(vec![RngRecord::U64(42), RngRecord::U32(13)]);
+/// assert_eq!(rng.next_u64(), 42);
+/// // panics: replay diverged at call 1: expected next_u32, found next_u64
+/// rng.next_u64();
+/// ```
+#[derive(Clone, Debug)]
+pub struct Replayer {
+	log: Vec<RngRecord>,
+	index: usize,
+}
+
+impl Replayer {
+	/// Creates a generator replaying the log from the start.
+	#[inline]
+	pub fn new(log: Vec<RngRecord>) -> Random<Replayer> {
+		Random(Replayer { log, index: 0 })
+	}
+	/// Returns the index of the next call in the log.
+	#[inline]
+	pub fn position(&self) -> usize {
+		self.index
+	}
+	/// Returns the number of calls remaining in the log.
+	#[inline]
+	pub fn remaining(&self) -> usize {
+		self.log.len() - self.index
+	}
+	#[inline]
+	fn replay(&mut self, call: Call) -> &RngRecord {
+		let index = self.index;
+		let Some(record) = self.log.get(index) else {
+			replay_exhausted(index, call)
+		};
+		if record.call() != call {
+			replay_diverged(index, record.call(), call);
+		}
+		self.index += 1;
+		record
+	}
+}
+
+impl Rng for Replayer {
+	#[inline]
+	fn next_u32(&mut self) -> u32 {
+		match *self.replay(Call::NextU32) {
+			RngRecord::U32(value) => value,
+			_ => unreachable!(),
+		}
+	}
+	#[inline]
+	fn next_u64(&mut self) -> u64 {
+		match *self.replay(Call::NextU64) {
+			RngRecord::U64(value) => value,
+			_ => unreachable!(),
+		}
+	}
+	#[inline]
+	fn next_f32(&mut self) -> f32 {
+		match *self.replay(Call::NextF32) {
+			RngRecord::F32(value) => value,
+			_ => unreachable!(),
+		}
+	}
+	#[inline]
+	fn next_f64(&mut self) -> f64 {
+		match *self.replay(Call::NextF64) {
+			RngRecord::F64(value) => value,
+			_ => unreachable!(),
+		}
+	}
+	#[inline]
+	fn fill_u32(&mut self, buffer: &mut [u32]) {
+		match self.replay(Call::FillU32(buffer.len())) {
+			RngRecord::FillU32(words) => buffer.copy_from_slice(words),
+			_ => unreachable!(),
+		}
+	}
+	#[inline]
+	fn fill_u64(&mut self, buffer: &mut [u64]) {
+		match self.replay(Call::FillU64(buffer.len())) {
+			RngRecord::FillU64(words) => buffer.copy_from_slice(words),
+			_ => unreachable!(),
+		}
+	}
+	#[inline]
+	fn fill_bytes(&mut self, buffer: &mut [u8]) {
+		match self.replay(Call::FillBytes(buffer.len())) {
+			RngRecord::FillBytes(bytes) => buffer.copy_from_slice(bytes),
+			_ => unreachable!(),
+		}
+	}
+	#[inline]
+	fn jump(&mut self) {
+		self.replay(Call::Jump);
+	}
+	#[inline]
+	fn jump_support(&self) -> JumpStrength {
+		JumpStrength::None
+	}
+}
+
+#[cold]
+fn replay_exhausted(index: usize, found: Call) -> ! {
+	panic!("replay log exhausted at call {}: found {}", index, found);
+}
+
+#[cold]
+fn replay_diverged(index: usize, expected: Call, found: Call) -> ! {
+	panic!("replay diverged at call {}: expected {}, found {}", index, expected, found);
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_roundtrip() {
+	let mut rng = crate::seeded(42).recording();
+	let a: u32 = rng.next_u32();
+	let b: u64 = rng.next_u64();
+	let c: f64 = rng.next();
+	let d: f32 = rng.range(1.0..2.0);
+	let mut e = [0u8; 100];
+	rng.fill_bytes(&mut e);
+	let f = rng.range(0..1000);
+	let mut g = [0u32; 3];
+	rng.fill_u32(&mut g);
+	let mut h = [0u64; 5];
+	rng.fill_u64(&mut h);
+	rng.jump();
+	let i: [u64; 2] = rng.next();
+	let log = rng.0.into_log();
+
+	let mut replay = Replayer::new(log.clone());
+	assert_eq!(replay.next_u32(), a);
+	assert_eq!(replay.next_u64(), b);
+	assert_eq!(replay.next::<f64>(), c);
+	assert_eq!(replay.range(1.0f32..2.0), d);
+	let mut buf = [0u8; 100];
+	replay.fill_bytes(&mut buf);
+	assert_eq!(buf, e);
+	assert_eq!(replay.range(0..1000), f);
+	let mut buf = [0u32; 3];
+	replay.fill_u32(&mut buf);
+	assert_eq!(buf, g);
+	let mut buf = [0u64; 5];
+	replay.fill_u64(&mut buf);
+	assert_eq!(buf, h);
+	replay.jump();
+	assert_eq!(replay.next::<[u64; 2]>(), i);
+	assert_eq!(replay.0.remaining(), 0);
+	assert_eq!(replay.0.position(), log.len());
+}
+
+#[test]
+fn test_debug_redacted() {
+	let mut rng = super::ChaCha20::from_seed(42).recording();
+	let value = rng.next_u64();
+	rng.fill_bytes(&mut [0u8; 16]);
+	let dump = format!("{:?}", rng.0);
+	assert!(dump.starts_with("Recorder { log_len: 2, rng: ChaCha20 { key: <redacted "), "{}", dump);
+	assert!(!dump.contains(&value.to_string()), "{}", dump);
+}
+
+#[test]
+fn test_diverged() {
+	let mut rng = crate::seeded(42).recording();
+	let _: u64 = rng.next_u64();
+	let _: u32 = rng.next_u32();
+	rng.fill_bytes(&mut [0u8; 16]);
+	let log = rng.0.into_log();
+
+	let result = std::panic::catch_unwind(move || {
+		let mut replay = Replayer::new(log);
+		replay.next_u64();
+		replay.next_u64();
+	});
+	let message = *result.unwrap_err().downcast::<String>().unwrap();
+	assert_eq!(message, "replay diverged at call 1: expected next_u32, found next_u64");
+
+	let log = vec![RngRecord::FillBytes(vec![0; 16])];
+	let result = std::panic::catch_unwind(move || {
+		Replayer::new(log).fill_bytes(&mut [0u8; 15]);
+	});
+	let message = *result.unwrap_err().downcast::<String>().unwrap();
+	assert_eq!(message, "replay diverged at call 0: expected fill_bytes of 16 bytes, found fill_bytes of 15 bytes");
+
+	let result = std::panic::catch_unwind(|| {
+		Replayer::new(Vec::new()).jump();
+	});
+	let message = *result.unwrap_err().downcast::<String>().unwrap();
+	assert_eq!(message, "replay log exhausted at call 0: found jump");
+}
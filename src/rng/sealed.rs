@@ -0,0 +1,91 @@
+use core::sync::atomic::{AtomicU8, Ordering};
+use crate::Random;
+use super::ChaCha20;
+
+const INIT: u8 = 1;
+const ENFORCED: u8 = 2;
+
+static STATE: AtomicU8 = AtomicU8::new(0);
+
+/// Derives all randomness from a single entropy pull at startup.
+///
+/// Security-sensitive applications may want to audit the one place where the process reads system entropy,
+/// and detect any later accidental use of [`getentropy`](super::getentropy), eg. because it may be interposed in a container.
+///
+/// 1. [`Sealed::init`](Sealed::init) pulls entropy once and returns the root generator, all other generators should be derived from it.
+/// 2. [`Sealed::enforce`](Sealed::enforce) makes every subsequent call to [`getentropy`](super::getentropy) panic with a descriptive message.
+///    This includes [`urandom::new`](crate::new), [`urandom::csprng`](crate::csprng) and [`SeedRng::new`](super::SeedRng::new) of every generator.
+///
+/// This is opt-in and off by default, until `enforce` is called the entropy source behaves as usual.
+/// The check costs a single relaxed atomic load per call to `getentropy`.
+///
+/// Only available on targets with 8-bit atomics, eg. not on `thumbv6m-none-eabi`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use urandom::rng::Sealed;
+///
+/// let mut root = Sealed::init();
+/// Sealed::enforce();
+///
+/// // Derive generators from the root generator
+/// let mut rng = urandom::rng::Xoshiro256::from_rng(&mut root);
+/// let value: u32 = rng.next();
+///
+/// // Panics: getentropy called after Sealed::enforce
+/// let _ = urandom::new();
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Sealed;
+
+impl Sealed {
+	/// Pulls entropy once and returns the root generator.
+	///
+	/// # Panics
+	///
+	/// Panics if called more than once, if called after [`enforce`](Sealed::enforce) or if the entropy source is unable to provide secure entropy.
+	pub fn init() -> Random<ChaCha20> {
+		if STATE.fetch_or(INIT, Ordering::AcqRel) != 0 {
+			sealed_init_error();
+		}
+		let mut seed = [0u8; 32];
+		super::entropy::getentropy_unchecked(&mut seed);
+		ChaCha20::from_seed_bytes(seed)
+	}
+	/// Makes every subsequent call to [`getentropy`](super::getentropy) panic.
+	///
+	/// This cannot be undone.
+	#[inline]
+	pub fn enforce() {
+		STATE.fetch_or(ENFORCED, Ordering::Release);
+	}
+	/// Returns `true` if [`init`](Sealed::init) has been called.
+	#[inline]
+	pub fn is_init() -> bool {
+		STATE.load(Ordering::Relaxed) & INIT != 0
+	}
+	/// Returns `true` if [`enforce`](Sealed::enforce) has been called.
+	#[inline]
+	pub fn is_enforced() -> bool {
+		STATE.load(Ordering::Relaxed) & ENFORCED != 0
+	}
+}
+
+/// Panics if the entropy source has been sealed.
+#[inline]
+pub(crate) fn check() {
+	if STATE.load(Ordering::Relaxed) & ENFORCED != 0 {
+		sealed_entropy_error();
+	}
+}
+
+#[cold]
+fn sealed_init_error() -> ! {
+	panic!("Sealed::init called more than once or after Sealed::enforce")
+}
+
+#[cold]
+fn sealed_entropy_error() -> ! {
+	panic!("getentropy called after Sealed::enforce, derive all randomness from the generator returned by Sealed::init")
+}
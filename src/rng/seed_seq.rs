@@ -0,0 +1,237 @@
+use crate::{Random, Rng};
+use crate::hash::mix64;
+use super::{FromSeedSeq, JumpStrength};
+
+const GOLDEN_GAMMA: u64 = 0x9e3779b97f4a7c15;
+
+/// Seed sequence for spawning reproducible, decorrelated child seeds.
+///
+/// Inspired by NumPy's `SeedSequence`, a seed sequence is identified by its root seed and a spawn key:
+/// the path of child indices leading from the root to this node in the tree of spawned seeds.
+/// Unlike [`jump`](Rng::jump) or seeding from a parent generator the resulting seeds depend only on this position in the tree,
+/// not on the order in which children were spawned or how much randomness was consumed elsewhere.
+///
+/// The seed and spawn key are absorbed into a 256-bit pool using the SplitMix64 finalizer.
+/// The generators are then seeded from a counter-based stream of the mixed pool words.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::SeedSeq;
+/// use urandom::rng::Xoshiro256;
+///
+/// let root = SeedSeq::new(42);
+/// // One generator per named phase of the simulation
+/// let mut setup = root.child(0).seed_rng::<Xoshiro256>();
+/// let mut simulate = root.child(1).seed_rng::<Xoshiro256>();
+///
+/// // The children are reproducible regardless of the order they are created in
+/// assert_eq!(simulate.next_u64(), root.child(1).seed_rng::<Xoshiro256>().next_u64());
+/// # let _ = setup.next_u64();
+/// ```
+#[derive(Clone, Debug)]
+pub struct SeedSeq {
+	pool: [u64; 4],
+	#[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+	spawned: u64,
+}
+
+impl SeedSeq {
+	/// Creates a new root seed sequence from the given seed.
+	#[inline]
+	pub const fn new(seed: u64) -> SeedSeq {
+		let mut pool = [0; 4];
+		let mut k = 0;
+		while k < pool.len() {
+			pool[k] = mix64(seed ^ mix64((k as u64 + 1).wrapping_mul(GOLDEN_GAMMA)));
+			k += 1;
+		}
+		SeedSeq { pool, spawned: 0 }
+	}
+
+	/// Creates a seed sequence from the given seed and spawn key.
+	///
+	/// This is equivalent to calling [`child`](SeedSeq::child) for every element of the spawn key in order.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use urandom::SeedSeq;
+	///
+	/// let a = SeedSeq::with_spawn_key(42, &[3, 1]);
+	/// let b = SeedSeq::new(42).child(3).child(1);
+	/// let mut state = ([0u64; 4], [0u64; 4]);
+	/// a.generate_state(&mut state.0);
+	/// b.generate_state(&mut state.1);
+	/// assert_eq!(state.0, state.1);
+	/// ```
+	#[inline]
+	pub fn with_spawn_key(seed: u64, spawn_key: &[u64]) -> SeedSeq {
+		let mut seq = SeedSeq::new(seed);
+		for &index in spawn_key {
+			seq = seq.child(index);
+		}
+		seq
+	}
+
+	/// Returns the child seed sequence at the given index.
+	///
+	/// The child depends only on this seed sequence and the index.
+	#[inline]
+	pub const fn child(&self, index: u64) -> SeedSeq {
+		let mut pool = self.pool;
+		let mut k = 0;
+		while k < pool.len() {
+			let word = mix64(index.wrapping_add((k as u64 + 1).wrapping_mul(GOLDEN_GAMMA)));
+			pool[k] = mix64((pool[k] ^ pool[(k + 1) % 4].rotate_left(17)).wrapping_add(GOLDEN_GAMMA) ^ word);
+			k += 1;
+		}
+		SeedSeq { pool, spawned: 0 }
+	}
+
+	/// Returns the next `n` children of this seed sequence.
+	///
+	/// Keeps track of the number of children spawned so far, repeated calls return new children.
+	/// The `i`th spawned child is identical to [`child(i)`](SeedSeq::child).
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use urandom::SeedSeq;
+	/// use urandom::rng::SplitMix64;
+	///
+	/// let mut root = SeedSeq::new(42);
+	/// let workers: Vec<_> = root.spawn(4).iter().map(|seq| seq.seed_rng::<SplitMix64>()).collect();
+	/// assert_eq!(workers.len(), 4);
+	/// ```
+	#[cfg(feature = "alloc")]
+	pub fn spawn(&mut self, n: usize) -> alloc::vec::Vec<SeedSeq> {
+		let start = self.spawned;
+		self.spawned += n as u64;
+		(start..self.spawned).map(|index| self.child(index)).collect()
+	}
+
+	/// Fills the buffer with seed material.
+	///
+	/// The output is reproducible given the same seed and spawn key.
+	/// _Changing_ the output of this function should be considered a breaking change.
+	#[inline]
+	pub fn generate_state(&self, state: &mut [u64]) {
+		let mut stream = SeedSeqStream { pool: self.pool, counter: 0 };
+		for elem in state {
+			*elem = stream.next_u64();
+		}
+	}
+
+	/// Creates a new PRNG seeded from this seed sequence.
+	///
+	/// See the [`FromSeedSeq`](FromSeedSeq) trait for more information.
+	#[inline]
+	pub fn seed_rng<R: FromSeedSeq>(&self) -> Random<R> {
+		R::from_seed_seq(self)
+	}
+
+	/// Returns a generator producing the seed material.
+	#[inline]
+	pub(crate) fn stream(&self) -> Random<impl Rng + Clone> {
+		Random(SeedSeqStream { pool: self.pool, counter: 0 })
+	}
+}
+
+//----------------------------------------------------------------
+// SeedSeq implementation details
+
+#[derive(Clone)]
+struct SeedSeqStream {
+	pool: [u64; 4],
+	counter: u64,
+}
+
+impl Rng for SeedSeqStream {
+	#[inline]
+	fn next_u32(&mut self) -> u32 {
+		self.next_u64() as u32
+	}
+	#[inline]
+	fn next_u64(&mut self) -> u64 {
+		let word = self.pool[(self.counter % 4) as usize];
+		self.counter = self.counter.wrapping_add(1);
+		mix64(word ^ mix64(self.counter.wrapping_mul(GOLDEN_GAMMA)))
+	}
+	#[inline]
+	fn fill_u32(&mut self, buffer: &mut [u32]) {
+		*self = crate::impls::fill_u32(self.clone(), buffer);
+	}
+	#[inline]
+	fn fill_u64(&mut self, buffer: &mut [u64]) {
+		*self = crate::impls::fill_u64(self.clone(), buffer);
+	}
+	#[inline]
+	fn fill_bytes(&mut self, buffer: &mut [u8]) {
+		*self = crate::impls::fill_bytes(self.clone(), buffer);
+	}
+	#[inline]
+	fn jump(&mut self) {
+		self.counter = self.counter.wrapping_add(1 << 32);
+	}
+	#[inline]
+	fn jump_support(&self) -> JumpStrength {
+		JumpStrength::Weak
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_reproducible() {
+	use super::{ChaCha20, Philox, SplitMix64, Xoshiro256};
+
+	fn check<R: FromSeedSeq + Rng>() {
+		let mut a = SeedSeq::with_spawn_key(42, &[1, 2, 3]).seed_rng::<R>();
+		let mut b = SeedSeq::new(42).child(1).child(2).child(3).seed_rng::<R>();
+		let mut c = SeedSeq::with_spawn_key(42, &[1, 2, 4]).seed_rng::<R>();
+		for _ in 0..100 {
+			let value = a.next_u64();
+			assert_eq!(value, b.next_u64());
+			assert_ne!(value, c.next_u64());
+		}
+	}
+	check::<Xoshiro256>();
+	check::<SplitMix64>();
+	check::<ChaCha20>();
+	check::<Philox>();
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_siblings() {
+	use std::collections::HashSet;
+	use super::Xoshiro256;
+
+	let mut root = SeedSeq::new(0);
+	let children = root.spawn(8);
+	let mut seen = HashSet::new();
+	for child in &children {
+		let mut rng = child.seed_rng::<Xoshiro256>();
+		for _ in 0..1000 {
+			assert!(seen.insert(rng.next_u64()));
+		}
+	}
+
+	// Spawn order does not affect the children
+	let mut reversed: Vec<_> = (0..8).rev().map(|index| root.child(index)).collect();
+	reversed.reverse();
+	for (a, b) in children.iter().zip(&reversed) {
+		let mut state = ([0u64; 4], [0u64; 4]);
+		a.generate_state(&mut state.0);
+		b.generate_state(&mut state.1);
+		assert_eq!(state.0, state.1);
+	}
+
+	// Repeated spawns continue where they left off
+	let next = root.spawn(1);
+	let mut state = ([0u64; 4], [0u64; 4]);
+	next[0].generate_state(&mut state.0);
+	root.child(8).generate_state(&mut state.1);
+	assert_eq!(state.0, state.1);
+}
@@ -1,5 +1,6 @@
 use crate::{Random, Rng};
-use super::SeedRng;
+use crate::hash::mix64;
+use super::{EntropyError, FromSeedSeq, JumpStrength, MixRng, SeedRng};
 
 /// Java 8's SplittableRandom generator.
 ///
@@ -15,10 +16,10 @@ pub struct SplitMix64(pub(crate) u64);
 
 impl SeedRng for SplitMix64 {
 	#[inline]
-	fn new() -> Random<SplitMix64> {
+	fn try_new() -> Result<Random<SplitMix64>, EntropyError> {
 		let mut state = 0u64;
-		super::getentropy(dataview::bytes_mut(&mut state));
-		Random(SplitMix64(state))
+		super::try_getentropy(dataview::bytes_mut(&mut state))?;
+		Ok(Random(SplitMix64(state)))
 	}
 	#[inline]
 	fn from_rng<R: ?Sized + Rng>(rng: &mut Random<R>) -> Random<SplitMix64> {
@@ -32,6 +33,85 @@ impl SeedRng for SplitMix64 {
 
 forward_seed_rng_impl!(SplitMix64);
 
+impl SplitMix64 {
+	/// Creates a new PRNG from the given state bytes in little-endian order.
+	///
+	/// This is equivalent to [`from_seed`](SplitMix64::from_seed) with the seed in little-endian byte order.
+	/// _Changing_ the implementation of this function is considered a breaking change.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::rng::SplitMix64::from_seed_bytes([0x01; 8]);
+	/// assert_eq!(rng.next_u64(), 0x79982a55c613df1f);
+	/// ```
+	#[inline]
+	pub fn from_seed_bytes(seed: [u8; 8]) -> Random<SplitMix64> {
+		Random(SplitMix64(u64::from_le_bytes(seed)))
+	}
+	/// Creates a new PRNG from the raw state of the reference implementation `splitmix64.c`.
+	///
+	/// Every state is valid, this is equivalent to [`from_seed`](SplitMix64::from_seed).
+	/// The state is incremented by `0x9e3779b97f4a7c15` before every output.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::rng::SplitMix64::from_state(1234567);
+	/// assert_eq!(rng.next_u64(), 6457827717110365317);
+	/// ```
+	#[inline]
+	pub const fn from_state(state: u64) -> Random<SplitMix64> {
+		Random(SplitMix64(state))
+	}
+	/// Creates a new PRNG using the given seed in a const context.
+	///
+	/// This is equivalent to [`from_seed`](SplitMix64::from_seed), eg. to initialize a generator in a `static` from a build-time seed.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::cell::RefCell;
+	/// use std::sync::Mutex;
+	/// use urandom::Random;
+	/// use urandom::rng::SplitMix64;
+	///
+	/// static RNG: Mutex<RefCell<Random<SplitMix64>>> = Mutex::new(RefCell::new(SplitMix64::from_seed_const(42)));
+	///
+	/// let value = RNG.lock().unwrap().borrow_mut().next_u64();
+	/// assert_eq!(value, SplitMix64::from_seed(42).next_u64());
+	/// ```
+	#[inline]
+	pub const fn from_seed_const(seed: u64) -> Random<SplitMix64> {
+		Random(SplitMix64(seed))
+	}
+	/// Returns the raw state of the reference implementation `splitmix64.c`.
+	#[inline]
+	pub const fn state(&self) -> u64 {
+		self.0
+	}
+	/// Returns the current state in little-endian byte order.
+	///
+	/// Passing the result to [`from_seed_bytes`](SplitMix64::from_seed_bytes) recreates the generator at its current position.
+	#[inline]
+	pub fn to_seed_bytes(&self) -> [u8; 8] {
+		self.0.to_le_bytes()
+	}
+}
+
+impl FromSeedSeq for SplitMix64 {}
+
+impl MixRng for SplitMix64 {
+	/// Every state is valid, the mixed bytes are XORed into the state.
+	#[inline]
+	fn mix(&mut self, bytes: &[u8]) {
+		if !bytes.is_empty() {
+			let [lane] = super::mix_lanes::<1>(bytes);
+			self.0 ^= lane;
+		}
+	}
+}
+
 impl Rng for SplitMix64 {
 	#[inline]
 	fn next_u32(&mut self) -> u32 {
@@ -47,16 +127,20 @@ impl Rng for SplitMix64 {
 	}
 	#[inline(never)]
 	fn fill_u64(&mut self, buffer: &mut [u64]) {
-		*self = crate::impls::fill_u64(self.clone(), buffer);
+		crate::impls::fill_u64_x4(self, buffer, |rng| next4(&mut rng.0));
 	}
 	#[inline(never)]
 	fn fill_bytes(&mut self, buffer: &mut [u8]) {
-		*self = crate::impls::fill_bytes(self.clone(), buffer);
+		crate::impls::fill_bytes_x4(self, buffer, |rng| next4(&mut rng.0));
 	}
 	#[inline]
 	fn jump(&mut self) {
 		jump(&mut self.0)
 	}
+	#[inline]
+	fn jump_support(&self) -> JumpStrength {
+		JumpStrength::Weak
+	}
 }
 
 //----------------------------------------------------------------
@@ -69,15 +153,142 @@ fn next(x: &mut u64) -> u64 {
 	*x = x.wrapping_add(GOLDEN_GAMMA);
 	mix64(*x)
 }
+// Outputs are independent of each other given the state, this allows LLVM to vectorize the mixing
+#[inline]
+fn next4(x: &mut u64) -> [u64; 4] {
+	let s = *x;
+	*x = s.wrapping_add(GOLDEN_GAMMA.wrapping_mul(4));
+	[
+		mix64(s.wrapping_add(GOLDEN_GAMMA.wrapping_mul(1))),
+		mix64(s.wrapping_add(GOLDEN_GAMMA.wrapping_mul(2))),
+		mix64(s.wrapping_add(GOLDEN_GAMMA.wrapping_mul(3))),
+		mix64(s.wrapping_add(GOLDEN_GAMMA.wrapping_mul(4))),
+	]
+}
 #[inline]
 fn jump(x: &mut u64) {
 	*x = x.wrapping_add(GOLDEN_GAMMA << 40);
 }
 
-// https://zimbry.blogspot.com/2011/09/better-bit-mixing-improving-on.html
-#[inline]
-const fn mix64(mut z: u64) -> u64 {
-	z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
-	z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
-	return z ^ (z >> 31);
+
+/// Portable hasher used to turn keys into seeds.
+///
+/// Integers are hashed as little-endian regardless of the target to keep results reproducible.
+pub(crate) struct KeyHasher(u64);
+
+impl KeyHasher {
+	#[inline]
+	pub(crate) const fn new() -> KeyHasher {
+		KeyHasher(GOLDEN_GAMMA)
+	}
+}
+
+impl core::hash::Hasher for KeyHasher {
+	#[inline]
+	fn finish(&self) -> u64 {
+		mix64(self.0)
+	}
+	#[inline]
+	fn write(&mut self, bytes: &[u8]) {
+		for chunk in bytes.chunks(8) {
+			let mut buf = [0u8; 8];
+			buf[..chunk.len()].copy_from_slice(chunk);
+			self.write_u64(u64::from_le_bytes(buf));
+		}
+	}
+	#[inline]
+	fn write_u8(&mut self, i: u8) {
+		self.write_u64(i as u64)
+	}
+	#[inline]
+	fn write_u16(&mut self, i: u16) {
+		self.write_u64(i as u64)
+	}
+	#[inline]
+	fn write_u32(&mut self, i: u32) {
+		self.write_u64(i as u64)
+	}
+	#[inline]
+	fn write_u64(&mut self, i: u64) {
+		self.0 = mix64((self.0 ^ i).wrapping_add(GOLDEN_GAMMA));
+	}
+	#[inline]
+	fn write_u128(&mut self, i: u128) {
+		self.write_u64(i as u64);
+		self.write_u64((i >> 64) as u64);
+	}
+	#[inline]
+	fn write_usize(&mut self, i: usize) {
+		self.write_u64(i as u64)
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_seed_bytes() {
+	for seed in [0, 1, 42, u64::MAX] {
+		let mut a = SplitMix64::from_seed(seed);
+		let mut b = SplitMix64::from_seed_bytes(seed.to_le_bytes());
+		assert_eq!(a.0.to_seed_bytes(), seed.to_le_bytes());
+		assert_eq!(a.next_u64(), b.next_u64());
+		let mut c = SplitMix64::from_seed_bytes(a.0.to_seed_bytes());
+		assert_eq!(a.next_u64(), c.next_u64());
+	}
+}
+
+#[test]
+fn test_fill_sequential() {
+	for len in (0..100).chain([1024, 1031]) {
+		let mut rng = SplitMix64::from_seed(len as u64);
+		let mut reference = SplitMix64::from_seed(len as u64);
+		let mut bytes = vec![0u8; len];
+		rng.fill_bytes(&mut bytes);
+		for chunk in bytes.chunks(8) {
+			assert_eq!(chunk, &reference.next_u64().to_le_bytes()[..chunk.len()]);
+		}
+		assert_eq!(rng.next_u64(), reference.next_u64());
+
+		let mut words = vec![0u64; len];
+		rng.fill_u64(&mut words);
+		for &word in &words {
+			assert_eq!(word, reference.next_u64());
+		}
+		assert_eq!(rng.next_u64(), reference.next_u64());
+	}
+}
+
+#[test]
+fn test_reference() {
+	// Reference outputs from splitmix64.c by Sebastiano Vigna
+	let mut rng = SplitMix64::from_state(1234567);
+	let expected = [6457827717110365317, 3203168211198807973, 9817491932198370423, 4593380528125082431, 16408922859458223821];
+	for &value in &expected {
+		assert_eq!(rng.next_u64(), value);
+	}
+	assert_eq!(rng.0.state(), 1234567u64.wrapping_add(5u64.wrapping_mul(0x9e3779b97f4a7c15)));
+}
+
+#[test]
+fn test_mix() {
+	let mut a = SplitMix64::from_seed(42);
+	let mut b = a.clone();
+	b.0.mix(&[]);
+	assert_eq!(a.0.state(), b.0.state());
+	let mut c = a.clone();
+	b.mix_entropy(b"fresh entropy");
+	c.mix_entropy(b"fresh entropy");
+	let value = b.next_u64();
+	assert_ne!(a.next_u64(), value);
+	assert_eq!(c.next_u64(), value);
+}
+
+#[test]
+fn test_from_seed_const() {
+	static RNG: std::sync::Mutex<Random<SplitMix64>> = std::sync::Mutex::new(SplitMix64::from_seed_const(42));
+	let mut reference = SplitMix64::from_seed(42);
+	let mut rng = RNG.lock().unwrap();
+	for _ in 0..10 {
+		assert_eq!(rng.next_u64(), reference.next_u64());
+	}
 }
@@ -0,0 +1,277 @@
+use std::{error, fmt, panic};
+use crate::Rng;
+use super::JumpStrength;
+
+/// Number of floats checked by [`verify`](verify).
+const FLOAT_SAMPLES: usize = 10000;
+
+/// Longest byte buffer filled by [`verify`](verify), three times the 64-byte block of [`ChaCha20`](super::ChaCha20).
+const MAX_FILL_LEN: usize = 192;
+
+/// Violation of the [`Rng`](Rng) contract found by [`verify`](verify).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ContractViolation {
+	/// [`next_f32`](Rng::next_f32) returned a value outside `[1.0, 2.0)`.
+	F32OutOfRange {
+		/// The value returned.
+		value: f32,
+	},
+	/// [`next_f64`](Rng::next_f64) returned a value outside `[1.0, 2.0)`.
+	F64OutOfRange {
+		/// The value returned.
+		value: f64,
+	},
+	/// [`fill_bytes`](Rng::fill_bytes) panicked.
+	FillBytesPanicked {
+		/// Length of the buffer.
+		len: usize,
+	},
+	/// [`fill_u32`](Rng::fill_u32) produced a word which differs from [`fill_bytes`](Rng::fill_bytes) read as little-endian words.
+	FillU32Mismatch {
+		/// Number of words filled.
+		len: usize,
+		/// Index of the first differing word.
+		index: usize,
+		/// The word read from `fill_bytes`.
+		expected: u32,
+		/// The word produced by `fill_u32`.
+		found: u32,
+	},
+	/// [`fill_u64`](Rng::fill_u64) produced a word which differs from [`fill_bytes`](Rng::fill_bytes) read as little-endian words.
+	FillU64Mismatch {
+		/// Number of words filled.
+		len: usize,
+		/// Index of the first differing word.
+		index: usize,
+		/// The word read from `fill_bytes`.
+		expected: u64,
+		/// The word produced by `fill_u64`.
+		found: u64,
+	},
+	/// The generator is in a different state after filling words than after filling the same number of bytes.
+	FillState {
+		/// Number of bytes filled.
+		len: usize,
+	},
+	/// [`jump`](Rng::jump) did not change the output although [`jump_support`](Rng::jump_support) is not [`JumpStrength::None`](JumpStrength::None).
+	JumpUnchanged,
+	/// [`fill_bytes`](Rng::fill_bytes) differs from consecutive [`next_u64`](Rng::next_u64) in little-endian order, see [`verify_next_u64`](verify_next_u64).
+	NextU64Mismatch {
+		/// Index of the first differing word.
+		index: usize,
+		/// The word read from `fill_bytes`.
+		expected: u64,
+		/// The word returned by `next_u64`.
+		found: u64,
+	},
+}
+
+impl fmt::Display for ContractViolation {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			ContractViolation::F32OutOfRange { value } => write!(f, "next_f32 returned {:?} outside [1.0, 2.0)", value),
+			ContractViolation::F64OutOfRange { value } => write!(f, "next_f64 returned {:?} outside [1.0, 2.0)", value),
+			ContractViolation::FillBytesPanicked { len } => write!(f, "fill_bytes panicked filling {} bytes", len),
+			ContractViolation::FillU32Mismatch { len, index, expected, found } => write!(f, "fill_u32 of {} words differs from fill_bytes at word {}: expected {:#x}, found {:#x}", len, index, expected, found),
+			ContractViolation::FillU64Mismatch { len, index, expected, found } => write!(f, "fill_u64 of {} words differs from fill_bytes at word {}: expected {:#x}, found {:#x}", len, index, expected, found),
+			ContractViolation::FillState { len } => write!(f, "filling {} bytes as words leaves a different state than fill_bytes", len),
+			ContractViolation::JumpUnchanged => f.write_str("jump did not change the output of a generator which claims to support jumping"),
+			ContractViolation::NextU64Mismatch { index, expected, found } => write!(f, "next_u64 differs from fill_bytes at word {}: expected {:#x}, found {:#x}", index, expected, found),
+		}
+	}
+}
+
+impl error::Error for ContractViolation {}
+
+/// Checks that the generator upholds the contract of the [`Rng`](Rng) trait.
+///
+/// A conformance tool for implementors of `Rng`, violations silently break every distribution built on top.
+/// The checks run in order and the first violation is returned:
+///
+/// 1. [`next_f32`](Rng::next_f32) and [`next_f64`](Rng::next_f64) return values in `[1.0, 2.0)` for 10000 calls each.
+///    Distributions such as [`UniformFloat`](crate::distributions::UniformFloat) rely on this interval exactly.
+/// 2. [`fill_bytes`](Rng::fill_bytes) does not panic for every length from 0 up to and including 192 bytes, three blocks of [`ChaCha20`](super::ChaCha20).
+/// 3. [`fill_u32`](Rng::fill_u32) and [`fill_u64`](Rng::fill_u64) produce the same words as `fill_bytes` read as little-endian words
+///    and leave the generator in the same state, as observed by the next `next_u64`.
+/// 4. [`jump`](Rng::jump) changes the output, unless [`jump_support`](Rng::jump_support) returns [`JumpStrength::None`](JumpStrength::None).
+///
+/// The generator is advanced by the checks, the comparisons run on clones.
+/// Sources whose clones produce independent output such as [`getentropy`](super::getentropy) fail the third check and cannot be verified.
+///
+/// The optional check [`verify_next_u64`](verify_next_u64) is not part of the contract.
+///
+/// # Examples
+///
+/// ```
+/// let mut rng = urandom::rng::Xoshiro256::from_seed(42);
+/// assert_eq!(urandom::rng::verify(&mut rng.0), Ok(()));
+/// ```
+pub fn verify<R: Rng + Clone>(rng: &mut R) -> Result<(), ContractViolation> {
+	for _ in 0..FLOAT_SAMPLES {
+		let value = rng.next_f32();
+		if !(value >= 1.0 && value < 2.0) {
+			return Err(ContractViolation::F32OutOfRange { value });
+		}
+	}
+	for _ in 0..FLOAT_SAMPLES {
+		let value = rng.next_f64();
+		if !(value >= 1.0 && value < 2.0) {
+			return Err(ContractViolation::F64OutOfRange { value });
+		}
+	}
+
+	let mut bytes = [0u8; MAX_FILL_LEN];
+	for len in 0..=MAX_FILL_LEN {
+		let result = panic::catch_unwind(panic::AssertUnwindSafe(|| rng.fill_bytes(&mut bytes[..len])));
+		if result.is_err() {
+			return Err(ContractViolation::FillBytesPanicked { len });
+		}
+	}
+
+	for len in 0..=MAX_FILL_LEN / 4 {
+		let mut a = rng.clone();
+		let mut b = rng.clone();
+		let mut words = [0u32; MAX_FILL_LEN / 4];
+		a.fill_bytes(&mut bytes[..len * 4]);
+		b.fill_u32(&mut words[..len]);
+		for (index, (chunk, &found)) in bytes.chunks_exact(4).zip(&words[..len]).enumerate() {
+			let expected = u32::from_le_bytes(chunk.try_into().unwrap());
+			if expected != found {
+				return Err(ContractViolation::FillU32Mismatch { len, index, expected, found });
+			}
+		}
+		if a.next_u64() != b.next_u64() {
+			return Err(ContractViolation::FillState { len: len * 4 });
+		}
+	}
+	for len in 0..=MAX_FILL_LEN / 8 {
+		let mut a = rng.clone();
+		let mut b = rng.clone();
+		let mut words = [0u64; MAX_FILL_LEN / 8];
+		a.fill_bytes(&mut bytes[..len * 8]);
+		b.fill_u64(&mut words[..len]);
+		for (index, (chunk, &found)) in bytes.chunks_exact(8).zip(&words[..len]).enumerate() {
+			let expected = u64::from_le_bytes(chunk.try_into().unwrap());
+			if expected != found {
+				return Err(ContractViolation::FillU64Mismatch { len, index, expected, found });
+			}
+		}
+		if a.next_u64() != b.next_u64() {
+			return Err(ContractViolation::FillState { len: len * 8 });
+		}
+	}
+
+	if rng.jump_support() != JumpStrength::None {
+		let mut a = rng.clone();
+		let mut b = rng.clone();
+		b.jump();
+		if (0..4).all(|_| a.next_u64() == b.next_u64()) {
+			return Err(ContractViolation::JumpUnchanged);
+		}
+	}
+	Ok(())
+}
+
+/// Checks that [`fill_bytes`](Rng::fill_bytes) produces the same bytes as consecutive [`next_u64`](Rng::next_u64) in little-endian order.
+///
+/// This is optional and not part of the [`Rng`](Rng) contract, generators may produce distinct values from their fill methods.
+/// Implementations which claim this property can verify it, eg. to make buffered and unbuffered consumers interchangeable.
+///
+/// # Examples
+///
+/// ```
+/// let rng = urandom::rng::SplitMix64::from_seed(42);
+/// assert_eq!(urandom::rng::verify_next_u64(&rng.0), Ok(()));
+/// ```
+pub fn verify_next_u64<R: Rng + Clone>(rng: &R) -> Result<(), ContractViolation> {
+	let mut a = rng.clone();
+	let mut b = rng.clone();
+	let mut bytes = [0u8; MAX_FILL_LEN];
+	a.fill_bytes(&mut bytes);
+	for (index, chunk) in bytes.chunks_exact(8).enumerate() {
+		let expected = u64::from_le_bytes(chunk.try_into().unwrap());
+		let found = b.next_u64();
+		if expected != found {
+			return Err(ContractViolation::NextU64Mismatch { index, expected, found });
+		}
+	}
+	Ok(())
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_shipped() {
+	use super::*;
+
+	verify(&mut SplitMix64::from_seed(42).0).unwrap();
+	verify(&mut Xoshiro256::from_seed(42).0).unwrap();
+	verify(&mut Xoroshiro128pp::from_seed(42).0).unwrap();
+	verify(&mut Xoroshiro128p::from_seed(42).0).unwrap();
+	verify(&mut Philox::from_seed(42).0).unwrap();
+	verify(&mut ChaCha20::from_seed(42).0).unwrap();
+	verify(&mut SeedSeq::new(42).stream().0).unwrap();
+	verify(&mut crate::new().counting().0).unwrap();
+	verify(&mut crate::new().byte_swapped().0).unwrap();
+	verify(&mut crate::new().lagged(WordOrder::LowFirst).0).unwrap();
+	verify(&mut crate::new().conditioned().0).unwrap();
+	verify(&mut crate::new().recording().0).unwrap();
+	let data: Vec<u8> = (0..1 << 20).map(|i| (i * 7 + i / 251) as u8).collect();
+	verify(&mut ReadRng::new(&data[..]).0).unwrap();
+
+	verify_next_u64(&SplitMix64::from_seed(42).0).unwrap();
+	verify_next_u64(&Xoshiro256::from_seed(42).0).unwrap();
+	verify_next_u64(&ChaCha20::from_seed(42).0).unwrap();
+}
+
+#[test]
+fn test_violations() {
+	use super::{MockRng, SplitMix64};
+
+	#[derive(Clone)]
+	struct Broken {
+		rng: SplitMix64,
+		float: Option<f64>,
+		big_endian: bool,
+		jump: bool,
+	}
+	impl Rng for Broken {
+		fn next_u32(&mut self) -> u32 {
+			self.rng.next_u32()
+		}
+		fn next_u64(&mut self) -> u64 {
+			self.rng.next_u64()
+		}
+		fn next_f64(&mut self) -> f64 {
+			self.float.unwrap_or_else(|| self.rng.next_f64())
+		}
+		fn fill_u32(&mut self, buffer: &mut [u32]) {
+			self.rng.fill_u32(buffer)
+		}
+		fn fill_u64(&mut self, buffer: &mut [u64]) {
+			self.rng.fill_u64(buffer);
+			if self.big_endian {
+				buffer.iter_mut().for_each(|word| *word = word.swap_bytes());
+			}
+		}
+		fn fill_bytes(&mut self, buffer: &mut [u8]) {
+			self.rng.fill_bytes(buffer)
+		}
+		fn jump(&mut self) {
+			if self.jump {
+				self.rng.jump()
+			}
+		}
+	}
+	let rng = Broken { rng: SplitMix64(1), float: None, big_endian: false, jump: true };
+	assert_eq!(verify(&mut rng.clone()), Ok(()));
+	assert_eq!(verify(&mut Broken { float: Some(2.0), ..rng.clone() }), Err(ContractViolation::F64OutOfRange { value: 2.0 }));
+	assert!(matches!(verify(&mut Broken { big_endian: true, ..rng.clone() }), Err(ContractViolation::FillU64Mismatch { len: 1, index: 0, .. })));
+	assert_eq!(verify(&mut Broken { jump: false, ..rng.clone() }), Err(ContractViolation::JumpUnchanged));
+
+	// The mock generator does not implement fill_bytes
+	assert_eq!(verify(&mut MockRng::repeat(0x3ff0000000000000).0), Err(ContractViolation::FillBytesPanicked { len: 0 }));
+
+	let err = ContractViolation::F32OutOfRange { value: 0.5 };
+	assert_eq!(err.to_string(), "next_f32 returned 0.5 outside [1.0, 2.0)");
+}
@@ -0,0 +1,269 @@
+use crate::{Random, Rng};
+use super::{EntropyError, FromSeedSeq, JumpStrength, SeedRng, StateError};
+
+/**
+This is xoroshiro128++ 1.0, one of our all-purpose, rock-solid, small-state generators.
+
+It is extremely (sub-ns) fast and it passes all tests we are aware of, but its state space is large enough only for mild parallelism.
+
+The state must be seeded so that it is not everywhere zero.
+If you have a 64-bit seed, we suggest to seed a SplitMix64 generator and use its output to fill s.
+*/
+///
+/// The state is 16 bytes, half of [`Xoshiro256`](super::Xoshiro256), for applications which embed many generators.
+///
+/// # Examples
+///
+/// ```
+/// let mut rng = urandom::rng::Xoroshiro128pp::from_seed(42);
+/// assert_eq!(rng.next_u64(), 0xe88af6caef1d3c23);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Xoroshiro128pp {
+	state: [u64; 2],
+}
+
+/**
+This is xoroshiro128+ 1.0, our best and fastest small-state generator for floating-point numbers, but its state space is large enough only for mild parallelism.
+
+We suggest to use its upper bits for floating-point generation, as it is slightly faster than xoroshiro128++.
+It passes all tests we are aware of except for the four lower bits, which might fail linearity tests (and just those).
+*/
+///
+/// The low bits are discarded when generating `u32` and floating point values.
+/// Prefer [`Xoroshiro128pp`](Xoroshiro128pp) if the full `u64` values are used, eg. for bit masks or modular arithmetic.
+///
+/// # Examples
+///
+/// ```
+/// let mut rng = urandom::rng::Xoroshiro128p::from_seed(42);
+/// assert_eq!(rng.next_u64(), 0xe6c71559e2525f98);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Xoroshiro128p {
+	state: [u64; 2],
+}
+
+macro_rules! impl_xoroshiro128 {
+	($ty:ident, $next:ident, $jump:expr) => {
+		impl SeedRng for $ty {
+			#[inline]
+			fn try_new() -> Result<Random<$ty>, EntropyError> {
+				let mut state = [0u64; 2];
+				super::try_getentropy(dataview::bytes_mut(&mut state))?;
+				Ok(Random($ty { state: nonzero(state) }))
+			}
+			#[inline]
+			fn from_rng<R: Rng + ?Sized>(rng: &mut Random<R>) -> Random<$ty> {
+				let mut state = [0u64; 2];
+				rng.fill_u64(&mut state);
+				Random($ty { state: nonzero(state) })
+			}
+			fn from_seed(seed: u64) -> Random<$ty> {
+				SeedRng::from_rng(&mut Random(super::SplitMix64(seed)))
+			}
+		}
+
+		forward_seed_rng_impl!($ty);
+
+		impl $ty {
+			/// Creates a new PRNG from the given state bytes as two little-endian `u64` words.
+			///
+			/// The all-zero state is invalid for this generator, it is replaced with the state of `from_seed(0)`.
+			/// _Changing_ the implementation of this function is considered a breaking change.
+			#[inline]
+			pub fn from_seed_bytes(seed: [u8; 16]) -> Random<$ty> {
+				let mut state = [0u64; 2];
+				for (word, bytes) in state.iter_mut().zip(seed.chunks_exact(8)) {
+					*word = u64::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7]]);
+				}
+				Random($ty { state: nonzero(state) })
+			}
+			/// Creates a new PRNG from the raw state words `s[0]` and `s[1]` of the reference implementation.
+			///
+			/// Returns an error for the all-zero state.
+			#[inline]
+			pub fn from_state(state: [u64; 2]) -> Result<Random<$ty>, StateError> {
+				if state == [0; 2] {
+					return Err(StateError::AllZero);
+				}
+				Ok(Random($ty { state }))
+			}
+			/// Returns the raw state words `s[0]` and `s[1]` of the reference implementation.
+			#[inline]
+			pub fn state(&self) -> [u64; 2] {
+				self.state
+			}
+			/// Returns the current state as two little-endian `u64` words.
+			///
+			/// Passing the result to `from_seed_bytes` recreates the generator at its current position.
+			#[inline]
+			pub fn to_seed_bytes(&self) -> [u8; 16] {
+				let mut seed = [0u8; 16];
+				for (bytes, word) in seed.chunks_exact_mut(8).zip(self.state) {
+					bytes.copy_from_slice(&word.to_le_bytes());
+				}
+				seed
+			}
+		}
+
+		impl FromSeedSeq for $ty {}
+
+		impl Rng for $ty {
+			#[inline]
+			fn next_u32(&mut self) -> u32 {
+				($next(&mut self.state) >> 32) as u32
+			}
+			#[inline]
+			fn next_u64(&mut self) -> u64 {
+				$next(&mut self.state)
+			}
+			#[inline(never)]
+			fn fill_u32(&mut self, buffer: &mut [u32]) {
+				*self = crate::impls::fill_u32(self.clone(), buffer);
+			}
+			#[inline(never)]
+			fn fill_u64(&mut self, buffer: &mut [u64]) {
+				*self = crate::impls::fill_u64(self.clone(), buffer);
+			}
+			#[inline(never)]
+			fn fill_bytes(&mut self, buffer: &mut [u8]) {
+				*self = crate::impls::fill_bytes(self.clone(), buffer);
+			}
+			#[inline]
+			fn jump(&mut self) {
+				jump(&mut self.state, &$jump, $next)
+			}
+			#[inline]
+			fn jump_support(&self) -> JumpStrength {
+				JumpStrength::Strong
+			}
+		}
+	};
+}
+
+impl_xoroshiro128!(Xoroshiro128pp, next_plusplus, [0x2bd7a6a6e99c2ddc, 0x0992ccaf6a6fca05]);
+impl_xoroshiro128!(Xoroshiro128p, next_plus, [0xdf900294d8f554a5, 0x170865df4b3201fc]);
+
+//----------------------------------------------------------------
+// Xoroshiro128 implementation details
+
+// The all-zero state is replaced with the state of `from_seed(0)`
+#[inline]
+fn nonzero(state: [u64; 2]) -> [u64; 2] {
+	if state == [0; 2] { [0xe220a8397b1dcdaf, 0x6e789e6aa1b965f4] } else { state }
+}
+
+#[inline]
+fn next_plusplus(s: &mut [u64; 2]) -> u64 {
+	let s0 = s[0];
+	let mut s1 = s[1];
+	let result = u64::wrapping_add(u64::wrapping_add(s0, s1).rotate_left(17), s0);
+
+	s1 ^= s0;
+	s[0] = s0.rotate_left(49) ^ s1 ^ (s1 << 21);
+	s[1] = s1.rotate_left(28);
+
+	return result;
+}
+#[inline]
+fn next_plus(s: &mut [u64; 2]) -> u64 {
+	let s0 = s[0];
+	let mut s1 = s[1];
+	let result = u64::wrapping_add(s0, s1);
+
+	s1 ^= s0;
+	s[0] = s0.rotate_left(24) ^ s1 ^ (s1 << 16);
+	s[1] = s1.rotate_left(37);
+
+	return result;
+}
+// Equivalent to 2^64 calls to next, the polynomial depends on the state transition of the generator
+#[inline(never)]
+fn jump(s: &mut [u64; 2], poly: &[u64; 2], next: fn(&mut [u64; 2]) -> u64) {
+	let mut s0 = 0;
+	let mut s1 = 0;
+	for i in 0..2 {
+		for b in 0..64 {
+			if (poly[i] & (1 << b)) != 0 {
+				s0 ^= s[0];
+				s1 ^= s[1];
+			}
+			next(s);
+		}
+	}
+	s[0] = s0;
+	s[1] = s1;
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_reference() {
+	// Reference outputs from the C implementations by David Blackman and Sebastiano Vigna
+	let mut rng = Xoroshiro128pp::from_state([1, 2]).unwrap();
+	let expected = [
+		393217, 669327710093319, 1732421326133921491, 11394790081659126983, 9555452776773192676,
+		3586421180005889563, 1691397964866707553, 10735626796753111697, 15216282715349408991, 14247243556711267923,
+	];
+	for &value in &expected {
+		assert_eq!(rng.next_u64(), value);
+	}
+
+	let mut rng = Xoroshiro128p::from_state([1, 2]).unwrap();
+	let expected = [
+		3, 412333834243, 2360170716294286339, 9295852285959843169, 2797080929874688578,
+		6019711933173041966, 3076529664176959358, 3521761819100106140, 7493067640054542992, 920801338098114767,
+	];
+	for &value in &expected {
+		assert_eq!(rng.next_u64(), value);
+	}
+
+	// The state round-trips at any position
+	let mut copy = Xoroshiro128p::from_state(rng.0.state()).unwrap();
+	assert_eq!(rng.next_u64(), copy.next_u64());
+	assert_eq!(Xoroshiro128pp::from_state([0; 2]).unwrap_err(), StateError::AllZero);
+}
+
+#[test]
+fn test_size() {
+	assert_eq!(core::mem::size_of::<Xoroshiro128pp>(), 16);
+	assert_eq!(core::mem::size_of::<Xoroshiro128p>(), 16);
+}
+
+#[test]
+fn test_seed_bytes() {
+	for seed in [0, 1, 42, u64::MAX] {
+		let mut bytes = [0u8; 16];
+		super::SplitMix64::from_seed(seed).fill_bytes(&mut bytes);
+		let mut a = Xoroshiro128pp::from_seed(seed);
+		let mut b = Xoroshiro128pp::from_seed_bytes(bytes);
+		assert_eq!(a.0.to_seed_bytes(), bytes);
+		assert_eq!(a.next_u64(), b.next_u64());
+		let mut c = Xoroshiro128pp::from_seed_bytes(a.0.to_seed_bytes());
+		assert_eq!(a.next_u64(), c.next_u64());
+	}
+	// The all-zero state is replaced
+	assert_eq!(Xoroshiro128pp::from_seed_bytes([0; 16]).0.to_seed_bytes(), Xoroshiro128pp::from_seed(0).0.to_seed_bytes());
+	assert_eq!(Xoroshiro128p::from_seed_bytes([0; 16]).0.to_seed_bytes(), Xoroshiro128p::from_seed(0).0.to_seed_bytes());
+	assert_eq!(Xoroshiro128p::from_rng(&mut super::MockRng::repeat(0)).0.state, Xoroshiro128p::from_seed(0).0.state);
+}
+
+#[test]
+fn test_jump() {
+	let mut a = Xoroshiro128pp::from_seed(42);
+	let mut b = a.clone();
+	b.jump();
+	let a: Vec<u64> = (0..1000).map(|_| a.next_u64()).collect();
+	let b: Vec<u64> = (0..1000).map(|_| b.next_u64()).collect();
+	assert!(a.iter().all(|value| !b.contains(value)));
+
+	// The jumped state is a position in the same sequence: jumping is linear in the state
+	let mut x = Random(Xoroshiro128p { state: [1, 0] });
+	let mut y = Random(Xoroshiro128p { state: [0, 1] });
+	let mut z = Random(Xoroshiro128p { state: [1, 1] });
+	x.jump();
+	y.jump();
+	z.jump();
+	assert_eq!([x.0.state[0] ^ y.0.state[0], x.0.state[1] ^ y.0.state[1]], z.0.state);
+}
@@ -1,5 +1,5 @@
 use crate::{Random, Rng};
-use super::SeedRng;
+use super::{EntropyError, FromSeedSeq, JumpStrength, MixRng, SeedRng, StateError};
 
 /**
 This is xoshiro256 1.0, one of our all-purpose, rock-solid generators.
@@ -10,6 +10,13 @@ The state must be seeded so that it is not everywhere zero.
 If you have a 64-bit seed, we suggest to seed a SplitMix64 generator and use its output to fill s.
 */
 ///
+/// # Variants
+///
+/// [`next_u64`](Rng::next_u64) and the fill methods produce the outputs of the reference `xoshiro256plusplus.c`,
+/// the state words `s[0]..s[3]` are in the same order as the reference `s` array.
+/// [`next_u32`](Rng::next_u32) and [`next_f64`](Rng::next_f64) use the faster xoshiro256+ output function on the same state, `next_u32` returns its upper 32 bits.
+/// Use [`from_state`](Xoshiro256::from_state) and [`state`](Xoshiro256::state) to continue the identical stream in another implementation.
+///
 /// # Examples
 ///
 /// ```
@@ -23,10 +30,10 @@ pub struct Xoshiro256 {
 
 impl SeedRng for Xoshiro256 {
 	#[inline]
-	fn new() -> Random<Xoshiro256> {
+	fn try_new() -> Result<Random<Xoshiro256>, EntropyError> {
 		let mut state = [0u64; 4];
-		super::getentropy(dataview::bytes_mut(&mut state));
-		Random(Xoshiro256 { state })
+		super::try_getentropy(dataview::bytes_mut(&mut state))?;
+		Ok(Random(Xoshiro256 { state }))
 	}
 	#[inline]
 	fn from_rng<R: Rng + ?Sized>(rng: &mut Random<R>) -> Random<Xoshiro256> {
@@ -41,6 +48,111 @@ impl SeedRng for Xoshiro256 {
 
 forward_seed_rng_impl!(Xoshiro256);
 
+impl Xoshiro256 {
+	/// Creates a new PRNG from the given state bytes as four little-endian `u64` words.
+	///
+	/// The all-zero state is invalid for this generator, it is replaced with the state of [`from_seed(0)`](Xoshiro256::from_seed).
+	/// _Changing_ the implementation of this function is considered a breaking change.
+	///
+	/// [`from_seed`](Xoshiro256::from_seed) is equivalent to this function with the seed bytes filled from [`SplitMix64`](super::SplitMix64) seeded with the same seed.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// let mut rng = urandom::rng::Xoshiro256::from_seed_bytes([0x01; 32]);
+	/// assert_eq!(rng.next_u64(), 0x0202020202020202);
+	/// ```
+	#[inline]
+	pub fn from_seed_bytes(seed: [u8; 32]) -> Random<Xoshiro256> {
+		let mut state = [0u64; 4];
+		for (word, bytes) in state.iter_mut().zip(seed.chunks_exact(8)) {
+			*word = u64::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7]]);
+		}
+		if state == [0; 4] {
+			return Xoshiro256::from_seed(0);
+		}
+		Random(Xoshiro256 { state })
+	}
+	/// Creates a new PRNG from the raw state words `s[0]..s[3]` of the reference implementation.
+	///
+	/// Returns an error for the all-zero state.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use urandom::rng::Xoshiro256;
+	///
+	/// let mut rng = Xoshiro256::from_state([1, 2, 3, 4]).unwrap();
+	/// assert_eq!(rng.next_u64(), 41943041);
+	/// assert!(Xoshiro256::from_state([0; 4]).is_err());
+	/// ```
+	#[inline]
+	pub fn from_state(state: [u64; 4]) -> Result<Random<Xoshiro256>, StateError> {
+		if state == [0; 4] {
+			return Err(StateError::AllZero);
+		}
+		Ok(Random(Xoshiro256 { state }))
+	}
+	/// Creates a new PRNG from the raw state words `s[0]..s[3]` in a const context.
+	///
+	/// The seed expansion of [`from_seed`](Xoshiro256::from_seed) is not available in const contexts,
+	/// use this to initialize a generator in a `static` from a build-time state.
+	/// The state should look random, eg. generated once with [`state`](Xoshiro256::state) from a seeded generator.
+	///
+	/// # Panics
+	///
+	/// Panics for the all-zero state, which fails compilation when evaluated in a const context.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::cell::RefCell;
+	/// use std::sync::Mutex;
+	/// use urandom::Random;
+	/// use urandom::rng::Xoshiro256;
+	///
+	/// const STATE: [u64; 4] = [0x9e3779b97f4a7c15, 0xbf58476d1ce4e5b9, 0x94d049bb133111eb, 0x2545f4914f6cdd1d];
+	/// static RNG: Mutex<RefCell<Random<Xoshiro256>>> = Mutex::new(RefCell::new(Xoshiro256::from_state_const(STATE)));
+	///
+	/// let value = RNG.lock().unwrap().borrow_mut().next_u64();
+	/// assert_eq!(value, Xoshiro256::from_state(STATE).unwrap().next_u64());
+	/// ```
+	#[inline]
+	pub const fn from_state_const(state: [u64; 4]) -> Random<Xoshiro256> {
+		if state[0] | state[1] | state[2] | state[3] == 0 {
+			panic!("Xoshiro256 state should not be all zero");
+		}
+		Random(Xoshiro256 { state })
+	}
+	/// Returns the raw state words `s[0]..s[3]` of the reference implementation.
+	#[inline]
+	pub fn state(&self) -> [u64; 4] {
+		self.state
+	}
+	/// Returns the current state as four little-endian `u64` words.
+	///
+	/// Passing the result to [`from_seed_bytes`](Xoshiro256::from_seed_bytes) recreates the generator at its current position.
+	#[inline]
+	pub fn to_seed_bytes(&self) -> [u8; 32] {
+		let mut seed = [0u8; 32];
+		for (bytes, word) in seed.chunks_exact_mut(8).zip(self.state) {
+			bytes.copy_from_slice(&word.to_le_bytes());
+		}
+		seed
+	}
+}
+
+impl FromSeedSeq for Xoshiro256 {}
+
+impl MixRng for Xoshiro256 {
+	#[inline]
+	fn mix(&mut self, bytes: &[u8]) {
+		if !bytes.is_empty() {
+			mix_state(&mut self.state, super::mix_lanes(bytes));
+		}
+	}
+}
+
 impl Rng for Xoshiro256 {
 	#[inline]
 	fn next_u32(&mut self) -> u32 {
@@ -64,21 +176,36 @@ impl Rng for Xoshiro256 {
 	}
 	#[inline(never)]
 	fn fill_u64(&mut self, buffer: &mut [u64]) {
-		*self = crate::impls::fill_u64(self.clone(), buffer);
+		crate::impls::fill_u64_x4(self, buffer, |rng| next4(&mut rng.state));
 	}
 	#[inline(never)]
 	fn fill_bytes(&mut self, buffer: &mut [u8]) {
-		*self = crate::impls::fill_bytes(self.clone(), buffer);
+		crate::impls::fill_bytes_x4(self, buffer, |rng| next4(&mut rng.state));
 	}
 	#[inline]
 	fn jump(&mut self) {
 		jump(&mut self.state)
 	}
+	#[inline]
+	fn jump_support(&self) -> JumpStrength {
+		JumpStrength::Strong
+	}
 }
 
 //----------------------------------------------------------------
 // Xoshiro256 implementation details
 
+// The all-zero state is replaced with the state of `from_seed(0)`
+#[inline]
+fn mix_state(s: &mut [u64; 4], lanes: [u64; 4]) {
+	for i in 0..4 {
+		s[i] ^= lanes[i];
+	}
+	if *s == [0; 4] {
+		*s = Xoshiro256::from_seed(0).0.state;
+	}
+}
+
 #[inline]
 fn next_plusplus(s: &mut [u64; 4]) -> u64 {
 	let result = u64::wrapping_add(u64::wrapping_add(s[0], s[3]).rotate_left(23), s[0]);
@@ -113,6 +240,11 @@ fn next_plus(s: &mut [u64; 4]) -> u64 {
 
 	return result;
 }
+// The state update is serial, unrolling still amortizes the stores
+#[inline]
+fn next4(s: &mut [u64; 4]) -> [u64; 4] {
+	[next_plusplus(s), next_plusplus(s), next_plusplus(s), next_plusplus(s)]
+}
 #[allow(dead_code)]
 fn next_starstar(s: &mut [u64; 4]) -> u64 {
 	let result = u64::wrapping_mul(u64::wrapping_mul(s[1], 5).rotate_left(7), 9);
@@ -154,3 +286,101 @@ fn jump(s: &mut [u64; 4]) {
 	s[2] = s2;
 	s[3] = s3;
 }
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_seed_bytes() {
+	for seed in [0, 1, 42, u64::MAX] {
+		let mut bytes = [0u8; 32];
+		super::SplitMix64::from_seed(seed).fill_bytes(&mut bytes);
+		let mut a = Xoshiro256::from_seed(seed);
+		let mut b = Xoshiro256::from_seed_bytes(bytes);
+		assert_eq!(a.0.to_seed_bytes(), bytes);
+		assert_eq!(a.next_u64(), b.next_u64());
+		let mut c = Xoshiro256::from_seed_bytes(a.0.to_seed_bytes());
+		assert_eq!(a.next_u64(), c.next_u64());
+	}
+	// The all-zero state is replaced
+	assert_eq!(Xoshiro256::from_seed_bytes([0; 32]).0.to_seed_bytes(), Xoshiro256::from_seed(0).0.to_seed_bytes());
+}
+
+#[test]
+fn test_fill_sequential() {
+	for len in (0..100).chain([1024, 1031]) {
+		let mut rng = Xoshiro256::from_seed(len as u64);
+		let mut reference = Xoshiro256::from_seed(len as u64);
+		let mut bytes = vec![0u8; len];
+		rng.fill_bytes(&mut bytes);
+		for chunk in bytes.chunks(8) {
+			assert_eq!(chunk, &reference.next_u64().to_le_bytes()[..chunk.len()]);
+		}
+		assert_eq!(rng.next_u64(), reference.next_u64());
+
+		let mut words = vec![0u64; len];
+		rng.fill_u64(&mut words);
+		for &word in &words {
+			assert_eq!(word, reference.next_u64());
+		}
+		assert_eq!(rng.next_u64(), reference.next_u64());
+	}
+}
+
+#[test]
+fn test_reference() {
+	// Reference outputs from xoshiro256plusplus.c by David Blackman and Sebastiano Vigna
+	let mut rng = Xoshiro256::from_state([1, 2, 3, 4]).unwrap();
+	let expected = [
+		41943041, 58720359, 3588806011781223, 3591011842654386, 9228616714210784205,
+		9973669472204895162, 14011001112246962877, 12406186145184390807, 15849039046786891736, 10450023813501588000,
+	];
+	for &value in &expected {
+		assert_eq!(rng.next_u64(), value);
+	}
+
+	// The state round-trips at any position
+	let mut copy = Xoshiro256::from_state(rng.0.state()).unwrap();
+	assert_eq!(rng.next_u64(), copy.next_u64());
+	assert_eq!(Xoshiro256::from_state([0; 4]).unwrap_err(), StateError::AllZero);
+}
+
+#[test]
+fn test_mix() {
+	let mut a = Xoshiro256::from_seed(42);
+	let mut b = a.clone();
+	b.0.mix(&[]);
+	assert_eq!(a.0.state(), b.0.state());
+	let mut c = a.clone();
+	b.mix_entropy(b"fresh entropy");
+	c.mix_entropy(b"fresh entropy");
+	let value = b.next_u64();
+	assert_ne!(a.next_u64(), value);
+	assert_eq!(c.next_u64(), value);
+	// Trailing zero bytes still change the state
+	let mut d = a.clone();
+	let mut e = a.clone();
+	d.mix_entropy(&[1]);
+	e.mix_entropy(&[1, 0]);
+	assert_ne!(d.0.state(), e.0.state());
+
+	// Mixing never produces the all-zero state
+	let mut rng = Xoshiro256::from_state(super::mix_lanes::<4>(b"cancel")).unwrap();
+	rng.mix_entropy(b"cancel");
+	assert_eq!(rng.0.state(), Xoshiro256::from_seed(0).0.state());
+}
+
+#[test]
+fn test_from_state_const() {
+	static RNG: std::sync::Mutex<Random<Xoshiro256>> = std::sync::Mutex::new(Xoshiro256::from_state_const([1, 2, 3, 4]));
+	let mut reference = Xoshiro256::from_state([1, 2, 3, 4]).unwrap();
+	let mut rng = RNG.lock().unwrap();
+	for _ in 0..10 {
+		assert_eq!(rng.next_u64(), reference.next_u64());
+	}
+}
+
+#[test]
+#[should_panic]
+fn test_from_state_const_zero() {
+	let _ = Xoshiro256::from_state_const([0; 4]);
+}
@@ -0,0 +1,390 @@
+/*!
+Random selection from sets.
+
+Verifiable selection
+--------------------
+
+Deterministic selection procedures which anyone can recompute from public inputs.
+The algorithms are fully specified so that they can be reimplemented in other languages with bit-exact results.
+_Changing_ the output of these functions for any input is considered a breaking change.
+
+* [`committee`](committee) selects participants by weight.
+
+Candidate sets
+--------------
+
+* [`BitSetSampler`](BitSetSampler) picks uniformly from a set of indices which shrinks and grows as constraints propagate.
+*/
+
+use crate::{Random, Rng};
+use crate::rng::ChaCha20;
+
+/// Selects a committee of `k` participants by weight from a public seed.
+///
+/// Returns the indices of the selected participants in order of selection.
+/// Participants are selected with probability proportional to their weight, without replacement.
+/// Participants with zero weight are never selected.
+///
+/// If `k` is greater than or equal to the number of participants with nonzero weight, all of them are returned in ascending index order.
+///
+/// # Specification
+///
+/// 1. The random stream is the [ChaCha20](https://datatracker.ietf.org/doc/html/rfc8439) keystream with the `seed` as the key,
+///    an all-zero nonce and the block counter starting at zero.
+///    The keystream is consumed as consecutive 64-bit little-endian unsigned integers.
+/// 2. Let `remaining` be a copy of the weights.
+///    If fewer than `k + 1` entries of `remaining` are nonzero, return the indices of the nonzero entries in ascending order.
+/// 3. Repeat `k` times:
+///    1. Let `total` be the sum of `remaining` as a 128-bit unsigned integer.
+///    2. Draw `x = a + b·2⁶⁴` where `a` and `b` are the next two 64-bit integers from the stream, in that order.
+///       If `x >= 2¹²⁸ - (2¹²⁸ mod total)` reject it and draw again.
+///       Let `r = x mod total`.
+///    3. Select the smallest index `i` such that the sum of `remaining[0..=i]` is greater than `r`.
+///    4. Append `i` to the result and set `remaining[i]` to zero.
+///
+/// The complexity is `O(n·k)` for `n` participants.
+///
+/// # Examples
+///
+/// ```
+/// let seed = [42; 32];
+/// let weights = [100, 0, 250, 50, 600];
+/// let committee = urandom::select::committee(seed, &weights, 2);
+/// assert_eq!(committee.len(), 2);
+/// assert!(!committee.contains(&1));
+/// ```
+pub fn committee(seed: [u8; 32], weights: &[u64], k: usize) -> Vec<usize> {
+	let participants = weights.iter().filter(|&&weight| weight != 0).count();
+	if k >= participants {
+		return (0..weights.len()).filter(|&i| weights[i] != 0).collect();
+	}
+
+	let mut rng = ChaCha20::from_seed_bytes(seed);
+	let mut remaining = weights.to_vec();
+	let mut total: u128 = remaining.iter().map(|&weight| weight as u128).sum();
+	let mut committee = Vec::with_capacity(k);
+	for _ in 0..k {
+		// Unbiased uniform integer in [0, total) by rejection
+		let zone = u128::MAX - (u128::MAX - total + 1) % total;
+		let r = loop {
+			let a = rng.next_u64() as u128;
+			let b = rng.next_u64() as u128;
+			let x = a | b << 64;
+			if x <= zone {
+				break x % total;
+			}
+		};
+
+		let mut sum = 0u128;
+		let index = remaining.iter().position(|&weight| {
+			sum += weight as u128;
+			sum > r
+		}).unwrap();
+
+		committee.push(index);
+		total -= remaining[index] as u128;
+		remaining[index] = 0;
+	}
+	committee
+}
+
+/// Uniform sampling from a bitset of candidate indices.
+///
+/// Procedural generation such as wave function collapse repeatedly picks a random candidate from a set that changes as constraints propagate.
+/// The set is stored as a bitmask of `u64` words, sampling takes `O(words)` time without allocating.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::select::BitSetSampler;
+///
+/// let mut rng = urandom::new();
+/// let mut tiles = BitSetSampler::new(9);
+/// tiles.remove(4);
+/// let tile = tiles.sample_and_remove(&mut rng).unwrap();
+/// assert!(tile != 4 && !tiles.contains(tile));
+/// assert_eq!(tiles.len(), 7);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BitSetSampler {
+	words: Vec<u64>,
+	universe: usize,
+	len: usize,
+}
+
+impl BitSetSampler {
+	/// Constructs a new set containing all indices in `0..universe`.
+	pub fn new(universe: usize) -> BitSetSampler {
+		let mut words = vec![!0u64; universe.div_ceil(64)];
+		if !universe.is_multiple_of(64) {
+			words[universe / 64] = (1u64 << (universe % 64)) - 1;
+		}
+		BitSetSampler { words, universe, len: universe }
+	}
+	/// Constructs a new empty set of indices in `0..universe`.
+	pub fn empty(universe: usize) -> BitSetSampler {
+		BitSetSampler { words: vec![0u64; universe.div_ceil(64)], universe, len: 0 }
+	}
+	/// Returns the number of indices in the set.
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.len
+	}
+	/// Returns `true` if the set is empty.
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+	/// Returns the exclusive upper bound of the indices in the set.
+	#[inline]
+	pub fn universe(&self) -> usize {
+		self.universe
+	}
+	/// Returns the bitmask words, bit `i % 64` of word `i / 64` is set if the index `i` is in the set.
+	#[inline]
+	pub fn as_words(&self) -> &[u64] {
+		&self.words
+	}
+	/// Returns `true` if the index is in the set.
+	///
+	/// # Panics
+	///
+	/// Panics if the index is not less than the universe.
+	#[inline]
+	pub fn contains(&self, index: usize) -> bool {
+		let (word, bit) = self.position(index);
+		self.words[word] & bit != 0
+	}
+	/// Inserts the index and returns `true` if it was not already in the set.
+	///
+	/// # Panics
+	///
+	/// Panics if the index is not less than the universe.
+	#[inline]
+	pub fn insert(&mut self, index: usize) -> bool {
+		let (word, bit) = self.position(index);
+		let inserted = self.words[word] & bit == 0;
+		self.words[word] |= bit;
+		self.len += inserted as usize;
+		inserted
+	}
+	/// Removes the index and returns `true` if it was in the set.
+	///
+	/// # Panics
+	///
+	/// Panics if the index is not less than the universe.
+	#[inline]
+	pub fn remove(&mut self, index: usize) -> bool {
+		let (word, bit) = self.position(index);
+		let removed = self.words[word] & bit != 0;
+		self.words[word] &= !bit;
+		self.len -= removed as usize;
+		removed
+	}
+	/// Returns a uniformly random index from the set, or `None` if the set is empty.
+	///
+	/// # Implementation notes
+	///
+	/// Samples a rank `r` uniformly in `[0, len)`, then skips whole words by their popcount
+	/// and selects the `r`-th set bit within the word by skipping whole bytes by their popcount.
+	pub fn sample<R: Rng + ?Sized>(&self, rand: &mut Random<R>) -> Option<usize> {
+		if self.len == 0 {
+			return None;
+		}
+		// The rank exceeds 32 bits for sets of more than 2³² indices
+		let mut rank = rand.index(self.len);
+		for (i, &word) in self.words.iter().enumerate() {
+			let count = word.count_ones() as usize;
+			if rank < count {
+				return Some(i * 64 + select_in_word(word, rank as u32) as usize);
+			}
+			rank -= count;
+		}
+		unreachable!()
+	}
+	/// Removes and returns a uniformly random index from the set, or `None` if the set is empty.
+	#[inline]
+	pub fn sample_and_remove<R: Rng + ?Sized>(&mut self, rand: &mut Random<R>) -> Option<usize> {
+		let index = self.sample(rand)?;
+		self.remove(index);
+		Some(index)
+	}
+	#[inline]
+	fn position(&self, index: usize) -> (usize, u64) {
+		if index >= self.universe {
+			bit_set_index_error(index, self.universe);
+		}
+		(index / 64, 1u64 << (index % 64))
+	}
+}
+
+/// Returns the position of the `rank`-th (zero-based) set bit of the word.
+#[inline]
+fn select_in_word(mut word: u64, mut rank: u32) -> u32 {
+	debug_assert!(rank < word.count_ones());
+	let mut offset = 0;
+	loop {
+		let count = (word & 0xff).count_ones();
+		if rank < count {
+			break;
+		}
+		rank -= count;
+		word >>= 8;
+		offset += 8;
+	}
+	for _ in 0..rank {
+		word &= word - 1;
+	}
+	offset + word.trailing_zeros()
+}
+
+#[cold]
+fn bit_set_index_error(index: usize, universe: usize) -> ! {
+	panic!("BitSetSampler index (is {}) should be < universe (is {})", index, universe);
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_keystream() {
+	// RFC 8439 appendix A.1 test vector #1
+	let mut rng = ChaCha20::from_seed_bytes([0; 32]);
+	assert_eq!(rng.next_u64(), 0x903df1a0ade0b876);
+}
+
+#[test]
+fn test_golden() {
+	// Computed with an independent implementation of the specification
+	let weights = [100, 0, 250, 50, 600, 1, 1 << 40, 7];
+	assert_eq!(committee([0; 32], &weights, 3), [6, 0, 7]);
+	assert_eq!(committee([42; 32], &[1; 10], 4), [0, 7, 4, 3]);
+	assert_eq!(committee([1; 32], &[3, 1, 4, 1, 5, 9, 2, 6, 5, 3], 5), [8, 4, 0, 7, 5]);
+}
+
+#[test]
+fn test_everyone() {
+	let weights = [5, 0, 3, 0, 1];
+	assert_eq!(committee([7; 32], &weights, 3), [0, 2, 4]);
+	assert_eq!(committee([7; 32], &weights, 10), [0, 2, 4]);
+	assert_eq!(committee([7; 32], &[], 1), []);
+	assert_eq!(committee([7; 32], &weights, 0), []);
+}
+
+#[test]
+fn test_inclusion() {
+	let weights = [1, 2, 0, 3, 4, 10];
+	let mut counts = [0i32; 6];
+	const N: i32 = 20000;
+	let mut rng = crate::seeded(42);
+	for _ in 0..N {
+		let seed: [u8; 32] = rng.next();
+		let selected = committee(seed, &weights, 1);
+		assert_eq!(selected.len(), 1);
+		counts[selected[0]] += 1;
+	}
+	assert_eq!(counts[2], 0);
+	for (i, &weight) in weights.iter().enumerate() {
+		let expected = N * weight as i32 / 20;
+		assert!((counts[i] - expected).abs() < 250, "index: {} count: {} expected: {}", i, counts[i], expected);
+	}
+
+	// Without replacement every selected index is distinct
+	for _ in 0..1000 {
+		let seed: [u8; 32] = rng.next();
+		let mut selected = committee(seed, &weights, 3);
+		assert!(!selected.contains(&2));
+		selected.sort();
+		selected.dedup();
+		assert_eq!(selected.len(), 3);
+	}
+}
+
+#[test]
+fn test_select_in_word() {
+	// Every bit of every small pattern, and every position of the full and single bit words
+	for word in 1u64..=0xffff {
+		let word = word | word << 40;
+		let mut rank = 0;
+		for bit in 0..64 {
+			if word & (1 << bit) != 0 {
+				assert_eq!(select_in_word(word, rank), bit, "word: {:#x} rank: {}", word, rank);
+				rank += 1;
+			}
+		}
+	}
+	for bit in 0..64 {
+		assert_eq!(select_in_word(!0, bit), bit);
+		assert_eq!(select_in_word(1 << bit, 0), bit);
+	}
+}
+
+#[test]
+fn test_bit_set_uniform() {
+	let mut rng = crate::seeded(42);
+	const N: i32 = 60000;
+
+	// Dense set with holes across several words
+	let mut dense = BitSetSampler::new(150);
+	for index in (0..150).filter(|i| i % 7 == 3) {
+		dense.remove(index);
+	}
+	let mut counts = [0i32; 150];
+	for _ in 0..N * 2 {
+		counts[dense.sample(&mut rng).unwrap()] += 1;
+	}
+	let expected = N * 2 / dense.len() as i32;
+	for (index, &count) in counts.iter().enumerate() {
+		if index % 7 == 3 {
+			assert_eq!(count, 0);
+		}
+		else {
+			assert!((count - expected).abs() < 200, "index: {} count: {} expected: {}", index, count, expected);
+		}
+	}
+
+	// Sparse set spread over many words
+	let mut sparse = BitSetSampler::empty(10000);
+	let members = [0, 63, 64, 999, 4242, 9999];
+	for &index in &members {
+		assert!(sparse.insert(index));
+	}
+	let mut counts = [0i32; 6];
+	for _ in 0..N {
+		let index = sparse.sample(&mut rng).unwrap();
+		counts[members.iter().position(|&m| m == index).unwrap()] += 1;
+	}
+	assert!(counts.iter().all(|&count| (count - N / 6).abs() < 300), "{:?}", counts);
+}
+
+#[test]
+fn test_bit_set_updates() {
+	let mut rng = crate::seeded(42);
+	let mut set = BitSetSampler::new(130);
+	assert_eq!(set.len(), 130);
+	assert_eq!(set.as_words(), [!0, !0, 3]);
+	assert!(!set.insert(129));
+	assert!(set.remove(129));
+	assert!(!set.remove(129));
+	assert_eq!(set.len(), 129);
+
+	let mut seen = [false; 130];
+	while let Some(index) = set.sample_and_remove(&mut rng) {
+		assert!(!seen[index] && index != 129);
+		seen[index] = true;
+	}
+	assert!(set.is_empty());
+	assert_eq!(set.sample(&mut rng), None);
+	assert_eq!(set, BitSetSampler::empty(130));
+
+	assert!(set.insert(77));
+	for _ in 0..10 {
+		assert_eq!(set.sample(&mut rng), Some(77));
+	}
+	assert_eq!(BitSetSampler::new(0).sample(&mut rng), None);
+}
+
+#[test]
+#[should_panic]
+fn test_bit_set_oob() {
+	BitSetSampler::new(64).insert(64);
+}
@@ -0,0 +1,326 @@
+/*!
+Power-on self-tests for certification environments.
+
+Standards such as FIPS 140-3 require a module to verify its algorithms and its entropy source before the first use.
+[`self_test`] runs the following checks and returns a [`SelfTestReport`] or the first [`SelfTestError`]:
+
+1. Known-answer tests of [`Xoshiro256`](crate::rng::Xoshiro256), [`SplitMix64`](crate::rng::SplitMix64) and [`ChaCha20`](crate::rng::ChaCha20) against the published reference vectors and the golden outputs of `from_seed`.
+2. Health tests of [`HEALTH_TEST_BYTES`] bytes from [`getentropy`](crate::rng::getentropy), see [`health_tests`].
+
+[`new_checked`] runs the known-answer tests of the default generator once per process before returning it.
+
+# Examples
+
+```
+let report = urandom::self_test().expect("self-test failed");
+assert_eq!(report.health.bytes, urandom::self_test::HEALTH_TEST_BYTES);
+```
+*/
+
+use std::{error, fmt};
+use std::sync::OnceLock;
+use crate::{Random, Rng};
+use crate::rng::{ChaCha20, ChaCha20State, SplitMix64, Xoshiro256};
+
+/// Number of entropy bytes sampled by [`self_test`].
+pub const HEALTH_TEST_BYTES: usize = 5120;
+
+/// Cutoff of the repetition count test.
+///
+/// Derived from SP 800-90B section 4.4.1 as `1 + ⌈20 / H⌉` for a false positive rate of 2<sup>-20</sup>,
+/// assuming a conservative min-entropy of `H = 4` bits per byte.
+pub const REPETITION_CUTOFF: usize = 6;
+
+/// Window size of the adaptive proportion test in bytes.
+pub const PROPORTION_WINDOW: usize = 512;
+
+/// Cutoff of the adaptive proportion test.
+///
+/// Derived from SP 800-90B section 4.4.2 as the critical binomial value for a window of 512 samples,
+/// a false positive rate of 2<sup>-20</sup> and a min-entropy of 4 bits per byte.
+pub const PROPORTION_CUTOFF: usize = 62;
+
+/// Number of bytes checked by the monobit test, 20000 bits as in FIPS 140-2.
+pub const MONOBIT_BYTES: usize = 2500;
+
+/// Cutoff of the monobit test, the distance of the number of one bits from 10000.
+///
+/// The bounds of FIPS 140-2 are only 3.9 standard deviations wide and fail a healthy source about once in 10<sup>4</sup> runs.
+/// The cutoff is 6 standard deviations of the 20000 bits for a false positive rate of about 2<sup>-29</sup>.
+pub const MONOBIT_CUTOFF: usize = 425;
+
+/// Results of a successful [`self_test`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct SelfTestReport {
+	/// Number of known-answer vectors which matched.
+	pub known_answers: usize,
+	/// Statistics of the entropy health tests.
+	pub health: HealthReport,
+}
+
+/// Statistics of successful [`health_tests`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct HealthReport {
+	/// Number of bytes tested.
+	pub bytes: usize,
+	/// Longest run of identical bytes.
+	pub longest_run: usize,
+	/// Highest count of the first byte of a window within its window.
+	pub max_proportion: usize,
+	/// Number of one bits among the monobit bytes.
+	pub ones: usize,
+}
+
+/// Self-test errors.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SelfTestError {
+	/// The output of a generator does not match its known answer.
+	KnownAnswer {
+		/// Name of the generator.
+		generator: &'static str,
+		/// Index of the mismatching word.
+		index: usize,
+	},
+	/// A byte repeats [`REPETITION_CUTOFF`] or more times in a row.
+	RepetitionCount {
+		/// Offset of the first byte of the run.
+		offset: usize,
+	},
+	/// A byte occurs [`PROPORTION_CUTOFF`] or more times in a window.
+	AdaptiveProportion {
+		/// Offset of the window.
+		offset: usize,
+		/// Number of occurrences of the first byte of the window.
+		count: usize,
+	},
+	/// The number of one bits differs from 10000 by [`MONOBIT_CUTOFF`] or more.
+	Monobit {
+		/// Number of one bits.
+		ones: usize,
+	},
+	/// Fewer than [`MONOBIT_BYTES`] bytes were provided.
+	InsufficientData {
+		/// Number of bytes provided.
+		len: usize,
+	},
+}
+
+impl fmt::Display for SelfTestError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			SelfTestError::KnownAnswer { generator, index } => write!(f, "known-answer test of {} failed at word {}", generator, index),
+			SelfTestError::RepetitionCount { offset } => write!(f, "repetition count test failed at offset {}", offset),
+			SelfTestError::AdaptiveProportion { offset, count } => write!(f, "adaptive proportion test failed at offset {}: count {}", offset, count),
+			SelfTestError::Monobit { ones } => write!(f, "monobit test failed: {} ones in 20000 bits", ones),
+			SelfTestError::InsufficientData { len } => write!(f, "health tests require at least {} bytes: found {}", MONOBIT_BYTES, len),
+		}
+	}
+}
+
+impl error::Error for SelfTestError {}
+
+/// Runs the known-answer tests and the entropy health tests.
+///
+/// See the [module documentation](self) for the checks performed.
+pub fn self_test() -> Result<SelfTestReport, SelfTestError> {
+	let known_answers = known_answer_tests()?;
+	let mut bytes = [0u8; HEALTH_TEST_BYTES];
+	crate::rng::getentropy(&mut bytes);
+	let health = health_tests(&bytes)?;
+	Ok(SelfTestReport { known_answers, health })
+}
+
+/// Creates the default generator after checking its known answers.
+///
+/// The known-answer tests of the default generator run once per process, on the first call.
+///
+/// # Panics
+///
+/// Panics if the known-answer tests fail.
+///
+/// # Examples
+///
+/// ```
+/// let mut rng = urandom::new_checked();
+/// let value: i32 = rng.next();
+/// ```
+pub fn new_checked() -> Random<crate::DefaultRng> {
+	static CHECKED: OnceLock<Result<usize, SelfTestError>> = OnceLock::new();
+	if let Err(err) = CHECKED.get_or_init(xoshiro256_known_answers) {
+		known_answer_failed(err)
+	}
+	crate::new()
+}
+
+#[cold]
+fn known_answer_failed(err: &SelfTestError) -> ! {
+	panic!("{}", err)
+}
+
+/// Runs the known-answer tests of all the generators.
+///
+/// Returns the number of vectors which matched.
+pub fn known_answer_tests() -> Result<usize, SelfTestError> {
+	Ok(xoshiro256_known_answers()? + splitmix64_known_answers()? + chacha20_known_answers()?)
+}
+
+fn check<R: Rng + ?Sized>(generator: &'static str, rng: &mut Random<R>, expected: &[u64]) -> Result<usize, SelfTestError> {
+	for (index, &value) in expected.iter().enumerate() {
+		if rng.next_u64() != value {
+			return Err(SelfTestError::KnownAnswer { generator, index });
+		}
+	}
+	Ok(1)
+}
+
+fn xoshiro256_known_answers() -> Result<usize, SelfTestError> {
+	// Reference outputs from xoshiro256plusplus.c by David Blackman and Sebastiano Vigna
+	let reference = check("Xoshiro256", &mut Xoshiro256::from_state_const([1, 2, 3, 4]), &[41943041, 58720359, 3588806011781223, 3591011842654386])?;
+	let seeded = check("Xoshiro256", &mut Xoshiro256::from_seed(0x5eed), &[0x8eb2871b24ae0c00, 0xfdd2c14d7560f757, 0x17460bdf1e7c3333, 0x6ff7f624b0c6310f])?;
+	Ok(reference + seeded)
+}
+
+fn splitmix64_known_answers() -> Result<usize, SelfTestError> {
+	// Reference outputs from splitmix64.c by Sebastiano Vigna
+	let reference = check("SplitMix64", &mut SplitMix64::from_state(1234567), &[6457827717110365317, 3203168211198807973, 9817491932198370423, 4593380528125082431])?;
+	let seeded = check("SplitMix64", &mut SplitMix64::from_seed(0x5eed), &[0x09f1fd9d03f0a9b4, 0x553274161bbf8475, 0x5d5bca4696b343b3, 0x70d29b6c7d22528d])?;
+	Ok(reference + seeded)
+}
+
+fn chacha20_known_answers() -> Result<usize, SelfTestError> {
+	// RFC 8439 section 2.3.2 test vector
+	let key = [0x03020100, 0x07060504, 0x0b0a0908, 0x0f0e0d0c, 0x13121110, 0x17161514, 0x1b1a1918, 0x1f1e1d1c];
+	let state = ChaCha20State { key, counter: 1 | 0x09000000 << 32, stream: 0x4a000000, index: 16 };
+	let Ok(mut rng) = ChaCha20::from_state(state) else {
+		return Err(SelfTestError::KnownAnswer { generator: "ChaCha20", index: 0 });
+	};
+	let reference = check("ChaCha20", &mut rng, &[0x15593bd1e4e7f110, 0xc47120a31fdd0f50, 0x0368c033c7f4d1c7, 0x4e6cd4c39aaa2204])?;
+	let seeded = check("ChaCha20", &mut ChaCha20::from_seed(0x5eed), &[0x67aade0cc1f233b0, 0x7233de6becfaff10, 0xaad28054ff890bb6, 0x79e3c77fa207a601])?;
+	Ok(reference + seeded)
+}
+
+/// Runs the health tests on a sample of the entropy source.
+///
+/// The tests follow NIST SP 800-90B section 4.4 and the monobit test of FIPS 140-2 with wider bounds:
+///
+/// * The repetition count test fails if any byte repeats [`REPETITION_CUTOFF`] times in a row.
+/// * The adaptive proportion test fails if, in any complete window of [`PROPORTION_WINDOW`] bytes, the first byte occurs [`PROPORTION_CUTOFF`] or more times.
+/// * The monobit test fails if the number of one bits in the first [`MONOBIT_BYTES`] bytes differs from 10000 by [`MONOBIT_CUTOFF`] or more.
+///
+/// # Errors
+///
+/// Returns the first failing test or [`InsufficientData`](SelfTestError::InsufficientData) if fewer than [`MONOBIT_BYTES`] bytes are provided.
+///
+/// # Examples
+///
+/// ```
+/// use urandom::self_test::{health_tests, SelfTestError};
+///
+/// let stuck = [0x55u8; 4096];
+/// assert_eq!(health_tests(&stuck), Err(SelfTestError::RepetitionCount { offset: 0 }));
+/// ```
+pub fn health_tests(bytes: &[u8]) -> Result<HealthReport, SelfTestError> {
+	if bytes.len() < MONOBIT_BYTES {
+		return Err(SelfTestError::InsufficientData { len: bytes.len() });
+	}
+	let longest_run = repetition_count(bytes)?;
+	let max_proportion = adaptive_proportion(bytes)?;
+	let ones = monobit(&bytes[..MONOBIT_BYTES])?;
+	Ok(HealthReport { bytes: bytes.len(), longest_run, max_proportion, ones })
+}
+
+fn repetition_count(bytes: &[u8]) -> Result<usize, SelfTestError> {
+	let mut longest = 0;
+	let mut start = 0;
+	for i in 0..bytes.len() {
+		if bytes[i] != bytes[start] {
+			start = i;
+		}
+		let run = i - start + 1;
+		if run >= REPETITION_CUTOFF {
+			return Err(SelfTestError::RepetitionCount { offset: start });
+		}
+		longest = longest.max(run);
+	}
+	Ok(longest)
+}
+
+fn adaptive_proportion(bytes: &[u8]) -> Result<usize, SelfTestError> {
+	let mut max = 0;
+	for (i, window) in bytes.chunks_exact(PROPORTION_WINDOW).enumerate() {
+		let count = window.iter().filter(|&&byte| byte == window[0]).count();
+		if count >= PROPORTION_CUTOFF {
+			return Err(SelfTestError::AdaptiveProportion { offset: i * PROPORTION_WINDOW, count });
+		}
+		max = max.max(count);
+	}
+	Ok(max)
+}
+
+fn monobit(bytes: &[u8]) -> Result<usize, SelfTestError> {
+	let ones: usize = bytes.iter().map(|byte| byte.count_ones() as usize).sum();
+	if ones.abs_diff(MONOBIT_BYTES * 4) >= MONOBIT_CUTOFF {
+		return Err(SelfTestError::Monobit { ones });
+	}
+	Ok(ones)
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_known_answers() {
+	assert_eq!(known_answer_tests(), Ok(6));
+	let _ = new_checked().next_u64();
+
+	let err = check("Test", &mut crate::seeded(42), &[0]).unwrap_err();
+	assert_eq!(err, SelfTestError::KnownAnswer { generator: "Test", index: 0 });
+}
+
+#[test]
+fn test_self_test() {
+	let report = self_test().unwrap();
+	assert_eq!(report.known_answers, 6);
+	assert_eq!(report.health.bytes, HEALTH_TEST_BYTES);
+	assert!(report.health.longest_run < REPETITION_CUTOFF);
+}
+
+#[test]
+fn test_health_tests() {
+	let mut bytes = [0u8; HEALTH_TEST_BYTES];
+	crate::seeded(42).fill_bytes(&mut bytes);
+	let report = health_tests(&bytes).unwrap();
+	assert_eq!(report.bytes, HEALTH_TEST_BYTES);
+
+	// Stuck output
+	assert_eq!(health_tests(&[0xff; 3000]), Err(SelfTestError::RepetitionCount { offset: 0 }));
+	let mut run = bytes;
+	run[100..100 + REPETITION_CUTOFF].fill(7);
+	assert_eq!(health_tests(&run), Err(SelfTestError::RepetitionCount { offset: 100 }));
+	run[101] = 8;
+	assert!(health_tests(&run).is_ok());
+
+	// A byte which is too common without long runs
+	let mut biased = bytes;
+	for i in (1024..1024 + PROPORTION_WINDOW).step_by(4) {
+		biased[i] = 0;
+	}
+	assert!(matches!(health_tests(&biased), Err(SelfTestError::AdaptiveProportion { offset: 1024, count }) if count >= PROPORTION_WINDOW / 4));
+
+	// A stuck bit passes the byte tests but not the monobit test
+	let skewed = bytes.map(|byte| byte | 1);
+	assert!(matches!(health_tests(&skewed), Err(SelfTestError::Monobit { ones }) if ones >= 10000 + MONOBIT_CUTOFF));
+
+	// The bounds are exclusive
+	let mut ones = [0u8; MONOBIT_BYTES];
+	let set = |ones: &mut [u8], count: usize| {
+		ones.fill(0);
+		ones[..count / 8].fill(0xff);
+		ones[count / 8] = (1u8 << (count % 8)).wrapping_sub(1);
+	};
+	set(&mut ones, 10000 + MONOBIT_CUTOFF - 1);
+	assert_eq!(monobit(&ones), Ok(10000 + MONOBIT_CUTOFF - 1));
+	set(&mut ones, 10000 - MONOBIT_CUTOFF);
+	assert_eq!(monobit(&ones), Err(SelfTestError::Monobit { ones: 10000 - MONOBIT_CUTOFF }));
+
+	assert_eq!(health_tests(&bytes[..100]), Err(SelfTestError::InsufficientData { len: 100 }));
+}
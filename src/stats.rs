@@ -0,0 +1,450 @@
+/*!
+Statistical goodness-of-fit tests.
+
+Checks whether a set of samples is consistent with a claimed distribution, eg. in the tests of a custom [`Distribution`](crate::Distribution) implementation.
+The tests return a p-value: the probability of observing a deviation at least this large if the samples follow the claimed distribution.
+Small p-values are evidence against the claimed distribution.
+
+* [`chi_squared_uniform`](chi_squared_uniform) and [`chi_squared`](chi_squared) test category counts of discrete distributions.
+* [`kolmogorov_smirnov`](kolmogorov_smirnov) tests samples of continuous distributions against their cumulative distribution function.
+* [`min_entropy`](min_entropy) estimates the min-entropy of a raw entropy source.
+
+The `assert_*` functions panic when the p-value is below the significance level, with a message describing the deviation.
+Under the claimed distribution they fail with probability equal to the significance level,
+tests should use a fixed seed or a small significance level such as `1e-4` to avoid spurious failures.
+
+# Examples
+
+```
+use urandom::Distribution;
+use urandom::distributions::Dice;
+
+let mut rng = urandom::seeded(42);
+let mut counts = [0u64; 6];
+for _ in 0..6000 {
+	counts[Dice::new(6).sample(&mut rng) as usize - 1] += 1;
+}
+urandom::stats::assert_uniform_counts(&counts, 1e-4);
+```
+*/
+
+/// Returns the p-value of Pearson's chi-squared test of the counts against the uniform distribution over the categories.
+///
+/// Returns `1.0` if there are fewer than two categories or no observations.
+///
+/// The test is reliable when every category is expected to have at least five observations.
+pub fn chi_squared_uniform(counts: &[u64]) -> f64 {
+	let total: u64 = counts.iter().sum();
+	if counts.len() < 2 || total == 0 {
+		return 1.0;
+	}
+	let expected = total as f64 / counts.len() as f64;
+	let statistic: f64 = counts.iter().map(|&count| (count as f64 - expected).powi(2) / expected).sum();
+	chi_squared_sf(statistic, (counts.len() - 1) as f64)
+}
+
+/// Returns the p-value of Pearson's chi-squared test of the counts against the probabilities of the categories.
+///
+/// The probabilities are normalized to sum to one. Categories with zero probability must have zero counts, otherwise the p-value is zero.
+///
+/// Returns `1.0` if there are fewer than two categories with nonzero probability or no observations.
+///
+/// # Panics
+///
+/// Panics if the lengths of the counts and probabilities differ or if any probability is negative or not finite.
+pub fn chi_squared(counts: &[u64], probabilities: &[f64]) -> f64 {
+	if counts.len() != probabilities.len() || !probabilities.iter().all(|&p| p >= 0.0 && p.is_finite()) {
+		chi_squared_error();
+	}
+	let total: u64 = counts.iter().sum();
+	let total_p: f64 = probabilities.iter().sum();
+	let mut statistic = 0.0;
+	let mut categories = 0;
+	for (&count, &p) in counts.iter().zip(probabilities) {
+		if p == 0.0 {
+			if count != 0 {
+				return 0.0;
+			}
+			continue;
+		}
+		let expected = p / total_p * total as f64;
+		statistic += (count as f64 - expected).powi(2) / expected;
+		categories += 1;
+	}
+	if categories < 2 || total == 0 {
+		return 1.0;
+	}
+	chi_squared_sf(statistic, (categories - 1) as f64)
+}
+
+/// Returns the p-value of the one-sample Kolmogorov–Smirnov test of the samples against the cumulative distribution function.
+///
+/// The samples are sorted in place. Returns `1.0` if there are no samples.
+///
+/// The p-value uses the asymptotic Kolmogorov distribution with Stephens' correction for the sample size,
+/// it is accurate for more than about 35 samples.
+pub fn kolmogorov_smirnov<F: Fn(f64) -> f64>(samples: &mut [f64], cdf: F) -> f64 {
+	if samples.is_empty() {
+		return 1.0;
+	}
+	samples.sort_unstable_by(f64::total_cmp);
+	let n = samples.len() as f64;
+	let mut statistic = 0.0f64;
+	for (i, &x) in samples.iter().enumerate() {
+		let p = cdf(x);
+		// NaN samples or probabilities are not consistent with any distribution
+		if p.is_nan() {
+			return 0.0;
+		}
+		statistic = statistic.max((i + 1) as f64 / n - p).max(p - i as f64 / n);
+	}
+	let sqrt_n = n.sqrt();
+	kolmogorov_sf((sqrt_n + 0.12 + 0.11 / sqrt_n) * statistic)
+}
+
+/// Asserts that the counts are consistent with the uniform distribution over the categories.
+///
+/// See [`chi_squared_uniform`](chi_squared_uniform).
+///
+/// # Panics
+///
+/// Panics if the p-value is less than the significance level.
+#[track_caller]
+pub fn assert_uniform_counts(counts: &[u64], significance: f64) {
+	let p_value = chi_squared_uniform(counts);
+	if p_value < significance {
+		panic!("counts are not uniform (p-value: {:e} < {:e}): {:?}", p_value, significance, counts);
+	}
+}
+
+/// Asserts that the counts are consistent with the probabilities of the categories.
+///
+/// See [`chi_squared`](chi_squared).
+///
+/// # Panics
+///
+/// Panics if the p-value is less than the significance level.
+#[track_caller]
+pub fn assert_counts(counts: &[u64], probabilities: &[f64], significance: f64) {
+	let p_value = chi_squared(counts, probabilities);
+	if p_value < significance {
+		panic!("counts do not fit the probabilities (p-value: {:e} < {:e}): {:?} {:?}", p_value, significance, counts, probabilities);
+	}
+}
+
+/// Asserts that the samples are consistent with the cumulative distribution function.
+///
+/// See [`kolmogorov_smirnov`](kolmogorov_smirnov).
+///
+/// # Panics
+///
+/// Panics if the p-value is less than the significance level.
+#[track_caller]
+pub fn assert_cdf<F: Fn(f64) -> f64>(samples: &mut [f64], cdf: F, significance: f64) {
+	let p_value = kolmogorov_smirnov(samples, cdf);
+	if p_value < significance {
+		panic!("samples do not fit the distribution (p-value: {:e} < {:e}, n: {})", p_value, significance, samples.len());
+	}
+}
+
+/// Returns a conservative estimate of the min-entropy of the bytes in bits per byte, between `0.0` and `8.0`.
+///
+/// Helps decide whether a raw source such as [`ReadRng`](crate::rng::ReadRng) over a hardware device can be trusted directly
+/// or must be fed through a conditioner such as [`Conditioned`](crate::rng::Conditioned).
+///
+/// Returns the minimum of the following estimators of NIST SP 800-90B section 6.3, both use the upper bound of a 99% confidence interval:
+///
+/// * The most common value estimate over the bytes: `-log2(p)` where `p` bounds the frequency of the most common byte.
+/// * The collision estimate over the bits of the bytes, least significant bit first, multiplied by eight.
+///   The mean time until two consecutive bits collide is `2 + 2p(1 - p)` for a bit which is one with probability `p`, solving for `p` gives the estimate.
+///
+/// The estimators assume the bytes are independent, they do not detect correlations between bytes such as the output of a counter.
+/// They are not a substitute for a full SP 800-90B assessment.
+///
+/// Returns `0.0` if there are fewer than two bytes.
+///
+/// # Examples
+///
+/// ```
+/// let mut bytes = vec![0u8; 100000];
+/// urandom::new().fill_bytes(&mut bytes);
+/// assert!(urandom::stats::min_entropy(&bytes) > 7.0);
+///
+/// assert_eq!(urandom::stats::min_entropy(&[0x55; 1000]), 0.0);
+/// ```
+pub fn min_entropy(bytes: &[u8]) -> f64 {
+	if bytes.len() < 2 {
+		return 0.0;
+	}
+	f64::min(most_common_value(bytes), 8.0 * collision_bits(bytes))
+}
+
+// Upper bound of the 99% confidence interval
+const Z_99: f64 = 2.576;
+
+// Most common value estimate in bits per byte, SP 800-90B section 6.3.1
+fn most_common_value(bytes: &[u8]) -> f64 {
+	let mut counts = [0u64; 256];
+	for &byte in bytes {
+		counts[byte as usize] += 1;
+	}
+	let n = bytes.len() as f64;
+	let p = counts.iter().copied().max().unwrap_or(0) as f64 / n;
+	let upper = f64::min(1.0, p + Z_99 * (p * (1.0 - p) / (n - 1.0)).sqrt());
+	f64::max(0.0, -upper.log2())
+}
+
+// Collision estimate in bits per bit, SP 800-90B section 6.3.2
+fn collision_bits(bytes: &[u8]) -> f64 {
+	let bit = |i: usize| bytes[i / 8] >> (i % 8) & 1;
+	let len = bytes.len() * 8;
+	let (mut sum, mut sum_sq, mut v) = (0.0f64, 0.0f64, 0.0f64);
+	let mut i = 0;
+	// Among three consecutive bits two always collide
+	while i + 2 < len {
+		let t = if bit(i) == bit(i + 1) { 2.0 } else { 3.0 };
+		i += t as usize;
+		sum += t;
+		sum_sq += t * t;
+		v += 1.0;
+	}
+	let mean = sum / v;
+	let sigma = ((sum_sq - v * mean * mean) / (v - 1.0)).max(0.0).sqrt();
+	let lower = mean - Z_99 * sigma / f64::sqrt(v);
+	// Solve the mean collision time 2 + 2p(1 - p) for the probability p >= 0.5 of the most likely bit
+	let pq = (lower - 2.0).clamp(0.0, 0.5);
+	let p = 0.5 * (1.0 + (1.0 - 2.0 * pq).sqrt());
+	f64::max(0.0, -p.log2())
+}
+
+/// Returns the survival function `1 - CDF(x)` of the chi-squared distribution with `k` degrees of freedom.
+///
+/// This is the p-value of a chi-squared statistic `x`.
+pub fn chi_squared_sf(x: f64, k: f64) -> f64 {
+	if x.is_nan() {
+		return f64::NAN;
+	}
+	if x <= 0.0 {
+		return 1.0;
+	}
+	gamma_q(k * 0.5, x * 0.5)
+}
+
+#[cold]
+fn chi_squared_error() -> ! {
+	panic!("chi_squared called with mismatched lengths or invalid probabilities")
+}
+
+// Natural logarithm of the gamma function for x > 0, Lanczos approximation with g = 7
+pub(crate) fn ln_gamma(x: f64) -> f64 {
+	const COEFFICIENTS: [f64; 9] = [
+		0.999_999_999_999_809_9,
+		676.520_368_121_885_1,
+		-1_259.139_216_722_402_8,
+		771.323_428_777_653_1,
+		-176.615_029_162_140_6,
+		12.507_343_278_686_905,
+		-0.138_571_095_265_720_12,
+		9.984_369_578_019_572e-6,
+		1.505_632_735_149_311_6e-7,
+	];
+	if x < 0.5 {
+		// Reflection formula
+		let pi = core::f64::consts::PI;
+		return (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x);
+	}
+	let x = x - 1.0;
+	let mut sum = COEFFICIENTS[0];
+	for (i, &c) in COEFFICIENTS.iter().enumerate().skip(1) {
+		sum += c / (x + i as f64);
+	}
+	let t = x + 7.5;
+	0.5 * (2.0 * core::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+// Regularized upper incomplete gamma function Q(a, x) = Γ(a, x) / Γ(a)
+fn gamma_q(a: f64, x: f64) -> f64 {
+	const EPSILON: f64 = 1e-15;
+	const MAX_ITERATIONS: usize = 1000;
+	let ln_prefix = a * x.ln() - x - ln_gamma(a);
+	if x < a + 1.0 {
+		// Series for the lower function P(a, x)
+		let mut term = 1.0 / a;
+		let mut sum = term;
+		for n in 1..MAX_ITERATIONS {
+			term *= x / (a + n as f64);
+			sum += term;
+			if term < sum * EPSILON {
+				break;
+			}
+		}
+		1.0 - sum * ln_prefix.exp()
+	}
+	else {
+		// Continued fraction for Q(a, x) evaluated with the modified Lentz method
+		const TINY: f64 = 1e-300;
+		let mut b = x + 1.0 - a;
+		let mut c = 1.0 / TINY;
+		let mut d = 1.0 / b;
+		let mut h = d;
+		for n in 1..MAX_ITERATIONS {
+			let an = -(n as f64) * (n as f64 - a);
+			b += 2.0;
+			d = an * d + b;
+			if d.abs() < TINY {
+				d = TINY;
+			}
+			c = b + an / c;
+			if c.abs() < TINY {
+				c = TINY;
+			}
+			d = 1.0 / d;
+			let delta = d * c;
+			h *= delta;
+			if (delta - 1.0).abs() < EPSILON {
+				break;
+			}
+		}
+		h * ln_prefix.exp()
+	}
+}
+
+// Survival function of the Kolmogorov distribution
+fn kolmogorov_sf(lambda: f64) -> f64 {
+	// The alternating series converges slowly for small lambda where the p-value is indistinguishable from one
+	if lambda < 0.2 {
+		return 1.0;
+	}
+	let mut sum = 0.0;
+	let mut sign = 2.0;
+	for j in 1..=100 {
+		let term = sign * (-2.0 * (j * j) as f64 * lambda * lambda).exp();
+		sum += term;
+		if term.abs() < 1e-16 * sum.abs() {
+			break;
+		}
+		sign = -sign;
+	}
+	sum.clamp(0.0, 1.0)
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_ln_gamma() {
+	let cases = [
+		(0.5, 0.572_364_942_924_700_1),
+		(1.0, 0.0),
+		(2.0, 0.0),
+		(3.5, 1.200_973_602_347_074_3),
+		(10.0, 12.801_827_480_081_469),
+		(100.0, 359.134_205_369_575_4),
+		(0.1, 2.252_712_651_734_206),
+	];
+	for &(x, expected) in &cases {
+		let value = ln_gamma(x);
+		assert!((value - expected).abs() < 1e-12 * f64::max(1.0, expected.abs()), "x: {} value: {} expected: {}", x, value, expected);
+	}
+}
+
+#[test]
+fn test_chi_squared_sf() {
+	// Critical values at 5% and 1% significance
+	let cases = [
+		(3.841_458_820_694_124, 1.0, 0.05),
+		(6.634_896_601_021_214, 1.0, 0.01),
+		(5.991_464_547_107_979, 2.0, 0.05),
+		(18.307_038_053_275_146, 10.0, 0.05),
+		(23.209_251_158_954_356, 10.0, 0.01),
+		(124.342_113_404_684_3, 100.0, 0.05),
+	];
+	for &(x, k, expected) in &cases {
+		let value = chi_squared_sf(x, k);
+		assert!((value - expected).abs() < 1e-9, "x: {} k: {} value: {} expected: {}", x, k, value, expected);
+	}
+	// Two degrees of freedom is the exponential distribution
+	for &x in &[0.1, 1.0, 2.0, 10.0, 50.0] {
+		assert!((chi_squared_sf(x, 2.0) - (-x / 2.0).exp()).abs() < 1e-14, "x: {}", x);
+	}
+	assert_eq!(chi_squared_sf(0.0, 3.0), 1.0);
+	assert!(chi_squared_sf(f64::NAN, 3.0).is_nan());
+}
+
+#[test]
+fn test_chi_squared() {
+	assert_eq!(chi_squared_uniform(&[100, 100, 100, 100]), 1.0);
+	assert!(chi_squared_uniform(&[150, 100, 100, 50]) < 1e-9);
+	assert_eq!(chi_squared_uniform(&[5]), 1.0);
+	assert_eq!(chi_squared_uniform(&[0, 0]), 1.0);
+
+	assert_eq!(chi_squared(&[100, 300, 0], &[1.0, 3.0, 0.0]), 1.0);
+	assert_eq!(chi_squared(&[100, 300, 1], &[1.0, 3.0, 0.0]), 0.0);
+	assert!(chi_squared(&[200, 200], &[1.0, 3.0]) < 1e-9);
+	// The statistic is 0.16 with one degree of freedom
+	assert!((chi_squared(&[48, 52], &[0.5, 0.5]) - chi_squared_sf(0.16, 1.0)).abs() < 1e-15);
+
+	let mut rng = crate::seeded(42);
+	let mut counts = [0u64; 10];
+	for _ in 0..10000 {
+		counts[rng.index(10)] += 1;
+	}
+	assert_uniform_counts(&counts, 1e-4);
+}
+
+#[test]
+#[should_panic(expected = "counts are not uniform")]
+fn test_assert_uniform_counts() {
+	assert_uniform_counts(&[1000, 1000, 1200], 1e-4);
+}
+
+#[test]
+fn test_kolmogorov_smirnov() {
+	let mut rng = crate::seeded(42);
+	let uniform_cdf = |x: f64| x.clamp(0.0, 1.0);
+	let exponential_cdf = |x: f64| if x > 0.0 { -(-x).exp_m1() } else { 0.0 };
+
+	// Accepts samples from its own distribution
+	let mut samples: Vec<f64> = (0..2000).map(|_| rng.next_f64() - 1.0).collect();
+	assert!(kolmogorov_smirnov(&mut samples, uniform_cdf) > 0.01);
+	assert_cdf(&mut samples, uniform_cdf, 1e-4);
+	let mut samples: Vec<f64> = (0..2000).map(|_| -(2.0 - rng.next_f64()).ln()).collect();
+	assert_cdf(&mut samples, exponential_cdf, 1e-4);
+
+	// Rejects an obviously wrong distribution
+	assert!(kolmogorov_smirnov(&mut samples, uniform_cdf) < 1e-9);
+	let mut samples: Vec<f64> = (0..2000).map(|_| (rng.next_f64() - 1.0).powi(2)).collect();
+	assert!(kolmogorov_smirnov(&mut samples, uniform_cdf) < 1e-9);
+
+	assert_eq!(kolmogorov_smirnov(&mut [], uniform_cdf), 1.0);
+	assert_eq!(kolmogorov_smirnov(&mut [0.5, f64::NAN], uniform_cdf), 0.0);
+	// Kolmogorov distribution at known quantiles
+	assert!((kolmogorov_sf(1.358_098_8) - 0.05).abs() < 1e-6);
+	assert!((kolmogorov_sf(1.627_624_1) - 0.01).abs() < 1e-6);
+}
+
+#[test]
+fn test_min_entropy() {
+	let mut rng = crate::seeded(42);
+	let mut bytes = vec![0u8; 1 << 20];
+	rng.fill_bytes(&mut bytes);
+	let entropy = min_entropy(&bytes);
+	assert!(entropy > 7.5 && entropy <= 8.0, "entropy: {}", entropy);
+	assert!(most_common_value(&bytes) > 7.8);
+
+	// Constant streams have no entropy
+	assert_eq!(min_entropy(&[0; 4096]), 0.0);
+	assert_eq!(min_entropy(&[0xa5; 4096]), 0.0);
+	assert_eq!(min_entropy(&[1]), 0.0);
+
+	// Bits which are one with probability 3/4 have -8·log2(3/4) bits per byte
+	let expected = -8.0 * 0.75f64.log2();
+	let biased: Vec<u8> = (0..1 << 18).map(|_| (0..8).fold(0u8, |byte, bit| byte | (rng.chance(0.75) as u8) << bit)).collect();
+	let entropy = min_entropy(&biased);
+	assert!((entropy - expected).abs() < 0.1, "entropy: {} expected: {}", entropy, expected);
+	assert!((8.0 * collision_bits(&biased) - expected).abs() < 0.1);
+
+	// A biased coin per byte has -log2(3/4) bits per byte
+	let coins: Vec<u8> = (0..1 << 18).map(|_| rng.chance(0.75) as u8).collect();
+	let entropy = min_entropy(&coins);
+	assert!((entropy + 0.75f64.log2()).abs() < 0.02, "entropy: {}", entropy);
+}
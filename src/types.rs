@@ -0,0 +1,330 @@
+/*!
+Self-documenting random scalar types.
+
+These newtypes make the kind of random value explicit and keep it within its natural range:
+
+* [`Turns`](Turns) and [`Radians`](Radians) are angles which wrap around to a single full rotation.
+* [`Probability`](Probability) is a value in the closed interval `[0, 1]`.
+
+All of them can be sampled from the [`Standard`](crate::distributions::Standard) distribution.
+
+The fields are public, values constructed directly are not normalized until they pass through an operation.
+
+# Examples
+
+```
+use urandom::types::{Probability, Radians, Turns};
+
+let mut rng = urandom::new();
+let heading: Radians = rng.next();
+let turned = heading + Radians::from(Turns(0.25));
+assert!(turned.0 >= 0.0 && turned.0 < std::f64::consts::TAU);
+
+let p: Probability = rng.next();
+let hit = p.chance(&mut rng);
+```
+*/
+
+use core::{fmt, ops};
+use core::f64::consts::TAU;
+use crate::{Distribution, Random, Rng};
+use crate::distributions::{Bernoulli, Float01, Standard};
+
+/// An angle measured in full rotations.
+///
+/// Operations wrap the result to the half-open interval `[0, 1)`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd)]
+pub struct Turns(pub f64);
+
+/// An angle measured in radians.
+///
+/// Operations wrap the result to the half-open interval `[0, 2π)`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd)]
+pub struct Radians(pub f64);
+
+/// A probability in the closed interval `[0, 1]`.
+///
+/// Operations keep the probability in range: products and complements cannot leave the interval,
+/// sums are either checked or saturating.
+#[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd)]
+pub struct Probability(pub f64);
+
+#[inline]
+fn wrap(value: f64, period: f64) -> f64 {
+	let wrapped = value.rem_euclid(period);
+	// Tiny negative values round up to the period
+	if wrapped < period { wrapped } else { 0.0 }
+}
+
+impl Turns {
+	/// Returns the angle wrapped to the half-open interval `[0, 1)`.
+	#[inline]
+	pub fn wrapped(self) -> Turns {
+		Turns(wrap(self.0, 1.0))
+	}
+}
+
+impl Radians {
+	/// Returns the angle wrapped to the half-open interval `[0, 2π)`.
+	#[inline]
+	pub fn wrapped(self) -> Radians {
+		Radians(wrap(self.0, TAU))
+	}
+}
+
+impl From<Turns> for Radians {
+	#[inline]
+	fn from(turns: Turns) -> Radians {
+		Radians(turns.0 * TAU)
+	}
+}
+impl From<Radians> for Turns {
+	#[inline]
+	fn from(radians: Radians) -> Turns {
+		Turns(radians.0 / TAU)
+	}
+}
+
+macro_rules! impl_angle_ops {
+	($ty:ident) => {
+		impl ops::Add for $ty {
+			type Output = $ty;
+			#[inline]
+			fn add(self, rhs: $ty) -> $ty {
+				$ty(self.0 + rhs.0).wrapped()
+			}
+		}
+		impl ops::Sub for $ty {
+			type Output = $ty;
+			#[inline]
+			fn sub(self, rhs: $ty) -> $ty {
+				$ty(self.0 - rhs.0).wrapped()
+			}
+		}
+		impl ops::Neg for $ty {
+			type Output = $ty;
+			#[inline]
+			fn neg(self) -> $ty {
+				$ty(-self.0).wrapped()
+			}
+		}
+		impl ops::Mul<f64> for $ty {
+			type Output = $ty;
+			#[inline]
+			fn mul(self, rhs: f64) -> $ty {
+				$ty(self.0 * rhs).wrapped()
+			}
+		}
+		impl ops::AddAssign for $ty {
+			#[inline]
+			fn add_assign(&mut self, rhs: $ty) {
+				*self = *self + rhs;
+			}
+		}
+		impl ops::SubAssign for $ty {
+			#[inline]
+			fn sub_assign(&mut self, rhs: $ty) {
+				*self = *self - rhs;
+			}
+		}
+	};
+}
+impl_angle_ops!(Turns);
+impl_angle_ops!(Radians);
+
+impl Probability {
+	/// Probability of an impossible event.
+	pub const NEVER: Probability = Probability(0.0);
+	/// Probability of a certain event.
+	pub const ALWAYS: Probability = Probability(1.0);
+
+	/// Constructs a probability, returns `None` if `p` is not in the closed interval `[0, 1]`.
+	#[inline]
+	pub fn new(p: f64) -> Option<Probability> {
+		if p >= 0.0 && p <= 1.0 { Some(Probability(p)) } else { None }
+	}
+	/// Constructs a probability, clamping `p` to the closed interval `[0, 1]`.
+	///
+	/// `NaN` is mapped to zero.
+	#[inline]
+	pub fn saturating(p: f64) -> Probability {
+		if p >= 1.0 { Probability(1.0) } else if p > 0.0 { Probability(p) } else { Probability(0.0) }
+	}
+	/// Returns the probability of the event not happening.
+	#[inline]
+	pub fn complement(self) -> Probability {
+		Probability::saturating(1.0 - self.0)
+	}
+	/// Returns the probability of both independent events happening.
+	#[inline]
+	pub fn and(self, other: Probability) -> Probability {
+		Probability::saturating(self.0 * other.0)
+	}
+	/// Returns the probability of either or both independent events happening.
+	#[inline]
+	pub fn or(self, other: Probability) -> Probability {
+		self.complement().and(other.complement()).complement()
+	}
+	/// Adds the probabilities of mutually exclusive events, returns `None` if the sum exceeds one.
+	#[inline]
+	pub fn checked_add(self, other: Probability) -> Option<Probability> {
+		Probability::new(self.0 + other.0)
+	}
+	/// Adds the probabilities of mutually exclusive events, saturating at one.
+	#[inline]
+	pub fn saturating_add(self, other: Probability) -> Probability {
+		Probability::saturating(self.0 + other.0)
+	}
+	/// Returns the [`Bernoulli`](Bernoulli) distribution with this probability of success.
+	#[inline]
+	pub const fn bernoulli(self) -> Bernoulli {
+		Bernoulli::new(self.0)
+	}
+	/// Returns `true` with this probability.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use urandom::types::Probability;
+	///
+	/// let mut rng = urandom::new();
+	/// assert!(Probability::ALWAYS.chance(&mut rng));
+	/// assert!(!Probability::NEVER.chance(&mut rng));
+	/// ```
+	#[inline]
+	pub fn chance<R: Rng + ?Sized>(&self, rand: &mut Random<R>) -> bool {
+		self.bernoulli().sample(rand)
+	}
+}
+
+impl ops::Mul for Probability {
+	type Output = Probability;
+	#[inline]
+	fn mul(self, rhs: Probability) -> Probability {
+		self.and(rhs)
+	}
+}
+impl ops::Not for Probability {
+	type Output = Probability;
+	#[inline]
+	fn not(self) -> Probability {
+		self.complement()
+	}
+}
+
+impl From<Probability> for f64 {
+	#[inline]
+	fn from(p: Probability) -> f64 {
+		p.0
+	}
+}
+
+impl fmt::Display for Turns {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Display::fmt(&self.0, f)?;
+		f.write_str(" turns")
+	}
+}
+impl fmt::Display for Radians {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Display::fmt(&self.0, f)?;
+		f.write_str(" rad")
+	}
+}
+impl fmt::Display for Probability {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Display::fmt(&self.0, f)
+	}
+}
+
+/// Uniformly distributed over a full rotation in the half-open interval `[0, 1)`.
+impl Distribution<Turns> for Standard {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> Turns {
+		Turns(rng.next_f64() - 1.0)
+	}
+}
+/// Uniformly distributed over a full rotation in the half-open interval `[0, 2π)`.
+impl Distribution<Radians> for Standard {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> Radians {
+		Radians::from(Distribution::<Turns>::sample(self, rng))
+	}
+}
+/// Uniformly distributed in the open interval `(0, 1)`, see [`Float01`](Float01).
+impl Distribution<Probability> for Standard {
+	#[inline]
+	fn sample<R: Rng + ?Sized>(&self, rng: &mut Random<R>) -> Probability {
+		Probability(Float01.sample(rng))
+	}
+}
+
+//----------------------------------------------------------------
+
+#[test]
+fn test_sample_ranges() {
+	let mut rng = crate::new();
+	for _ in 0..10000 {
+		let turns: Turns = rng.next();
+		assert!(turns.0 >= 0.0 && turns.0 < 1.0, "{}", turns);
+		let radians: Radians = rng.next();
+		assert!(radians.0 >= 0.0 && radians.0 < TAU, "{}", radians);
+		let p: Probability = rng.next();
+		assert!(p.0 > 0.0 && p.0 < 1.0, "{}", p);
+	}
+	// The extremes of the underlying generator stay in range
+	let mut rng = crate::rng::MockRng::slice(&[!0, !0]);
+	let turns: Turns = rng.next();
+	let radians: Radians = rng.next();
+	assert!(turns.0 < 1.0 && radians.0 < TAU);
+}
+
+#[test]
+fn test_conversions() {
+	assert_eq!(Radians::from(Turns(0.5)), Radians(core::f64::consts::PI));
+	assert_eq!(Turns::from(Radians(core::f64::consts::FRAC_PI_2)), Turns(0.25));
+	for i in 0..100 {
+		let turns = Turns(i as f64 / 100.0);
+		let back = Turns::from(Radians::from(turns));
+		assert!((back.0 - turns.0).abs() < 1e-15, "{} {}", turns, back);
+	}
+}
+
+#[test]
+fn test_angle_ops() {
+	assert_eq!(Turns(0.75) + Turns(0.5), Turns(0.25));
+	assert_eq!(Turns(0.25) - Turns(0.5), Turns(0.75));
+	assert_eq!(-Turns(0.25), Turns(0.75));
+	assert_eq!(Turns(0.75) * 3.0, Turns(0.25));
+	assert_eq!(Turns(-1e-20).wrapped(), Turns(0.0));
+	assert_eq!(Radians(-1e-20).wrapped(), Radians(0.0));
+	let mut angle = Radians(0.0);
+	angle -= Radians(1.0);
+	assert!((angle.0 - (TAU - 1.0)).abs() < 1e-15);
+	angle += Radians(1.0);
+	assert!(angle.0 < 1e-15 || angle.0 > TAU - 1e-15);
+}
+
+#[test]
+fn test_probability_ops() {
+	assert_eq!(Probability::new(1.5), None);
+	assert_eq!(Probability::new(f64::NAN), None);
+	assert_eq!(Probability::new(0.5), Some(Probability(0.5)));
+	assert_eq!(Probability::saturating(-0.5), Probability::NEVER);
+	assert_eq!(Probability::saturating(1.5), Probability::ALWAYS);
+	assert_eq!(Probability::saturating(f64::NAN), Probability::NEVER);
+	assert_eq!(!Probability(0.25), Probability(0.75));
+	assert_eq!(Probability(0.5) * Probability(0.5), Probability(0.25));
+	assert_eq!(Probability(0.5).or(Probability(0.5)), Probability(0.75));
+	assert_eq!(Probability(0.75).checked_add(Probability(0.5)), None);
+	assert_eq!(Probability(0.25).checked_add(Probability(0.5)), Some(Probability(0.75)));
+	assert_eq!(Probability(0.75).saturating_add(Probability(0.5)), Probability::ALWAYS);
+}
+
+#[test]
+fn test_chance() {
+	let mut rng = crate::seeded(42);
+	let p = Probability(0.3);
+	let hits = (0..10000).filter(|_| p.chance(&mut rng)).count();
+	assert!((hits as i32 - 3000).abs() < 200, "hits: {}", hits);
+}
@@ -0,0 +1,32 @@
+//! Distributions reconstructed from their parameters sample exactly the same values as the original.
+
+#![cfg(feature = "std")]
+
+use urandom::Distribution;
+use urandom::distributions::{Bernoulli, LogUniform, LogUniformCandidates, PiecewiseConstant};
+
+fn check<T: PartialEq + std::fmt::Debug, D: Distribution<T>>(original: &D, loaded: &D) {
+	let mut a = urandom::seeded(42);
+	let mut b = urandom::seeded(42);
+	for _ in 0..1000 {
+		assert_eq!(original.sample(&mut a), loaded.sample(&mut b));
+	}
+}
+
+#[test]
+fn reconstruct() {
+	let bernoulli = Bernoulli::new(0.3);
+	check::<bool, _>(&bernoulli, &Bernoulli::new(bernoulli.p()));
+
+	let log_uniform = LogUniform::new(0.001, 10.0);
+	check::<f64, _>(&log_uniform, &LogUniform::new(log_uniform.low(), log_uniform.high()));
+
+	let log_uniform = LogUniform::new(1u32, 1_000_000);
+	check::<u32, _>(&log_uniform, &LogUniform::new(log_uniform.low(), log_uniform.high()));
+
+	let candidates = LogUniformCandidates::new(50_000);
+	check::<u64, _>(&candidates, &LogUniformCandidates::new(candidates.vocab_size()));
+
+	let histogram = PiecewiseConstant::new(&[0.0, 1.0, 2.5, 4.0], &[0.5, 0.0, 2.0]);
+	check::<f64, _>(&histogram, &PiecewiseConstant::new(histogram.edges(), histogram.densities()));
+}
@@ -0,0 +1,19 @@
+//! Runs the examples as integration tests.
+
+#![cfg(feature = "std")]
+
+#[path = "../examples/monte_carlo.rs"]
+mod monte_carlo;
+
+#[path = "../examples/state_checkpoint.rs"]
+mod state_checkpoint;
+
+#[test]
+fn test_monte_carlo() {
+	monte_carlo::main();
+}
+
+#[test]
+fn test_state_checkpoint() {
+	state_checkpoint::main();
+}
@@ -0,0 +1,42 @@
+//! Forks the process to verify that `ForkGuard` reseeds in the child.
+//!
+//! Forking a multithreaded test harness is fragile, run explicitly: `cargo test --test fork -- --ignored --test-threads=1`.
+
+#![cfg(all(feature = "std", unix))]
+
+use urandom::rng::{ChaCha20, ForkGuard};
+
+extern "C" {
+	fn fork() -> i32;
+	fn waitpid(pid: i32, status: *mut i32, options: i32) -> i32;
+	fn _exit(status: i32) -> !;
+}
+
+#[test]
+#[ignore]
+fn child_outputs_differ() {
+	let mut rng = ForkGuard::wrap(ChaCha20::from_seed(42));
+	let _ = rng.next_u64();
+	let path = std::env::temp_dir().join(format!("urandom-fork-{}", std::process::id()));
+
+	let pid = unsafe { fork() };
+	assert!(pid >= 0, "fork failed");
+	if pid == 0 {
+		let value = rng.next_u64();
+		let ok = std::fs::write(&path, value.to_le_bytes()).is_ok();
+		unsafe { _exit(if ok { 0 } else { 1 }) }
+	}
+
+	let mut status = 0;
+	assert_eq!(unsafe { waitpid(pid, &mut status, 0) }, pid);
+	assert_eq!(status, 0);
+	let child = std::fs::read(&path).unwrap();
+	let _ = std::fs::remove_file(&path);
+	let parent = rng.next_u64();
+	assert_ne!(child, parent.to_le_bytes());
+
+	// Without the guard the child continues the parent's stream
+	let mut reference = ChaCha20::from_seed(42);
+	let _ = reference.next_u64();
+	assert_eq!(parent, reference.next_u64());
+}
@@ -0,0 +1,53 @@
+//! Seals the process-global entropy source, runs in its own process to not affect other tests.
+
+#![cfg(feature = "getrandom")]
+
+use std::{panic, thread};
+use urandom::rng::{getentropy, Sealed, Xoshiro256};
+
+#[test]
+fn sealed() {
+	// Before init everything behaves as usual
+	assert!(!Sealed::is_init() && !Sealed::is_enforced());
+	let mut buffer = [0u8; 32];
+	getentropy(&mut buffer);
+	let _ = urandom::new().next_u64();
+	let _ = urandom::csprng().next_u64();
+
+	// Exactly one thread wins the race to init
+	let handles: Vec<_> = (0..8).map(|_| thread::spawn(|| panic::catch_unwind(Sealed::init).is_ok())).collect();
+	let winners = handles.into_iter().map(|handle| handle.join().unwrap()).filter(|&ok| ok).count();
+	assert_eq!(winners, 1);
+	assert!(Sealed::is_init());
+
+	// Init does not enforce, the entropy source still works
+	getentropy(&mut buffer);
+
+	// Racing with enforce, every thread either gets entropy before the seal or panics
+	let handles: Vec<_> = (0..8).map(|i| thread::spawn(move || {
+		if i == 4 {
+			Sealed::enforce();
+		}
+		let _ = panic::catch_unwind(|| urandom::new().next_u64());
+	})).collect();
+	for handle in handles {
+		handle.join().unwrap();
+	}
+	assert!(Sealed::is_enforced());
+
+	// After enforce every path to the entropy source panics with a descriptive message
+	let message = |result: thread::Result<()>| {
+		let err = result.unwrap_err();
+		err.downcast_ref::<&str>().map(|s| s.to_string()).or_else(|| err.downcast_ref::<String>().cloned()).unwrap()
+	};
+	let err = message(panic::catch_unwind(|| getentropy(&mut [0u8; 8])));
+	assert!(err.contains("getentropy called after Sealed::enforce"), "{}", err);
+	assert!(panic::catch_unwind(|| { let _ = urandom::new(); }).is_err());
+	assert!(panic::catch_unwind(|| { let _ = urandom::csprng(); }).is_err());
+	assert!(panic::catch_unwind(|| { let _ = Xoshiro256::new(); }).is_err());
+	let err = message(panic::catch_unwind(|| { let _ = Sealed::init(); }));
+	assert!(err.contains("Sealed::init called more than once"), "{}", err);
+
+	// Deterministic generators are unaffected
+	let _ = urandom::seeded(42).next_u64();
+}
@@ -0,0 +1,121 @@
+//! Exercises the code paths backed by unsafe code and byte reinterpretation.
+//!
+//! These tests are designed to be run under Miri: `cargo +nightly miri test --test unsafe_surface`.
+
+#![cfg(feature = "std")]
+
+use std::io;
+use urandom::rng::{ChaCha20, Philox, ReadRng, SplitMix64, Xoshiro256};
+
+// ChaCha20 produces blocks of 16 words
+const BLOCK_WORDS: usize = 16;
+
+#[test]
+fn chacha20_fill_u32_blocks() {
+	for len in 0..=3 * BLOCK_WORDS {
+		let mut rng = ChaCha20::from_seed(len as u64);
+		let mut reference = rng.clone();
+		let mut buffer = vec![0u32; len];
+		rng.fill_u32(&mut buffer);
+		for &word in &buffer {
+			assert_eq!(word, reference.next_u32(), "len: {}", len);
+		}
+	}
+}
+
+#[test]
+fn chacha20_fill_u64_blocks() {
+	for len in 0..=3 * BLOCK_WORDS / 2 {
+		let mut rng = ChaCha20::from_seed(len as u64);
+		let mut reference = rng.clone();
+		let mut buffer = vec![0u64; len];
+		rng.fill_u64(&mut buffer);
+		for &word in &buffer {
+			let low = reference.next_u32() as u64;
+			let high = reference.next_u32() as u64;
+			assert_eq!(word, high << 32 | low, "len: {}", len);
+		}
+	}
+}
+
+#[test]
+fn chacha20_fill_bytes_blocks() {
+	for len in 0..=3 * BLOCK_WORDS * 4 {
+		let mut rng = ChaCha20::from_seed(len as u64);
+		let mut reference = rng.clone();
+		let mut buffer = vec![0u8; len];
+		rng.fill_bytes(&mut buffer);
+		for chunk in buffer.chunks(4) {
+			assert_eq!(chunk, &reference.next_u32().to_le_bytes()[..chunk.len()], "len: {}", len);
+		}
+	}
+}
+
+#[test]
+fn chacha20_partial_blocks() {
+	// Interleave every fill method with partially consumed blocks
+	let mut rng = ChaCha20::from_seed(42);
+	for len in 0..=3 * BLOCK_WORDS {
+		let mut words = vec![0u32; len];
+		let mut dwords = vec![0u64; len / 2];
+		let mut bytes = vec![0u8; len];
+		rng.fill_u32(&mut words);
+		let _ = rng.next_u32();
+		rng.fill_u64(&mut dwords);
+		let _ = rng.next_u64();
+		rng.fill_bytes(&mut bytes);
+	}
+}
+
+#[test]
+fn unaligned_buffers() {
+	// Offset the buffers by one element so they are not aligned to the larger types
+	let mut storage = [0u16; 65];
+	let mut rng = ChaCha20::from_seed(1);
+	rng.fill_u16(&mut storage[1..]);
+	let mut storage = [0u8; 67];
+	rng.fill_bytes(&mut storage[3..]);
+
+	let mut split = SplitMix64::from_seed(1);
+	let mut xoshiro = Xoshiro256::from_seed(1);
+	let mut philox = Philox::from_seed(1);
+	for len in 0..40 {
+		split.fill_bytes(&mut storage[1..1 + len]);
+		xoshiro.fill_bytes(&mut storage[1..1 + len]);
+		philox.fill_bytes(&mut storage[1..1 + len]);
+	}
+}
+
+/// Reader that inspects the buffer it is handed.
+///
+/// Reading uninitialized memory is undefined behavior which Miri reports.
+struct InspectingReader(u8);
+
+impl io::Read for InspectingReader {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		let mut sum = 0u8;
+		for byte in buf.iter_mut() {
+			sum = sum.wrapping_add(*byte);
+			*byte = self.0;
+			self.0 = self.0.wrapping_add(1);
+		}
+		std::hint::black_box(sum);
+		Ok(buf.len())
+	}
+}
+
+#[test]
+fn read_rng_initialized() {
+	let mut rng = ReadRng::new(InspectingReader(0));
+	assert_eq!(rng.next_u32(), u32::from_le_bytes([0, 1, 2, 3]));
+	assert_eq!(rng.next_u64(), u64::from_le_bytes([4, 5, 6, 7, 8, 9, 10, 11]));
+	let mut words = [0u32; 5];
+	rng.fill_u32(&mut words);
+	let mut dwords = [0u64; 5];
+	rng.fill_u64(&mut dwords);
+	let mut bytes = [0u8; 13];
+	rng.fill_bytes(&mut bytes);
+	assert_eq!(bytes[0], 12 + 20 + 40);
+	let _: [u16; 7] = rng.next();
+	let _: f64 = rng.next();
+}